@@ -11,3 +11,15 @@ pub trait AuthCallee {
     /// NOTE: implementations are recommended to be `#[payable]`
     fn on_auth(&mut self, signer_id: AccountId, msg: String) -> PromiseOrValue<()>;
 }
+
+#[ext_contract(ext_auth_value_callee)]
+pub trait AuthValueCallee {
+    /// Same as [`AuthCallee::on_auth`], but returns a value, which is
+    /// then emitted by the caller, so that composed protocols can read it.
+    ///
+    /// Verification of `signer_id` is still the responsibility of the
+    /// implementation, i.e. it MUST whitelist allowed callers.
+    ///
+    /// NOTE: implementations are recommended to be `#[payable]`
+    fn on_auth_value(&mut self, signer_id: AccountId, msg: String) -> PromiseOrValue<String>;
+}
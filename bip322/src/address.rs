@@ -0,0 +1,153 @@
+use core::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+use bech32::{
+    Hrp,
+    segwit::{self, VERSION_0, VERSION_1},
+};
+use thiserror::Error as ThisError;
+
+/// Bitcoin mainnet address
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    serde_with::SerializeDisplay,
+    serde_with::DeserializeFromStr,
+)]
+pub enum Address {
+    /// Pay-to-Witness-Public-Key-Hash, see
+    /// [BIP-141](https://github.com/bitcoin/bips/blob/master/bip-0141.mediawiki#p2wpkh)
+    P2wpkh { pubkey_hash: [u8; 20] },
+    /// Pay-to-Taproot, see
+    /// [BIP-341](https://github.com/bitcoin/bips/blob/master/bip-0341.mediawiki)
+    P2tr { output_key: [u8; 32] },
+}
+
+impl Address {
+    /// Human-readable part of mainnet segwit addresses
+    pub const HRP: Hrp = bech32::hrp::BC;
+
+    /// `scriptPubKey` of outputs locked to this address
+    pub fn script_pubkey(&self) -> Vec<u8> {
+        match self {
+            // OP_0 OP_PUSHBYTES_20 <pubkey_hash>
+            Self::P2wpkh { pubkey_hash } => [[0x00, 0x14].as_slice(), pubkey_hash].concat(),
+            // OP_1 OP_PUSHBYTES_32 <output_key>
+            Self::P2tr { output_key } => [[0x51, 0x20].as_slice(), output_key].concat(),
+        }
+    }
+}
+
+impl Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::P2wpkh { pubkey_hash } => {
+                segwit::encode_lower_to_fmt_unchecked(f, Self::HRP, VERSION_0, pubkey_hash)
+            }
+            Self::P2tr { output_key } => {
+                segwit::encode_lower_to_fmt_unchecked(f, Self::HRP, VERSION_1, output_key)
+            }
+        }
+    }
+}
+
+impl FromStr for Address {
+    type Err = ParseAddressError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.starts_with(['1', '3']) {
+            return Err(ParseAddressError::Legacy);
+        }
+        let (hrp, version, program) = segwit::decode(s)?;
+        if hrp != Self::HRP {
+            return Err(ParseAddressError::UnsupportedNetwork);
+        }
+        match version {
+            VERSION_0 => Ok(Self::P2wpkh {
+                pubkey_hash: program
+                    .try_into()
+                    .map_err(|_| ParseAddressError::UnsupportedWitnessProgram)?,
+            }),
+            VERSION_1 => Ok(Self::P2tr {
+                output_key: program
+                    .try_into()
+                    .map_err(|_| ParseAddressError::UnsupportedWitnessProgram)?,
+            }),
+            _ => Err(ParseAddressError::UnsupportedWitnessProgram),
+        }
+    }
+}
+
+#[derive(Debug, ThisError)]
+pub enum ParseAddressError {
+    #[error("legacy (base58) addresses are not supported")]
+    Legacy,
+    #[error("bech32: {0}")]
+    Bech32(#[from] segwit::DecodeError),
+    #[error("unsupported network")]
+    UnsupportedNetwork,
+    #[error("unsupported witness program")]
+    UnsupportedWitnessProgram,
+}
+
+#[cfg(test)]
+mod tests {
+    use hex_literal::hex;
+
+    use super::*;
+
+    #[test]
+    fn p2wpkh() {
+        let s = "bc1q9vza2e8x573nczrlzms0wvx3gsqjx7vavgkx0l";
+        let address: Address = s.parse().unwrap();
+
+        assert_eq!(
+            address,
+            Address::P2wpkh {
+                pubkey_hash: hex!("2b05d564e6a7a33c087f16e0f730d1440123799d"),
+            }
+        );
+        assert_eq!(address.to_string(), s);
+    }
+
+    #[test]
+    fn p2tr() {
+        let s = "bc1ppv609nr0vr25u07u95waq5lucwfm6tde4nydujnu8npg4q75mr5sxq8lt3";
+        let address: Address = s.parse().unwrap();
+
+        assert_eq!(
+            address,
+            Address::P2tr {
+                output_key: hex!(
+                    "0b34f2cc6f60d54e3fdc2d1dd053fcc393bd2db9acc8de4a7c3cc28a83d4d8e9"
+                ),
+            }
+        );
+        assert_eq!(address.to_string(), s);
+    }
+
+    #[test]
+    fn unsupported() {
+        // P2PKH
+        assert!(matches!(
+            "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa".parse::<Address>(),
+            Err(ParseAddressError::Legacy)
+        ));
+        // testnet
+        assert!(matches!(
+            "tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx".parse::<Address>(),
+            Err(ParseAddressError::UnsupportedNetwork)
+        ));
+        // P2WSH
+        assert!(matches!(
+            "bc1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3qccfmv3".parse::<Address>(),
+            Err(ParseAddressError::UnsupportedWitnessProgram)
+        ));
+    }
+}
@@ -0,0 +1,361 @@
+//! [BIP-322](https://github.com/bitcoin/bips/blob/master/bip-0322.mediawiki)
+//! "simple" signatures of arbitrary messages by Bitcoin addresses
+
+mod address;
+mod tx;
+
+pub use self::address::*;
+
+use defuse_crypto::{CryptoHash, Curve, Payload, Secp256k1, SignedPayload};
+use defuse_serde_utils::base64::Base64;
+use impl_tools::autoimpl;
+use k256::{elliptic_curve::sec1::ToEncodedPoint, schnorr};
+use near_sdk::near;
+use serde_with::serde_as;
+use thiserror::Error as ThisError;
+
+#[near(serializers = [json])]
+#[derive(Debug, Clone)]
+pub struct Bip322Payload {
+    #[cfg_attr(
+        all(feature = "abi", not(target_arch = "wasm32")),
+        schemars(with = "String")
+    )]
+    pub address: Address,
+    pub message: String,
+}
+
+impl Bip322Payload {
+    /// Hash of `to_spend` transaction
+    #[inline]
+    pub fn to_spend_txid(&self) -> CryptoHash {
+        tx::to_spend_txid(
+            &tx::message_hash(self.message.as_bytes()),
+            &self.address.script_pubkey(),
+        )
+    }
+
+    fn sighash(&self, hash_type: u8) -> CryptoHash {
+        match self.address {
+            Address::P2wpkh { pubkey_hash } => {
+                tx::p2wpkh_sighash(&self.to_spend_txid(), &pubkey_hash)
+            }
+            Address::P2tr { .. } => tx::p2tr_sighash(
+                &self.to_spend_txid(),
+                &self.address.script_pubkey(),
+                hash_type,
+            ),
+        }
+    }
+}
+
+impl Payload for Bip322Payload {
+    /// Signature hash of `to_sign` transaction.
+    ///
+    /// For P2TR, this is the hash for `SIGHASH_DEFAULT`, while
+    /// signatures can also commit to explicit `SIGHASH_ALL`.
+    #[inline]
+    fn hash(&self) -> CryptoHash {
+        self.sighash(tx::SIGHASH_DEFAULT)
+    }
+}
+
+#[cfg_attr(
+    all(feature = "abi", not(target_arch = "wasm32")),
+    serde_as(schemars = true)
+)]
+#[cfg_attr(
+    not(all(feature = "abi", not(target_arch = "wasm32"))),
+    serde_as(schemars = false)
+)]
+#[near(serializers = [json])]
+#[autoimpl(Deref using self.payload)]
+#[derive(Debug, Clone)]
+pub struct SignedBip322Payload {
+    #[serde(flatten)]
+    pub payload: Bip322Payload,
+
+    /// "Simple" signature, i.e. consensus-encoded witness stack
+    /// of `to_sign` transaction
+    #[serde_as(as = "Base64")]
+    pub signature: Vec<u8>,
+}
+
+impl SignedBip322Payload {
+    /// Verifies the signature according to the script type of the address
+    /// and returns the signer's public key.
+    ///
+    /// For P2TR, the returned key is the output key lifted to even `y`.
+    pub fn try_verify(&self) -> Result<<Secp256k1 as Curve>::PublicKey, Bip322Error> {
+        let witness = parse_witness(&self.signature).ok_or(Bip322Error::InvalidWitness)?;
+        match self.payload.address {
+            Address::P2wpkh { pubkey_hash } => self.verify_p2wpkh(&witness, &pubkey_hash),
+            Address::P2tr { output_key } => self.verify_p2tr(&witness, &output_key),
+        }
+    }
+
+    fn verify_p2wpkh(
+        &self,
+        witness: &[&[u8]],
+        pubkey_hash: &[u8; 20],
+    ) -> Result<<Secp256k1 as Curve>::PublicKey, Bip322Error> {
+        let [signature, public_key] = witness else {
+            return Err(Bip322Error::InvalidWitness);
+        };
+
+        let (&hash_type, signature) = signature
+            .split_last()
+            .ok_or(Bip322Error::InvalidSignature)?;
+        if hash_type != tx::SIGHASH_ALL {
+            return Err(Bip322Error::UnsupportedSighashType(hash_type));
+        }
+        let public_key: &[u8; 33] = (*public_key)
+            .try_into()
+            .map_err(|_| Bip322Error::InvalidPublicKey)?;
+        if tx::hash160(public_key) != *pubkey_hash {
+            return Err(Bip322Error::AddressMismatch);
+        }
+
+        let [r, s] = parse_der_signature(signature).ok_or(Bip322Error::InvalidSignature)?;
+        let hash = self.payload.sighash(hash_type);
+        // recover the public key with each possible recovery byte and
+        // compare it with the one committed to in the witness
+        (0..=1)
+            .find_map(|v| {
+                let mut signature = [0; 65];
+                signature[..32].copy_from_slice(&r);
+                signature[32..64].copy_from_slice(&s);
+                signature[64] = v;
+
+                Secp256k1::verify(&signature, &hash, &())
+                    .filter(|recovered| compress_public_key(recovered) == *public_key)
+            })
+            .ok_or(Bip322Error::InvalidSignature)
+    }
+
+    fn verify_p2tr(
+        &self,
+        witness: &[&[u8]],
+        output_key: &[u8; 32],
+    ) -> Result<<Secp256k1 as Curve>::PublicKey, Bip322Error> {
+        // key path spend
+        let [signature] = witness else {
+            return Err(Bip322Error::InvalidWitness);
+        };
+
+        let (signature, hash_type) = match signature.split_first_chunk::<64>() {
+            Some((signature, [])) => (signature, tx::SIGHASH_DEFAULT),
+            // SIGHASH_DEFAULT can't be specified explicitly
+            Some((signature, [tx::SIGHASH_ALL])) => (signature, tx::SIGHASH_ALL),
+            Some((_, &[hash_type])) => return Err(Bip322Error::UnsupportedSighashType(hash_type)),
+            _ => return Err(Bip322Error::InvalidSignature),
+        };
+
+        let verifying_key = schnorr::VerifyingKey::from_bytes(output_key)
+            .map_err(|_| Bip322Error::InvalidPublicKey)?;
+        let signature = schnorr::Signature::try_from(signature.as_slice())
+            .map_err(|_| Bip322Error::InvalidSignature)?;
+        verifying_key
+            .verify_raw(&self.payload.sighash(hash_type), &signature)
+            .map_err(|_| Bip322Error::InvalidSignature)?;
+
+        let mut public_key = [0; 64];
+        public_key
+            .copy_from_slice(&verifying_key.as_affine().to_encoded_point(false).as_bytes()[1..]);
+        Ok(public_key)
+    }
+}
+
+impl Payload for SignedBip322Payload {
+    #[inline]
+    fn hash(&self) -> CryptoHash {
+        self.payload.hash()
+    }
+}
+
+impl SignedPayload for SignedBip322Payload {
+    type PublicKey = <Secp256k1 as Curve>::PublicKey;
+
+    #[inline]
+    fn verify(&self) -> Option<Self::PublicKey> {
+        self.try_verify().ok()
+    }
+}
+
+#[derive(Debug, ThisError, PartialEq, Eq)]
+pub enum Bip322Error {
+    #[error("invalid witness")]
+    InvalidWitness,
+    #[error("invalid signature")]
+    InvalidSignature,
+    #[error("invalid public key")]
+    InvalidPublicKey,
+    #[error("unsupported sighash type: {0:#04x}")]
+    UnsupportedSighashType(u8),
+    #[error("public key doesn't match the address")]
+    AddressMismatch,
+}
+
+/// Parses consensus-encoded witness stack
+fn parse_witness(mut data: &[u8]) -> Option<Vec<&[u8]>> {
+    let n = tx::read_compact_size(&mut data)?;
+    let items = (0..n)
+        .map(|_| {
+            let len = tx::read_compact_size(&mut data)?.try_into().ok()?;
+            let (item, rest) = data.split_at_checked(len)?;
+            data = rest;
+            Some(item)
+        })
+        .collect::<Option<_>>()?;
+    data.is_empty().then_some(items)
+}
+
+/// Parses strict DER-encoded ECDSA signature into `[r, s]`
+fn parse_der_signature(der: &[u8]) -> Option<[[u8; 32]; 2]> {
+    let [0x30, len, der @ ..] = der else {
+        return None;
+    };
+    if usize::from(*len) != der.len() {
+        return None;
+    }
+    let (r, der) = parse_der_integer(der)?;
+    let (s, der) = parse_der_integer(der)?;
+    der.is_empty().then_some([r, s])
+}
+
+fn parse_der_integer(der: &[u8]) -> Option<([u8; 32], &[u8])> {
+    let [0x02, len, der @ ..] = der else {
+        return None;
+    };
+    let (int, rest) = der.split_at_checked(usize::from(*len))?;
+    // positive integers have a leading zero byte if the highest bit is set
+    let int = match int {
+        [0x00, tail @ ..] if tail.first().is_some_and(|b| b & 0x80 != 0) => tail,
+        [first, ..] if first & 0x80 == 0 => int,
+        _ => return None,
+    };
+    let padded_len = 32usize.checked_sub(int.len())?;
+    let mut out = [0; 32];
+    out[padded_len..].copy_from_slice(int);
+    Some((out, rest))
+}
+
+/// SEC1 compressed encoding of uncompressed `x || y` public key
+fn compress_public_key(public_key: &[u8; 64]) -> [u8; 33] {
+    let mut compressed = [0; 33];
+    compressed[0] = 0x02 | (public_key[63] & 1);
+    compressed[1..].copy_from_slice(&public_key[..32]);
+    compressed
+}
+
+#[cfg(test)]
+mod tests {
+    use defuse_test_utils::{
+        random::{Rng, rng},
+        tamper::{tamper_bytes, tamper_string},
+    };
+    use hex_literal::hex;
+    use near_sdk::base64::{Engine, engine::general_purpose::STANDARD};
+    use rstest::rstest;
+
+    use super::*;
+
+    /// Test vectors from BIP-322, signed by `L3VFeEujGtevx9w18HD1fhRbCH67Az2dpCymeRE1SoPK6XQtaN2k`
+    const P2WPKH_ADDRESS: &str = "bc1q9vza2e8x573nczrlzms0wvx3gsqjx7vavgkx0l";
+    const P2WPKH_PUBLIC_KEY: [u8; 64] = hex!(
+        "c7f12003196442943d8588e01aee840423cc54fc1521526a3b85c2b0cbd58872e18b74c078d89c58ea278942bcc26563f976d0cc31b5a4cedfa42c716b83b1fe"
+    );
+    const P2WPKH_HELLO_WORLD: &str = "AkcwRAIgZRfIY3p7/DoVTty6YZbWS71bc5Vct9p9Fia83eRmw2QCICK/ENGfwLtptFluMGs2KsqoNSk89pO7F29zJLUx9a/sASECx/EgAxlkQpQ9hYjgGu6EBCPMVPwVIVJqO4XCsMvViHI=";
+
+    const P2TR_ADDRESS: &str = "bc1ppv609nr0vr25u07u95waq5lucwfm6tde4nydujnu8npg4q75mr5sxq8lt3";
+    const P2TR_PUBLIC_KEY: [u8; 64] = hex!(
+        "0b34f2cc6f60d54e3fdc2d1dd053fcc393bd2db9acc8de4a7c3cc28a83d4d8e9fd4f26986a42b7666a067d539600178e0747f0e262fea4b226f3778b147d3d02"
+    );
+    const P2TR_HELLO_WORLD: &str = "AUHd69PrJQEv+oKTfZ8l+WROBHuy9HKrbFCJu7U1iK2iiEy1vMU5EfMtjc+VSHM7aU0SDbak5IUZRVno2P5mjSafAQ==";
+
+    fn signed(address: &str, message: &str, signature: &str) -> SignedBip322Payload {
+        SignedBip322Payload {
+            payload: Bip322Payload {
+                address: address.parse().unwrap(),
+                message: message.to_string(),
+            },
+            signature: STANDARD.decode(signature).unwrap(),
+        }
+    }
+
+    #[rstest]
+    #[case(
+        P2WPKH_ADDRESS,
+        "",
+        "AkcwRAIgM2gBAQqvZX15ZiysmKmQpDrG83avLIT492QBzLnQIxYCIBaTpOaD20qRlEylyxFSeEA2ba9YOixpX8z46TSDtS40ASECx/EgAxlkQpQ9hYjgGu6EBCPMVPwVIVJqO4XCsMvViHI=",
+        P2WPKH_PUBLIC_KEY
+    )]
+    #[case(P2WPKH_ADDRESS, "Hello World", P2WPKH_HELLO_WORLD, P2WPKH_PUBLIC_KEY)]
+    #[case(P2TR_ADDRESS, "Hello World", P2TR_HELLO_WORLD, P2TR_PUBLIC_KEY)]
+    fn verify(
+        #[case] address: &str,
+        #[case] message: &str,
+        #[case] signature: &str,
+        #[case] public_key: [u8; 64],
+    ) {
+        assert_eq!(
+            signed(address, message, signature).verify(),
+            Some(public_key)
+        );
+    }
+
+    #[rstest]
+    #[case(P2WPKH_ADDRESS, P2WPKH_HELLO_WORLD, P2WPKH_PUBLIC_KEY)]
+    #[case(P2TR_ADDRESS, P2TR_HELLO_WORLD, P2TR_PUBLIC_KEY)]
+    fn tamper_message_fails(
+        mut rng: impl Rng,
+        #[case] address: &str,
+        #[case] signature: &str,
+        #[case] public_key: [u8; 64],
+    ) {
+        let mut signed = signed(address, "Hello World", signature);
+        signed.payload.message = tamper_string(&mut rng, &signed.payload.message);
+
+        assert_ne!(signed.verify(), Some(public_key));
+    }
+
+    #[rstest]
+    #[case(P2WPKH_ADDRESS, P2WPKH_HELLO_WORLD, P2WPKH_PUBLIC_KEY)]
+    #[case(P2TR_ADDRESS, P2TR_HELLO_WORLD, P2TR_PUBLIC_KEY)]
+    fn tamper_signature_fails(
+        mut rng: impl Rng,
+        #[case] address: &str,
+        #[case] signature: &str,
+        #[case] public_key: [u8; 64],
+    ) {
+        let mut signed = signed(address, "Hello World", signature);
+        signed.signature = tamper_bytes(&mut rng, &signed.signature, false);
+
+        assert_ne!(signed.verify(), Some(public_key));
+    }
+
+    #[test]
+    fn wrong_address_fails() {
+        let mut signed = signed(P2WPKH_ADDRESS, "Hello World", P2WPKH_HELLO_WORLD);
+        signed.payload.address = Address::P2wpkh {
+            pubkey_hash: [0; 20],
+        };
+        assert_eq!(signed.try_verify(), Err(Bip322Error::AddressMismatch));
+
+        // P2WPKH witness for P2TR address
+        signed.payload.address = P2TR_ADDRESS.parse().unwrap();
+        assert_eq!(signed.try_verify(), Err(Bip322Error::InvalidWitness));
+    }
+
+    #[test]
+    fn unsupported_sighash_type() {
+        let mut signed = signed(P2TR_ADDRESS, "Hello World", P2TR_HELLO_WORLD);
+        // SIGHASH_NONE
+        *signed.signature.last_mut().unwrap() = 0x02;
+
+        assert_eq!(
+            signed.try_verify(),
+            Err(Bip322Error::UnsupportedSighashType(0x02))
+        );
+    }
+}
@@ -0,0 +1,258 @@
+//! Virtual `to_spend` and `to_sign` transactions, see
+//! [BIP-322](https://github.com/bitcoin/bips/blob/master/bip-0322.mediawiki#full)
+
+use defuse_crypto::CryptoHash;
+use near_sdk::env;
+
+const TAG: &[u8] = b"BIP0322-signed-message";
+
+/// `OP_RETURN`, the only output of `to_sign` transaction
+const TO_SIGN_SCRIPT_PUBKEY: &[u8] = &[0x6a];
+
+/// Taproot-only, implies [`SIGHASH_ALL`] semantics
+pub const SIGHASH_DEFAULT: u8 = 0x00;
+pub const SIGHASH_ALL: u8 = 0x01;
+
+/// `SHA256(SHA256(tag) || SHA256(tag) || message)`
+#[inline]
+pub fn message_hash(message: &[u8]) -> CryptoHash {
+    tagged_hash(TAG, message)
+}
+
+/// [BIP-340](https://github.com/bitcoin/bips/blob/master/bip-0340.mediawiki#design)
+/// tagged hash
+fn tagged_hash(tag: &[u8], data: &[u8]) -> CryptoHash {
+    let tag = env::sha256_array(tag);
+    env::sha256_array(&[tag.as_slice(), &tag, data].concat())
+}
+
+/// Txid of `to_spend` transaction:
+/// ```text
+/// nVersion = 0
+/// nLockTime = 0
+/// vin[0].prevout = 000...000:0xFFFFFFFF
+/// vin[0].scriptSig = OP_0 PUSH32[message_hash]
+/// vin[0].nSequence = 0
+/// vout[0].nValue = 0
+/// vout[0].scriptPubKey = message_challenge
+/// ```
+pub fn to_spend_txid(message_hash: &CryptoHash, script_pubkey: &[u8]) -> CryptoHash {
+    let mut tx = Vec::new();
+    // nVersion
+    tx.extend_from_slice(&0u32.to_le_bytes());
+    // vin
+    write_compact_size(&mut tx, 1);
+    tx.extend_from_slice(&[0; 32]);
+    tx.extend_from_slice(&u32::MAX.to_le_bytes());
+    write_compact_size(&mut tx, 34);
+    tx.extend_from_slice(&[0x00, 0x20]);
+    tx.extend_from_slice(message_hash);
+    tx.extend_from_slice(&0u32.to_le_bytes());
+    // vout
+    write_compact_size(&mut tx, 1);
+    tx.extend_from_slice(&0u64.to_le_bytes());
+    write_script(&mut tx, script_pubkey);
+    // nLockTime
+    tx.extend_from_slice(&0u32.to_le_bytes());
+
+    double_sha256(&tx)
+}
+
+/// [BIP-143](https://github.com/bitcoin/bips/blob/master/bip-0143.mediawiki)
+/// signature hash of `to_sign` transaction spending `to_spend` output
+/// locked to P2WPKH
+pub fn p2wpkh_sighash(to_spend_txid: &CryptoHash, pubkey_hash: &[u8; 20]) -> CryptoHash {
+    // vin[0].prevout = to_spend_txid:0
+    let outpoint = [to_spend_txid.as_slice(), &0u32.to_le_bytes()].concat();
+
+    let mut outputs = Vec::new();
+    outputs.extend_from_slice(&0u64.to_le_bytes());
+    write_script(&mut outputs, TO_SIGN_SCRIPT_PUBKEY);
+
+    let mut preimage = Vec::new();
+    // nVersion
+    preimage.extend_from_slice(&0u32.to_le_bytes());
+    // hashPrevouts
+    preimage.extend_from_slice(&double_sha256(&outpoint));
+    // hashSequence
+    preimage.extend_from_slice(&double_sha256(&0u32.to_le_bytes()));
+    // outpoint
+    preimage.extend_from_slice(&outpoint);
+    // scriptCode: OP_DUP OP_HASH160 OP_PUSHBYTES_20 <pubkey_hash> OP_EQUALVERIFY OP_CHECKSIG
+    write_script(
+        &mut preimage,
+        &[[0x76, 0xa9, 0x14].as_slice(), pubkey_hash, &[0x88, 0xac]].concat(),
+    );
+    // amount
+    preimage.extend_from_slice(&0u64.to_le_bytes());
+    // nSequence
+    preimage.extend_from_slice(&0u32.to_le_bytes());
+    // hashOutputs
+    preimage.extend_from_slice(&double_sha256(&outputs));
+    // nLockTime
+    preimage.extend_from_slice(&0u32.to_le_bytes());
+    // sighash type
+    preimage.extend_from_slice(&u32::from(SIGHASH_ALL).to_le_bytes());
+
+    double_sha256(&preimage)
+}
+
+/// [BIP-341](https://github.com/bitcoin/bips/blob/master/bip-0341.mediawiki#common-signature-message)
+/// signature hash of `to_sign` transaction spending `to_spend` output
+/// locked to P2TR via key path
+pub fn p2tr_sighash(to_spend_txid: &CryptoHash, script_pubkey: &[u8], hash_type: u8) -> CryptoHash {
+    let mut spent_script_pubkeys = Vec::new();
+    write_script(&mut spent_script_pubkeys, script_pubkey);
+
+    let mut outputs = Vec::new();
+    outputs.extend_from_slice(&0u64.to_le_bytes());
+    write_script(&mut outputs, TO_SIGN_SCRIPT_PUBKEY);
+
+    let mut msg = Vec::new();
+    // epoch
+    msg.push(0x00);
+    msg.push(hash_type);
+    // nVersion
+    msg.extend_from_slice(&0u32.to_le_bytes());
+    // nLockTime
+    msg.extend_from_slice(&0u32.to_le_bytes());
+    // sha_prevouts: to_spend_txid:0
+    msg.extend_from_slice(&env::sha256_array(
+        &[to_spend_txid.as_slice(), &0u32.to_le_bytes()].concat(),
+    ));
+    // sha_amounts
+    msg.extend_from_slice(&env::sha256_array(&0u64.to_le_bytes()));
+    // sha_scriptpubkeys
+    msg.extend_from_slice(&env::sha256_array(&spent_script_pubkeys));
+    // sha_sequences
+    msg.extend_from_slice(&env::sha256_array(&0u32.to_le_bytes()));
+    // sha_outputs
+    msg.extend_from_slice(&env::sha256_array(&outputs));
+    // spend_type: key path, no annex
+    msg.push(0x00);
+    // input_index
+    msg.extend_from_slice(&0u32.to_le_bytes());
+
+    tagged_hash(b"TapSighash", &msg)
+}
+
+/// `RIPEMD160(SHA256(data))`
+pub fn hash160(data: &[u8]) -> [u8; 20] {
+    env::ripemd160_array(&env::sha256_array(data))
+}
+
+fn double_sha256(data: &[u8]) -> CryptoHash {
+    env::sha256_array(&env::sha256_array(data))
+}
+
+fn write_script(buf: &mut Vec<u8>, script: &[u8]) {
+    write_compact_size(
+        buf,
+        script.len().try_into().unwrap_or_else(|_| unreachable!()),
+    );
+    buf.extend_from_slice(script);
+}
+
+fn write_compact_size(buf: &mut Vec<u8>, n: u64) {
+    if let Ok(n @ ..0xfd) = u8::try_from(n) {
+        buf.push(n);
+    } else if let Ok(n) = u16::try_from(n) {
+        buf.push(0xfd);
+        buf.extend_from_slice(&n.to_le_bytes());
+    } else if let Ok(n) = u32::try_from(n) {
+        buf.push(0xfe);
+        buf.extend_from_slice(&n.to_le_bytes());
+    } else {
+        buf.push(0xff);
+        buf.extend_from_slice(&n.to_le_bytes());
+    }
+}
+
+/// Reads `CompactSize` unsigned integer from the beginning of `data`
+pub fn read_compact_size(data: &mut &[u8]) -> Option<u64> {
+    let (&first, rest) = data.split_first()?;
+    *data = rest;
+    Some(match first {
+        0xfd => u16::from_le_bytes(read_array(data)?).into(),
+        0xfe => u32::from_le_bytes(read_array(data)?).into(),
+        0xff => u64::from_le_bytes(read_array(data)?),
+        n => n.into(),
+    })
+}
+
+fn read_array<const N: usize>(data: &mut &[u8]) -> Option<[u8; N]> {
+    let (head, rest) = data.split_first_chunk()?;
+    *data = rest;
+    Some(*head)
+}
+
+#[cfg(test)]
+mod tests {
+    use hex_literal::hex;
+    use rstest::rstest;
+
+    use super::*;
+
+    const PUBKEY_HASH: [u8; 20] = hex!("2b05d564e6a7a33c087f16e0f730d1440123799d");
+
+    /// Test vectors from BIP-322
+    #[rstest]
+    #[case(
+        b"",
+        hex!("c90c269c4f8fcbe6880f72a721ddfbf1914268a794cbb21cfafee13770ae19f1"),
+        hex!("c5680aa69bb8d860bf82d4e9cd3504b55dde018de765a91bb566283c545a99a7"),
+    )]
+    #[case(
+        b"Hello World",
+        hex!("f0eb03b1a75ac6d9847f55c624a99169b5dccba2a31f5b23bea77ba270de0a7a"),
+        hex!("b79d196740ad5217771c1098fc4a4b51e0535c32236c71f1ea4d61a2d603352b"),
+    )]
+    fn hashes(
+        #[case] message: &[u8],
+        #[case] expected_message_hash: CryptoHash,
+        #[case] expected_to_spend_txid: CryptoHash,
+    ) {
+        let hash = message_hash(message);
+        assert_eq!(hash, expected_message_hash);
+
+        let mut txid = to_spend_txid(&hash, &[[0x00, 0x14].as_slice(), &PUBKEY_HASH].concat());
+        // txids are displayed in reversed byte order
+        txid.reverse();
+        assert_eq!(txid, expected_to_spend_txid);
+    }
+
+    #[test]
+    fn p2tr() {
+        let script_pubkey = [
+            [0x51, 0x20].as_slice(),
+            &hex!("0b34f2cc6f60d54e3fdc2d1dd053fcc393bd2db9acc8de4a7c3cc28a83d4d8e9"),
+        ]
+        .concat();
+
+        let txid = to_spend_txid(&message_hash(b"Hello World"), &script_pubkey);
+        assert_eq!(
+            txid.iter().rev().copied().collect::<Vec<_>>(),
+            hex!("21666e9fd208b18d127b655275a9bb9831c33678ba9879a3a57c6a1623db7906")
+        );
+        assert_eq!(
+            p2tr_sighash(&txid, &script_pubkey, SIGHASH_ALL),
+            hex!("3c32825d6b1de7ec928033cbad24e7bfe51aeb16a493c76fbf59dd7b445ded22")
+        );
+    }
+
+    #[rstest]
+    #[case(0)]
+    #[case(0xfc)]
+    #[case(0xfd)]
+    #[case(u16::MAX.into())]
+    #[case(u32::MAX.into())]
+    #[case(u64::MAX)]
+    fn compact_size(#[case] n: u64) {
+        let mut buf = Vec::new();
+        write_compact_size(&mut buf, n);
+
+        let mut data = buf.as_slice();
+        assert_eq!(read_compact_size(&mut data), Some(n));
+        assert!(data.is_empty());
+    }
+}
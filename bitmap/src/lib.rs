@@ -83,6 +83,15 @@ where
         }
     }
 
+    /// Iterate over stored 256-bit words along with their 248-bit prefixes
+    #[inline]
+    pub fn iter_words(&self) -> impl Iterator<Item = (&U248, &U256)> + '_
+    where
+        T: IterableMap,
+    {
+        self.0.iter()
+    }
+
     /// Iterate over set U256
     #[inline]
     pub fn as_iter(&self) -> impl Iterator<Item = U256> + '_
@@ -0,0 +1,60 @@
+use std::io;
+
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+
+use super::{BorshDeserializeAs, BorshSerializeAs};
+
+/// Fixed-width big-endian encoding, as opposed to little-endian used by
+/// borsh for integers. Serialized bytes compare lexicographically in the
+/// same order as the values, which keeps storage keys ordered.
+///
+/// NOTE: only unsigned integers preserve ordering this way.
+pub struct BigEndian;
+
+macro_rules! impl_big_endian {
+    ($($t:ty),+ $(,)?) => {$(
+        impl BorshSerializeAs<$t> for BigEndian {
+            #[inline]
+            fn serialize_as<W>(source: &$t, writer: &mut W) -> io::Result<()>
+            where
+                W: io::Write,
+            {
+                writer.write_all(&source.to_be_bytes())
+            }
+        }
+
+        impl BorshDeserializeAs<$t> for BigEndian {
+            #[inline]
+            fn deserialize_as<R>(reader: &mut R) -> io::Result<$t>
+            where
+                R: io::Read,
+            {
+                <[u8; size_of::<$t>()]>::deserialize_reader(reader).map(<$t>::from_be_bytes)
+            }
+        }
+    )+};
+}
+
+impl_big_endian!(u16, u32, u64, u128);
+
+/// Byte arrays are already stored as-is, so this is an identity, allowing
+/// big-endian words to be used interchangeably with integers
+impl<const N: usize> BorshSerializeAs<[u8; N]> for BigEndian {
+    #[inline]
+    fn serialize_as<W>(source: &[u8; N], writer: &mut W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        source.serialize(writer)
+    }
+}
+
+impl<const N: usize> BorshDeserializeAs<[u8; N]> for BigEndian {
+    #[inline]
+    fn deserialize_as<R>(reader: &mut R) -> io::Result<[u8; N]>
+    where
+        R: io::Read,
+    {
+        <[u8; N]>::deserialize_reader(reader)
+    }
+}
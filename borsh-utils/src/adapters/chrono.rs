@@ -147,6 +147,48 @@ where
     }
 }
 
+/// Non-negative UNIX timestamp in seconds, encoded as `i64`.
+///
+/// Wire-compatible with [`TimestampSeconds<i64>`](TimestampSeconds), but
+/// rejects timestamps before UNIX epoch both on serialization and
+/// deserialization, matching what can be hashed by signing standards that
+/// encode timestamps as `u64` (e.g. TON Connect).
+/// Sub-second precision is truncated.
+pub struct Timestamp;
+
+impl Timestamp {
+    fn check(timestamp: i64) -> io::Result<i64> {
+        if timestamp < 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "timestamp: negative",
+            ));
+        }
+        Ok(timestamp)
+    }
+}
+
+impl BorshSerializeAs<DateTime<Utc>> for Timestamp {
+    #[inline]
+    fn serialize_as<W>(source: &DateTime<Utc>, writer: &mut W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        Self::check(source.timestamp())?.serialize(writer)
+    }
+}
+
+impl BorshDeserializeAs<DateTime<Utc>> for Timestamp {
+    fn deserialize_as<R>(reader: &mut R) -> io::Result<DateTime<Utc>>
+    where
+        R: io::Read,
+    {
+        let timestamp = Self::check(i64::deserialize_reader(reader)?)?;
+        DateTime::<Utc>::from_timestamp(timestamp, 0)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "timestamp: out of range"))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::adapters::tests::roundtrip_as;
@@ -179,4 +221,49 @@ mod tests {
             1_600_000_000_123_456_789,
         ));
     }
+
+    #[test]
+    fn timestamp_roundtrip() {
+        roundtrip_as::<_, Timestamp>(&DateTime::UNIX_EPOCH);
+        roundtrip_as::<_, Timestamp>(&Utc.timestamp_opt(1_747_760_435, 0).unwrap());
+    }
+
+    #[test]
+    fn timestamp_compatible_with_seconds() {
+        let t = Utc.timestamp_opt(1_747_760_435, 0).unwrap();
+        assert_eq!(
+            crate::adapters::to_vec_as::<_, Timestamp>(&t).unwrap(),
+            crate::adapters::to_vec_as::<_, TimestampSeconds<i64>>(&t).unwrap(),
+        );
+    }
+
+    #[test]
+    fn timestamp_negative_rejected() {
+        let t = Utc.timestamp_opt(-1, 0).unwrap();
+
+        let mut buf = Vec::new();
+        assert_eq!(
+            Timestamp::serialize_as(&t, &mut buf).unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+
+        let bytes = crate::adapters::to_vec_as::<_, TimestampSeconds<i64>>(&t).unwrap();
+        assert_eq!(
+            <Timestamp as BorshDeserializeAs<DateTime<Utc>>>::deserialize_as(&mut bytes.as_slice())
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::InvalidData
+        );
+    }
+
+    #[test]
+    fn timestamp_out_of_range_rejected() {
+        let bytes = i64::MAX.to_le_bytes();
+        assert_eq!(
+            <Timestamp as BorshDeserializeAs<DateTime<Utc>>>::deserialize_as(&mut bytes.as_slice())
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::InvalidData
+        );
+    }
 }
@@ -12,8 +12,10 @@ use defuse_io_utils::ReadExt;
 use impl_tools::autoimpl;
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 
+mod big_endian;
 #[cfg(feature = "chrono")]
 mod chrono;
+pub use self::big_endian::*;
 #[cfg(feature = "chrono")]
 pub use self::chrono::*;
 
@@ -121,6 +123,14 @@ where
     borsh::to_vec(&AsWrap::<&T, &As>::new(source))
 }
 
+/// Counterpart of [`to_vec_as`]. Fails if not all bytes were consumed.
+pub fn from_slice_as<T, As>(bytes: &[u8]) -> io::Result<T>
+where
+    As: BorshDeserializeAs<T> + ?Sized,
+{
+    borsh::from_slice::<AsWrap<T, As>>(bytes).map(AsWrap::into_inner)
+}
+
 impl<T, As> BorshDeserialize for AsWrap<T, As>
 where
     As: BorshDeserializeAs<T> + ?Sized,
@@ -487,6 +497,43 @@ where
     }
 }
 
+pub struct TryFromIntoRef<T: ?Sized>(PhantomData<T>);
+
+impl<T, U> BorshSerializeAs<T> for TryFromIntoRef<U>
+where
+    for<'a> &'a T: TryInto<U>,
+    for<'a> <&'a T as TryInto<U>>::Error: Display,
+    U: BorshSerialize,
+{
+    #[inline]
+    fn serialize_as<W>(source: &T, writer: &mut W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        source
+            .try_into()
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))?
+            .serialize(writer)
+    }
+}
+
+impl<T, U> BorshDeserializeAs<T> for TryFromIntoRef<U>
+where
+    U: BorshDeserialize + TryInto<T>,
+    <U as TryInto<T>>::Error: Display,
+{
+    #[inline]
+    fn deserialize_as<R>(reader: &mut R) -> io::Result<T>
+    where
+        R: io::Read,
+    {
+        U::deserialize_reader(reader).and_then(|v| {
+            v.try_into()
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))
+        })
+    }
+}
+
 pub struct Or<T1: ?Sized, T2: ?Sized>(PhantomData<T1>, PhantomData<T2>);
 
 impl<T, As1, As2> BorshDeserializeAs<T> for Or<As1, As2>
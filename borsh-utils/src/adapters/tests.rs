@@ -31,3 +31,115 @@ fn array() {
 fn tuple() {
     roundtrip_as::<(u8, u8), (Same, Same)>(&(10, 20));
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Meters(u32);
+
+impl From<Meters> for u32 {
+    fn from(Meters(m): Meters) -> Self {
+        m
+    }
+}
+
+impl From<u32> for Meters {
+    fn from(m: u32) -> Self {
+        Self(m)
+    }
+}
+
+#[test]
+fn to_vec_from_slice_as() {
+    let orig = Meters(42);
+    let bytes = to_vec_as::<_, FromInto<u32>>(&orig).unwrap();
+    assert_eq!(bytes, borsh::to_vec(&42u32).unwrap());
+    assert_eq!(
+        from_slice_as::<Meters, FromInto<u32>>(&bytes).unwrap(),
+        orig
+    );
+
+    // trailing bytes are rejected
+    from_slice_as::<Meters, FromInto<u32>>(&[bytes.as_slice(), &[0]].concat()).unwrap_err();
+}
+
+/// Serializes as a single byte only if fits into it
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Compact(u64);
+
+impl From<&Compact> for u64 {
+    fn from(Compact(n): &Compact) -> Self {
+        *n
+    }
+}
+
+impl TryFrom<&Compact> for u8 {
+    type Error = core::num::TryFromIntError;
+
+    fn try_from(Compact(n): &Compact) -> Result<Self, Self::Error> {
+        (*n).try_into()
+    }
+}
+
+impl From<u64> for Compact {
+    fn from(n: u64) -> Self {
+        Self(n)
+    }
+}
+
+impl From<u8> for Compact {
+    fn from(n: u8) -> Self {
+        Self(n.into())
+    }
+}
+
+#[test]
+fn from_into_ref() {
+    roundtrip_as::<Compact, FromIntoRef<u64>>(&Compact(u64::MAX));
+}
+
+#[test]
+fn try_from_into_ref() {
+    roundtrip_as::<Compact, TryFromIntoRef<u8>>(&Compact(42));
+    assert_eq!(
+        to_vec_as::<_, TryFromIntoRef<u8>>(&Compact(42)).unwrap(),
+        [42]
+    );
+
+    assert_eq!(
+        to_vec_as::<_, TryFromIntoRef<u8>>(&Compact(256))
+            .unwrap_err()
+            .kind(),
+        io::ErrorKind::InvalidData
+    );
+}
+
+#[test]
+fn big_endian() {
+    roundtrip_as::<u64, BigEndian>(&0x0102_0304_0506_0708);
+    roundtrip_as::<u128, BigEndian>(&u128::MAX);
+    roundtrip_as::<[u8; 32], BigEndian>(&[7; 32]);
+
+    assert_eq!(
+        to_vec_as::<u64, BigEndian>(&0x0102_0304_0506_0708).unwrap(),
+        [1, 2, 3, 4, 5, 6, 7, 8]
+    );
+    assert_eq!(
+        to_vec_as::<u128, BigEndian>(&1).unwrap(),
+        [[0; 15].as_slice(), &[1]].concat()
+    );
+    assert_eq!(
+        to_vec_as::<[u8; 3], BigEndian>(&[1, 2, 3]).unwrap(),
+        [1, 2, 3]
+    );
+}
+
+#[test]
+fn big_endian_preserves_order() {
+    let values = [0, 1, 0xff, 0x100, 0xffff_ffff, 1 << 32, u64::MAX];
+    for pair in values.windows(2) {
+        let [a, b] = [pair[0], pair[1]].map(|v| to_vec_as::<u64, BigEndian>(&v).unwrap());
+        assert!(a < b, "{a:?} >= {b:?}");
+    }
+
+    // little-endian borsh doesn't preserve it
+    assert!(borsh::to_vec(&0x100u64).unwrap() < borsh::to_vec(&0xffu64).unwrap());
+}
@@ -1,17 +1,25 @@
-use core::fmt::Debug;
-use std::{borrow::Cow, collections::BTreeMap};
+use core::fmt::{self, Debug};
+use std::{borrow::Cow, collections::BTreeMap, marker::PhantomData};
 
 use defuse_map_utils::{IterableMap, cleanup::DefaultMap};
 use defuse_num_utils::{CheckedAdd, CheckedSub};
 use impl_tools::autoimpl;
 use near_sdk::{
     near,
-    serde::{Deserializer, Serializer},
+    serde::{
+        Deserializer, Serializer,
+        de::{self, MapAccess, Visitor},
+    },
 };
-use serde_with::{DeserializeAs, SerializeAs};
+use serde_with::{DeserializeAs, SerializeAs, de::DeserializeAsWrap};
 
 use crate::token_id::TokenId;
 
+/// Maximum number of tokens in [`Amounts`] deserialized via
+/// [`DeserializeAs`], e.g. in intents, so that untrusted payloads
+/// can't make the contract process arbitrarily large maps
+pub const MAX_TOKENS_PER_AMOUNTS: usize = 256;
+
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[near(serializers = [borsh, json])]
 #[autoimpl(Deref using self.0)]
@@ -139,6 +147,41 @@ where
     }
 }
 
+impl<T> Amounts<T>
+where
+    T: DefaultMap<V = u128>,
+{
+    /// Subtracts at most `amount` of `k`, saturating at zero.
+    /// Returns the amount actually subtracted, which is less than
+    /// `amount` if the stored one is insufficient.
+    #[inline]
+    pub fn sub_saturating(&mut self, k: T::K, amount: u128) -> u128 {
+        let mut a = self.0.entry_or_default(k);
+        let subtracted = amount.min(*a);
+        *a -= subtracted;
+        subtracted
+    }
+}
+
+impl Amounts {
+    /// Returns signed deltas `other[t] - self[t]` over the union of tokens
+    /// in both sets, omitting tokens with zero delta.
+    /// Returns `None` on overflow.
+    #[must_use]
+    pub fn delta(&self, other: &Self) -> Option<Amounts<BTreeMap<TokenId, i128>>> {
+        Amounts::<BTreeMap<_, i128>>::default()
+            .with_add_many(
+                other
+                    .iter()
+                    .map(|(token_id, amount)| (token_id.clone(), *amount)),
+            )?
+            .with_sub_many(
+                self.iter()
+                    .map(|(token_id, amount)| (token_id.clone(), *amount)),
+            )
+    }
+}
+
 #[allow(clippy::iter_without_into_iter)]
 impl<T> Amounts<T>
 where
@@ -192,6 +235,24 @@ where
     }
 }
 
+/// Sums amounts for duplicate keys, same as [`Amounts::add`].
+///
+/// # Panics
+///
+/// On overflow
+impl<T> FromIterator<(T::K, u128)> for Amounts<T>
+where
+    T: DefaultMap + Default,
+    T::V: Copy + CheckedAdd<u128>,
+{
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = (T::K, u128)>>(iter: I) -> Self {
+        Self::default()
+            .with_add_many(iter)
+            .unwrap_or_else(|| panic!("amount overflow"))
+    }
+}
+
 impl<T> From<Amounts<T>> for Cow<'_, Amounts<T>>
 where
     T: Clone,
@@ -214,16 +275,68 @@ where
     }
 }
 
-impl<'de, T, As> DeserializeAs<'de, Amounts<T>> for Amounts<As>
+impl<'de, K, V, KAs, VAs> DeserializeAs<'de, Amounts<BTreeMap<K, V>>>
+    for Amounts<BTreeMap<KAs, VAs>>
 where
-    As: DeserializeAs<'de, T>,
+    K: Ord,
+    KAs: DeserializeAs<'de, K>,
+    VAs: DeserializeAs<'de, V>,
 {
     #[inline]
-    fn deserialize_as<D>(deserializer: D) -> Result<Amounts<T>, D::Error>
+    fn deserialize_as<D>(deserializer: D) -> Result<Amounts<BTreeMap<K, V>>, D::Error>
     where
         D: Deserializer<'de>,
     {
-        As::deserialize_as(deserializer).map(Amounts)
+        deserializer
+            .deserialize_map(BoundedVisitor::<K, V, KAs, VAs>(PhantomData))
+            .map(Amounts)
+    }
+}
+
+/// Deserializes a map of at most [`MAX_TOKENS_PER_AMOUNTS`] entries,
+/// failing as soon as the limit is exceeded rather than after the
+/// whole map was read
+struct BoundedVisitor<K, V, KAs, VAs>(PhantomData<fn() -> (K, V, KAs, VAs)>);
+
+impl<'de, K, V, KAs, VAs> Visitor<'de> for BoundedVisitor<K, V, KAs, VAs>
+where
+    K: Ord,
+    KAs: DeserializeAs<'de, K>,
+    VAs: DeserializeAs<'de, V>,
+{
+    type Value = BTreeMap<K, V>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "a map of at most {MAX_TOKENS_PER_AMOUNTS} tokens"
+        )
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        // size hint comes from untrusted input, so it's only used to
+        // reject oversized maps early
+        if let Some(len) = map.size_hint().filter(|&len| len > MAX_TOKENS_PER_AMOUNTS) {
+            return Err(de::Error::custom(format_args!(
+                "too many tokens: {len}, max: {MAX_TOKENS_PER_AMOUNTS}",
+            )));
+        }
+
+        let mut amounts = BTreeMap::new();
+        while let Some((token_id, amount)) =
+            map.next_entry::<DeserializeAsWrap<K, KAs>, DeserializeAsWrap<V, VAs>>()?
+        {
+            if amounts.len() >= MAX_TOKENS_PER_AMOUNTS {
+                return Err(de::Error::custom(format_args!(
+                    "too many tokens, max: {MAX_TOKENS_PER_AMOUNTS}",
+                )));
+            }
+            amounts.insert(token_id.into_inner(), amount.into_inner());
+        }
+        Ok(amounts)
     }
 }
 
@@ -258,11 +371,65 @@ mod abi {
 
 #[cfg(test)]
 mod tests {
+    use near_sdk::serde_json::{self, json};
+    use rstest::rstest;
 
-    use crate::token_id::nep141::Nep141TokenId;
+    use crate::{intents::tokens::Transfer, token_id::nep141::Nep141TokenId};
 
     use super::*;
 
+    #[rstest]
+    #[test]
+    fn max_tokens(
+        #[values(1, MAX_TOKENS_PER_AMOUNTS, MAX_TOKENS_PER_AMOUNTS + 1)] num_tokens: usize,
+    ) {
+        let tokens: BTreeMap<_, _> = (0..num_tokens)
+            .map(|i| {
+                (
+                    TokenId::from(Nep141TokenId::new(format!("ft{i}.near").parse().unwrap()))
+                        .to_string(),
+                    "1",
+                )
+            })
+            .collect();
+
+        let result = serde_json::from_value::<Transfer>(json!({
+            "receiver_id": "bob.near",
+            "tokens": tokens,
+        }));
+
+        if num_tokens > MAX_TOKENS_PER_AMOUNTS {
+            assert!(result.unwrap_err().to_string().contains("too many tokens"));
+        } else {
+            assert_eq!(result.unwrap().tokens.len(), num_tokens);
+        }
+    }
+
+    #[test]
+    fn max_tokens_fails_early() {
+        let mut tokens: BTreeMap<_, _> = (0..=MAX_TOKENS_PER_AMOUNTS)
+            .map(|i| {
+                (
+                    TokenId::from(Nep141TokenId::new(format!("ft{i}.near").parse().unwrap()))
+                        .to_string(),
+                    "1",
+                )
+            })
+            .collect();
+        // entries after the limit are not even parsed
+        tokens.insert("nep141:zz.near".to_string(), "invalid");
+
+        assert!(
+            serde_json::from_value::<Transfer>(json!({
+                "receiver_id": "bob.near",
+                "tokens": tokens,
+            }))
+            .unwrap_err()
+            .to_string()
+            .contains("too many tokens")
+        );
+    }
+
     #[test]
     fn invariant() {
         let [t1, t2] =
@@ -311,4 +478,84 @@ mod tests {
                 .is_empty()
         );
     }
+
+    #[test]
+    fn sub_saturating() {
+        let [t1, t2] =
+            ["t1.near", "t2.near"].map(|t| TokenId::Nep141(Nep141TokenId::new(t.parse().unwrap())));
+
+        let mut amounts = Amounts::new([(t1.clone(), 10)].into_iter().collect::<BTreeMap<_, _>>());
+
+        // sufficient
+        assert_eq!(amounts.sub_saturating(t1.clone(), 3), 3);
+        assert_eq!(amounts.amount_for(&t1), 7);
+
+        // saturates and cleans up
+        assert_eq!(amounts.sub_saturating(t1.clone(), 10), 7);
+        assert_eq!(amounts.amount_for(&t1), 0);
+        assert!(amounts.is_empty());
+
+        // missing
+        assert_eq!(amounts.sub_saturating(t2, u128::MAX), 0);
+        assert!(amounts.is_empty());
+    }
+
+    #[test]
+    fn from_iter() {
+        let [t1, t2] =
+            ["t1.near", "t2.near"].map(|t| TokenId::Nep141(Nep141TokenId::new(t.parse().unwrap())));
+
+        let amounts: Amounts = [(t1.clone(), 1), (t2.clone(), 2), (t1.clone(), 3)]
+            .into_iter()
+            .collect();
+        assert_eq!(amounts, Amounts::new([(t1, 4), (t2, 2)].into()));
+
+        assert_eq!(
+            amounts.into_iter().map(|(_, amount)| amount).sum::<u128>(),
+            6
+        );
+
+        assert!(std::iter::empty().collect::<Amounts>().is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "amount overflow")]
+    fn from_iter_overflow() {
+        let t1 = TokenId::Nep141(Nep141TokenId::new("t1.near".parse().unwrap()));
+
+        let _: Amounts = [(t1.clone(), u128::MAX), (t1, 1)].into_iter().collect();
+    }
+
+    #[test]
+    fn delta() {
+        let [t1, t2, t3, t4] = ["t1.near", "t2.near", "t3.near", "t4.near"]
+            .map(|t| TokenId::Nep141(Nep141TokenId::new(t.parse().unwrap())));
+
+        let before = Amounts::new(
+            [(t1.clone(), 10), (t2.clone(), 5), (t4.clone(), 7)]
+                .into_iter()
+                .collect(),
+        );
+        let after = Amounts::new(
+            [(t2.clone(), 8), (t3.clone(), 3), (t4.clone(), 7)]
+                .into_iter()
+                .collect(),
+        );
+
+        assert_eq!(
+            before.delta(&after).unwrap(),
+            Amounts::new([(t1.clone(), -10), (t2.clone(), 3), (t3.clone(), 3)].into())
+        );
+        assert_eq!(
+            after.delta(&before).unwrap(),
+            Amounts::new([(t1, 10), (t2, -3), (t3, -3)].into())
+        );
+        assert!(before.delta(&before).unwrap().is_empty());
+
+        assert!(
+            Amounts::default()
+                .delta(&Amounts::new([(t4, u128::MAX)].into()))
+                .is_none()
+        );
+    }
 }
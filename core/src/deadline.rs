@@ -4,7 +4,7 @@ use core::{
 };
 use std::io;
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, TimeDelta, Utc};
 use defuse_borsh_utils::adapters::{BorshDeserializeAs, BorshSerializeAs, TimestampNanoSeconds};
 use near_sdk::near;
 
@@ -44,6 +44,17 @@ impl Deadline {
         Self::now() > self
     }
 
+    /// Same as [`has_expired`](Self::has_expired), but tolerates clock skew
+    /// by treating the deadline as still valid for `grace` after it passes
+    #[must_use]
+    #[inline]
+    pub fn has_expired_with_grace(self, grace: Duration) -> bool {
+        TimeDelta::from_std(grace)
+            .ok()
+            .and_then(|grace| self.0.checked_add_signed(grace))
+            .is_some_and(|deadline| Self::now().0 > deadline)
+    }
+
     #[must_use]
     #[inline]
     pub const fn into_timestamp(self) -> DateTime<Utc> {
@@ -84,3 +95,25 @@ impl BorshDeserializeAs<Deadline> for TimestampNanoSeconds {
         Self::deserialize_as(reader).map(Deadline)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::no_grace(Duration::ZERO, true)]
+    #[case::grace_too_short(Duration::from_secs(30), true)]
+    #[case::within_grace(Duration::from_secs(3600), false)]
+    fn has_expired_with_grace(#[case] grace: Duration, #[case] expired: bool) {
+        let deadline = Deadline::new(Utc::now() - Duration::from_secs(60));
+        assert!(deadline.has_expired());
+        assert_eq!(deadline.has_expired_with_grace(grace), expired);
+    }
+
+    #[test]
+    fn max_with_grace_does_not_overflow() {
+        assert!(!Deadline::MAX.has_expired_with_grace(Duration::MAX));
+    }
+}
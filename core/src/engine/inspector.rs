@@ -1,6 +1,7 @@
-use crate::{Deadline, Nonce, events::DefuseEvent};
+use crate::{Deadline, Nonce, events::DefuseEvent, token_id::TokenId};
+use defuse_crypto::PublicKey;
 use impl_tools::autoimpl;
-use near_sdk::{AccountIdRef, CryptoHash};
+use near_sdk::{AccountId, AccountIdRef, CryptoHash, Gas};
 
 #[autoimpl(for <T: trait + ?Sized> &mut T, Box<T>)]
 pub trait Inspector {
@@ -9,4 +10,58 @@ pub trait Inspector {
     fn on_event(&mut self, event: DefuseEvent<'_>);
 
     fn on_intent_executed(&mut self, signer_id: &AccountIdRef, hash: CryptoHash, nonce: Nonce);
+
+    /// Called for each signed payload once its signature was verified
+    /// and `public_key` was found among keys of `signer_id`
+    #[inline]
+    fn on_public_key_verified(&mut self, _signer_id: &AccountIdRef, _public_key: &PublicKey) {}
+
+    /// Called with minimum required gas for each Function Call Promise
+    /// created during execution of intents
+    #[inline]
+    fn on_promise_gas(&mut self, _gas: Gas) {}
+
+    /// Whether balance changes should be collected at all. If `false`,
+    /// [`.on_balance_change()`](Self::on_balance_change) is never called
+    #[inline]
+    fn records_balance_changes(&self) -> bool {
+        false
+    }
+
+    /// Called for each change of internal balance, including fees, in
+    /// the order they were made. Changes made by intents of a signed
+    /// payload are reported once all of them were executed.
+    /// Changes which don't fit into `i128` are saturated
+    #[inline]
+    fn on_balance_change(&mut self, _account_id: &AccountIdRef, _token_id: &TokenId, _delta: i128) {
+    }
+}
+
+/// [`Inspector`] which records all balance changes, e.g. to explain
+/// to users how their balances were affected by simulated intents
+#[derive(Debug, Default, Clone)]
+pub struct RecordingInspector {
+    pub balance_changes: Vec<(AccountId, TokenId, i128)>,
+}
+
+impl Inspector for RecordingInspector {
+    #[inline]
+    fn on_deadline(&mut self, _deadline: Deadline) {}
+
+    #[inline]
+    fn on_event(&mut self, _event: DefuseEvent<'_>) {}
+
+    #[inline]
+    fn on_intent_executed(&mut self, _signer_id: &AccountIdRef, _hash: CryptoHash, _nonce: Nonce) {}
+
+    #[inline]
+    fn records_balance_changes(&self) -> bool {
+        true
+    }
+
+    #[inline]
+    fn on_balance_change(&mut self, account_id: &AccountIdRef, token_id: &TokenId, delta: i128) {
+        self.balance_changes
+            .push((account_id.to_owned(), token_id.clone(), delta));
+    }
 }
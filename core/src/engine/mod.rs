@@ -3,12 +3,13 @@ mod state;
 
 pub use self::{inspector::*, state::*};
 
-use defuse_crypto::{Payload, SignedPayload};
+use defuse_crypto::Payload;
+use near_sdk::AccountIdRef;
 
 use crate::{
-    DefuseError, ExpirableNonce, Result,
+    DefuseError, ExpirableNonce, Nonce, Result,
     intents::{DefuseIntents, ExecutableIntent},
-    payload::{DefusePayload, ExtractDefusePayload, multi::MultiPayload},
+    payload::{DefusePayload, multi::MultiPayload},
 };
 
 use self::deltas::{Deltas, Transfers};
@@ -26,7 +27,7 @@ where
     #[inline]
     pub fn new(state: S, inspector: I) -> Self {
         Self {
-            state: Deltas::new(state),
+            state: Deltas::new(state).with_balance_changes(inspector.records_balance_changes()),
             inspector,
         }
     }
@@ -41,26 +42,64 @@ where
         self.finalize()
     }
 
-    fn execute_signed_intent(&mut self, signed: MultiPayload) -> Result<()> {
-        // verify signed payload and get public key
-        let public_key = signed.verify().ok_or(DefuseError::InvalidSignature)?;
+    /// Executes intents on behalf of `signer_id` without any signature.
+    ///
+    /// NOTE: the caller MUST make sure that `signer_id` was already
+    /// authenticated, i.e. it equals to `PREDECESSOR_ID`.
+    ///
+    /// Since there is no signed payload, intents are hashed themselves
+    /// and no nonce is committed, so [`Inspector::on_intent_executed`]
+    /// is reported with zero nonce.
+    pub fn execute_intents_by_predecessor(
+        mut self,
+        signer_id: &AccountIdRef,
+        intents: DefuseIntents,
+    ) -> Result<Transfers> {
+        if !self.state.is_auth_by_predecessor_id_enabled(signer_id) {
+            return Err(DefuseError::AuthByPredecessorIdDisabled(
+                signer_id.to_owned(),
+            ));
+        }
+
+        if self.state.is_account_locked(signer_id) {
+            return Err(DefuseError::AccountLocked(
+                signer_id.to_owned(),
+                self.state.account_lock_reason(signer_id),
+            ));
+        }
+
+        // there is no signed payload, so hash intents themselves
+        let hash = intents.hash()?;
+
+        intents.execute_intent(signer_id, &mut self, hash)?;
+        self.report_balance_changes();
+        self.inspector
+            .on_intent_executed(signer_id, hash, Nonce::default());
+
+        self.finalize()
+    }
 
+    fn execute_signed_intent(&mut self, signed: MultiPayload) -> Result<()> {
         // calculate intent hash
         let hash = signed.hash();
 
-        // extract NEP-413 payload
-        let DefusePayload::<DefuseIntents> {
+        // verify signed payload and extract it along with public key
+        let (public_key, payload) = signed.verify_and_extract::<DefuseIntents>()?;
+
+        // check recipient and make sure message is still valid
+        payload.validate_with_grace(
+            &self.state.verifying_contract(),
+            self.state.deadline_grace(),
+        )?;
+        payload.validate_network(self.state.network_id().as_deref())?;
+
+        let DefusePayload {
             signer_id,
-            verifying_contract,
             deadline,
             nonce,
             message: intents,
-        } = signed.extract_defuse_payload()?;
-
-        // check recipient
-        if verifying_contract != *self.state.verifying_contract() {
-            return Err(DefuseError::WrongVerifyingContract);
-        }
+            ..
+        } = payload;
 
         self.inspector.on_deadline(deadline);
 
@@ -68,25 +107,30 @@ where
             return Err(DefuseError::DeadlineGreaterThanNonce);
         }
 
-        // make sure message is still valid
-        if deadline.has_expired() {
-            return Err(DefuseError::DeadlineExpired);
-        }
-
         // make sure the account has this public key
         if !self.state.has_public_key(&signer_id, &public_key) {
             return Err(DefuseError::PublicKeyNotExist(signer_id, public_key));
         }
+        self.inspector
+            .on_public_key_verified(&signer_id, &public_key);
 
         // commit nonce
         self.state.commit_nonce(signer_id.clone(), nonce)?;
 
         intents.execute_intent(&signer_id, self, hash)?;
+        self.report_balance_changes();
         self.inspector.on_intent_executed(&signer_id, hash, nonce);
 
         Ok(())
     }
 
+    fn report_balance_changes(&mut self) {
+        for (account_id, token_id, delta) in self.state.take_balance_changes() {
+            self.inspector
+                .on_balance_change(&account_id, &token_id, delta);
+        }
+    }
+
     #[inline]
     fn finalize(self) -> Result<Transfers> {
         self.state
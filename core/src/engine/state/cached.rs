@@ -1,20 +1,25 @@
 use crate::{
     DefuseError, Nonce, Nonces, Result,
     amounts::Amounts,
-    fees::Pips,
+    fees::{FeeLimits, Pips},
     intents::{
+        allowance::Allowance,
         auth::AuthCall,
-        tokens::{FtWithdraw, MtWithdraw, NativeWithdraw, NftWithdraw, StorageDeposit},
+        token_diff::TokenDeltas,
+        tokens::{FtWithdraw, MtWithdraw, NativeWithdraw, NftWithdraw, StorageDeposit, UnwrapNear},
     },
-    token_id::{TokenId, nep141::Nep141TokenId, nep171::Nep171TokenId, nep245::Nep245TokenId},
+    token_id::TokenId,
 };
 use defuse_bitmap::{U248, U256};
 use defuse_crypto::PublicKey;
 use defuse_near_utils::Lock;
-use near_sdk::{AccountId, AccountIdRef};
+use defuse_serde_utils::base64::Base64;
+use near_sdk::{AccountId, AccountIdRef, near};
+use serde_with::{DisplayFromStr, serde_as};
 use std::{
     borrow::Cow,
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    time::Duration,
 };
 
 use super::{State, StateView};
@@ -23,6 +28,8 @@ use super::{State, StateView};
 pub struct CachedState<W: StateView> {
     view: W,
     accounts: CachedAccounts,
+    /// Tokens leaving the contract, see [`.assert_invariants()`](Self::assert_invariants)
+    withdrawn: Amounts,
 }
 
 impl<W> CachedState<W>
@@ -34,8 +41,100 @@ where
         Self {
             view,
             accounts: CachedAccounts::new(),
+            withdrawn: Amounts::default(),
         }
     }
+
+    /// Panics if total balance of any token across all accounts has
+    /// changed other than by withdrawals, i.e. tokens were minted or burnt.
+    ///
+    /// NOTE: transfers net to zero only after all intents were executed
+    /// and matched, so this should be called after
+    /// [`Engine`](crate::engine::Engine) has successfully finished.
+    pub fn assert_invariants(&self) {
+        let mut totals: HashMap<&TokenId, (u128, u128)> = HashMap::new();
+        for (account_id, account) in &self.accounts.0 {
+            for (token_id, &after) in &account.as_inner_unchecked().token_amounts {
+                let (total_before, total_after) = totals.entry(token_id).or_default();
+                *total_before = total_before
+                    .checked_add(self.view.balance_of(account_id, token_id))
+                    .unwrap_or_else(|| panic!("total balance of {token_id} overflowed"));
+                *total_after = total_after
+                    .checked_add(after)
+                    .unwrap_or_else(|| panic!("total balance of {token_id} overflowed"));
+            }
+        }
+
+        for (token_id, (before, after)) in totals {
+            let withdrawn = self.withdrawn.amount_for(token_id);
+            assert!(
+                before.checked_sub(withdrawn) == Some(after),
+                "total balance of {token_id} is not conserved: \
+                 {before} before, {after} after, {withdrawn} withdrawn",
+            );
+        }
+    }
+
+    /// Subtracts balances of tokens leaving the contract
+    fn internal_withdraw(
+        &mut self,
+        owner_id: &AccountIdRef,
+        token_amounts: impl IntoIterator<Item = (TokenId, u128)>,
+    ) -> Result<()> {
+        for (token_id, amount) in token_amounts {
+            self.internal_sub_balance(owner_id, [(token_id.clone(), amount)])?;
+            self.withdrawn
+                .add(token_id, amount)
+                .ok_or(DefuseError::BalanceOverflow)?;
+        }
+        Ok(())
+    }
+
+    /// Returns net changes made on top of the underlying view,
+    /// so that they can be applied to durable storage one by one.
+    pub fn into_deltas(self) -> Result<StateDeltas> {
+        let mut deltas = StateDeltas::default();
+        for (account_id, account) in self.accounts.0 {
+            let account = account.into_inner_unchecked();
+
+            let mut token_deltas = TokenDeltas::default();
+            for (token_id, amount) in account.token_amounts {
+                let before = self.view.balance_of(&account_id, &token_id);
+                let delta = if amount >= before {
+                    i128::try_from(amount - before).ok()
+                } else {
+                    i128::try_from(before - amount)
+                        .ok()
+                        .and_then(i128::checked_neg)
+                }
+                .ok_or(DefuseError::BalanceOverflow)?;
+                token_deltas = token_deltas
+                    .with_apply_delta(token_id, delta)
+                    .ok_or(DefuseError::BalanceOverflow)?;
+            }
+
+            let mut allowances: BTreeMap<_, BTreeMap<_, _>> = BTreeMap::new();
+            for ((delegate_id, token_id), amount) in account.allowances {
+                allowances
+                    .entry(delegate_id)
+                    .or_default()
+                    .insert(token_id, amount);
+            }
+
+            let account_deltas = AccountDeltas {
+                token_deltas,
+                public_keys_added: account.public_keys_added.into_iter().collect(),
+                public_keys_removed: account.public_keys_removed.into_iter().collect(),
+                nonces_committed: account.nonces.iter_used().collect(),
+                auth_by_predecessor_id_toggled: account.auth_by_predecessor_id_toggled,
+                allowances,
+            };
+            if !account_deltas.is_empty() {
+                deltas.accounts.insert(account_id, account_deltas);
+            }
+        }
+        Ok(deltas)
+    }
 }
 
 impl<W> StateView for CachedState<W>
@@ -62,6 +161,36 @@ where
         self.view.fee_collector()
     }
 
+    #[inline]
+    fn referral_fee_share(&self) -> Pips {
+        self.view.referral_fee_share()
+    }
+
+    #[inline]
+    fn fee_limits(&self, token_id: &TokenId) -> FeeLimits {
+        self.view.fee_limits(token_id)
+    }
+
+    #[inline]
+    fn verbose_events(&self) -> bool {
+        self.view.verbose_events()
+    }
+
+    #[inline]
+    fn max_public_keys(&self) -> Option<u32> {
+        self.view.max_public_keys()
+    }
+
+    #[inline]
+    fn deadline_grace(&self) -> Duration {
+        self.view.deadline_grace()
+    }
+
+    #[inline]
+    fn network_id(&self) -> Option<String> {
+        self.view.network_id()
+    }
+
     fn has_public_key(&self, account_id: &AccountIdRef, public_key: &PublicKey) -> bool {
         if let Some(account) = self.accounts.get(account_id).map(Lock::as_inner_unchecked) {
             if account.public_keys_added.contains(public_key) {
@@ -104,12 +233,56 @@ where
             .unwrap_or_else(|| self.view.balance_of(account_id, token_id))
     }
 
+    fn allowance(
+        &self,
+        owner_id: &AccountIdRef,
+        delegate_id: &AccountIdRef,
+        token_id: &TokenId,
+    ) -> u128 {
+        self.accounts
+            .get(owner_id)
+            .map(Lock::as_inner_unchecked)
+            .and_then(|account| {
+                account
+                    .allowances
+                    .get(&(delegate_id.to_owned(), token_id.clone()))
+                    .copied()
+            })
+            .unwrap_or_else(|| self.view.allowance(owner_id, delegate_id, token_id))
+    }
+
+    fn allowances_count(&self, owner_id: &AccountIdRef) -> u32 {
+        let count = self.view.allowances_count(owner_id);
+        let Some(account) = self.accounts.get(owner_id).map(Lock::as_inner_unchecked) else {
+            return count;
+        };
+        account
+            .allowances
+            .iter()
+            .fold(count, |count, ((delegate_id, token_id), &amount)| {
+                match (
+                    self.view.allowance(owner_id, delegate_id, token_id) != 0,
+                    amount != 0,
+                ) {
+                    (false, true) => count.saturating_add(1),
+                    (true, false) => count.saturating_sub(1),
+                    _ => count,
+                }
+            })
+    }
+
     fn is_account_locked(&self, account_id: &AccountIdRef) -> bool {
         self.accounts
             .get(account_id)
             .map_or_else(|| self.view.is_account_locked(account_id), Lock::is_locked)
     }
 
+    #[inline]
+    fn account_lock_reason(&self, account_id: &AccountIdRef) -> Option<String> {
+        // accounts can't be locked or unlocked by intents
+        self.view.account_lock_reason(account_id)
+    }
+
     fn is_auth_by_predecessor_id_enabled(&self, account_id: &AccountIdRef) -> bool {
         let was_enabled = self.view.is_auth_by_predecessor_id_enabled(account_id);
         let toggled = self
@@ -127,13 +300,31 @@ where
 {
     fn add_public_key(&mut self, account_id: AccountId, public_key: PublicKey) -> Result<()> {
         let had = self.view.has_public_key(&account_id, &public_key);
+        // implicit public key doesn't count against the limit
+        let exceeded = self.max_public_keys().filter(|&max| {
+            account_id != public_key.to_implicit_account_id()
+                && !self.has_public_key(&account_id, &public_key)
+                && self
+                    .iter_public_keys(&account_id)
+                    .filter(|pk| account_id != pk.to_implicit_account_id())
+                    .count()
+                    >= usize::try_from(max).unwrap_or(usize::MAX)
+        });
         let account = self
             .accounts
             .get_or_create(account_id.clone(), |account_id| {
                 self.view.is_account_locked(account_id)
             })
             .get_mut()
-            .ok_or_else(|| DefuseError::AccountLocked(account_id.clone()))?;
+            .ok_or_else(|| {
+                DefuseError::AccountLocked(
+                    account_id.clone(),
+                    self.view.account_lock_reason(&account_id),
+                )
+            })?;
+        if let Some(max) = exceeded {
+            return Err(DefuseError::TooManyPublicKeys(account_id, max));
+        }
         let added = if had {
             account.public_keys_removed.remove(&public_key)
         } else {
@@ -153,7 +344,12 @@ where
                 self.view.is_account_locked(account_id)
             })
             .get_mut()
-            .ok_or_else(|| DefuseError::AccountLocked(account_id.clone()))?;
+            .ok_or_else(|| {
+                DefuseError::AccountLocked(
+                    account_id.clone(),
+                    self.view.account_lock_reason(&account_id),
+                )
+            })?;
         let removed = if had {
             account.public_keys_removed.insert(public_key)
         } else {
@@ -171,8 +367,15 @@ where
                 self.view.is_account_locked(account_id)
             })
             .get_mut()
-            .ok_or(DefuseError::AccountLocked(account_id))?
-            .commit_nonce(nonce)
+            .ok_or_else(|| {
+                DefuseError::AccountLocked(
+                    account_id.clone(),
+                    self.view.account_lock_reason(&account_id),
+                )
+            })?
+            .commit_nonce(nonce)?
+            .then_some(())
+            .ok_or(DefuseError::NonceUsed { account_id, nonce })
     }
 
     fn cleanup_expired_nonces(
@@ -205,15 +408,12 @@ where
             })
             .as_inner_unchecked_mut();
         for (token_id, amount) in token_amounts {
-            if account.token_amounts.get(&token_id).is_none() {
-                account
-                    .token_amounts
-                    .add(token_id.clone(), self.view.balance_of(&owner_id, &token_id))
-                    .ok_or(DefuseError::BalanceOverflow)?;
-            }
-            account
+            let balance = account
                 .token_amounts
-                .add(token_id, amount)
+                .entry(token_id)
+                .or_insert_with_key(|token_id| self.view.balance_of(&owner_id, token_id));
+            *balance = balance
+                .checked_add(amount)
                 .ok_or(DefuseError::BalanceOverflow)?;
         }
         Ok(())
@@ -230,90 +430,85 @@ where
                 self.view.is_account_locked(owner_id)
             })
             .get_mut()
-            .ok_or_else(|| DefuseError::AccountLocked(owner_id.to_owned()))?;
+            .ok_or_else(|| {
+                DefuseError::AccountLocked(
+                    owner_id.to_owned(),
+                    self.view.account_lock_reason(owner_id),
+                )
+            })?;
         for (token_id, amount) in token_amounts {
             if amount == 0 {
                 return Err(DefuseError::InvalidIntent);
             }
 
-            if account.token_amounts.get(&token_id).is_none() {
-                account
-                    .token_amounts
-                    .add(token_id.clone(), self.view.balance_of(owner_id, &token_id))
-                    .ok_or(DefuseError::BalanceOverflow)?;
-            }
-            account
+            let balance = account
                 .token_amounts
-                .sub(token_id, amount)
+                .entry(token_id)
+                .or_insert_with_key(|token_id| self.view.balance_of(owner_id, token_id));
+            *balance = balance
+                .checked_sub(amount)
                 .ok_or(DefuseError::BalanceOverflow)?;
         }
         Ok(())
     }
 
+    fn set_allowance(
+        &mut self,
+        owner_id: AccountId,
+        delegate_id: AccountId,
+        token_id: TokenId,
+        amount: u128,
+    ) -> Result<()> {
+        let exceeded = (amount != 0
+            && self.allowance(&owner_id, &delegate_id, &token_id) == 0
+            && self.allowances_count(&owner_id) >= Allowance::MAX_PER_ACCOUNT)
+            .then_some(Allowance::MAX_PER_ACCOUNT);
+        let account = self
+            .accounts
+            .get_or_create(owner_id.clone(), |owner_id| {
+                self.view.is_account_locked(owner_id)
+            })
+            .get_mut()
+            .ok_or_else(|| {
+                let reason = self.view.account_lock_reason(&owner_id);
+                DefuseError::AccountLocked(owner_id.clone(), reason)
+            })?;
+        if let Some(max) = exceeded {
+            return Err(DefuseError::TooManyAllowances(owner_id, max));
+        }
+        account.allowances.insert((delegate_id, token_id), amount);
+        Ok(())
+    }
+
     fn ft_withdraw(&mut self, owner_id: &AccountIdRef, withdraw: FtWithdraw) -> Result<()> {
-        self.internal_sub_balance(
-            owner_id,
-            std::iter::once((
-                Nep141TokenId::new(withdraw.token.clone()).into(),
-                withdraw.amount.0,
-            ))
-            .chain(withdraw.storage_deposit.map(|amount| {
-                (
-                    Nep141TokenId::new(self.wnear_id().into_owned()).into(),
-                    amount.as_yoctonear(),
-                )
-            })),
-        )
+        let tokens = withdraw.withdrawn_tokens(&self.wnear_id());
+        self.internal_withdraw(owner_id, tokens)
     }
 
     fn nft_withdraw(&mut self, owner_id: &AccountIdRef, withdraw: NftWithdraw) -> Result<()> {
-        self.internal_sub_balance(
-            owner_id,
-            std::iter::once((
-                Nep171TokenId::new(withdraw.token.clone(), withdraw.token_id.clone())?.into(),
-                1,
-            ))
-            .chain(withdraw.storage_deposit.map(|amount| {
-                (
-                    Nep141TokenId::new(self.wnear_id().into_owned()).into(),
-                    amount.as_yoctonear(),
-                )
-            })),
-        )
+        let tokens = withdraw.withdrawn_tokens(&self.wnear_id())?;
+        self.internal_withdraw(owner_id, tokens)
     }
 
     fn mt_withdraw(&mut self, owner_id: &AccountIdRef, withdraw: MtWithdraw) -> Result<()> {
-        if withdraw.token_ids.len() != withdraw.amounts.len() || withdraw.token_ids.is_empty() {
+        if !withdraw.is_valid() {
             return Err(DefuseError::InvalidIntent);
         }
 
-        let token_ids = std::iter::repeat(withdraw.token.clone())
-            .zip(withdraw.token_ids.iter().cloned())
-            .map(|(token, token_id)| Nep245TokenId::new(token, token_id))
-            .collect::<Result<Vec<_>, _>>()?;
-
-        self.internal_sub_balance(
-            owner_id,
-            token_ids
-                .into_iter()
-                .map(Into::into)
-                .zip(withdraw.amounts.iter().map(|a| a.0))
-                .chain(
-                    withdraw
-                        .storage_deposit
-                        .map(|amount| (self.wnear_token_id(), amount.as_yoctonear())),
-                ),
-        )
+        let tokens = withdraw.withdrawn_tokens(&self.wnear_id())?;
+        self.internal_withdraw(owner_id, tokens)
     }
 
     fn native_withdraw(&mut self, owner_id: &AccountIdRef, withdraw: NativeWithdraw) -> Result<()> {
-        self.internal_sub_balance(
-            owner_id,
-            [(
-                Nep141TokenId::new(self.wnear_id().into_owned()).into(),
-                withdraw.amount.as_yoctonear(),
-            )],
-        )
+        let tokens = withdraw.withdrawn_tokens(&self.wnear_id());
+        self.internal_withdraw(owner_id, tokens)
+    }
+
+    fn unwrap_near(&mut self, owner_id: &AccountIdRef, unwrap: UnwrapNear) -> Result<()> {
+        // native balance is credited only after `near_withdraw()` succeeds,
+        // so it's not available within the same call
+        let tokens = unwrap.withdrawn_tokens(&self.wnear_id());
+        self.internal_withdraw(owner_id, tokens)
     }
 
     fn storage_deposit(
@@ -321,13 +516,8 @@ where
         owner_id: &AccountIdRef,
         storage_deposit: StorageDeposit,
     ) -> Result<()> {
-        self.internal_sub_balance(
-            owner_id,
-            [(
-                Nep141TokenId::new(self.wnear_id().into_owned()).into(),
-                storage_deposit.amount.as_yoctonear(),
-            )],
-        )
+        let tokens = storage_deposit.withdrawn_tokens(&self.wnear_id());
+        self.internal_withdraw(owner_id, tokens)
     }
 
     fn set_auth_by_predecessor_id(&mut self, account_id: AccountId, enable: bool) -> Result<bool> {
@@ -339,7 +529,10 @@ where
                     self.view.is_account_locked(owner_id)
                 })
                 .get_mut()
-                .ok_or(DefuseError::AccountLocked(account_id))?
+                .ok_or_else(|| {
+                    let reason = self.view.account_lock_reason(&account_id);
+                    DefuseError::AccountLocked(account_id, reason)
+                })?
                 // toggle
                 .auth_by_predecessor_id_toggled ^= true;
         }
@@ -347,17 +540,57 @@ where
     }
 
     fn auth_call(&mut self, signer_id: &AccountIdRef, auth_call: AuthCall) -> Result<()> {
-        if !auth_call.attached_deposit.is_zero() {
-            self.internal_sub_balance(
-                signer_id,
-                [(
-                    Nep141TokenId::new(self.wnear_id().into_owned()).into(),
-                    auth_call.attached_deposit.as_yoctonear(),
-                )],
-            )?;
+        let tokens = auth_call.withdrawn_tokens(&self.wnear_id());
+        if tokens.is_empty() {
+            return Ok(());
         }
+        self.internal_withdraw(signer_id, tokens)
+    }
+}
 
-        Ok(())
+/// Net changes accumulated by [`CachedState`], see [`CachedState::into_deltas`]
+#[near(serializers = [json])]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StateDeltas {
+    pub accounts: BTreeMap<AccountId, AccountDeltas>,
+}
+
+#[cfg_attr(
+    all(feature = "abi", not(target_arch = "wasm32")),
+    serde_as(schemars = true)
+)]
+#[cfg_attr(
+    not(all(feature = "abi", not(target_arch = "wasm32"))),
+    serde_as(schemars = false)
+)]
+#[near(serializers = [json])]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AccountDeltas {
+    #[serde_as(as = "Amounts<BTreeMap<_, DisplayFromStr>>")]
+    pub token_deltas: TokenDeltas,
+
+    pub public_keys_added: BTreeSet<PublicKey>,
+    pub public_keys_removed: BTreeSet<PublicKey>,
+
+    #[serde_as(as = "BTreeSet<Base64>")]
+    pub nonces_committed: BTreeSet<Nonce>,
+
+    pub auth_by_predecessor_id_toggled: bool,
+
+    /// Allowances set by the account, by delegate and token
+    #[serde_as(as = "BTreeMap<_, BTreeMap<_, DisplayFromStr>>")]
+    pub allowances: BTreeMap<AccountId, BTreeMap<TokenId, u128>>,
+}
+
+impl AccountDeltas {
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.token_deltas.is_empty()
+            && self.public_keys_added.is_empty()
+            && self.public_keys_removed.is_empty()
+            && self.nonces_committed.is_empty()
+            && !self.auth_by_predecessor_id_toggled
+            && self.allowances.is_empty()
     }
 }
 
@@ -402,7 +635,13 @@ pub struct CachedAccount {
     public_keys_added: HashSet<PublicKey>,
     public_keys_removed: HashSet<PublicKey>,
 
-    token_amounts: Amounts<HashMap<TokenId, u128>>,
+    /// Cached balances, including the ones drained to zero, so that
+    /// they don't fall back to the underlying view
+    token_amounts: HashMap<TokenId, u128>,
+
+    /// Allowances by delegate and token, including the ones revoked
+    /// or spent to zero, so that they don't fall back to the underlying view
+    allowances: HashMap<(AccountId, TokenId), u128>,
 }
 
 impl CachedAccount {
@@ -411,8 +650,9 @@ impl CachedAccount {
         self.nonces.is_used(nonce)
     }
 
+    /// Returns `false` if the nonce was already committed before.
     #[inline]
-    pub fn commit_nonce(&mut self, n: U256) -> Result<()> {
+    pub fn commit_nonce(&mut self, n: U256) -> Result<bool> {
         self.nonces.commit(n)
     }
 
@@ -421,3 +661,824 @@ impl CachedAccount {
         self.nonces.clear_expired(n)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use arbitrary::Unstructured;
+    use defuse_test_utils::random::{Rng, random_bytes, rng};
+    use near_sdk::{CryptoHash, NearToken, serde_json};
+    use rstest::rstest;
+
+    use crate::{
+        Deadline,
+        engine::{Engine, Inspector, RecordingInspector},
+        events::DefuseEvent,
+        intents::{
+            DefuseIntents, Intent,
+            allowance::{Allowance, TransferFrom},
+            token_diff::{TokenDeltas, TokenDiff},
+            tokens::{FtWithdraw, MAX_MEMO_LEN, Transfer},
+        },
+        token_id::{nep141::Nep141TokenId, nep171::Nep171TokenId, nep245::Nep245TokenId},
+    };
+
+    use super::*;
+
+    #[derive(Debug, Default)]
+    struct MockView {
+        balances: HashMap<(AccountId, TokenId), u128>,
+        /// Balance of any token not present in `balances`
+        default_balance: u128,
+        allowances: HashMap<(AccountId, AccountId, TokenId), u128>,
+        fee: Pips,
+        fee_limits: HashMap<TokenId, FeeLimits>,
+    }
+
+    struct MockInspector;
+
+    impl Inspector for MockInspector {
+        fn on_deadline(&mut self, _deadline: Deadline) {}
+
+        fn on_event(&mut self, _event: DefuseEvent<'_>) {}
+
+        fn on_intent_executed(
+            &mut self,
+            _signer_id: &AccountIdRef,
+            _hash: CryptoHash,
+            _nonce: Nonce,
+        ) {
+        }
+    }
+
+    impl StateView for MockView {
+        fn verifying_contract(&self) -> Cow<'_, AccountIdRef> {
+            Cow::Borrowed(AccountIdRef::new_or_panic("intents.near"))
+        }
+
+        fn wnear_id(&self) -> Cow<'_, AccountIdRef> {
+            Cow::Borrowed(AccountIdRef::new_or_panic("wrap.near"))
+        }
+
+        fn fee(&self) -> Pips {
+            self.fee
+        }
+
+        fn fee_collector(&self) -> Cow<'_, AccountIdRef> {
+            self.verifying_contract()
+        }
+
+        fn referral_fee_share(&self) -> Pips {
+            Pips::ZERO
+        }
+
+        fn fee_limits(&self, token_id: &TokenId) -> FeeLimits {
+            self.fee_limits.get(token_id).copied().unwrap_or_default()
+        }
+
+        fn verbose_events(&self) -> bool {
+            false
+        }
+
+        fn max_public_keys(&self) -> Option<u32> {
+            None
+        }
+
+        fn deadline_grace(&self) -> Duration {
+            Duration::ZERO
+        }
+
+        fn network_id(&self) -> Option<String> {
+            None
+        }
+
+        fn has_public_key(&self, _account_id: &AccountIdRef, _public_key: &PublicKey) -> bool {
+            false
+        }
+
+        fn iter_public_keys(
+            &self,
+            _account_id: &AccountIdRef,
+        ) -> impl Iterator<Item = PublicKey> + '_ {
+            std::iter::empty()
+        }
+
+        fn is_nonce_used(&self, _account_id: &AccountIdRef, _nonce: Nonce) -> bool {
+            false
+        }
+
+        fn balance_of(&self, account_id: &AccountIdRef, token_id: &TokenId) -> u128 {
+            self.balances
+                .get(&(account_id.to_owned(), token_id.clone()))
+                .copied()
+                .unwrap_or(self.default_balance)
+        }
+
+        fn allowance(
+            &self,
+            owner_id: &AccountIdRef,
+            delegate_id: &AccountIdRef,
+            token_id: &TokenId,
+        ) -> u128 {
+            self.allowances
+                .get(&(
+                    owner_id.to_owned(),
+                    delegate_id.to_owned(),
+                    token_id.clone(),
+                ))
+                .copied()
+                .unwrap_or_default()
+        }
+
+        fn allowances_count(&self, owner_id: &AccountIdRef) -> u32 {
+            self.allowances
+                .iter()
+                .filter(|((owner, _, _), amount)| owner == owner_id && **amount != 0)
+                .count()
+                .try_into()
+                .unwrap()
+        }
+
+        fn is_account_locked(&self, _account_id: &AccountIdRef) -> bool {
+            false
+        }
+
+        fn account_lock_reason(&self, _account_id: &AccountIdRef) -> Option<String> {
+            None
+        }
+
+        fn is_auth_by_predecessor_id_enabled(&self, _account_id: &AccountIdRef) -> bool {
+            true
+        }
+    }
+
+    #[rstest]
+    fn into_deltas(mut rng: impl Rng) {
+        let [alice, bob]: [AccountId; 2] = ["alice.near", "bob.near"].map(|a| a.parse().unwrap());
+        let [ft1, ft2]: [TokenId; 2] =
+            ["ft1.near", "ft2.near"].map(|t| Nep141TokenId::new(t.parse().unwrap()).into());
+        let public_key: PublicKey = "ed25519:5TagutioHgKLh7KZ1VEFBYfgRkPtqnKm9LoMnJMJugxm"
+            .parse()
+            .unwrap();
+        let nonce: Nonce = rng.random();
+
+        let view = MockView {
+            balances: [
+                ((alice.clone(), ft1.clone()), 1000),
+                ((bob.clone(), ft2.clone()), 500),
+            ]
+            .into(),
+            ..Default::default()
+        };
+        let mut state = CachedState::new(&view);
+
+        state
+            .internal_sub_balance(&alice, [(ft1.clone(), 300)])
+            .unwrap();
+        state
+            .internal_add_balance(bob.clone(), [(ft1.clone(), 300)])
+            .unwrap();
+        state
+            .internal_sub_balance(&bob, [(ft2.clone(), 200)])
+            .unwrap();
+        state
+            .internal_add_balance(alice.clone(), [(ft2.clone(), 200)])
+            .unwrap();
+        state.add_public_key(bob.clone(), public_key).unwrap();
+        state.commit_nonce(alice.clone(), nonce).unwrap();
+        state
+            .set_auth_by_predecessor_id(alice.clone(), false)
+            .unwrap();
+
+        let post: HashMap<_, _> = [&alice, &bob]
+            .into_iter()
+            .flat_map(|account_id| [&ft1, &ft2].map(|t| (account_id.clone(), t.clone())))
+            .map(|(account_id, token_id)| {
+                let balance = state.balance_of(&account_id, &token_id);
+                ((account_id, token_id), balance)
+            })
+            .collect();
+
+        let deltas = state.into_deltas().unwrap();
+
+        // applying deltas on top of the view reconstructs the post-state
+        for ((account_id, token_id), balance) in post {
+            let delta = deltas
+                .accounts
+                .get(&account_id)
+                .map_or(0, |a| a.token_deltas.amount_for(&token_id));
+            assert_eq!(
+                view.balance_of(&account_id, &token_id)
+                    .checked_add_signed(delta),
+                Some(balance),
+            );
+        }
+
+        let alice_deltas = &deltas.accounts[&alice];
+        assert_eq!(alice_deltas.nonces_committed, [nonce].into());
+        assert!(alice_deltas.auth_by_predecessor_id_toggled);
+        assert!(alice_deltas.public_keys_added.is_empty());
+
+        let bob_deltas = &deltas.accounts[&bob];
+        assert_eq!(bob_deltas.public_keys_added, [public_key].into());
+        assert!(bob_deltas.nonces_committed.is_empty());
+        assert!(!bob_deltas.auth_by_predecessor_id_toggled);
+
+        // serializable
+        assert_eq!(
+            serde_json::from_value::<StateDeltas>(serde_json::to_value(&deltas).unwrap()).unwrap(),
+            deltas
+        );
+    }
+
+    #[test]
+    fn into_deltas_large_balances() {
+        let [alice, bob]: [AccountId; 2] = ["alice.near", "bob.near"].map(|a| a.parse().unwrap());
+        let ft1: TokenId = Nep141TokenId::new("ft1.near".parse().unwrap()).into();
+
+        let view = MockView {
+            balances: [((alice.clone(), ft1.clone()), u128::MAX)].into(),
+            ..Default::default()
+        };
+
+        let mut state = CachedState::new(&view);
+        state
+            .internal_sub_balance(&alice, [(ft1.clone(), 1)])
+            .unwrap();
+        let deltas = state.into_deltas().unwrap();
+        assert_eq!(deltas.accounts[&alice].token_deltas.amount_for(&ft1), -1);
+
+        // delta itself doesn't fit into i128
+        let mut state = CachedState::new(&view);
+        state
+            .internal_add_balance(bob, [(ft1.clone(), u128::MAX)])
+            .unwrap();
+        assert!(matches!(
+            state.into_deltas(),
+            Err(DefuseError::BalanceOverflow)
+        ));
+    }
+
+    /// Tokens leaving the contract as a result of successful execution
+    /// of given intents
+    fn withdrawn(intents: &[Intent], wnear: &TokenId) -> TokenDeltas {
+        let mut withdrawn = TokenDeltas::default();
+        let mut withdraw = |token_id: TokenId, amount: u128| {
+            withdrawn.sub(token_id, amount).unwrap();
+        };
+        for intent in intents {
+            match intent {
+                Intent::FtWithdraw(w) => {
+                    withdraw(Nep141TokenId::new(w.token.clone()).into(), w.amount.0);
+                    if let Some(deposit) = w.storage_deposit {
+                        withdraw(wnear.clone(), deposit.as_yoctonear());
+                    }
+                }
+                Intent::NftWithdraw(w) => {
+                    withdraw(
+                        Nep171TokenId::new(w.token.clone(), w.token_id.clone())
+                            .unwrap()
+                            .into(),
+                        1,
+                    );
+                    if let Some(deposit) = w.storage_deposit {
+                        withdraw(wnear.clone(), deposit.as_yoctonear());
+                    }
+                }
+                Intent::MtWithdraw(w) => {
+                    for (token_id, amount) in w.token_ids.iter().zip(&w.amounts) {
+                        withdraw(
+                            Nep245TokenId::new(w.token.clone(), token_id.clone())
+                                .unwrap()
+                                .into(),
+                            amount.0,
+                        );
+                    }
+                    if let Some(deposit) = w.storage_deposit {
+                        withdraw(wnear.clone(), deposit.as_yoctonear());
+                    }
+                }
+                Intent::NativeWithdraw(w) => withdraw(wnear.clone(), w.amount.as_yoctonear()),
+                Intent::UnwrapNear(w) => withdraw(wnear.clone(), w.amount.as_yoctonear()),
+                Intent::StorageDeposit(d) => withdraw(
+                    if d.from_native {
+                        TokenId::Native
+                    } else {
+                        wnear.clone()
+                    },
+                    d.amount.as_yoctonear(),
+                ),
+                Intent::StorageDepositSelf(d) => withdraw(
+                    if d.from_native {
+                        TokenId::Native
+                    } else {
+                        wnear.clone()
+                    },
+                    d.amount.as_yoctonear(),
+                ),
+                Intent::AuthCall(call) if !call.attached_deposit.is_zero() => {
+                    withdraw(wnear.clone(), call.attached_deposit.as_yoctonear());
+                }
+                _ => {}
+            }
+        }
+        withdrawn
+    }
+
+    /// Random batches of intents can only move tokens between accounts
+    /// or withdraw them from the contract, but never mint new ones
+    #[rstest]
+    fn balance_conservation(#[with(10_000..100_000)] random_bytes: Vec<u8>) {
+        let signers: [AccountId; 2] = ["alice.near", "bob.near"].map(|a| a.parse().unwrap());
+        let view = MockView {
+            // large enough, but leaves room for incoming transfers
+            default_balance: 1 << 100,
+            ..Default::default()
+        };
+        let wnear: TokenId = Nep141TokenId::new(view.wnear_id().into_owned()).into();
+
+        let mut u = Unstructured::new(&random_bytes);
+        let mut executed = 0;
+        while !u.is_empty() {
+            let signer_id = u.choose(&signers).unwrap();
+            let intents: DefuseIntents = u.arbitrary().unwrap();
+
+            let mut state = CachedState::new(&view);
+            if Engine::new(&mut state, MockInspector)
+                .execute_intents_by_predecessor(signer_id, intents.clone())
+                .is_err()
+            {
+                continue;
+            }
+            executed += 1;
+            state.assert_invariants();
+
+            let mut total = TokenDeltas::default();
+            for account in state.into_deltas().unwrap().accounts.into_values() {
+                for (token_id, delta) in account.token_deltas {
+                    total.apply_delta(token_id, delta).unwrap();
+                }
+            }
+            assert_eq!(total, withdrawn(&intents.intents, &wnear));
+        }
+        assert!(executed > 0, "no batch was executed successfully");
+    }
+
+    #[test]
+    fn drain_balance() {
+        let [alice, bob]: [AccountId; 2] = ["alice.near", "bob.near"].map(|a| a.parse().unwrap());
+        let ft1: TokenId = Nep141TokenId::new("ft1.near".parse().unwrap()).into();
+
+        let view = MockView {
+            balances: [((alice.clone(), ft1.clone()), 1000)].into(),
+            ..Default::default()
+        };
+        let mut state = CachedState::new(&view);
+
+        Engine::new(&mut state, MockInspector)
+            .execute_intents_by_predecessor(
+                &alice,
+                DefuseIntents {
+                    intents: vec![
+                        Transfer {
+                            receiver_id: bob.clone(),
+                            tokens: [(ft1.clone(), 1000)].into_iter().collect(),
+                            memo: None,
+                        }
+                        .into(),
+                    ],
+                },
+            )
+            .unwrap();
+
+        // drained balance should not fall back to the view
+        assert_eq!(state.balance_of(&alice, &ft1), 0);
+        assert_eq!(state.balance_of(&bob, &ft1), 1000);
+        assert!(
+            state
+                .internal_sub_balance(&alice, [(ft1.clone(), 1)])
+                .is_err()
+        );
+        state.assert_invariants();
+
+        let deltas = state.into_deltas().unwrap();
+        assert_eq!(deltas.accounts[&alice].token_deltas.amount_for(&ft1), -1000);
+        assert_eq!(deltas.accounts[&bob].token_deltas.amount_for(&ft1), 1000);
+    }
+
+    #[test]
+    fn balances_of_matches_balance_of() {
+        let [alice, bob]: [AccountId; 2] = ["alice.near", "bob.near"].map(|a| a.parse().unwrap());
+        let [ft1, ft2, ft3]: [TokenId; 3] = ["ft1.near", "ft2.near", "ft3.near"]
+            .map(|t| Nep141TokenId::new(t.parse().unwrap()).into());
+
+        let view = MockView {
+            balances: [
+                ((alice.clone(), ft1.clone()), 1000),
+                ((alice.clone(), ft2.clone()), 500),
+            ]
+            .into(),
+            ..Default::default()
+        };
+        let mut state = CachedState::new(&view);
+        state
+            .internal_sub_balance(&alice, [(ft2.clone(), 200)])
+            .unwrap();
+        state
+            .internal_add_balance(bob.clone(), [(ft2.clone(), 200)])
+            .unwrap();
+
+        let token_ids = [ft1, ft2.clone(), ft3, ft2];
+        for account_id in [&alice, &bob] {
+            assert_eq!(
+                state.balances_of(account_id, &token_ids),
+                token_ids
+                    .iter()
+                    .map(|token_id| state.balance_of(account_id, token_id))
+                    .collect::<Vec<_>>(),
+            );
+        }
+        assert_eq!(state.balances_of(&alice, &token_ids), [1000, 300, 0, 300]);
+        assert!(state.balances_of(&alice, &[]).is_empty());
+    }
+
+    #[rstest]
+    fn memo_len(
+        #[values(MAX_MEMO_LEN, MAX_MEMO_LEN + 1)] len: usize,
+        #[values(false, true)] withdraw: bool,
+    ) {
+        let [alice, bob]: [AccountId; 2] = ["alice.near", "bob.near"].map(|a| a.parse().unwrap());
+        let ft: AccountId = "ft1.near".parse().unwrap();
+
+        let view = MockView {
+            default_balance: 1000,
+            ..Default::default()
+        };
+        let memo = Some("a".repeat(len));
+        let intent: Intent = if withdraw {
+            FtWithdraw {
+                token: ft,
+                receiver_id: bob,
+                amount: 100.into(),
+                memo,
+                msg: None,
+                storage_deposit: None,
+                min_gas: None,
+            }
+            .into()
+        } else {
+            Transfer {
+                receiver_id: bob,
+                tokens: [(Nep141TokenId::new(ft).into(), 100)].into_iter().collect(),
+                memo,
+            }
+            .into()
+        };
+
+        let result = Engine::new(CachedState::new(&view), MockInspector)
+            .execute_intents_by_predecessor(
+                &alice,
+                DefuseIntents {
+                    intents: vec![intent],
+                },
+            );
+        if len > MAX_MEMO_LEN {
+            assert!(matches!(
+                result,
+                Err(DefuseError::MemoTooLong(MAX_MEMO_LEN))
+            ));
+        } else {
+            result.unwrap();
+        }
+    }
+
+    #[rstest]
+    fn transfer_from_allowance(#[values(400, 401)] amount: u128) {
+        let [alice, bob, carol]: [AccountId; 3] =
+            ["alice.near", "bob.near", "carol.near"].map(|a| a.parse().unwrap());
+        let ft1: TokenId = Nep141TokenId::new("ft1.near".parse().unwrap()).into();
+
+        let view = MockView {
+            balances: [((alice.clone(), ft1.clone()), 1000)].into(),
+            allowances: [((alice.clone(), bob.clone(), ft1.clone()), 100)].into(),
+            ..Default::default()
+        };
+        let mut state = CachedState::new(&view);
+
+        // overrides the allowance from the view
+        Engine::new(&mut state, MockInspector)
+            .execute_intents_by_predecessor(
+                &alice,
+                DefuseIntents {
+                    intents: vec![
+                        Allowance {
+                            delegate: bob.clone(),
+                            token_id: ft1.clone(),
+                            amount: 500,
+                        }
+                        .into(),
+                    ],
+                },
+            )
+            .unwrap();
+        assert_eq!(state.allowance(&alice, &bob, &ft1), 500);
+
+        let transfer_from = |amount| DefuseIntents {
+            intents: vec![
+                TransferFrom {
+                    owner_id: alice.clone(),
+                    transfer: Transfer {
+                        receiver_id: carol.clone(),
+                        tokens: [(ft1.clone(), amount)].into_iter().collect(),
+                        memo: None,
+                    },
+                }
+                .into(),
+            ],
+        };
+
+        Engine::new(&mut state, MockInspector)
+            .execute_intents_by_predecessor(&bob, transfer_from(100))
+            .unwrap();
+
+        let result = Engine::new(&mut state, MockInspector)
+            .execute_intents_by_predecessor(&bob, transfer_from(amount));
+        if amount > 400 {
+            assert!(matches!(result, Err(DefuseError::AllowanceExceeded { .. })));
+        } else {
+            result.unwrap();
+        }
+
+        let spent = if amount > 400 { 100 } else { 500 };
+        assert_eq!(state.allowance(&alice, &bob, &ft1), 500 - spent);
+        assert_eq!(state.balance_of(&alice, &ft1), 1000 - spent);
+        assert_eq!(state.balance_of(&carol, &ft1), spent);
+        // only the owner can grant allowances
+        assert_eq!(state.allowance(&bob, &alice, &ft1), 0);
+        state.assert_invariants();
+
+        assert_eq!(
+            state.into_deltas().unwrap().accounts[&alice].allowances,
+            [(bob, [(ft1, 500 - spent)].into())].into(),
+        );
+    }
+
+    #[test]
+    fn allowances_limit() {
+        let [alice, bob]: [AccountId; 2] = ["alice.near", "bob.near"].map(|a| a.parse().unwrap());
+        let [ft0, ft1]: [TokenId; 2] =
+            ["ft0.near", "ft1.near"].map(|ft| Nep141TokenId::new(ft.parse().unwrap()).into());
+
+        // all but one allowed slots are already taken in the view
+        let view = MockView {
+            allowances: (1..Allowance::MAX_PER_ACCOUNT)
+                .map(|i| {
+                    (
+                        (
+                            alice.clone(),
+                            format!("delegate{i}.near").parse().unwrap(),
+                            ft0.clone(),
+                        ),
+                        1,
+                    )
+                })
+                .collect(),
+            ..Default::default()
+        };
+        let mut state = CachedState::new(&view);
+
+        state
+            .set_allowance(alice.clone(), bob.clone(), ft0.clone(), 100)
+            .unwrap();
+        assert_eq!(state.allowances_count(&alice), Allowance::MAX_PER_ACCOUNT);
+        assert!(matches!(
+            state.set_allowance(alice.clone(), bob.clone(), ft1.clone(), 100),
+            Err(DefuseError::TooManyAllowances(_, max)) if max == Allowance::MAX_PER_ACCOUNT
+        ));
+
+        // overriding existing allowance doesn't take a new slot
+        state
+            .set_allowance(alice.clone(), bob.clone(), ft0.clone(), 200)
+            .unwrap();
+
+        // revoking one frees a slot
+        state
+            .set_allowance(alice.clone(), "delegate1.near".parse().unwrap(), ft0, 0)
+            .unwrap();
+        state.set_allowance(alice.clone(), bob, ft1, 100).unwrap();
+        assert_eq!(state.allowances_count(&alice), Allowance::MAX_PER_ACCOUNT);
+    }
+
+    #[rstest]
+    #[test]
+    fn token_diff_without_net_change(
+        #[values(&[][..], &[0][..], &[-100, 0][..], &[100, 0][..])] deltas: &[i128],
+    ) {
+        let alice: AccountId = "alice.near".parse().unwrap();
+
+        let view = MockView {
+            default_balance: 1000,
+            ..Default::default()
+        };
+        let mut state = CachedState::new(&view);
+
+        // deltas are keyed by token, so they can never cancel each other
+        // out: a diff is a no-op only if it is empty or has zero deltas
+        let diff = TokenDiff {
+            diff: TokenDeltas::new(
+                deltas
+                    .iter()
+                    .enumerate()
+                    .map(|(i, delta)| {
+                        (
+                            Nep141TokenId::new(format!("ft{i}.near").parse().unwrap()).into(),
+                            *delta,
+                        )
+                    })
+                    .collect(),
+            ),
+            memo: None,
+            referral: None,
+        };
+
+        assert!(matches!(
+            Engine::new(&mut state, MockInspector).execute_intents_by_predecessor(
+                &alice,
+                DefuseIntents {
+                    intents: vec![diff.into()],
+                },
+            ),
+            Err(DefuseError::InvalidIntent)
+        ));
+    }
+
+    #[rstest]
+    #[case::floor(FeeLimits { min_fee: Some(5), max_fee: None }, 100, 5)]
+    #[case::cap(FeeLimits { min_fee: None, max_fee: Some(1_000) }, 1_000_000, 1_000)]
+    #[case::within(FeeLimits { min_fee: Some(5), max_fee: Some(1_000) }, 10_000, 100)]
+    #[test]
+    fn token_diff_fee_limits(
+        #[case] limits: FeeLimits,
+        #[case] amount: u128,
+        #[case] expected_fee: u128,
+    ) {
+        let alice: AccountId = "alice.near".parse().unwrap();
+        let [ft1, ft2]: [TokenId; 2] =
+            ["ft1.near", "ft2.near"].map(|ft| Nep141TokenId::new(ft.parse().unwrap()).into());
+
+        let view = MockView {
+            default_balance: 10_000_000,
+            fee: Pips::ONE_PERCENT,
+            fee_limits: [(ft1.clone(), limits)].into(),
+            ..Default::default()
+        };
+        let mut state = CachedState::new(&view);
+
+        // a lone diff has no counterparty to be matched with, but fees
+        // are collected before deltas are finalized
+        assert!(matches!(
+            Engine::new(&mut state, MockInspector).execute_intents_by_predecessor(
+                &alice,
+                DefuseIntents {
+                    intents: vec![
+                        TokenDiff {
+                            diff: TokenDeltas::new(
+                                [
+                                    (ft1.clone(), -i128::try_from(amount).unwrap()),
+                                    (ft2.clone(), 1),
+                                ]
+                                .into(),
+                            ),
+                            memo: None,
+                            referral: None,
+                        }
+                        .into(),
+                    ],
+                },
+            ),
+            Err(DefuseError::InvariantViolated(_))
+        ));
+
+        assert_eq!(
+            state.balance_of(&view.fee_collector(), &ft1),
+            view.default_balance + expected_fee,
+        );
+    }
+
+    #[test]
+    fn token_diff_balance_changes() {
+        let alice: AccountId = "alice.near".parse().unwrap();
+        let [ft1, ft2]: [TokenId; 2] =
+            ["ft1.near", "ft2.near"].map(|ft| Nep141TokenId::new(ft.parse().unwrap()).into());
+
+        let view = MockView {
+            default_balance: 1000,
+            fee: Pips::ONE_PERCENT,
+            ..Default::default()
+        };
+        let wnear: TokenId = Nep141TokenId::new(view.wnear_id().into_owned()).into();
+        let mut state = CachedState::new(&view);
+        let mut inspector = RecordingInspector::default();
+
+        // a lone diff has no counterparty to be matched with, but balance
+        // changes are reported before deltas are finalized
+        assert!(matches!(
+            Engine::new(&mut state, &mut inspector).execute_intents_by_predecessor(
+                &alice,
+                DefuseIntents {
+                    intents: vec![
+                        TokenDiff {
+                            diff: TokenDeltas::new(
+                                [(ft1.clone(), -500), (ft2.clone(), 100)].into(),
+                            ),
+                            memo: None,
+                            referral: None,
+                        }
+                        .into(),
+                        FtWithdraw {
+                            token: "ft2.near".parse().unwrap(),
+                            receiver_id: alice.clone(),
+                            amount: 30.into(),
+                            memo: None,
+                            msg: None,
+                            storage_deposit: Some(NearToken::from_yoctonear(7)),
+                            min_gas: None,
+                        }
+                        .into(),
+                    ],
+                },
+            ),
+            Err(DefuseError::InvariantViolated(_))
+        ));
+
+        assert_eq!(
+            inspector.balance_changes,
+            [
+                (alice.clone(), ft1.clone(), -500),
+                (alice.clone(), ft2.clone(), 100),
+                // 1% of token_in goes to fee collector
+                (view.fee_collector().into_owned(), ft1, 5),
+                // withdrawals leave the contract along with storage deposit
+                (alice.clone(), ft2, -30),
+                (alice, wnear, -7),
+            ],
+        );
+    }
+
+    #[test]
+    fn balance_changes_saturate() {
+        let [alice, bob]: [AccountId; 2] = ["alice.near", "bob.near"].map(|a| a.parse().unwrap());
+        let ft1: TokenId = Nep141TokenId::new("ft1.near".parse().unwrap()).into();
+
+        let view = MockView {
+            balances: [((alice.clone(), ft1.clone()), u128::MAX)].into(),
+            ..Default::default()
+        };
+        let mut state = CachedState::new(&view);
+        let mut inspector = RecordingInspector::default();
+
+        // amounts above i128::MAX are still transferable, but reported
+        // balance changes are saturated
+        Engine::new(&mut state, &mut inspector)
+            .execute_intents_by_predecessor(
+                &alice,
+                DefuseIntents {
+                    intents: vec![
+                        Transfer {
+                            receiver_id: bob.clone(),
+                            tokens: [(ft1.clone(), u128::MAX)].into_iter().collect(),
+                            memo: None,
+                        }
+                        .into(),
+                    ],
+                },
+            )
+            .unwrap();
+
+        assert_eq!(state.balance_of(&bob, &ft1), u128::MAX);
+        assert_eq!(
+            inspector.balance_changes,
+            [(alice, ft1.clone(), -i128::MAX), (bob, ft1, i128::MAX),],
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "is not conserved")]
+    fn assert_invariants_minted() {
+        let [alice, bob]: [AccountId; 2] = ["alice.near", "bob.near"].map(|a| a.parse().unwrap());
+        let ft1: TokenId = Nep141TokenId::new("ft1.near".parse().unwrap()).into();
+
+        let view = MockView {
+            balances: [((alice.clone(), ft1.clone()), 1000)].into(),
+            ..Default::default()
+        };
+        let mut state = CachedState::new(&view);
+
+        // unmatched deltas: bob receives more than alice sent
+        state
+            .internal_apply_deltas(&alice, [(ft1.clone(), -1000)])
+            .unwrap();
+        state.internal_apply_deltas(&bob, [(ft1, 1001)]).unwrap();
+
+        state.assert_invariants();
+    }
+}
@@ -1,11 +1,11 @@
 use crate::{
     DefuseError, Nonce, Result,
     amounts::Amounts,
-    fees::Pips,
+    fees::{FeeLimits, Pips},
     intents::{
         auth::AuthCall,
         token_diff::TokenDeltas,
-        tokens::{FtWithdraw, MtWithdraw, NativeWithdraw, NftWithdraw, StorageDeposit},
+        tokens::{FtWithdraw, MtWithdraw, NativeWithdraw, NftWithdraw, StorageDeposit, UnwrapNear},
     },
     token_id::TokenId,
 };
@@ -18,7 +18,8 @@ use std::{
     borrow::Cow,
     cmp::Reverse,
     collections::{BTreeMap, HashMap},
-    iter,
+    iter, mem,
+    time::Duration,
 };
 
 use super::{State, StateView};
@@ -26,6 +27,11 @@ use super::{State, StateView};
 pub struct Deltas<S> {
     state: S,
     deltas: TransferMatcher,
+    /// Whether balance changes should be recorded at all, see
+    /// [`Inspector::records_balance_changes`](crate::engine::Inspector::records_balance_changes)
+    record_balance_changes: bool,
+    /// Balance changes not yet reported to the inspector
+    balance_changes: Vec<(AccountId, TokenId, i128)>,
 }
 
 impl<S> Deltas<S> {
@@ -34,13 +40,59 @@ impl<S> Deltas<S> {
         Self {
             state,
             deltas: TransferMatcher::new(),
+            record_balance_changes: false,
+            balance_changes: Vec::new(),
         }
     }
 
+    /// Enables recording of balance changes, which can be retrieved
+    /// with [`.take_balance_changes()`](Self::take_balance_changes)
+    #[must_use]
+    #[inline]
+    pub const fn with_balance_changes(mut self, record: bool) -> Self {
+        self.record_balance_changes = record;
+        self
+    }
+
+    /// Returns balance changes made since the previous call
+    #[inline]
+    pub fn take_balance_changes(&mut self) -> Vec<(AccountId, TokenId, i128)> {
+        mem::take(&mut self.balance_changes)
+    }
+
     #[inline]
     pub fn finalize(self) -> Result<Transfers, InvariantViolated> {
         self.deltas.finalize()
     }
+
+    /// Records balance change of `amount`, saturated to fit into `i128`,
+    /// since balance changes are informational and shouldn't make
+    /// otherwise valid execution fail
+    fn record_balance_change(
+        &mut self,
+        owner_id: &AccountIdRef,
+        token_id: TokenId,
+        amount: u128,
+        negative: bool,
+    ) {
+        if !self.record_balance_changes {
+            return;
+        }
+        let delta = i128::try_from(amount).unwrap_or(i128::MAX);
+        self.balance_changes.push((
+            owner_id.to_owned(),
+            token_id,
+            if negative { -delta } else { delta },
+        ));
+    }
+
+    /// Records tokens leaving the contract, which are withdrawn by the
+    /// underlying state bypassing `internal_sub_balance()`
+    fn record_withdrawn(&mut self, owner_id: &AccountIdRef, tokens: Option<Vec<(TokenId, u128)>>) {
+        for (token_id, amount) in tokens.into_iter().flatten() {
+            self.record_balance_change(owner_id, token_id, amount, true);
+        }
+    }
 }
 
 impl<S> StateView for Deltas<S>
@@ -67,6 +119,36 @@ where
         self.state.fee_collector()
     }
 
+    #[inline]
+    fn referral_fee_share(&self) -> Pips {
+        self.state.referral_fee_share()
+    }
+
+    #[inline]
+    fn fee_limits(&self, token_id: &TokenId) -> FeeLimits {
+        self.state.fee_limits(token_id)
+    }
+
+    #[inline]
+    fn verbose_events(&self) -> bool {
+        self.state.verbose_events()
+    }
+
+    #[inline]
+    fn max_public_keys(&self) -> Option<u32> {
+        self.state.max_public_keys()
+    }
+
+    #[inline]
+    fn deadline_grace(&self) -> Duration {
+        self.state.deadline_grace()
+    }
+
+    #[inline]
+    fn network_id(&self) -> Option<String> {
+        self.state.network_id()
+    }
+
     #[inline]
     fn has_public_key(&self, account_id: &AccountIdRef, public_key: &PublicKey) -> bool {
         self.state.has_public_key(account_id, public_key)
@@ -87,11 +169,36 @@ where
         self.state.balance_of(account_id, token_id)
     }
 
+    #[inline]
+    fn balances_of(&self, account_id: &AccountIdRef, token_ids: &[TokenId]) -> Vec<u128> {
+        self.state.balances_of(account_id, token_ids)
+    }
+
+    #[inline]
+    fn allowance(
+        &self,
+        owner_id: &AccountIdRef,
+        delegate_id: &AccountIdRef,
+        token_id: &TokenId,
+    ) -> u128 {
+        self.state.allowance(owner_id, delegate_id, token_id)
+    }
+
+    #[inline]
+    fn allowances_count(&self, owner_id: &AccountIdRef) -> u32 {
+        self.state.allowances_count(owner_id)
+    }
+
     #[inline]
     fn is_account_locked(&self, account_id: &AccountIdRef) -> bool {
         self.state.is_account_locked(account_id)
     }
 
+    #[inline]
+    fn account_lock_reason(&self, account_id: &AccountIdRef) -> Option<String> {
+        self.state.account_lock_reason(account_id)
+    }
+
     #[inline]
     fn is_auth_by_predecessor_id_enabled(&self, account_id: &AccountIdRef) -> bool {
         self.state.is_auth_by_predecessor_id_enabled(account_id)
@@ -134,9 +241,13 @@ where
         for (token_id, amount) in tokens {
             self.state
                 .internal_add_balance(owner_id.clone(), [(token_id.clone(), amount)])?;
-            if !self.deltas.deposit(owner_id.clone(), token_id, amount) {
+            if !self
+                .deltas
+                .deposit(owner_id.clone(), token_id.clone(), amount)
+            {
                 return Err(DefuseError::BalanceOverflow);
             }
+            self.record_balance_change(&owner_id, token_id, amount, false);
         }
         Ok(())
     }
@@ -149,40 +260,116 @@ where
         for (token_id, amount) in tokens {
             self.state
                 .internal_sub_balance(owner_id, [(token_id.clone(), amount)])?;
-            if !self.deltas.withdraw(owner_id.to_owned(), token_id, amount) {
+            if !self
+                .deltas
+                .withdraw(owner_id.to_owned(), token_id.clone(), amount)
+            {
                 return Err(DefuseError::BalanceOverflow);
             }
+            self.record_balance_change(owner_id, token_id, amount, true);
+        }
+        Ok(())
+    }
+
+    fn internal_apply_deltas(
+        &mut self,
+        owner_id: &AccountIdRef,
+        tokens: impl IntoIterator<Item = (TokenId, i128)>,
+    ) -> Result<()> {
+        for (token_id, delta) in tokens {
+            #[cfg(debug_assertions)]
+            let before = self.balance_of(owner_id, &token_id);
+
+            let amounts = [(token_id.clone(), delta.unsigned_abs())];
+            if delta.is_negative() {
+                self.internal_sub_balance(owner_id, amounts)?;
+            } else {
+                self.internal_add_balance(owner_id.to_owned(), amounts)?;
+            }
+
+            // catch arithmetic regressions in underlying state early
+            #[cfg(debug_assertions)]
+            assert_eq!(
+                before.checked_add_signed(delta),
+                Some(self.balance_of(owner_id, &token_id)),
+                "balance of {token_id} for {owner_id} was not changed by {delta}",
+            );
         }
         Ok(())
     }
 
     #[inline]
+    fn set_allowance(
+        &mut self,
+        owner_id: AccountId,
+        delegate_id: AccountId,
+        token_id: TokenId,
+        amount: u128,
+    ) -> Result<()> {
+        self.state
+            .set_allowance(owner_id, delegate_id, token_id, amount)
+    }
+
     fn ft_withdraw(&mut self, owner_id: &AccountIdRef, withdraw: FtWithdraw) -> Result<()> {
-        self.state.ft_withdraw(owner_id, withdraw)
+        let tokens = self
+            .record_balance_changes
+            .then(|| withdraw.withdrawn_tokens(&self.state.wnear_id()));
+        self.state.ft_withdraw(owner_id, withdraw)?;
+        self.record_withdrawn(owner_id, tokens);
+        Ok(())
     }
 
-    #[inline]
     fn nft_withdraw(&mut self, owner_id: &AccountIdRef, withdraw: NftWithdraw) -> Result<()> {
-        self.state.nft_withdraw(owner_id, withdraw)
+        // invalid withdrawals are rejected by underlying state
+        let tokens = self
+            .record_balance_changes
+            .then(|| withdraw.withdrawn_tokens(&self.state.wnear_id()).ok())
+            .flatten();
+        self.state.nft_withdraw(owner_id, withdraw)?;
+        self.record_withdrawn(owner_id, tokens);
+        Ok(())
     }
 
-    #[inline]
     fn mt_withdraw(&mut self, owner_id: &AccountIdRef, withdraw: MtWithdraw) -> Result<()> {
-        self.state.mt_withdraw(owner_id, withdraw)
+        // invalid withdrawals are rejected by underlying state
+        let tokens = self
+            .record_balance_changes
+            .then(|| withdraw.withdrawn_tokens(&self.state.wnear_id()).ok())
+            .flatten();
+        self.state.mt_withdraw(owner_id, withdraw)?;
+        self.record_withdrawn(owner_id, tokens);
+        Ok(())
     }
 
-    #[inline]
     fn native_withdraw(&mut self, owner_id: &AccountIdRef, withdraw: NativeWithdraw) -> Result<()> {
-        self.state.native_withdraw(owner_id, withdraw)
+        let tokens = self
+            .record_balance_changes
+            .then(|| withdraw.withdrawn_tokens(&self.state.wnear_id()));
+        self.state.native_withdraw(owner_id, withdraw)?;
+        self.record_withdrawn(owner_id, tokens);
+        Ok(())
+    }
+
+    fn unwrap_near(&mut self, owner_id: &AccountIdRef, unwrap: UnwrapNear) -> Result<()> {
+        let tokens = self
+            .record_balance_changes
+            .then(|| unwrap.withdrawn_tokens(&self.state.wnear_id()));
+        self.state.unwrap_near(owner_id, unwrap)?;
+        self.record_withdrawn(owner_id, tokens);
+        Ok(())
     }
 
-    #[inline]
     fn storage_deposit(
         &mut self,
         owner_id: &AccountIdRef,
         storage_deposit: StorageDeposit,
     ) -> Result<()> {
-        self.state.storage_deposit(owner_id, storage_deposit)
+        let tokens = self
+            .record_balance_changes
+            .then(|| storage_deposit.withdrawn_tokens(&self.state.wnear_id()));
+        self.state.storage_deposit(owner_id, storage_deposit)?;
+        self.record_withdrawn(owner_id, tokens);
+        Ok(())
     }
 
     #[inline]
@@ -190,9 +377,13 @@ where
         self.state.set_auth_by_predecessor_id(account_id, enable)
     }
 
-    #[inline]
     fn auth_call(&mut self, signer_id: &AccountIdRef, auth_call: AuthCall) -> Result<()> {
-        self.state.auth_call(signer_id, auth_call)
+        let tokens = self
+            .record_balance_changes
+            .then(|| auth_call.withdrawn_tokens(&self.state.wnear_id()));
+        self.state.auth_call(signer_id, auth_call)?;
+        self.record_withdrawn(signer_id, tokens);
+        Ok(())
     }
 }
 
@@ -285,15 +476,9 @@ impl TokenTransferMatcher {
         owner_id: AccountId,
         mut amount: u128,
     ) -> bool {
-        let s = sub.amount_for(&owner_id);
-        if s > 0 {
-            let a = s.min(amount);
-            sub.sub(owner_id.clone(), a)
-                .unwrap_or_else(|| unreachable!());
-            amount = amount.saturating_sub(a);
-            if amount == 0 {
-                return true;
-            }
+        amount -= sub.sub_saturating(owner_id.clone(), amount);
+        if amount == 0 {
+            return true;
         }
         add.add(owner_id, amount).is_some()
     }
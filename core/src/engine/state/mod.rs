@@ -3,10 +3,10 @@ pub mod deltas;
 
 use crate::{
     Nonce, Result,
-    fees::Pips,
+    fees::{FeeLimits, Pips},
     intents::{
         auth::AuthCall,
-        tokens::{FtWithdraw, MtWithdraw, NativeWithdraw, NftWithdraw, StorageDeposit},
+        tokens::{FtWithdraw, MtWithdraw, NativeWithdraw, NftWithdraw, StorageDeposit, UnwrapNear},
     },
     token_id::{TokenId, nep141::Nep141TokenId},
 };
@@ -14,7 +14,7 @@ use cached::CachedState;
 use defuse_crypto::PublicKey;
 use impl_tools::autoimpl;
 use near_sdk::{AccountId, AccountIdRef};
-use std::borrow::Cow;
+use std::{borrow::Cow, time::Duration};
 
 #[autoimpl(for<T: trait + ?Sized> &T, &mut T, Box<T>)]
 pub trait StateView {
@@ -26,6 +26,23 @@ pub trait StateView {
 
     fn fee(&self) -> Pips;
     fn fee_collector(&self) -> Cow<'_, AccountIdRef>;
+    /// Share of the fee which goes to `referral` of [`TokenDiff`](crate::intents::token_diff::TokenDiff)
+    fn referral_fee_share(&self) -> Pips;
+    /// Absolute bounds on the fee taken from given token by
+    /// [`TokenDiff`](crate::intents::token_diff::TokenDiff)
+    fn fee_limits(&self, token_id: &TokenId) -> FeeLimits;
+    /// Whether to emit [`DefuseEvent::IntentExecuted`](crate::events::DefuseEvent::IntentExecuted)
+    /// for each executed intent
+    fn verbose_events(&self) -> bool;
+    /// Maximum number of public keys an account can have, not counting
+    /// the implicit one. `None` means unlimited
+    fn max_public_keys(&self) -> Option<u32>;
+    /// Tolerance for clock skew between signers and the chain applied
+    /// when checking deadlines of signed payloads
+    fn deadline_grace(&self) -> Duration;
+    /// NEAR network the contract is deployed to, e.g. `mainnet`. Signed
+    /// payloads bound to other networks are rejected
+    fn network_id(&self) -> Option<String>;
 
     #[must_use]
     fn has_public_key(&self, account_id: &AccountIdRef, public_key: &PublicKey) -> bool;
@@ -37,8 +54,37 @@ pub trait StateView {
     #[must_use]
     fn balance_of(&self, account_id: &AccountIdRef, token_id: &TokenId) -> u128;
 
+    /// Batch version of [`balance_of`](StateView::balance_of), returns
+    /// balances in the same order as `token_ids`
+    #[must_use]
+    fn balances_of(&self, account_id: &AccountIdRef, token_ids: &[TokenId]) -> Vec<u128> {
+        token_ids
+            .iter()
+            .map(|token_id| self.balance_of(account_id, token_id))
+            .collect()
+    }
+
+    /// Returns remaining amount of `token_id` that `delegate_id` is
+    /// allowed to transfer from `owner_id`, see
+    /// [`Allowance`](crate::intents::allowance::Allowance)
+    #[must_use]
+    fn allowance(
+        &self,
+        owner_id: &AccountIdRef,
+        delegate_id: &AccountIdRef,
+        token_id: &TokenId,
+    ) -> u128;
+
+    /// Returns number of non-zero allowances granted by `owner_id`, which
+    /// is limited by [`Allowance::MAX_PER_ACCOUNT`](crate::intents::allowance::Allowance::MAX_PER_ACCOUNT)
+    #[must_use]
+    fn allowances_count(&self, owner_id: &AccountIdRef) -> u32;
+
     fn is_account_locked(&self, account_id: &AccountIdRef) -> bool;
 
+    /// Returns the reason given when the account was locked, if any
+    fn account_lock_reason(&self, account_id: &AccountIdRef) -> Option<String>;
+
     /// Returns whether authentication by `PREDECESSOR_ID` is enabled.
     fn is_auth_by_predecessor_id_enabled(&self, account_id: &AccountIdRef) -> bool;
 
@@ -93,6 +139,16 @@ pub trait State: StateView {
         Ok(())
     }
 
+    /// Overrides the allowance of `delegate_id` to transfer `token_id`
+    /// from `owner_id`, zero `amount` revokes it
+    fn set_allowance(
+        &mut self,
+        owner_id: AccountId,
+        delegate_id: AccountId,
+        token_id: TokenId,
+        amount: u128,
+    ) -> Result<()>;
+
     fn ft_withdraw(&mut self, owner_id: &AccountIdRef, withdraw: FtWithdraw) -> Result<()>;
 
     fn nft_withdraw(&mut self, owner_id: &AccountIdRef, withdraw: NftWithdraw) -> Result<()>;
@@ -101,6 +157,8 @@ pub trait State: StateView {
 
     fn native_withdraw(&mut self, owner_id: &AccountIdRef, withdraw: NativeWithdraw) -> Result<()>;
 
+    fn unwrap_near(&mut self, owner_id: &AccountIdRef, unwrap: UnwrapNear) -> Result<()>;
+
     fn storage_deposit(
         &mut self,
         owner_id: &AccountIdRef,
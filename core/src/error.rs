@@ -1,9 +1,11 @@
 use crate::{
+    Deadline, Nonce,
     engine::deltas::InvariantViolated,
+    payload::multi::SigningStandard,
     token_id::{TokenId, error::TokenIdError, nep171::Nep171TokenId},
 };
 use defuse_crypto::PublicKey;
-use near_sdk::{AccountId, FunctionError, serde_json};
+use near_sdk::{AccountId, AccountIdRef, FunctionError, bs58, near, serde_json};
 use thiserror::Error as ThisError;
 
 pub type Result<T, E = DefuseError> = ::core::result::Result<T, E>;
@@ -13,8 +15,20 @@ pub enum DefuseError {
     #[error("account '{0}' not found")]
     AccountNotFound(AccountId),
 
-    #[error("account '{0}' is locked")]
-    AccountLocked(AccountId),
+    #[error(
+        "account '{0}' is locked{reason}",
+        reason = .1.as_ref().map(|reason| format!(": {reason}")).unwrap_or_default(),
+    )]
+    AccountLocked(AccountId, Option<String>),
+
+    #[error(
+        "allowance of '{delegate_id}' for token '{token_id}' on account '{owner_id}' is exceeded"
+    )]
+    AllowanceExceeded {
+        owner_id: AccountId,
+        delegate_id: AccountId,
+        token_id: TokenId,
+    },
 
     #[error("authentication by PREDECESSOR_ID is disabled for account '{0}'")]
     AuthByPredecessorIdDisabled(AccountId),
@@ -22,8 +36,13 @@ pub enum DefuseError {
     #[error("insufficient balance or overflow")]
     BalanceOverflow,
 
-    #[error("deadline has expired")]
-    DeadlineExpired,
+    /// `now` is the block timestamp the `deadline` was checked against
+    #[error(
+        "deadline '{}' has expired at '{}'",
+        .deadline.into_timestamp(),
+        .now.into_timestamp(),
+    )]
+    DeadlineExpired { deadline: Deadline, now: Deadline },
 
     #[error("deadline is greater than nonce")]
     DeadlineGreaterThanNonce,
@@ -31,6 +50,12 @@ pub enum DefuseError {
     #[error("gas overflow")]
     GasOverflow,
 
+    #[error("idempotency key was already used with different payloads")]
+    IdempotencyKeyMismatch,
+
+    #[error("idempotency key requires at least one signed payload")]
+    IdempotencyKeyWithoutPayloads,
+
     #[error("invalid intent")]
     InvalidIntent,
 
@@ -46,15 +71,30 @@ pub enum DefuseError {
     #[error("JSON: {0}")]
     JSON(#[from] serde_json::Error),
 
-    #[error("NFT '{}' is already deposited", TokenId::Nep171(.0.clone()))]
-    NftAlreadyDeposited(Nep171TokenId),
+    #[error("memo is too long: at most {0} bytes allowed")]
+    MemoTooLong(usize),
 
-    #[error("nonce was already used")]
-    NonceUsed,
+    /// Holder is known only if it's the same account the NFT is being
+    /// deposited to, since there is no reverse look-up of NFT owners
+    #[error(
+        "NFT '{}' is already deposited{holder}",
+        TokenId::Nep171(.0.clone()),
+        holder = .1.as_ref().map(|holder| format!(" to '{holder}'")).unwrap_or_default(),
+    )]
+    NftAlreadyDeposited(Nep171TokenId, Option<AccountId>),
+
+    #[error(
+        "nonce '{}' was already used by account '{account_id}'",
+        bs58::encode(nonce).into_string(),
+    )]
+    NonceUsed { account_id: AccountId, nonce: Nonce },
 
     #[error("nonce was already expired")]
     NonceExpired,
 
+    #[error("precondition failed")]
+    PreconditionFailed,
+
     #[error("public key '{1}' already exists for account '{0}'")]
     PublicKeyExists(AccountId, PublicKey),
 
@@ -64,6 +104,199 @@ pub enum DefuseError {
     #[error("token_id: {0}")]
     ParseTokenId(#[from] TokenIdError),
 
+    #[error("signing standard '{0}' is not allowed")]
+    SigningStandardNotAllowed(SigningStandard),
+
+    #[error("account '{0}' has too many allowances: at most {1} allowed")]
+    TooManyAllowances(AccountId, u32),
+
+    #[error("too many intents: at most {0} allowed per call")]
+    TooManyIntents(u32),
+
+    #[error("account '{0}' has too many public keys: at most {1} allowed")]
+    TooManyPublicKeys(AccountId, u32),
+
+    #[error("unknown signing standard: '{0}'")]
+    UnknownSigningStandard(String),
+
+    #[error("wrong network_id")]
+    WrongNetworkId,
+
     #[error("wrong verifying_contract")]
     WrongVerifyingContract,
 }
+
+impl DefuseError {
+    #[inline]
+    pub fn account_locked(account_id: &AccountIdRef, reason: Option<&str>) -> Self {
+        Self::AccountLocked(account_id.to_owned(), reason.map(ToOwned::to_owned))
+    }
+
+    /// Returns stable machine-readable code of the error
+    pub const fn code(&self) -> DefuseErrorCode {
+        match self {
+            Self::AccountNotFound(..) => DefuseErrorCode::AccountNotFound,
+            Self::AccountLocked(..) => DefuseErrorCode::AccountLocked,
+            Self::AllowanceExceeded { .. } => DefuseErrorCode::AllowanceExceeded,
+            Self::AuthByPredecessorIdDisabled(..) => DefuseErrorCode::AuthByPredecessorIdDisabled,
+            Self::BalanceOverflow => DefuseErrorCode::BalanceOverflow,
+            Self::DeadlineExpired { .. } => DefuseErrorCode::DeadlineExpired,
+            Self::DeadlineGreaterThanNonce => DefuseErrorCode::DeadlineGreaterThanNonce,
+            Self::GasOverflow => DefuseErrorCode::GasOverflow,
+            Self::IdempotencyKeyMismatch => DefuseErrorCode::IdempotencyKeyMismatch,
+            Self::IdempotencyKeyWithoutPayloads => DefuseErrorCode::IdempotencyKeyWithoutPayloads,
+            Self::InvalidIntent => DefuseErrorCode::InvalidIntent,
+            Self::InvalidSignature => DefuseErrorCode::InvalidSignature,
+            Self::InvariantViolated(..) => DefuseErrorCode::InvariantViolated,
+            Self::JSON(..) => DefuseErrorCode::Json,
+            Self::MemoTooLong(..) => DefuseErrorCode::MemoTooLong,
+            Self::NftAlreadyDeposited(..) => DefuseErrorCode::NftAlreadyDeposited,
+            Self::NonceUsed { .. } => DefuseErrorCode::NonceUsed,
+            Self::NonceExpired => DefuseErrorCode::NonceExpired,
+            Self::PreconditionFailed => DefuseErrorCode::PreconditionFailed,
+            Self::PublicKeyExists(..) => DefuseErrorCode::PublicKeyExists,
+            Self::PublicKeyNotExist(..) => DefuseErrorCode::PublicKeyNotExist,
+            Self::ParseTokenId(..) => DefuseErrorCode::ParseTokenId,
+            Self::SigningStandardNotAllowed(..) => DefuseErrorCode::SigningStandardNotAllowed,
+            Self::TooManyAllowances(..) => DefuseErrorCode::TooManyAllowances,
+            Self::TooManyIntents(..) => DefuseErrorCode::TooManyIntents,
+            Self::TooManyPublicKeys(..) => DefuseErrorCode::TooManyPublicKeys,
+            Self::UnknownSigningStandard(..) => DefuseErrorCode::UnknownSigningStandard,
+            Self::WrongNetworkId => DefuseErrorCode::WrongNetworkId,
+            Self::WrongVerifyingContract => DefuseErrorCode::WrongVerifyingContract,
+        }
+    }
+}
+
+/// Machine-readable code of [`DefuseError`].
+///
+/// NOTE: these are part of public API, so existing codes MUST NOT be renamed
+#[near(serializers = [json])]
+#[serde(rename_all = "snake_case")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, strum::AsRefStr, strum::EnumIter)]
+#[strum(serialize_all = "snake_case")]
+pub enum DefuseErrorCode {
+    AccountNotFound,
+    AccountLocked,
+    AllowanceExceeded,
+    AuthByPredecessorIdDisabled,
+    BalanceOverflow,
+    DeadlineExpired,
+    DeadlineGreaterThanNonce,
+    GasOverflow,
+    IdempotencyKeyMismatch,
+    IdempotencyKeyWithoutPayloads,
+    InvalidIntent,
+    InvalidSignature,
+    InvariantViolated,
+    Json,
+    MemoTooLong,
+    NftAlreadyDeposited,
+    NonceUsed,
+    NonceExpired,
+    PreconditionFailed,
+    PublicKeyExists,
+    PublicKeyNotExist,
+    ParseTokenId,
+    SigningStandardNotAllowed,
+    TooManyAllowances,
+    TooManyIntents,
+    TooManyPublicKeys,
+    UnknownSigningStandard,
+    WrongNetworkId,
+    WrongVerifyingContract,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn code() {
+        assert_eq!(
+            DefuseError::NonceUsed {
+                account_id: "alice.near".parse().unwrap(),
+                nonce: [0; 32],
+            }
+            .code(),
+            DefuseErrorCode::NonceUsed
+        );
+        assert_eq!(
+            DefuseError::TooManyIntents(1).code(),
+            DefuseErrorCode::TooManyIntents
+        );
+        assert_eq!(
+            serde_json::to_value(DefuseErrorCode::AuthByPredecessorIdDisabled).unwrap(),
+            "auth_by_predecessor_id_disabled"
+        );
+    }
+
+    #[test]
+    fn nft_already_deposited_message() {
+        let tid = Nep171TokenId::new("nft.near".parse().unwrap(), "1".to_string()).unwrap();
+
+        assert_eq!(
+            DefuseError::NftAlreadyDeposited(tid.clone(), None).to_string(),
+            "NFT 'nep171:nft.near:1' is already deposited"
+        );
+        assert_eq!(
+            DefuseError::NftAlreadyDeposited(tid, Some("alice.near".parse().unwrap())).to_string(),
+            "NFT 'nep171:nft.near:1' is already deposited to 'alice.near'"
+        );
+    }
+
+    #[test]
+    fn code_serde_matches_as_ref() {
+        for code in DefuseErrorCode::iter() {
+            assert_eq!(serde_json::to_value(code).unwrap(), code.as_ref());
+            assert_eq!(
+                serde_json::from_value::<DefuseErrorCode>(code.as_ref().into()).unwrap(),
+                code
+            );
+        }
+    }
+
+    #[cfg(all(feature = "abi", not(target_arch = "wasm32")))]
+    #[test]
+    fn code_schema() {
+        let schema =
+            serde_json::to_value(near_sdk::schemars::schema_for!(DefuseErrorCode)).unwrap();
+
+        assert_eq!(schema["type"], "string");
+        assert_eq!(
+            schema["enum"],
+            serde_json::json!([
+                "account_not_found",
+                "account_locked",
+                "allowance_exceeded",
+                "auth_by_predecessor_id_disabled",
+                "balance_overflow",
+                "deadline_expired",
+                "deadline_greater_than_nonce",
+                "gas_overflow",
+                "idempotency_key_mismatch",
+                "idempotency_key_without_payloads",
+                "invalid_intent",
+                "invalid_signature",
+                "invariant_violated",
+                "json",
+                "memo_too_long",
+                "nft_already_deposited",
+                "nonce_used",
+                "nonce_expired",
+                "precondition_failed",
+                "public_key_exists",
+                "public_key_not_exist",
+                "parse_token_id",
+                "signing_standard_not_allowed",
+                "too_many_allowances",
+                "too_many_intents",
+                "too_many_public_keys",
+                "unknown_signing_standard",
+                "wrong_network_id",
+                "wrong_verifying_contract",
+            ])
+        );
+    }
+}
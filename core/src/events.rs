@@ -5,15 +5,29 @@ use near_sdk::{near, serde::Deserialize};
 
 use crate::{
     accounts::{AccountEvent, NonceEvent, PublicKeyEvent},
-    fees::{FeeChangedEvent, FeeCollectorChangedEvent},
+    fees::{
+        FeeChangedEvent, FeeCollectorChangedEvent, FeeLimitsChangedEvent,
+        ReferralFeeShareChangedEvent,
+    },
     intents::{
-        IntentEvent,
+        IntentEvent, IntentExecutedEvent,
         account::SetAuthByPredecessorId,
+        allowance::{Allowance, TransferFrom},
+        auth::AuthCallValueEvent,
         token_diff::TokenDiffEvent,
-        tokens::{FtWithdraw, MtWithdraw, NativeWithdraw, NftWithdraw, StorageDeposit, Transfer},
+        tokens::{
+            FtWithdraw, Gift, MtWithdraw, NativeWithdraw, NftWithdraw, StorageDeposit, Transfer,
+            UnwrapNear,
+        },
     },
 };
 
+/// [NEP-297](https://github.com/near/NEPs/blob/master/neps/nep-0297.md)
+/// events of `dip4` standard.
+///
+/// Each variant is versioned separately: its `event_version` is emitted
+/// in `version` field of the envelope and MUST be bumped on any change
+/// of the variant's schema.
 #[must_use = "make sure to `.emit()` this event"]
 #[near(event_json(standard = "dip4"))]
 #[derive(Debug, Clone, Deserialize, From)]
@@ -29,29 +43,48 @@ pub enum DefuseEvent<'a> {
     FeeChanged(FeeChangedEvent),
     #[event_version("0.3.0")]
     FeeCollectorChanged(FeeCollectorChangedEvent<'a>),
+    #[event_version("0.3.0")]
+    ReferralFeeShareChanged(ReferralFeeShareChangedEvent),
+    #[event_version("0.3.0")]
+    FeeLimitsChanged(FeeLimitsChangedEvent<'a>),
 
     #[event_version("0.3.0")]
     Transfer(Cow<'a, [IntentEvent<AccountEvent<'a, Cow<'a, Transfer>>>]>),
 
     #[event_version("0.3.0")]
+    Gift(Cow<'a, [IntentEvent<AccountEvent<'a, Cow<'a, Gift>>>]>),
+
+    #[event_version("0.3.0")]
+    Allowance(Cow<'a, [IntentEvent<AccountEvent<'a, Cow<'a, Allowance>>>]>),
+
+    #[event_version("0.3.0")]
+    TransferFrom(Cow<'a, [IntentEvent<AccountEvent<'a, Cow<'a, TransferFrom>>>]>),
+
+    #[event_version("0.4.0")]
     TokenDiff(Cow<'a, [IntentEvent<AccountEvent<'a, TokenDiffEvent<'a>>>]>),
 
     #[event_version("0.3.1")]
     IntentsExecuted(Cow<'a, [IntentEvent<AccountEvent<'a, NonceEvent>>]>),
 
+    #[event_version("0.3.0")]
+    IntentExecuted(IntentEvent<AccountEvent<'a, IntentExecutedEvent<'a>>>),
+
     #[event_version("0.3.0")]
     FtWithdraw(Cow<'a, [IntentEvent<AccountEvent<'a, Cow<'a, FtWithdraw>>>]>),
 
     #[event_version("0.3.0")]
     NftWithdraw(Cow<'a, [IntentEvent<AccountEvent<'a, Cow<'a, NftWithdraw>>>]>),
 
-    #[event_version("0.3.0")]
+    #[event_version("0.4.0")]
     MtWithdraw(Cow<'a, [IntentEvent<AccountEvent<'a, Cow<'a, MtWithdraw>>>]>),
 
     #[event_version("0.3.0")]
     NativeWithdraw(Cow<'a, [IntentEvent<AccountEvent<'a, Cow<'a, NativeWithdraw>>>]>),
 
     #[event_version("0.3.0")]
+    UnwrapNear(Cow<'a, [IntentEvent<AccountEvent<'a, Cow<'a, UnwrapNear>>>]>),
+
+    #[event_version("0.4.0")]
     StorageDeposit(Cow<'a, [IntentEvent<AccountEvent<'a, Cow<'a, StorageDeposit>>>]>),
 
     #[event_version("0.3.0")]
@@ -63,6 +96,9 @@ pub enum DefuseEvent<'a> {
 
     #[event_version("0.3.0")]
     SetAuthByPredecessorId(AccountEvent<'a, SetAuthByPredecessorId>),
+
+    #[event_version("0.3.0")]
+    AuthCallValue(AccountEvent<'a, AuthCallValueEvent>),
 }
 
 pub trait DefuseIntentEmit<'a>: Into<DefuseEvent<'a>> {
@@ -73,3 +109,105 @@ pub trait DefuseIntentEmit<'a>: Into<DefuseEvent<'a>> {
 }
 
 impl<'a, T> DefuseIntentEmit<'a> for T where T: Into<DefuseEvent<'a>> {}
+
+#[cfg(test)]
+mod tests {
+    use near_sdk::{
+        AccountIdRef, NearToken,
+        serde_json::{self, json},
+    };
+
+    use crate::{Nonce, accounts::NonceEvent, fees::Pips};
+
+    use super::*;
+
+    #[test]
+    fn envelope() {
+        let account_id = AccountIdRef::new_or_panic("user.near");
+
+        for (event, expected) in [
+            (
+                DefuseEvent::FeeChanged(FeeChangedEvent {
+                    old_fee: Pips::ZERO,
+                    new_fee: Pips::ONE_PIP,
+                }),
+                json!({
+                    "standard": "dip4",
+                    "version": "0.3.0",
+                    "event": "fee_changed",
+                    "data": {
+                        "old_fee": 0,
+                        "new_fee": 1,
+                    },
+                }),
+            ),
+            (
+                DefuseEvent::AccountLocked(AccountEvent::new(account_id, ())),
+                json!({
+                    "standard": "dip4",
+                    "version": "0.3.0",
+                    "event": "account_locked",
+                    "data": {
+                        "account_id": "user.near",
+                    },
+                }),
+            ),
+            (
+                DefuseEvent::IntentsExecuted(
+                    [IntentEvent::new(
+                        AccountEvent::new(account_id, NonceEvent::new(Nonce::default())),
+                        [0; 32],
+                    )]
+                    .as_slice()
+                    .into(),
+                ),
+                json!({
+                    "standard": "dip4",
+                    "version": "0.3.1",
+                    "event": "intents_executed",
+                    "data": [{
+                        "intent_hash": "11111111111111111111111111111111",
+                        "account_id": "user.near",
+                        "nonce": "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=",
+                    }],
+                }),
+            ),
+            (
+                DefuseEvent::StorageDeposit(
+                    [IntentEvent::new(
+                        AccountEvent::new(
+                            account_id,
+                            Cow::Owned(StorageDeposit {
+                                contract_id: "ft.near".parse().unwrap(),
+                                deposit_for_account_id: account_id.to_owned(),
+                                amount: NearToken::from_yoctonear(1),
+                                from_native: true,
+                            }),
+                        ),
+                        [0; 32],
+                    )]
+                    .as_slice()
+                    .into(),
+                ),
+                json!({
+                    "standard": "dip4",
+                    "version": "0.4.0",
+                    "event": "storage_deposit",
+                    "data": [{
+                        "intent_hash": "11111111111111111111111111111111",
+                        "account_id": "user.near",
+                        "contract_id": "ft.near",
+                        "deposit_for_account_id": "user.near",
+                        "amount": "1",
+                        "from_native": true,
+                    }],
+                }),
+            ),
+        ] {
+            assert_eq!(
+                serde_json::from_str::<serde_json::Value>(&event.to_json_string()).unwrap(),
+                expected,
+            );
+        }
+    }
+}
@@ -10,8 +10,11 @@ use near_sdk::{
     borsh::{BorshDeserialize, BorshSchema, BorshSerialize},
     near,
 };
+use serde_with::{DisplayFromStr, serde_as};
 use thiserror::Error as ThisError;
 
+use crate::token_id::TokenId;
+
 #[near(serializers = [borsh, json])]
 #[derive(Debug, Clone)]
 pub struct FeesConfig {
@@ -19,6 +22,43 @@ pub struct FeesConfig {
     pub fee_collector: AccountId,
 }
 
+/// Absolute bounds on the fee taken from a single token, in its own units.
+/// Unset bounds leave the percentage fee as is.
+#[cfg_attr(
+    all(feature = "abi", not(target_arch = "wasm32")),
+    serde_as(schemars = true)
+)]
+#[cfg_attr(
+    not(all(feature = "abi", not(target_arch = "wasm32"))),
+    serde_as(schemars = false)
+)]
+#[near(serializers = [borsh, json])]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FeeLimits {
+    /// Floor for tiny trades, where the percentage fee is negligible
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_fee: Option<u128>,
+
+    /// Cap for huge trades. Takes precedence over `min_fee`
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_fee: Option<u128>,
+}
+
+impl FeeLimits {
+    /// Bounds `fee` taken from `amount`, so that it never exceeds
+    /// the `amount` itself
+    #[must_use]
+    #[inline]
+    pub fn apply(self, fee: u128, amount: u128) -> u128 {
+        let fee = self.min_fee.map_or(fee, |min_fee| fee.max(min_fee));
+        self.max_fee
+            .map_or(fee, |max_fee| fee.min(max_fee))
+            .min(amount)
+    }
+}
+
 /// 1 pip == 1/100th of bip == 0.0001%
 #[near(serializers = [json])]
 #[serde(try_from = "u32")]
@@ -100,6 +140,27 @@ impl Pips {
         Self(Self::MAX.as_pips() - self.as_pips())
     }
 
+    /// Returns fee equivalent to applying `self` and `other` sequentially,
+    /// i.e. `1 - (1 - self) * (1 - other)`, rounded up
+    #[must_use]
+    #[inline]
+    pub fn compose(self, other: Self) -> Self {
+        let (a, b) = (u64::from(self.as_pips()), u64::from(other.as_pips()));
+        let pips = a + b - a * b / u64::from(Self::MAX.as_pips());
+        Self(u32::try_from(pips).unwrap_or_else(|_| unreachable!()))
+    }
+
+    /// Splits `self` into `(share, rest)`, where `share` is `ratio` of `self`
+    /// rounded down, so that `share + rest == self`
+    #[must_use]
+    #[inline]
+    pub fn split(self, ratio: Self) -> (Self, Self) {
+        let share =
+            u64::from(self.as_pips()) * u64::from(ratio.as_pips()) / u64::from(Self::MAX.as_pips());
+        let share = Self(u32::try_from(share).unwrap_or_else(|_| unreachable!()));
+        (share, self - share)
+    }
+
     #[inline]
     pub fn fee(self, amount: u128) -> u128 {
         amount
@@ -213,6 +274,23 @@ pub struct FeeCollectorChangedEvent<'a> {
     pub new_fee_collector: Cow<'a, AccountIdRef>,
 }
 
+#[must_use = "make sure to `.emit()` this event"]
+#[near(serializers = [json])]
+#[derive(Debug, Clone)]
+pub struct ReferralFeeShareChangedEvent {
+    pub old_referral_fee_share: Pips,
+    pub new_referral_fee_share: Pips,
+}
+
+#[must_use = "make sure to `.emit()` this event"]
+#[near(serializers = [json])]
+#[derive(Debug, Clone)]
+pub struct FeeLimitsChangedEvent<'a> {
+    pub token_id: Cow<'a, TokenId>,
+    pub old_fee_limits: FeeLimits,
+    pub new_fee_limits: FeeLimits,
+}
+
 impl BorshDeserialize for Pips {
     fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
         let pips: u32 = near_sdk::borsh::BorshDeserialize::deserialize_reader(reader)?;
@@ -224,3 +302,70 @@ impl BorshDeserialize for Pips {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case(Pips::ZERO, Pips::ZERO, Pips::ZERO)]
+    #[case(Pips::ONE_PIP, Pips::ZERO, Pips::ONE_PIP)]
+    #[case(Pips::MAX, Pips::ONE_PIP, Pips::MAX)]
+    #[case(Pips::MAX, Pips::MAX, Pips::MAX)]
+    // 1 + 1 - 1e-6 pips, rounded up
+    #[case(Pips::ONE_PIP, Pips::ONE_PIP, Pips::from_pips(2).unwrap())]
+    // 50% + 50% - 25%
+    #[case(
+        Pips::from_percent(50).unwrap(),
+        Pips::from_percent(50).unwrap(),
+        Pips::from_percent(75).unwrap(),
+    )]
+    // 1% + 1% - 0.01%
+    #[case(
+        Pips::ONE_PERCENT,
+        Pips::ONE_PERCENT,
+        Pips::from_bips(199).unwrap(),
+    )]
+    fn compose(#[case] a: Pips, #[case] b: Pips, #[case] expected: Pips) {
+        assert_eq!(a.compose(b), expected);
+        assert_eq!(b.compose(a), expected);
+    }
+
+    #[rstest]
+    #[case(FeeLimits::default(), 1, 1_000, 1)]
+    // floor applies to a tiny trade
+    #[case(FeeLimits { min_fee: Some(5), max_fee: None }, 1, 100, 5)]
+    #[case(FeeLimits { min_fee: Some(5), max_fee: None }, 10, 1_000, 10)]
+    // but never exceeds the amount itself
+    #[case(FeeLimits { min_fee: Some(5), max_fee: None }, 1, 3, 3)]
+    // cap applies to a large trade
+    #[case(FeeLimits { min_fee: None, max_fee: Some(1_000) }, 10_000, 1_000_000, 1_000)]
+    #[case(FeeLimits { min_fee: None, max_fee: Some(1_000) }, 10, 1_000, 10)]
+    // cap wins over floor
+    #[case(FeeLimits { min_fee: Some(10), max_fee: Some(5) }, 1, 1_000, 5)]
+    fn fee_limits(
+        #[case] limits: FeeLimits,
+        #[case] fee: u128,
+        #[case] amount: u128,
+        #[case] expected: u128,
+    ) {
+        assert_eq!(limits.apply(fee, amount), expected);
+    }
+
+    #[rstest]
+    #[case(Pips::ZERO, Pips::MAX, Pips::ZERO)]
+    #[case(Pips::ONE_PERCENT, Pips::ZERO, Pips::ZERO)]
+    #[case(Pips::ONE_PERCENT, Pips::MAX, Pips::ONE_PERCENT)]
+    #[case(Pips::ONE_PERCENT, Pips::from_percent(50).unwrap(), Pips::from_bips(50).unwrap())]
+    // 1 pip can't be split, so the share is rounded down
+    #[case(Pips::ONE_PIP, Pips::from_percent(99).unwrap(), Pips::ZERO)]
+    #[case(Pips::from_pips(3).unwrap(), Pips::from_percent(50).unwrap(), Pips::ONE_PIP)]
+    #[case(Pips::MAX, Pips::ONE_PIP, Pips::ONE_PIP)]
+    fn split(#[case] fee: Pips, #[case] ratio: Pips, #[case] expected_share: Pips) {
+        let (share, rest) = fee.split(ratio);
+        assert_eq!(share, expected_share);
+        assert_eq!(share + rest, fee);
+    }
+}
@@ -3,13 +3,14 @@ use near_sdk::{AccountIdRef, CryptoHash, near};
 use serde_with::serde_as;
 
 use crate::{
-    Result,
+    DefuseError, Result,
     accounts::AccountEvent,
-    engine::{Engine, Inspector, State},
+    engine::{Engine, Inspector, State, StateView},
 };
 
 use super::ExecutableIntent;
 
+#[cfg_attr(any(feature = "arbitrary", test), derive(arbitrary::Arbitrary))]
 #[near(serializers = [borsh, json])]
 #[derive(Debug, Clone)]
 /// Given an account id, the user can add public keys. The added public keys can sign
@@ -39,6 +40,7 @@ impl ExecutableIntent for AddPublicKey {
     }
 }
 
+#[cfg_attr(any(feature = "arbitrary", test), derive(arbitrary::Arbitrary))]
 #[near(serializers = [borsh, json])]
 #[derive(Debug, Clone)]
 /// Remove the public key associated with a given account. See `AddPublicKey`.
@@ -64,6 +66,42 @@ impl ExecutableIntent for RemovePublicKey {
     }
 }
 
+#[cfg_attr(any(feature = "arbitrary", test), derive(arbitrary::Arbitrary))]
+#[near(serializers = [borsh, json])]
+#[derive(Debug, Clone)]
+/// Atomically replace `old` public key with `new` one for the signer.
+/// Fails if `old` public key doesn't exist or `new` one was already added.
+/// See `AddPublicKey`.
+pub struct RotatePublicKey {
+    pub old: PublicKey,
+    pub new: PublicKey,
+}
+
+impl ExecutableIntent for RotatePublicKey {
+    #[inline]
+    fn execute_intent<S, I>(
+        self,
+        signer_id: &AccountIdRef,
+        engine: &mut Engine<S, I>,
+        _intent_hash: CryptoHash,
+    ) -> Result<()>
+    where
+        S: State,
+        I: Inspector,
+    {
+        // check in advance, so that `old` is not removed if `new` can't be added
+        if engine.state.has_public_key(signer_id, &self.new) {
+            return Err(DefuseError::PublicKeyExists(signer_id.to_owned(), self.new));
+        }
+
+        engine
+            .state
+            .remove_public_key(signer_id.to_owned(), self.old)?;
+        engine.state.add_public_key(signer_id.to_owned(), self.new)
+    }
+}
+
+#[cfg_attr(any(feature = "arbitrary", test), derive(arbitrary::Arbitrary))]
 #[cfg_attr(
     all(feature = "abi", not(target_arch = "wasm32")),
     serde_as(schemars = true)
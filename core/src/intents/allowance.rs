@@ -0,0 +1,143 @@
+use std::borrow::Cow;
+
+use near_sdk::{AccountId, AccountIdRef, CryptoHash, near};
+use serde_with::{DisplayFromStr, serde_as};
+
+use crate::{
+    DefuseError, Result,
+    accounts::AccountEvent,
+    engine::{Engine, Inspector, State, StateView},
+    events::DefuseEvent,
+    token_id::TokenId,
+};
+
+#[cfg(any(feature = "arbitrary", test))]
+use super::bounded;
+use super::{ExecutableIntent, IntentEvent, tokens::Transfer};
+
+#[cfg_attr(any(feature = "arbitrary", test), derive(arbitrary::Arbitrary))]
+#[cfg_attr(
+    all(feature = "abi", not(target_arch = "wasm32")),
+    serde_as(schemars = true)
+)]
+#[cfg_attr(
+    not(all(feature = "abi", not(target_arch = "wasm32"))),
+    serde_as(schemars = false)
+)]
+#[near(serializers = [borsh, json])]
+#[derive(Debug, Clone)]
+/// Allow `delegate` to move up to `amount` of `token_id` from the signer's
+/// balance via [`TransferFrom`], without having access to signer's keys.
+/// Overrides the previous allowance, zero `amount` revokes it.
+pub struct Allowance {
+    #[cfg_attr(any(feature = "arbitrary", test), arbitrary(with = bounded::account_id))]
+    pub delegate: AccountId,
+
+    pub token_id: TokenId,
+
+    #[serde_as(as = "DisplayFromStr")]
+    pub amount: u128,
+}
+
+impl Allowance {
+    /// Maximum number of non-zero allowances a single account can have
+    /// granted at once, since storage for them is not paid by the owner
+    pub const MAX_PER_ACCOUNT: u32 = 32;
+}
+
+impl ExecutableIntent for Allowance {
+    fn execute_intent<S, I>(
+        self,
+        signer_id: &AccountIdRef,
+        engine: &mut Engine<S, I>,
+        intent_hash: CryptoHash,
+    ) -> Result<()>
+    where
+        S: State,
+        I: Inspector,
+    {
+        if signer_id == self.delegate {
+            return Err(DefuseError::InvalidIntent);
+        }
+
+        engine
+            .inspector
+            .on_event(DefuseEvent::Allowance(Cow::Borrowed(
+                [IntentEvent::new(
+                    AccountEvent::new(signer_id, Cow::Borrowed(&self)),
+                    intent_hash,
+                )]
+                .as_slice(),
+            )));
+
+        engine.state.set_allowance(
+            signer_id.to_owned(),
+            self.delegate,
+            self.token_id,
+            self.amount,
+        )
+    }
+}
+
+#[cfg_attr(any(feature = "arbitrary", test), derive(arbitrary::Arbitrary))]
+#[near(serializers = [borsh, json])]
+#[derive(Debug, Clone)]
+/// Transfer a set of tokens from `owner_id` to a specified account id on
+/// behalf of the signer, who acts as a delegate. Moves balances exactly like
+/// [`Transfer`] signed by `owner_id`, but first spends allowances previously
+/// granted by `owner_id` to the signer via [`Allowance`] intent.
+pub struct TransferFrom {
+    #[cfg_attr(any(feature = "arbitrary", test), arbitrary(with = bounded::account_id))]
+    pub owner_id: AccountId,
+
+    #[serde(flatten)]
+    pub transfer: Transfer,
+}
+
+impl ExecutableIntent for TransferFrom {
+    fn execute_intent<S, I>(
+        self,
+        signer_id: &AccountIdRef,
+        engine: &mut Engine<S, I>,
+        intent_hash: CryptoHash,
+    ) -> Result<()>
+    where
+        S: State,
+        I: Inspector,
+    {
+        if signer_id == self.owner_id {
+            return Err(DefuseError::InvalidIntent);
+        }
+        self.transfer.check(&self.owner_id)?;
+
+        engine
+            .inspector
+            .on_event(DefuseEvent::TransferFrom(Cow::Borrowed(
+                [IntentEvent::new(
+                    AccountEvent::new(signer_id, Cow::Borrowed(&self)),
+                    intent_hash,
+                )]
+                .as_slice(),
+            )));
+
+        for (token_id, &amount) in &self.transfer.tokens {
+            let allowance = engine
+                .state
+                .allowance(&self.owner_id, signer_id, token_id)
+                .checked_sub(amount)
+                .ok_or_else(|| DefuseError::AllowanceExceeded {
+                    owner_id: self.owner_id.clone(),
+                    delegate_id: signer_id.to_owned(),
+                    token_id: token_id.clone(),
+                })?;
+            engine.state.set_allowance(
+                self.owner_id.clone(),
+                signer_id.to_owned(),
+                token_id.clone(),
+                allowance,
+            )?;
+        }
+
+        self.transfer.apply(&self.owner_id, &mut engine.state)
+    }
+}
@@ -4,11 +4,16 @@ use serde_with::serde_as;
 use crate::{
     Result,
     engine::{Engine, Inspector, State},
-    intents::ExecutableIntent,
+    intents::{ExecutableIntent, tokens::wnear_withdrawn},
+    token_id::TokenId,
 };
 
+#[cfg(any(feature = "arbitrary", test))]
+use super::bounded;
+
 /// Call [`.on_auth`](::defuse_auth_call::AuthCallee::on_auth) with `signer_id`
 /// of intent.
+#[cfg_attr(any(feature = "arbitrary", test), derive(arbitrary::Arbitrary))]
 #[cfg_attr(
     all(feature = "abi", not(target_arch = "wasm32")),
     serde_as(schemars = true)
@@ -21,6 +26,7 @@ use crate::{
 #[derive(Debug, Clone)]
 pub struct AuthCall {
     /// Callee for [`.on_auth`](::defuse_auth_call::AuthCallee::on_auth)
+    #[cfg_attr(any(feature = "arbitrary", test), arbitrary(with = bounded::account_id))]
     pub contract_id: AccountId,
 
     /// `msg` to pass in [`.on_auth`](::defuse_auth_call::AuthCallee::on_auth)
@@ -32,6 +38,7 @@ pub struct AuthCall {
     ///
     /// NOTE: the `wNEAR` will not be refunded in case of fail.
     #[serde(default, skip_serializing_if = "NearToken::is_zero")]
+    #[cfg_attr(any(feature = "arbitrary", test), arbitrary(with = bounded::near_token))]
     pub attached_deposit: NearToken,
 
     /// Optional minimum gas required for created promise to succeed.
@@ -41,7 +48,23 @@ pub struct AuthCall {
     /// Remaining gas will be distributed evenly across all Function Call
     /// Promises created during execution of current receipt.
     #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(any(feature = "arbitrary", test), arbitrary(with = bounded::optional_gas))]
     pub min_gas: Option<Gas>,
+
+    /// Call [`.on_auth_value`](::defuse_auth_call::AuthValueCallee::on_auth_value)
+    /// instead and emit its result in [`AuthCallValueEvent`]
+    #[serde(default, skip_serializing_if = "::core::ops::Not::not")]
+    pub return_value: bool,
+}
+
+/// Result of [`.on_auth_value`](::defuse_auth_call::AuthValueCallee::on_auth_value)
+#[near(serializers = [json])]
+#[derive(Debug, Clone)]
+pub struct AuthCallValueEvent {
+    pub contract_id: AccountId,
+
+    /// `None` if the call has failed
+    pub value: Option<String>,
 }
 
 impl AuthCall {
@@ -51,6 +74,16 @@ impl AuthCall {
     pub fn min_gas(&self) -> Gas {
         self.min_gas.unwrap_or(Self::MIN_GAS_DEFAULT)
     }
+
+    /// Returns tokens withdrawn from the signer's internal balance,
+    /// i.e. `wNEAR` for non-zero `attached_deposit`
+    #[inline]
+    pub fn withdrawn_tokens(&self, wnear_id: &AccountIdRef) -> Vec<(TokenId, u128)> {
+        if self.attached_deposit.is_zero() {
+            return Vec::new();
+        }
+        vec![wnear_withdrawn(wnear_id, self.attached_deposit)]
+    }
 }
 
 impl ExecutableIntent for AuthCall {
@@ -64,6 +97,8 @@ impl ExecutableIntent for AuthCall {
         S: State,
         I: Inspector,
     {
+        engine.inspector.on_promise_gas(self.min_gas());
+
         engine.state.auth_call(signer_id, self)
     }
 }
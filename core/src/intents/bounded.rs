@@ -0,0 +1,110 @@
+//! Bounded generators for [`Arbitrary`] implementations of intents, so
+//! that generated batches are valid and can't overflow balances on their own
+
+use arbitrary_with::{Arbitrary, LimitLen, Result, Unstructured, UnstructuredExt};
+use defuse_near_utils::arbitrary::ArbitraryAccountId;
+use near_sdk::{AccountId, Gas, NearToken, json_types::U128};
+
+use crate::{amounts::Amounts, token_id::MAX_ALLOWED_TOKEN_ID_LEN};
+
+use super::{Intent, token_diff::TokenDeltas, tokens::MtWithdraw};
+
+/// Maximum number of intents in generated [`DefuseIntents`](super::DefuseIntents)
+pub const MAX_INTENTS: usize = 8;
+/// Maximum number of tokens in generated [`Amounts`] or `token_ids`
+pub const MAX_TOKENS: usize = 4;
+/// Maximum generated token amount, so that sums within a batch can't overflow
+pub const MAX_AMOUNT: u128 = 1 << 64;
+/// Maximum generated `min_gas`, i.e. max prepaid gas of a transaction
+pub const MAX_GAS: Gas = Gas::from_tgas(300);
+
+pub fn intents(u: &mut Unstructured<'_>) -> Result<Vec<Intent>> {
+    let len = u.int_in_range(0..=MAX_INTENTS)?;
+    (0..len).map(|_| u.arbitrary()).collect()
+}
+
+/// Non-zero amount up to [`MAX_AMOUNT`]
+pub fn amount(u: &mut Unstructured<'_>) -> Result<u128> {
+    u.int_in_range(1..=MAX_AMOUNT)
+}
+
+pub fn u128_amount(u: &mut Unstructured<'_>) -> Result<U128> {
+    amount(u).map(U128)
+}
+
+pub fn near_token(u: &mut Unstructured<'_>) -> Result<NearToken> {
+    amount(u).map(NearToken::from_yoctonear)
+}
+
+pub fn optional_near_token(u: &mut Unstructured<'_>) -> Result<Option<NearToken>> {
+    optional(u, near_token)
+}
+
+pub fn optional_gas(u: &mut Unstructured<'_>) -> Result<Option<Gas>> {
+    optional(u, |u| {
+        u.int_in_range(0..=MAX_GAS.as_gas()).map(Gas::from_gas)
+    })
+}
+
+pub fn account_id(u: &mut Unstructured<'_>) -> Result<AccountId> {
+    u.arbitrary_as::<_, ArbitraryAccountId>()
+}
+
+pub fn optional_account_id(u: &mut Unstructured<'_>) -> Result<Option<AccountId>> {
+    optional(u, account_id)
+}
+
+/// NFT or MT token id of allowed length
+pub fn token_id(u: &mut Unstructured<'_>) -> Result<String> {
+    u.arbitrary_as::<_, LimitLen<MAX_ALLOWED_TOKEN_ID_LEN>>()
+}
+
+/// Non-empty set of up to [`MAX_TOKENS`] tokens with non-zero amounts
+pub fn amounts(u: &mut Unstructured<'_>) -> Result<Amounts> {
+    let len = u.int_in_range(1..=MAX_TOKENS)?;
+    (0..len)
+        .map(|_| -> Result<_> { Ok((u.arbitrary()?, amount(u)?)) })
+        .collect::<Result<_>>()
+        .map(Amounts::new)
+}
+
+/// Non-empty set of up to [`MAX_TOKENS`] tokens with non-zero deltas
+pub fn token_deltas(u: &mut Unstructured<'_>) -> Result<TokenDeltas> {
+    let len = u.int_in_range(1..=MAX_TOKENS)?;
+    (0..len)
+        .map(|_| -> Result<_> {
+            let delta = i128::try_from(amount(u)?).unwrap_or_else(|_| unreachable!());
+            Ok((u.arbitrary()?, if u.arbitrary()? { delta } else { -delta }))
+        })
+        .collect::<Result<_>>()
+        .map(Amounts::new)
+}
+
+fn optional<'a, T>(
+    u: &mut Unstructured<'a>,
+    f: impl FnOnce(&mut Unstructured<'a>) -> Result<T>,
+) -> Result<Option<T>> {
+    if u.arbitrary()? {
+        f(u).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+/// Keeps lengths of `token_ids` and `amounts` consistent,
+/// see [`MtWithdraw::is_valid`]
+impl<'a> Arbitrary<'a> for MtWithdraw {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let len = u.int_in_range(1..=MAX_TOKENS)?;
+        Ok(Self {
+            token: account_id(u)?,
+            receiver_id: account_id(u)?,
+            token_ids: (0..len).map(|_| token_id(u)).collect::<Result<_>>()?,
+            amounts: (0..len).map(|_| u128_amount(u)).collect::<Result<_>>()?,
+            memo: u.arbitrary()?,
+            msg: u.arbitrary()?,
+            storage_deposit: optional_near_token(u)?,
+            min_gas: optional_gas(u)?,
+        })
+    }
+}
@@ -1,26 +1,36 @@
 pub mod account;
+pub mod allowance;
 pub mod auth;
 pub mod token_diff;
 pub mod tokens;
 
+#[cfg(any(feature = "arbitrary", test))]
+pub mod bounded;
+
+use std::borrow::Cow;
+
 use defuse_serde_utils::base58::Base58;
 use derive_more::derive::From;
-use near_sdk::{AccountIdRef, CryptoHash, near};
+use near_sdk::{AccountIdRef, CryptoHash, env, near, serde_json};
 use serde_with::serde_as;
-use tokens::{NativeWithdraw, StorageDeposit};
+use tokens::{NativeWithdraw, RequireBalance, StorageDeposit, StorageDepositSelf, UnwrapNear};
 
 use crate::{
     Result,
-    engine::{Engine, Inspector, State},
+    accounts::AccountEvent,
+    engine::{Engine, Inspector, State, StateView},
+    events::DefuseEvent,
     intents::{account::SetAuthByPredecessorId, auth::AuthCall},
 };
 
 use self::{
-    account::{AddPublicKey, RemovePublicKey},
+    account::{AddPublicKey, RemovePublicKey, RotatePublicKey},
+    allowance::{Allowance, TransferFrom},
     token_diff::TokenDiff,
-    tokens::{FtWithdraw, MtWithdraw, NftWithdraw, Transfer},
+    tokens::{FtWithdraw, Gift, MtWithdraw, NftWithdraw, Transfer},
 };
 
+#[cfg_attr(any(feature = "arbitrary", test), derive(arbitrary::Arbitrary))]
 #[near(serializers = [json])]
 #[derive(Debug, Clone)]
 pub struct DefuseIntents {
@@ -29,12 +39,15 @@ pub struct DefuseIntents {
     /// the `nonce` for the signer
     /// WARNING: Promises created by different intents are executed concurrently and does not rely on the order of the intents in this structure
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[cfg_attr(any(feature = "arbitrary", test), arbitrary(with = bounded::intents))]
     pub intents: Vec<Intent>,
 }
 
+#[cfg_attr(any(feature = "arbitrary", test), derive(arbitrary::Arbitrary))]
 #[near(serializers = [json])]
 #[serde(tag = "intent", rename_all = "snake_case")]
-#[derive(Debug, Clone, From)]
+#[derive(Debug, Clone, From, strum::IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
 pub enum Intent {
     /// See [`AddPublicKey`]
     AddPublicKey(AddPublicKey),
@@ -42,9 +55,21 @@ pub enum Intent {
     /// See [`RemovePublicKey`]
     RemovePublicKey(RemovePublicKey),
 
+    /// See [`RotatePublicKey`]
+    RotatePublicKey(RotatePublicKey),
+
     /// See [`Transfer`]
     Transfer(Transfer),
 
+    /// See [`Gift`]
+    Gift(Gift),
+
+    /// See [`Allowance`]
+    Allowance(Allowance),
+
+    /// See [`TransferFrom`]
+    TransferFrom(TransferFrom),
+
     /// See [`FtWithdraw`]
     FtWithdraw(FtWithdraw),
 
@@ -57,9 +82,15 @@ pub enum Intent {
     /// See [`NativeWithdraw`]
     NativeWithdraw(NativeWithdraw),
 
+    /// See [`UnwrapNear`]
+    UnwrapNear(UnwrapNear),
+
     /// See [`StorageDeposit`]
     StorageDeposit(StorageDeposit),
 
+    /// See [`StorageDepositSelf`]
+    StorageDepositSelf(StorageDepositSelf),
+
     /// See [`TokenDiff`]
     TokenDiff(TokenDiff),
 
@@ -68,6 +99,21 @@ pub enum Intent {
 
     /// See [`AuthCall`]
     AuthCall(AuthCall),
+
+    /// See [`RequireBalance`]
+    RequireBalance(RequireBalance),
+}
+
+impl DefuseIntents {
+    /// Hash of the intents emitted as `intent_hash` in [`IntentEvent`]s
+    /// when they are executed by `PREDECESSOR_ID` without a signed payload.
+    /// For signed payloads, it's [`Payload::hash`](defuse_crypto::Payload::hash)
+    /// of the whole [`MultiPayload`](crate::payload::multi::MultiPayload)
+    /// instead.
+    #[inline]
+    pub fn hash(&self) -> Result<CryptoHash> {
+        Ok(env::sha256_array(&serde_json::to_vec(self)?))
+    }
 }
 
 pub trait ExecutableIntent {
@@ -93,8 +139,24 @@ impl ExecutableIntent for DefuseIntents {
         S: State,
         I: Inspector,
     {
-        for intent in self.intents {
+        let verbose = engine.state.verbose_events();
+        for (index, intent) in self.intents.into_iter().enumerate() {
+            let kind: &'static str = (&intent).into();
             intent.execute_intent(signer_id, engine, intent_hash)?;
+            if verbose {
+                engine
+                    .inspector
+                    .on_event(DefuseEvent::IntentExecuted(IntentEvent::new(
+                        AccountEvent::new(
+                            signer_id,
+                            IntentExecutedEvent {
+                                index,
+                                kind: Cow::Borrowed(kind),
+                            },
+                        ),
+                        intent_hash,
+                    )));
+            }
         }
         Ok(())
     }
@@ -114,17 +176,26 @@ impl ExecutableIntent for Intent {
         match self {
             Self::AddPublicKey(intent) => intent.execute_intent(signer_id, engine, intent_hash),
             Self::RemovePublicKey(intent) => intent.execute_intent(signer_id, engine, intent_hash),
+            Self::RotatePublicKey(intent) => intent.execute_intent(signer_id, engine, intent_hash),
             Self::Transfer(intent) => intent.execute_intent(signer_id, engine, intent_hash),
+            Self::Gift(intent) => intent.execute_intent(signer_id, engine, intent_hash),
+            Self::Allowance(intent) => intent.execute_intent(signer_id, engine, intent_hash),
+            Self::TransferFrom(intent) => intent.execute_intent(signer_id, engine, intent_hash),
             Self::FtWithdraw(intent) => intent.execute_intent(signer_id, engine, intent_hash),
             Self::NftWithdraw(intent) => intent.execute_intent(signer_id, engine, intent_hash),
             Self::MtWithdraw(intent) => intent.execute_intent(signer_id, engine, intent_hash),
             Self::NativeWithdraw(intent) => intent.execute_intent(signer_id, engine, intent_hash),
+            Self::UnwrapNear(intent) => intent.execute_intent(signer_id, engine, intent_hash),
             Self::StorageDeposit(intent) => intent.execute_intent(signer_id, engine, intent_hash),
+            Self::StorageDepositSelf(intent) => {
+                intent.execute_intent(signer_id, engine, intent_hash)
+            }
             Self::TokenDiff(intent) => intent.execute_intent(signer_id, engine, intent_hash),
             Self::SetAuthByPredecessorId(intent) => {
                 intent.execute_intent(signer_id, engine, intent_hash)
             }
             Self::AuthCall(intent) => intent.execute_intent(signer_id, engine, intent_hash),
+            Self::RequireBalance(intent) => intent.execute_intent(signer_id, engine, intent_hash),
         }
     }
 }
@@ -141,6 +212,7 @@ impl ExecutableIntent for Intent {
 #[near(serializers = [json])]
 #[derive(Debug, Clone)]
 pub struct IntentEvent<T> {
+    /// See [`DefuseIntents::hash`]
     #[serde_as(as = "Base58")]
     pub intent_hash: CryptoHash,
 
@@ -154,3 +226,16 @@ impl<T> IntentEvent<T> {
         Self { intent_hash, event }
     }
 }
+
+/// Emitted for each intent in [`DefuseIntents`] in the order of
+/// processing, if verbose events are enabled. Note that promises created
+/// by these intents can still be resolved in a different order.
+#[must_use = "make sure to `.emit()` this event"]
+#[near(serializers = [json])]
+#[derive(Debug, Clone)]
+pub struct IntentExecutedEvent<'a> {
+    /// Index of the intent in [`DefuseIntents::intents`]
+    pub index: usize,
+    /// Name of the intent, e.g. `transfer`
+    pub kind: Cow<'a, str>,
+}
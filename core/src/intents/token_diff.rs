@@ -1,3 +1,5 @@
+#[cfg(any(feature = "arbitrary", test))]
+use super::bounded;
 use super::{ExecutableIntent, IntentEvent};
 use crate::{
     DefuseError, Result,
@@ -5,17 +7,21 @@ use crate::{
     amounts::Amounts,
     engine::{Engine, Inspector, State, StateView},
     events::DefuseEvent,
-    fees::Pips,
+    fees::{FeeLimits, Pips},
     token_id::{TokenId, TokenIdType},
 };
 use defuse_num_utils::CheckedMulDiv;
 use impl_tools::autoimpl;
 use near_sdk::{AccountId, AccountIdRef, CryptoHash, near};
 use serde_with::{DisplayFromStr, serde_as};
-use std::{borrow::Cow, collections::BTreeMap};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap},
+};
 
 pub type TokenDeltas = Amounts<BTreeMap<TokenId, i128>>;
 
+#[cfg_attr(any(feature = "arbitrary", test), derive(arbitrary::Arbitrary))]
 #[cfg_attr(
     all(feature = "abi", not(target_arch = "wasm32")),
     serde_as(schemars = true)
@@ -35,12 +41,14 @@ pub type TokenDeltas = Amounts<BTreeMap<TokenId, i128>>;
 /// not just token A for token B.
 pub struct TokenDiff {
     #[serde_as(as = "Amounts<BTreeMap<_, DisplayFromStr>>")]
+    #[cfg_attr(any(feature = "arbitrary", test), arbitrary(with = bounded::token_deltas))]
     pub diff: TokenDeltas,
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub memo: Option<String>,
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(any(feature = "arbitrary", test), arbitrary(with = bounded::optional_account_id))]
     pub referral: Option<AccountId>,
 }
 
@@ -60,7 +68,13 @@ impl ExecutableIntent for TokenDiff {
         }
 
         let protocol_fee = engine.state.fee();
+        let referral_fee_share = if self.referral.is_some() {
+            engine.state.referral_fee_share()
+        } else {
+            Pips::ZERO
+        };
         let mut fees_collected: Amounts = Amounts::default();
+        let mut referral_fees: Amounts = Amounts::default();
 
         for (token_id, delta) in &self.diff {
             if *delta == 0 {
@@ -75,12 +89,29 @@ impl ExecutableIntent for TokenDiff {
             // take fees only from negative deltas (i.e. token_in)
             if *delta < 0 {
                 let amount = delta.unsigned_abs();
-                let fee = Self::token_fee(token_id, amount, protocol_fee).fee_ceil(amount);
+                let token_fee = Self::token_fee(token_id, amount, protocol_fee);
+                let mut fee = token_fee.fee_ceil(amount);
+
+                // route part of the fee to referral, rounded down so that
+                // it never exceeds the collected fee
+                let (referral_fee, _) = token_fee.split(referral_fee_share);
+                let mut referral_fee = referral_fee.fee(amount);
+
+                if !token_fee.is_zero() {
+                    let limited = engine.state.fee_limits(token_id).apply(fee, amount);
+                    if limited != fee {
+                        fee = limited;
+                        referral_fee = referral_fee_share.fee(fee);
+                    }
+                }
 
                 // collect fee
                 fees_collected
                     .add(token_id.clone(), fee)
                     .ok_or(DefuseError::BalanceOverflow)?;
+                referral_fees
+                    .add(token_id.clone(), referral_fee)
+                    .ok_or(DefuseError::BalanceOverflow)?;
             }
         }
 
@@ -91,6 +122,7 @@ impl ExecutableIntent for TokenDiff {
                     TokenDiffEvent {
                         diff: Cow::Borrowed(&self),
                         fees_collected: fees_collected.clone(),
+                        referral_fees: referral_fees.clone(),
                     },
                 ),
                 intent_hash,
@@ -99,7 +131,15 @@ impl ExecutableIntent for TokenDiff {
             .into(),
         ));
 
-        // deposit fees to collector
+        // deposit referral fees
+        if let Some(referral) = self.referral.filter(|_| !referral_fees.is_empty()) {
+            fees_collected = fees_collected
+                .with_sub_many(referral_fees.iter().map(|(t, a)| (t.clone(), *a)))
+                .ok_or(DefuseError::BalanceOverflow)?;
+            engine.state.internal_add_balance(referral, referral_fees)?;
+        }
+
+        // deposit the rest of fees to collector
         if !fees_collected.is_empty() {
             engine
                 .state
@@ -128,67 +168,103 @@ pub struct TokenDiffEvent<'a> {
     #[serde_as(as = "Amounts<BTreeMap<_, DisplayFromStr>>")]
     #[serde(skip_serializing_if = "Amounts::is_empty")]
     pub fees_collected: Amounts,
+
+    /// Part of `fees_collected` which went to `referral`
+    #[serde_as(as = "Amounts<BTreeMap<_, DisplayFromStr>>")]
+    #[serde(default, skip_serializing_if = "Amounts::is_empty")]
+    pub referral_fees: Amounts,
 }
 
 impl TokenDiff {
     /// Returns [`TokenDiff`] closure to successfully execute `self`
-    /// assuming given `fee`
+    /// assuming given `fee` and `fee_limits`
     #[inline]
-    pub fn closure(self, fee: Pips) -> Option<TokenDeltas> {
-        Self::closure_deltas(self.diff.into_inner(), fee)
+    pub fn closure(
+        self,
+        fee: Pips,
+        fee_limits: &HashMap<TokenId, FeeLimits>,
+    ) -> Option<TokenDeltas> {
+        Self::closure_deltas(self.diff.into_inner(), fee, fee_limits)
     }
 
     /// Returns [`TokenDiff`] closure to successfully execute given set
-    /// of distinct [`TokenDiff`] assuming given `fee`
+    /// of distinct [`TokenDiff`] assuming given `fee` and `fee_limits`
     #[inline]
-    pub fn closure_many(diffs: impl IntoIterator<Item = Self>, fee: Pips) -> Option<TokenDeltas> {
-        Self::closure_deltas(diffs.into_iter().flat_map(|d| d.diff.into_inner()), fee)
+    pub fn closure_many(
+        diffs: impl IntoIterator<Item = Self>,
+        fee: Pips,
+        fee_limits: &HashMap<TokenId, FeeLimits>,
+    ) -> Option<TokenDeltas> {
+        Self::closure_deltas(
+            diffs.into_iter().flat_map(|d| d.diff.into_inner()),
+            fee,
+            fee_limits,
+        )
     }
 
     /// Returns closure for deltas that should be given in a single
     /// [`TokenDiff`] to successfully execute given set of distinct `deltas`
-    /// assuming given `fee`
+    /// assuming given `fee` and per-token `fee_limits`
     #[inline]
     pub fn closure_deltas(
         deltas: impl IntoIterator<Item = (TokenId, i128)>,
         fee: Pips,
+        fee_limits: &HashMap<TokenId, FeeLimits>,
     ) -> Option<TokenDeltas> {
+        let limits_for = |token_id: &TokenId| fee_limits.get(token_id).copied().unwrap_or_default();
+
         deltas
             .into_iter()
             // collect total supply deltas
             .try_fold(TokenDeltas::default(), |deltas, (token_id, delta)| {
-                let supply_delta = Self::supply_delta(&token_id, delta, fee)?;
+                let supply_delta =
+                    Self::supply_delta(&token_id, delta, fee, limits_for(&token_id))?;
                 deltas.with_apply_delta(token_id, supply_delta)
             })?
             .into_inner()
             .into_iter()
             // calculate closures from total supply deltas
             .try_fold(TokenDeltas::default(), |deltas, (token_id, delta)| {
-                let closure = Self::closure_supply_delta(&token_id, delta, fee)?;
+                let closure =
+                    Self::closure_supply_delta(&token_id, delta, fee, limits_for(&token_id))?;
                 deltas.with_apply_delta(token_id, closure)
             })
     }
 
     /// Returns closure for delta that should be given in a single
     /// [`TokenDiff`] to successfully execute [`TokenDiff`] with given
-    /// `delta` on the same token assuming given `fee`.
+    /// `delta` on the same token assuming given `fee` and `fee_limits`.
     #[inline]
-    pub fn closure_delta(token_id: &TokenId, delta: i128, fee: Pips) -> Option<i128> {
-        Self::closure_supply_delta(token_id, Self::supply_delta(token_id, delta, fee)?, fee)
+    pub fn closure_delta(
+        token_id: &TokenId,
+        delta: i128,
+        fee: Pips,
+        fee_limits: FeeLimits,
+    ) -> Option<i128> {
+        Self::closure_supply_delta(
+            token_id,
+            Self::supply_delta(token_id, delta, fee, fee_limits)?,
+            fee,
+            fee_limits,
+        )
     }
 
     /// Returns total supply delta from token delta
     #[inline]
-    fn supply_delta(token_id: &TokenId, delta: i128, fee: Pips) -> Option<i128> {
+    fn supply_delta(
+        token_id: &TokenId,
+        delta: i128,
+        fee: Pips,
+        fee_limits: FeeLimits,
+    ) -> Option<i128> {
         if delta < 0 {
             // fee is taken only on negative deltas (i.e. token_in)
-            delta.checked_mul_div_ceil(
-                Self::token_fee(token_id, delta.unsigned_abs(), fee)
-                    .invert()
-                    .as_pips()
-                    .into(),
-                Pips::MAX.as_pips().into(),
-            )
+            delta.checked_add_unsigned(Self::fee_amount(
+                token_id,
+                delta.unsigned_abs(),
+                fee,
+                fee_limits,
+            ))
         } else {
             // token_out
             Some(delta)
@@ -197,30 +273,73 @@ impl TokenDiff {
 
     /// Returns closure for total supply delta that should be given in
     /// a single [`TokenDiff`] to successfully execute [`TokenDiff`] with
-    /// given `delta` on the same token assuming given `fee`.
+    /// given `delta` on the same token assuming given `fee` and
+    /// `fee_limits`.
     #[inline]
-    pub fn closure_supply_delta(token_id: &TokenId, delta: i128, fee: Pips) -> Option<i128> {
+    pub fn closure_supply_delta(
+        token_id: &TokenId,
+        delta: i128,
+        fee: Pips,
+        fee_limits: FeeLimits,
+    ) -> Option<i128> {
         let closure = delta.checked_neg()?;
-        if closure < 0 {
-            // fee is taken only on negative deltas (i.e. token_in)
-            closure.checked_mul_div_euclid(
-                Pips::MAX.as_pips().into(),
-                Self::token_fee(token_id, delta.unsigned_abs(), fee)
-                    .invert()
-                    .as_pips()
-                    .into(),
-            )
-        } else {
+        if closure >= 0 {
             // token_out
-            Some(closure)
+            return Some(closure);
         }
+
+        // fee is taken only on negative deltas (i.e. token_in)
+        let unlimited = closure.checked_mul_div_euclid(
+            Pips::MAX.as_pips().into(),
+            Self::token_fee(token_id, delta.unsigned_abs(), fee)
+                .invert()
+                .as_pips()
+                .into(),
+        )?;
+
+        // The smallest amount that covers `delta` after fees is either
+        // in the range where percentage fee applies or at one of the
+        // limits, so it's enough to check only these candidates
+        let net = delta.unsigned_abs();
+        [
+            Some(unlimited.unsigned_abs()),
+            fee_limits
+                .min_fee
+                .map(|min_fee| {
+                    fee_limits
+                        .max_fee
+                        .map_or(min_fee, |max_fee| min_fee.min(max_fee))
+                })
+                .and_then(|min_fee| net.checked_add(min_fee)),
+            fee_limits
+                .max_fee
+                .and_then(|max_fee| net.checked_add(max_fee)),
+        ]
+        .into_iter()
+        .flatten()
+        .filter(|&amount| amount - Self::fee_amount(token_id, amount, fee, fee_limits) >= net)
+        .min()
+        .and_then(|amount| i128::try_from(amount).ok())
+        .and_then(i128::checked_neg)
+    }
+
+    /// Returns fee taken from `amount` of `token_in` assuming given `fee`
+    /// and `fee_limits`
+    #[inline]
+    pub fn fee_amount(token_id: &TokenId, amount: u128, fee: Pips, fee_limits: FeeLimits) -> u128 {
+        let token_fee = Self::token_fee(token_id, amount, fee);
+        if token_fee.is_zero() {
+            // tokens exempt from percentage fee are exempt from limits too
+            return 0;
+        }
+        fee_limits.apply(token_fee.fee_ceil(amount), amount)
     }
 
     #[inline]
     pub fn token_fee(token_id: impl Into<TokenIdType>, amount: u128, fee: Pips) -> Pips {
         let token_id = token_id.into();
         match token_id {
-            TokenIdType::Nep141 => {}
+            TokenIdType::Nep141 | TokenIdType::Native => {}
             TokenIdType::Nep245 if amount > 1 => {}
 
             // do not take fees on NFTs and MTs with |delta| <= 1
@@ -261,22 +380,29 @@ mod tests {
             Pips::ONE_PERCENT * 50,
         )]
         fee: Pips,
+        #[values(
+            FeeLimits::default(),
+            FeeLimits { min_fee: Some(500), max_fee: None },
+            FeeLimits { min_fee: None, max_fee: Some(3) },
+            FeeLimits { min_fee: Some(500), max_fee: Some(3) },
+        )]
+        fee_limits: FeeLimits,
     ) {
         let (token_id, delta) = token_delta;
-        let closure = TokenDiff::closure_delta(&token_id, delta, fee).unwrap();
+        let closure = TokenDiff::closure_delta(&token_id, delta, fee, fee_limits).unwrap();
 
         assert_eq!(
-            TokenDiff::supply_delta(&token_id, delta, fee).unwrap()
-                + TokenDiff::supply_delta(&token_id, closure, fee).unwrap(),
+            TokenDiff::supply_delta(&token_id, delta, fee, fee_limits).unwrap()
+                + TokenDiff::supply_delta(&token_id, closure, fee, fee_limits).unwrap(),
             0,
-            "invariant violated for {token_id}: delta: {delta}, closure: {closure}, fee: {fee}",
+            "invariant violated for {token_id}: delta: {delta}, closure: {closure}, fee: {fee}, fee_limits: {fee_limits:?}",
         );
     }
 
     #[test]
     fn closure_deltas_empty() {
         assert!(
-            TokenDiff::closure_deltas(None, Pips::ONE_BIP)
+            TokenDiff::closure_deltas(None, Pips::ONE_BIP, &HashMap::new())
                 .unwrap()
                 .is_empty()
         );
@@ -314,14 +440,24 @@ mod tests {
                     ]
                     .into_iter()
                     .flatten(),
-                    fee
+                    fee,
+                    &HashMap::new(),
                 )
                 .unwrap(),
                 TokenDeltas::default()
                     .with_apply_deltas([
-                        (t1.clone(), TokenDiff::closure_delta(&t1, d1, fee).unwrap()),
-                        (t2.clone(), TokenDiff::closure_delta(&t2, d2, fee).unwrap()),
-                        (t3.clone(), TokenDiff::closure_delta(&t3, d3, fee).unwrap()),
+                        (
+                            t1.clone(),
+                            TokenDiff::closure_delta(&t1, d1, fee, FeeLimits::default()).unwrap()
+                        ),
+                        (
+                            t2.clone(),
+                            TokenDiff::closure_delta(&t2, d2, fee, FeeLimits::default()).unwrap()
+                        ),
+                        (
+                            t3.clone(),
+                            TokenDiff::closure_delta(&t3, d3, fee, FeeLimits::default()).unwrap()
+                        ),
                     ])
                     .unwrap(),
                 "d1: {d1}, d2: {d2}, d3: {d3}"
@@ -350,6 +486,7 @@ mod tests {
             .into_iter()
             .flatten(),
             fee,
+            &HashMap::new(),
         )
         .unwrap();
         assert!(!closure.is_empty());
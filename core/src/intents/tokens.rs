@@ -1,5 +1,6 @@
-use std::{borrow::Cow, collections::BTreeMap};
+use std::{borrow::Cow, collections::BTreeMap, iter};
 
+use defuse_wnear::NEAR_WITHDRAW_GAS;
 use near_contract_standards::non_fungible_token;
 use near_sdk::{AccountId, AccountIdRef, CryptoHash, Gas, NearToken, json_types::U128, near};
 use serde_with::{DisplayFromStr, serde_as};
@@ -8,12 +9,36 @@ use crate::{
     DefuseError, Result,
     accounts::AccountEvent,
     amounts::Amounts,
-    engine::{Engine, Inspector, State},
+    engine::{Engine, Inspector, State, StateView},
     events::DefuseEvent,
+    token_id::{TokenId, nep141::Nep141TokenId, nep171::Nep171TokenId, nep245::Nep245TokenId},
 };
 
+#[cfg(any(feature = "arbitrary", test))]
+use super::bounded;
 use super::{ExecutableIntent, IntentEvent};
 
+/// `wNEAR` withdrawn to make `storage_deposit` or attach NEAR
+#[inline]
+pub(crate) fn wnear_withdrawn(wnear_id: &AccountIdRef, amount: NearToken) -> (TokenId, u128) {
+    (
+        Nep141TokenId::new(wnear_id.to_owned()).into(),
+        amount.as_yoctonear(),
+    )
+}
+
+/// Maximum length of `memo` in bytes
+pub const MAX_MEMO_LEN: usize = 256;
+
+#[inline]
+fn check_memo(memo: Option<&str>) -> Result<()> {
+    if memo.is_some_and(|memo| memo.len() > MAX_MEMO_LEN) {
+        return Err(DefuseError::MemoTooLong(MAX_MEMO_LEN));
+    }
+    Ok(())
+}
+
+#[cfg_attr(any(feature = "arbitrary", test), derive(arbitrary::Arbitrary))]
 #[cfg_attr(
     all(feature = "abi", not(target_arch = "wasm32")),
     serde_as(schemars = true)
@@ -26,9 +51,11 @@ use super::{ExecutableIntent, IntentEvent};
 #[derive(Debug, Clone)]
 /// Transfer a set of tokens from the signer to a specified account id, within the intents contract.
 pub struct Transfer {
+    #[cfg_attr(any(feature = "arbitrary", test), arbitrary(with = bounded::account_id))]
     pub receiver_id: AccountId,
 
     #[serde_as(as = "Amounts<BTreeMap<_, DisplayFromStr>>")]
+    #[cfg_attr(any(feature = "arbitrary", test), arbitrary(with = bounded::amounts))]
     pub tokens: Amounts,
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -46,9 +73,7 @@ impl ExecutableIntent for Transfer {
         S: State,
         I: Inspector,
     {
-        if sender_id == self.receiver_id || self.tokens.is_empty() {
-            return Err(DefuseError::InvalidIntent);
-        }
+        self.check(sender_id)?;
 
         engine
             .inspector
@@ -60,27 +85,82 @@ impl ExecutableIntent for Transfer {
                 .as_slice(),
             )));
 
-        engine
-            .state
-            .internal_sub_balance(sender_id, self.tokens.clone())?;
-        engine
-            .state
-            .internal_add_balance(self.receiver_id, self.tokens)?;
-        Ok(())
+        self.apply(sender_id, &mut engine.state)
     }
 }
 
+impl Transfer {
+    #[inline]
+    pub(super) fn check(&self, sender_id: &AccountIdRef) -> Result<()> {
+        if sender_id == self.receiver_id || self.tokens.is_empty() {
+            return Err(DefuseError::InvalidIntent);
+        }
+        check_memo(self.memo.as_deref())
+    }
+
+    #[inline]
+    pub(super) fn apply<S>(self, sender_id: &AccountIdRef, state: &mut S) -> Result<()>
+    where
+        S: State,
+    {
+        state.internal_sub_balance(sender_id, self.tokens.clone())?;
+        state.internal_add_balance(self.receiver_id, self.tokens)
+    }
+}
+
+#[cfg_attr(any(feature = "arbitrary", test), derive(arbitrary::Arbitrary))]
+#[near(serializers = [borsh, json])]
+#[derive(Debug, Clone)]
+/// Gift a set of tokens from the signer to a specified account id, within the intents contract.
+/// Moves balances exactly like [`Transfer`], but emits a dedicated
+/// [`DefuseEvent::Gift`], so that indexers can attribute it to the
+/// signer via `memo`.
+pub struct Gift {
+    #[serde(flatten)]
+    pub transfer: Transfer,
+}
+
+impl ExecutableIntent for Gift {
+    fn execute_intent<S, I>(
+        self,
+        sender_id: &AccountIdRef,
+        engine: &mut Engine<S, I>,
+        intent_hash: CryptoHash,
+    ) -> Result<()>
+    where
+        S: State,
+        I: Inspector,
+    {
+        self.transfer.check(sender_id)?;
+
+        engine.inspector.on_event(DefuseEvent::Gift(Cow::Borrowed(
+            [IntentEvent::new(
+                AccountEvent::new(sender_id, Cow::Borrowed(&self)),
+                intent_hash,
+            )]
+            .as_slice(),
+        )));
+
+        self.transfer.apply(sender_id, &mut engine.state)
+    }
+}
+
+#[cfg_attr(any(feature = "arbitrary", test), derive(arbitrary::Arbitrary))]
 #[near(serializers = [borsh, json])]
 #[derive(Debug, Clone)]
 /// Withdraw given FT tokens from the intents contract to a given external account id (external being outside of intents).
 pub struct FtWithdraw {
+    #[cfg_attr(any(feature = "arbitrary", test), arbitrary(with = bounded::account_id))]
     pub token: AccountId,
+    #[cfg_attr(any(feature = "arbitrary", test), arbitrary(with = bounded::account_id))]
     pub receiver_id: AccountId,
+    #[cfg_attr(any(feature = "arbitrary", test), arbitrary(with = bounded::u128_amount))]
     pub amount: U128,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub memo: Option<String>,
 
     /// Message to pass to `ft_transfer_call`. Otherwise, `ft_transfer` will be used.
+    /// Unused amount returned by `ft_on_transfer` is refunded to the owner.
     /// NOTE: No refund will be made in case of insufficient `storage_deposit`
     /// on `token` for `receiver_id`
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -90,6 +170,7 @@ pub struct FtWithdraw {
     /// The amount will be subtracted from user's NEP-141 `wNEAR` balance.
     /// NOTE: the `wNEAR` will not be refunded in case of fail
     #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(any(feature = "arbitrary", test), arbitrary(with = bounded::optional_near_token))]
     pub storage_deposit: Option<NearToken>,
 
     /// Optional minimum required Near gas for created Promise to succeed:
@@ -99,6 +180,7 @@ pub struct FtWithdraw {
     /// Remaining gas will be distributed evenly across all Function Call
     /// Promises created during execution of current receipt.
     #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(any(feature = "arbitrary", test), arbitrary(with = bounded::optional_gas))]
     pub min_gas: Option<Gas>,
 }
 
@@ -116,6 +198,18 @@ impl FtWithdraw {
         self.msg.is_some()
     }
 
+    /// Returns tokens withdrawn from the owner's internal balance,
+    /// including `wNEAR` for `storage_deposit`
+    #[inline]
+    pub fn withdrawn_tokens(&self, wnear_id: &AccountIdRef) -> Vec<(TokenId, u128)> {
+        iter::once((Nep141TokenId::new(self.token.clone()).into(), self.amount.0))
+            .chain(
+                self.storage_deposit
+                    .map(|amount| wnear_withdrawn(wnear_id, amount)),
+            )
+            .collect()
+    }
+
     /// Returns minimum required gas
     #[inline]
     pub fn min_gas(&self) -> Gas {
@@ -151,6 +245,8 @@ impl ExecutableIntent for FtWithdraw {
         S: State,
         I: Inspector,
     {
+        check_memo(self.memo.as_deref())?;
+
         engine
             .inspector
             .on_event(DefuseEvent::FtWithdraw(Cow::Borrowed(
@@ -161,16 +257,22 @@ impl ExecutableIntent for FtWithdraw {
                 .as_slice(),
             )));
 
+        engine.inspector.on_promise_gas(self.min_gas());
+
         engine.state.ft_withdraw(owner_id, self)
     }
 }
 
+#[cfg_attr(any(feature = "arbitrary", test), derive(arbitrary::Arbitrary))]
 #[near(serializers = [borsh, json])]
 #[derive(Debug, Clone)]
 /// Withdraw given NFT tokens from the intents contract to a given external account id (external being outside of intents).
 pub struct NftWithdraw {
+    #[cfg_attr(any(feature = "arbitrary", test), arbitrary(with = bounded::account_id))]
     pub token: AccountId,
+    #[cfg_attr(any(feature = "arbitrary", test), arbitrary(with = bounded::account_id))]
     pub receiver_id: AccountId,
+    #[cfg_attr(any(feature = "arbitrary", test), arbitrary(with = bounded::token_id))]
     pub token_id: non_fungible_token::TokenId,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub memo: Option<String>,
@@ -185,6 +287,7 @@ pub struct NftWithdraw {
     /// The amount will be subtracted from user's NEP-141 `wNEAR` balance.
     /// NOTE: the `wNEAR` will not be refunded in case of fail
     #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(any(feature = "arbitrary", test), arbitrary(with = bounded::optional_near_token))]
     pub storage_deposit: Option<NearToken>,
 
     /// Optional minimum required Near gas for created Promise to succeed:
@@ -194,6 +297,7 @@ pub struct NftWithdraw {
     /// Remaining gas will be distributed evenly across all Function Call
     /// Promises created during execution of current receipt.
     #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(any(feature = "arbitrary", test), arbitrary(with = bounded::optional_gas))]
     pub min_gas: Option<Gas>,
 }
 
@@ -211,6 +315,21 @@ impl NftWithdraw {
         self.msg.is_some()
     }
 
+    /// Returns tokens withdrawn from the owner's internal balance,
+    /// including `wNEAR` for `storage_deposit`
+    #[inline]
+    pub fn withdrawn_tokens(&self, wnear_id: &AccountIdRef) -> Result<Vec<(TokenId, u128)>> {
+        Ok(iter::once((
+            Nep171TokenId::new(self.token.clone(), self.token_id.clone())?.into(),
+            1,
+        ))
+        .chain(
+            self.storage_deposit
+                .map(|amount| wnear_withdrawn(wnear_id, amount)),
+        )
+        .collect())
+    }
+
     /// Returns minimum required gas
     #[inline]
     pub fn min_gas(&self) -> Gas {
@@ -246,6 +365,8 @@ impl ExecutableIntent for NftWithdraw {
         S: State,
         I: Inspector,
     {
+        check_memo(self.memo.as_deref())?;
+
         engine
             .inspector
             .on_event(DefuseEvent::NftWithdraw(Cow::Borrowed(
@@ -256,6 +377,8 @@ impl ExecutableIntent for NftWithdraw {
                 .as_slice(),
             )));
 
+        engine.inspector.on_promise_gas(self.min_gas());
+
         engine.state.nft_withdraw(owner_id, self)
     }
 }
@@ -286,9 +409,10 @@ pub struct MtWithdraw {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub storage_deposit: Option<NearToken>,
 
-    /// Optional minimum required Near gas for created Promise to succeed:
-    /// * `mt_batch_transfer`:      minimum: 15TGas, default: 15TGas
-    /// * `mt_batch_transfer_call`: minimum: 35TGas, default: 50TGas
+    /// Optional minimum required Near gas for created Promise to succeed,
+    /// where `n` is the number of `token_ids`:
+    /// * `mt_batch_transfer`:      minimum: 19TGas + n * 1TGas, default: 19TGas + n * 1TGas
+    /// * `mt_batch_transfer_call`: minimum: 32TGas + n * 3TGas, default: 47TGas + n * 3TGas
     ///
     /// Remaining gas will be distributed evenly across all Function Call
     /// Promises created during execution of current receipt.
@@ -297,12 +421,19 @@ pub struct MtWithdraw {
 }
 
 impl MtWithdraw {
-    // TODO: gas_base + gas_per_token * token_ids.len()
-    const MT_BATCH_TRANSFER_GAS_MIN: Gas = Gas::from_tgas(20);
-    const MT_BATCH_TRANSFER_GAS_DEFAULT: Gas = Gas::from_tgas(20);
-
-    const MT_BATCH_TRANSFER_CALL_GAS_MIN: Gas = Gas::from_tgas(35);
-    const MT_BATCH_TRANSFER_CALL_GAS_DEFAULT: Gas = Gas::from_tgas(50);
+    // Base values are chosen so that single-token withdrawals require
+    // the same gas as before: 20TGas and 35/50TGas respectively
+    const MT_BATCH_TRANSFER_GAS_MIN_BASE: Gas = Gas::from_tgas(19);
+    const MT_BATCH_TRANSFER_GAS_DEFAULT_BASE: Gas = Gas::from_tgas(19);
+    /// Transfer of a single token on receiver's side
+    const MT_BATCH_TRANSFER_GAS_PER_TOKEN: Gas = Gas::from_tgas(1);
+
+    const MT_BATCH_TRANSFER_CALL_GAS_MIN_BASE: Gas = Gas::from_tgas(32);
+    const MT_BATCH_TRANSFER_CALL_GAS_DEFAULT_BASE: Gas = Gas::from_tgas(47);
+    /// Transfer of a single token plus its share of `mt_resolve_transfer()`
+    /// on receiver's side, which is 2TGas per token for NEP-245 tokens
+    /// implemented by this contract
+    const MT_BATCH_TRANSFER_CALL_GAS_PER_TOKEN: Gas = Gas::from_tgas(3);
 
     /// Returns whether it's `mt_batch_transfer_call()`
     #[inline]
@@ -310,20 +441,54 @@ impl MtWithdraw {
         self.msg.is_some()
     }
 
-    /// Returns minimum required gas
+    /// Returns tokens withdrawn from the owner's internal balance,
+    /// including `wNEAR` for `storage_deposit`
+    #[inline]
+    pub fn withdrawn_tokens(&self, wnear_id: &AccountIdRef) -> Result<Vec<(TokenId, u128)>> {
+        self.token_ids
+            .iter()
+            .zip(&self.amounts)
+            .map(|(token_id, amount)| -> Result<_> {
+                Ok((
+                    Nep245TokenId::new(self.token.clone(), token_id.clone())?.into(),
+                    amount.0,
+                ))
+            })
+            .chain(
+                self.storage_deposit
+                    .map(|amount| Ok(wnear_withdrawn(wnear_id, amount))),
+            )
+            .collect()
+    }
+
+    /// Checks that `token_ids` and `amounts` have the same non-zero length
+    #[inline]
+    pub fn is_valid(&self) -> bool {
+        !self.token_ids.is_empty() && self.token_ids.len() == self.amounts.len()
+    }
+
+    /// Returns minimum required gas: `gas_base + gas_per_token * token_ids.len()`
     #[inline]
     pub fn min_gas(&self) -> Gas {
-        let (min, default) = if self.is_call() {
+        let (min_base, default_base, per_token) = if self.is_call() {
             (
-                Self::MT_BATCH_TRANSFER_CALL_GAS_MIN,
-                Self::MT_BATCH_TRANSFER_CALL_GAS_DEFAULT,
+                Self::MT_BATCH_TRANSFER_CALL_GAS_MIN_BASE,
+                Self::MT_BATCH_TRANSFER_CALL_GAS_DEFAULT_BASE,
+                Self::MT_BATCH_TRANSFER_CALL_GAS_PER_TOKEN,
             )
         } else {
             (
-                Self::MT_BATCH_TRANSFER_GAS_MIN,
-                Self::MT_BATCH_TRANSFER_GAS_DEFAULT,
+                Self::MT_BATCH_TRANSFER_GAS_MIN_BASE,
+                Self::MT_BATCH_TRANSFER_GAS_DEFAULT_BASE,
+                Self::MT_BATCH_TRANSFER_GAS_PER_TOKEN,
             )
         };
+        let per_tokens = u64::try_from(self.token_ids.len())
+            .ok()
+            .and_then(|n| per_token.checked_mul(n))
+            .unwrap_or(Gas::from_gas(u64::MAX));
+        let min = min_base.saturating_add(per_tokens);
+        let default = default_base.saturating_add(per_tokens);
 
         self.min_gas
             .unwrap_or(default)
@@ -348,6 +513,8 @@ impl ExecutableIntent for MtWithdraw {
         S: State,
         I: Inspector,
     {
+        check_memo(self.memo.as_deref())?;
+
         engine
             .inspector
             .on_event(DefuseEvent::MtWithdraw(Cow::Borrowed(
@@ -358,19 +525,60 @@ impl ExecutableIntent for MtWithdraw {
                 .as_slice(),
             )));
 
+        engine.inspector.on_promise_gas(self.min_gas());
+
         engine.state.mt_withdraw(owner_id, self)
     }
 }
 
+#[cfg_attr(any(feature = "arbitrary", test), derive(arbitrary::Arbitrary))]
 #[near(serializers = [borsh, json])]
 #[derive(Debug, Clone)]
 /// Withdraw native tokens (NEAR) from the intents contract to a given external account id (external being outside of intents).
 /// This will subtract from the account's wNEAR balance, and will be sent to the account specified as native NEAR.
 /// NOTE: the `wNEAR` will not be refunded in case of fail (e.g. `receiver_id`
-/// account does not exist).
+/// account does not exist), unless `require_account_exists` is set.
 pub struct NativeWithdraw {
+    #[cfg_attr(any(feature = "arbitrary", test), arbitrary(with = bounded::account_id))]
     pub receiver_id: AccountId,
+    #[cfg_attr(any(feature = "arbitrary", test), arbitrary(with = bounded::near_token))]
     pub amount: NearToken,
+
+    /// Refund `amount` of `wNEAR` if the transfer fails, e.g. when
+    /// `receiver_id` account does not exist. Since NEAR was already
+    /// unwrapped by then, it gets wrapped back before the refund.
+    /// Requires more gas for the callbacks.
+    #[serde(default, skip_serializing_if = "::core::ops::Not::not")]
+    pub require_account_exists: bool,
+}
+
+impl NativeWithdraw {
+    pub const DO_NATIVE_WITHDRAW_GAS: Gas = Gas::from_tgas(10);
+    /// Includes re-wrapping NEAR and the refund in case of failed transfer
+    pub const NATIVE_RESOLVE_WITHDRAW_GAS: Gas = Gas::from_tgas(25);
+
+    /// Returns gas for the callback that transfers unwrapped NEAR
+    #[inline]
+    pub const fn do_native_withdraw_gas(&self) -> Gas {
+        if self.require_account_exists {
+            Self::DO_NATIVE_WITHDRAW_GAS.saturating_add(Self::NATIVE_RESOLVE_WITHDRAW_GAS)
+        } else {
+            Self::DO_NATIVE_WITHDRAW_GAS
+        }
+    }
+
+    /// Returns minimum required gas: `near_withdraw()` on `wNEAR`
+    /// followed by [`do_native_withdraw_gas`](Self::do_native_withdraw_gas)
+    #[inline]
+    pub const fn min_gas(&self) -> Gas {
+        NEAR_WITHDRAW_GAS.saturating_add(self.do_native_withdraw_gas())
+    }
+
+    /// Returns tokens withdrawn from the owner's internal balance
+    #[inline]
+    pub fn withdrawn_tokens(&self, wnear_id: &AccountIdRef) -> Vec<(TokenId, u128)> {
+        vec![wnear_withdrawn(wnear_id, self.amount)]
+    }
 }
 
 impl ExecutableIntent for NativeWithdraw {
@@ -395,10 +603,74 @@ impl ExecutableIntent for NativeWithdraw {
                 .as_slice(),
             )));
 
+        engine.inspector.on_promise_gas(self.min_gas());
+
         engine.state.native_withdraw(owner_id, self)
     }
 }
 
+#[cfg_attr(any(feature = "arbitrary", test), derive(arbitrary::Arbitrary))]
+/// Unwrap `amount` of user's NEP-141 `wNEAR` balance into internal native
+/// NEAR balance, i.e. [`TokenId::Native`](crate::token_id::TokenId::Native).
+/// The native balance is credited only after `near_withdraw()` on `wNEAR`
+/// succeeds, otherwise `wNEAR` is refunded.
+///
+/// The resulting native balance can be used by [`StorageDeposit`] with
+/// `from_native` set.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, Clone)]
+pub struct UnwrapNear {
+    #[cfg_attr(any(feature = "arbitrary", test), arbitrary(with = bounded::near_token))]
+    pub amount: NearToken,
+}
+
+impl UnwrapNear {
+    pub const DO_UNWRAP_NEAR_GAS: Gas = Gas::from_tgas(10);
+
+    /// Returns minimum required gas: `near_withdraw()` on `wNEAR`
+    /// followed by crediting native balance
+    #[inline]
+    pub const fn min_gas(&self) -> Gas {
+        NEAR_WITHDRAW_GAS.saturating_add(Self::DO_UNWRAP_NEAR_GAS)
+    }
+
+    /// Returns tokens withdrawn from the owner's internal balance.
+    /// Native balance is credited later, so it's not included
+    #[inline]
+    pub fn withdrawn_tokens(&self, wnear_id: &AccountIdRef) -> Vec<(TokenId, u128)> {
+        vec![wnear_withdrawn(wnear_id, self.amount)]
+    }
+}
+
+impl ExecutableIntent for UnwrapNear {
+    #[inline]
+    fn execute_intent<S, I>(
+        self,
+        owner_id: &AccountIdRef,
+        engine: &mut Engine<S, I>,
+        intent_hash: CryptoHash,
+    ) -> Result<()>
+    where
+        S: State,
+        I: Inspector,
+    {
+        engine
+            .inspector
+            .on_event(DefuseEvent::UnwrapNear(Cow::Borrowed(
+                [IntentEvent::new(
+                    AccountEvent::new(owner_id, Cow::Borrowed(&self)),
+                    intent_hash,
+                )]
+                .as_slice(),
+            )));
+
+        engine.inspector.on_promise_gas(self.min_gas());
+
+        engine.state.unwrap_near(owner_id, self)
+    }
+}
+
+#[cfg_attr(any(feature = "arbitrary", test), derive(arbitrary::Arbitrary))]
 /// Make [NEP-145](https://nomicon.io/Standards/StorageManagement#nep-145)
 /// `storage_deposit` for an `account_id` on `contract_id`.
 /// The `amount` will be subtracted from user's NEP-141 `wNEAR` balance.
@@ -413,14 +685,50 @@ impl ExecutableIntent for NativeWithdraw {
 #[near(serializers = [borsh, json])]
 #[derive(Debug, Clone)]
 pub struct StorageDeposit {
+    #[cfg_attr(any(feature = "arbitrary", test), arbitrary(with = bounded::account_id))]
     pub contract_id: AccountId,
     #[serde(
         // There was field collision for `account_id` in `AccountEvent`,
         // but we keep it for backwards-compatibility
         alias = "account_id",
     )]
+    #[cfg_attr(any(feature = "arbitrary", test), arbitrary(with = bounded::account_id))]
     pub deposit_for_account_id: AccountId,
+    #[cfg_attr(any(feature = "arbitrary", test), arbitrary(with = bounded::near_token))]
     pub amount: NearToken,
+
+    /// Take `amount` from internal native NEAR balance (see [`UnwrapNear`])
+    /// instead of `wNEAR`. Since no unwrapping is needed in this case,
+    /// `storage_deposit` is not affected by ordering of other intents.
+    #[serde(default, skip_serializing_if = "::core::ops::Not::not")]
+    pub from_native: bool,
+}
+
+impl StorageDeposit {
+    pub const STORAGE_DEPOSIT_GAS: Gas = Gas::from_tgas(10);
+    pub const DO_STORAGE_DEPOSIT_GAS: Gas =
+        Gas::from_tgas(5).saturating_add(Self::STORAGE_DEPOSIT_GAS);
+
+    /// Returns minimum required gas: `storage_deposit()` itself, preceded
+    /// by `near_withdraw()` on `wNEAR` unless `from_native` is set
+    #[inline]
+    pub const fn min_gas(&self) -> Gas {
+        if self.from_native {
+            Self::STORAGE_DEPOSIT_GAS
+        } else {
+            NEAR_WITHDRAW_GAS.saturating_add(Self::DO_STORAGE_DEPOSIT_GAS)
+        }
+    }
+
+    /// Returns tokens withdrawn from the owner's internal balance
+    #[inline]
+    pub fn withdrawn_tokens(&self, wnear_id: &AccountIdRef) -> Vec<(TokenId, u128)> {
+        vec![if self.from_native {
+            (TokenId::Native, self.amount.as_yoctonear())
+        } else {
+            wnear_withdrawn(wnear_id, self.amount)
+        }]
+    }
 }
 
 impl ExecutableIntent for StorageDeposit {
@@ -445,6 +753,94 @@ impl ExecutableIntent for StorageDeposit {
                 .as_slice(),
             )));
 
+        engine.inspector.on_promise_gas(self.min_gas());
+
         engine.state.storage_deposit(owner_id, self)
     }
 }
+
+#[cfg_attr(any(feature = "arbitrary", test), derive(arbitrary::Arbitrary))]
+/// Same as [`StorageDeposit`], but makes `storage_deposit` for the signer
+/// itself, i.e. registers it on `contract_id`.
+/// The `amount` will be subtracted from user's NEP-141 `wNEAR` balance.
+/// NOTE: the `wNEAR` will not be refunded in any case.
+///
+/// WARNING: the same ordering considerations as for [`StorageDeposit`] apply.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, Clone)]
+pub struct StorageDepositSelf {
+    #[cfg_attr(any(feature = "arbitrary", test), arbitrary(with = bounded::account_id))]
+    pub contract_id: AccountId,
+    #[cfg_attr(any(feature = "arbitrary", test), arbitrary(with = bounded::near_token))]
+    pub amount: NearToken,
+
+    /// See [`StorageDeposit::from_native`]
+    #[serde(default, skip_serializing_if = "::core::ops::Not::not")]
+    pub from_native: bool,
+}
+
+impl ExecutableIntent for StorageDepositSelf {
+    #[inline]
+    fn execute_intent<S, I>(
+        self,
+        owner_id: &AccountIdRef,
+        engine: &mut Engine<S, I>,
+        intent_hash: CryptoHash,
+    ) -> Result<()>
+    where
+        S: State,
+        I: Inspector,
+    {
+        StorageDeposit {
+            contract_id: self.contract_id,
+            deposit_for_account_id: owner_id.to_owned(),
+            amount: self.amount,
+            from_native: self.from_native,
+        }
+        .execute_intent(owner_id, engine, intent_hash)
+    }
+}
+
+#[cfg_attr(any(feature = "arbitrary", test), derive(arbitrary::Arbitrary))]
+/// Require signer's balance of `token_id` to be at least `min` at the
+/// moment of execution. Doesn't modify any state.
+///
+/// Since intents are executed atomically, placing it before other intents
+/// (e.g. [`TokenDiff`](super::token_diff::TokenDiff)) makes the whole batch
+/// fail if the precondition is not met.
+#[cfg_attr(
+    all(feature = "abi", not(target_arch = "wasm32")),
+    serde_as(schemars = true)
+)]
+#[cfg_attr(
+    not(all(feature = "abi", not(target_arch = "wasm32"))),
+    serde_as(schemars = false)
+)]
+#[near(serializers = [borsh, json])]
+#[derive(Debug, Clone)]
+pub struct RequireBalance {
+    pub token_id: TokenId,
+
+    #[serde_as(as = "DisplayFromStr")]
+    #[cfg_attr(any(feature = "arbitrary", test), arbitrary(with = bounded::amount))]
+    pub min: u128,
+}
+
+impl ExecutableIntent for RequireBalance {
+    #[inline]
+    fn execute_intent<S, I>(
+        self,
+        owner_id: &AccountIdRef,
+        engine: &mut Engine<S, I>,
+        _intent_hash: CryptoHash,
+    ) -> Result<()>
+    where
+        S: State,
+        I: Inspector,
+    {
+        if engine.state.balance_of(owner_id, &self.token_id) < self.min {
+            return Err(DefuseError::PreconditionFailed);
+        }
+        Ok(())
+    }
+}
@@ -1,3 +1,4 @@
+use chrono::DateTime;
 use defuse_bitmap::{BitMap256, U248, U256};
 use defuse_borsh_utils::adapters::{As, TimestampNanoSeconds};
 use defuse_map_utils::{IterableMap, Map};
@@ -31,17 +32,15 @@ where
         self.0.get_bit(n)
     }
 
+    /// Commits the nonce.
+    /// Returns `false` if it was already committed before.
     #[inline]
-    pub fn commit(&mut self, n: Nonce) -> Result<()> {
+    pub fn commit(&mut self, n: Nonce) -> Result<bool> {
         if ExpirableNonce::maybe_from(n).is_some_and(|expirable| expirable.has_expired()) {
             return Err(DefuseError::NonceExpired);
         }
 
-        if self.0.set_bit(n) {
-            return Err(DefuseError::NonceUsed);
-        }
-
-        Ok(())
+        Ok(!self.0.set_bit(n))
     }
 
     #[inline]
@@ -54,13 +53,81 @@ where
         false
     }
 
+    /// Clears all expirable nonces which have expired by `now`
+    /// (nanoseconds since UNIX epoch).
+    /// Returns number of cleared nonces.
+    pub fn clear_all_expired(&mut self, now: u64) -> u64
+    where
+        T: IterableMap,
+    {
+        let now = Deadline::new(DateTime::from_timestamp_nanos(
+            now.try_into().unwrap_or(i64::MAX),
+        ));
+
+        let expired: Vec<_> = self
+            .0
+            .iter_words()
+            .filter(|(prefix, _)| {
+                let mut n = Nonce::default();
+                n[..prefix.len()].copy_from_slice(prefix.as_slice());
+                ExpirableNonce::maybe_from(n).is_some_and(|n| now > n.deadline)
+            })
+            .map(|(prefix, word)| {
+                (
+                    *prefix,
+                    word.iter()
+                        .map(|byte| u64::from(byte.count_ones()))
+                        .sum::<u64>(),
+                )
+            })
+            .collect();
+
+        expired
+            .into_iter()
+            .filter(|(prefix, _)| self.0.clear_by_prefix(*prefix))
+            .map(|(_, count)| count)
+            .sum()
+    }
+
+    /// Iterates over all used (i.e. committed and not yet cleared) nonces
     #[inline]
-    pub fn iter(&self) -> impl Iterator<Item = Nonce> + '_
+    pub fn iter_used(&self) -> impl Iterator<Item = Nonce> + '_
     where
         T: IterableMap,
     {
         self.0.as_iter()
     }
+
+    #[deprecated(note = "Please use `Nonces::iter_used` instead.")]
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = Nonce> + '_
+    where
+        T: IterableMap,
+    {
+        self.iter_used()
+    }
+}
+
+/// Helpers for building and inspecting expirable nonces without
+/// dealing with their layout directly, see [`ExpirableNonce`].
+pub trait NonceExt {
+    /// Packs `deadline` and random `entropy` into an expirable nonce
+    fn with_deadline(deadline: Deadline, entropy: [u8; 20]) -> Self;
+
+    /// Returns deadline of the nonce if it's expirable
+    fn deadline(&self) -> Option<Deadline>;
+}
+
+impl NonceExt for Nonce {
+    #[inline]
+    fn with_deadline(deadline: Deadline, entropy: [u8; 20]) -> Self {
+        ExpirableNonce::new(deadline, entropy).into()
+    }
+
+    #[inline]
+    fn deadline(&self) -> Option<Deadline> {
+        ExpirableNonce::maybe_from(*self).map(|n| n.deadline)
+    }
 }
 
 /// To distinguish between legacy nonces and expirable nonces
@@ -118,11 +185,27 @@ impl ExpirableNonce {
 mod tests {
     use super::*;
 
+    use std::collections::{HashMap, HashSet};
+
     use arbitrary::Unstructured;
     use chrono::{Days, Utc};
-    use defuse_test_utils::random::random_bytes;
+    use defuse_test_utils::random::{Rng, random_bytes, rng};
     use rstest::rstest;
 
+    #[rstest]
+    fn iter_used(mut rng: impl Rng) {
+        let mut nonces = Nonces::<HashMap<U248, U256>>::default();
+        let committed: HashSet<Nonce> = (0..rng.random_range(1..100))
+            .map(|_| rng.random())
+            .collect();
+
+        for n in &committed {
+            assert!(nonces.commit(*n).unwrap());
+        }
+
+        assert_eq!(nonces.iter_used().collect::<HashSet<_>>(), committed);
+    }
+
     #[rstest]
     fn roundtrip_layout(random_bytes: Vec<u8>) {
         let mut u = Unstructured::new(&random_bytes);
@@ -136,6 +219,88 @@ mod tests {
         assert_eq!(unpacked, exp);
     }
 
+    #[rstest]
+    fn with_deadline(mut rng: impl Rng) {
+        let deadline = Deadline::new(Utc::now());
+        let entropy: [u8; 20] = rng.random();
+
+        let nonce = Nonce::with_deadline(deadline, entropy);
+        assert_eq!(nonce.deadline(), Some(deadline));
+        assert_eq!(
+            Into::<Nonce>::into(ExpirableNonce::new(deadline, entropy)),
+            nonce
+        );
+
+        let legacy: Nonce = rng.random();
+        assert_eq!(legacy.deadline(), None);
+    }
+
+    #[rstest]
+    fn with_deadline_clear_expired(mut rng: impl Rng) {
+        let mut nonces = Nonces::<HashMap<U248, U256>>::default();
+
+        let expired = Nonce::with_deadline(
+            Deadline::new(Utc::now().checked_sub_days(Days::new(1)).unwrap()),
+            rng.random(),
+        );
+        assert!(matches!(
+            nonces.commit(expired).unwrap_err(),
+            DefuseError::NonceExpired
+        ));
+        // simulate a nonce that was committed before it expired
+        nonces.0.set_bit(expired);
+        assert!(nonces.is_used(expired));
+        assert!(nonces.clear_expired(expired));
+        assert!(!nonces.is_used(expired));
+
+        let active = Nonce::with_deadline(
+            Deadline::new(Utc::now().checked_add_days(Days::new(1)).unwrap()),
+            rng.random(),
+        );
+        assert!(nonces.commit(active).unwrap());
+        assert!(!nonces.commit(active).unwrap());
+        assert!(!nonces.clear_expired(active));
+        assert!(nonces.is_used(active));
+    }
+
+    #[rstest]
+    fn clear_all_expired(mut rng: impl Rng) {
+        let mut nonces = Nonces::<HashMap<U248, U256>>::default();
+        let now = Utc::now();
+
+        let expired: Vec<Nonce> = (1..=3)
+            .map(|days| {
+                Nonce::with_deadline(
+                    Deadline::new(now.checked_sub_days(Days::new(days)).unwrap()),
+                    rng.random(),
+                )
+            })
+            .collect();
+        let active: Vec<Nonce> = (1..=3)
+            .map(|days| {
+                Nonce::with_deadline(
+                    Deadline::new(now.checked_add_days(Days::new(days)).unwrap()),
+                    rng.random(),
+                )
+            })
+            .collect();
+        let legacy: Vec<Nonce> = (0..3).map(|_| rng.random()).collect();
+
+        for n in expired.iter().chain(&active).chain(&legacy) {
+            // simulate nonces committed before they expired
+            nonces.0.set_bit(*n);
+        }
+
+        let now = u64::try_from(now.timestamp_nanos_opt().unwrap()).unwrap();
+        assert_eq!(nonces.clear_all_expired(now), 3);
+
+        assert!(expired.iter().all(|n| !nonces.is_used(*n)));
+        assert!(active.iter().chain(&legacy).all(|n| nonces.is_used(*n)));
+
+        // nothing left to clear
+        assert_eq!(nonces.clear_all_expired(now), 0);
+    }
+
     #[rstest]
     fn nonexpirable_test(random_bytes: Vec<u8>) {
         let mut u = Unstructured::new(&random_bytes);
@@ -1,4 +1,4 @@
-use super::{DefusePayload, ExtractDefusePayload};
+use super::{DefusePayload, ExtractDefusePayload, ExtractDefusePayloadError};
 use defuse_erc191::SignedErc191Payload;
 use near_sdk::{serde::de::DeserializeOwned, serde_json};
 
@@ -6,10 +6,10 @@ impl<T> ExtractDefusePayload<T> for SignedErc191Payload
 where
     T: DeserializeOwned,
 {
-    type Error = serde_json::Error;
+    type Error = ExtractDefusePayloadError;
 
     #[inline]
     fn extract_defuse_payload(self) -> Result<DefusePayload<T>, Self::Error> {
-        serde_json::from_str(&self.payload.0)
+        serde_json::from_str(&self.payload.0).map_err(Into::into)
     }
 }
@@ -1,5 +1,6 @@
 pub mod erc191;
 pub mod multi;
+pub mod near_ed25519;
 pub mod nep413;
 pub mod raw;
 pub mod sep53;
@@ -7,14 +8,15 @@ pub mod tip191;
 pub mod ton_connect;
 pub mod webauthn;
 
-use core::convert::Infallible;
+use core::{convert::Infallible, time::Duration};
 
 use defuse_serde_utils::base64::Base64;
 use impl_tools::autoimpl;
-use near_sdk::{AccountId, near};
+use near_sdk::{AccountId, AccountIdRef, near, serde_json};
 use serde_with::serde_as;
+use thiserror::Error as ThisError;
 
-use crate::{Deadline, Nonce};
+use crate::{Deadline, DefuseError, Nonce};
 
 // TODO: add version
 #[cfg_attr(
@@ -32,6 +34,11 @@ use crate::{Deadline, Nonce};
 pub struct DefusePayload<T> {
     pub signer_id: AccountId,
     pub verifying_contract: AccountId,
+    /// Optionally binds the payload to a specific NEAR network, e.g.
+    /// `mainnet`, so that it can't be replayed on another network where
+    /// `verifying_contract` happens to exist, too
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network_id: Option<String>,
     pub deadline: Deadline,
     #[serde_as(as = "Base64")]
     #[cfg_attr(
@@ -44,6 +51,53 @@ pub struct DefusePayload<T> {
     pub message: T,
 }
 
+impl<T> DefusePayload<T> {
+    /// Checks that the payload was signed for `verifying_contract`
+    /// and its deadline has not expired yet
+    #[inline]
+    pub fn validate(
+        &self,
+        verifying_contract: &AccountIdRef,
+    ) -> Result<(), ExtractDefusePayloadError> {
+        self.validate_with_grace(verifying_contract, Duration::ZERO)
+    }
+
+    /// Same as [`validate`](Self::validate), but tolerates `grace` of
+    /// clock skew when checking the deadline
+    pub fn validate_with_grace(
+        &self,
+        verifying_contract: &AccountIdRef,
+        grace: Duration,
+    ) -> Result<(), ExtractDefusePayloadError> {
+        if self.verifying_contract != *verifying_contract {
+            return Err(ExtractDefusePayloadError::WrongVerifyingContract);
+        }
+        if self.deadline.has_expired_with_grace(grace) {
+            return Err(ExtractDefusePayloadError::DeadlineExpired {
+                deadline: self.deadline,
+                now: Deadline::now(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Checks that the payload is either not bound to any network or
+    /// bound to `network_id` the contract is configured with
+    pub fn validate_network(
+        &self,
+        network_id: Option<&str>,
+    ) -> Result<(), ExtractDefusePayloadError> {
+        if self
+            .network_id
+            .as_deref()
+            .is_some_and(|bound| Some(bound) != network_id)
+        {
+            return Err(ExtractDefusePayloadError::WrongNetworkId);
+        }
+        Ok(())
+    }
+}
+
 pub trait ExtractDefusePayload<T> {
     type Error;
 
@@ -59,6 +113,42 @@ impl<T> ExtractDefusePayload<T> for DefusePayload<T> {
     }
 }
 
+#[derive(Debug, ThisError)]
+pub enum ExtractDefusePayloadError {
+    #[error("invalid signature")]
+    SignatureInvalid,
+
+    #[error("wrong verifying_contract")]
+    WrongVerifyingContract,
+
+    #[error("wrong network_id")]
+    WrongNetworkId,
+
+    #[error(
+        "deadline '{}' has expired at '{}'",
+        .deadline.into_timestamp(),
+        .now.into_timestamp(),
+    )]
+    DeadlineExpired { deadline: Deadline, now: Deadline },
+
+    #[error("malformed payload: {0}")]
+    Malformed(#[from] serde_json::Error),
+}
+
+impl From<ExtractDefusePayloadError> for DefuseError {
+    fn from(err: ExtractDefusePayloadError) -> Self {
+        match err {
+            ExtractDefusePayloadError::SignatureInvalid => Self::InvalidSignature,
+            ExtractDefusePayloadError::WrongVerifyingContract => Self::WrongVerifyingContract,
+            ExtractDefusePayloadError::WrongNetworkId => Self::WrongNetworkId,
+            ExtractDefusePayloadError::DeadlineExpired { deadline, now } => {
+                Self::DeadlineExpired { deadline, now }
+            }
+            ExtractDefusePayloadError::Malformed(err) => Self::JSON(err),
+        }
+    }
+}
+
 #[cfg(all(feature = "abi", not(target_arch = "wasm32")))]
 mod examples {
     use super::*;
@@ -69,3 +159,158 @@ mod examples {
         base64::engine::general_purpose::STANDARD.encode(Nonce::default())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Utc};
+    use defuse_crypto::SignedPayload;
+    use near_sdk::serde_json::json;
+    use rstest::rstest;
+
+    use super::{multi::MultiPayload, *};
+
+    fn payload(deadline: Deadline) -> DefusePayload<()> {
+        DefusePayload {
+            signer_id: "alice.near".parse().unwrap(),
+            verifying_contract: "intents.near".parse().unwrap(),
+            network_id: None,
+            deadline,
+            nonce: Nonce::default(),
+            message: (),
+        }
+    }
+
+    #[test]
+    fn validate() {
+        payload(Deadline::MAX)
+            .validate(AccountIdRef::new_or_panic("intents.near"))
+            .unwrap();
+    }
+
+    #[test]
+    fn wrong_verifying_contract() {
+        assert!(matches!(
+            payload(Deadline::MAX).validate(AccountIdRef::new_or_panic("other.near")),
+            Err(ExtractDefusePayloadError::WrongVerifyingContract)
+        ));
+    }
+
+    #[test]
+    fn deadline_expired() {
+        let deadline = Deadline::new(DateTime::UNIX_EPOCH);
+        let before = Deadline::now();
+
+        let Err(ExtractDefusePayloadError::DeadlineExpired {
+            deadline: expired,
+            now,
+        }) = payload(deadline).validate(AccountIdRef::new_or_panic("intents.near"))
+        else {
+            panic!("deadline should have expired");
+        };
+        assert_eq!(expired, deadline);
+        assert!(before <= now && now <= Deadline::now());
+
+        let err = DefuseError::from(ExtractDefusePayloadError::DeadlineExpired { deadline, now });
+        assert!(matches!(
+            err,
+            DefuseError::DeadlineExpired { deadline: d, now: n } if d == deadline && n == now,
+        ));
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "deadline '{}' has expired at '{}'",
+                deadline.into_timestamp(),
+                now.into_timestamp(),
+            ),
+        );
+    }
+
+    #[rstest]
+    #[case::unbound(None, Some("testnet"), true)]
+    #[case::unbound_unconfigured(None, None, true)]
+    #[case::same_network(Some("mainnet"), Some("mainnet"), true)]
+    #[case::other_network(Some("mainnet"), Some("testnet"), false)]
+    #[case::unconfigured(Some("mainnet"), None, false)]
+    fn validate_network(
+        #[case] bound: Option<&str>,
+        #[case] configured: Option<&str>,
+        #[case] valid: bool,
+    ) {
+        let payload = DefusePayload {
+            network_id: bound.map(ToOwned::to_owned),
+            ..payload(Deadline::MAX)
+        };
+        let result = payload.validate_network(configured);
+        if valid {
+            result.unwrap();
+        } else {
+            assert!(matches!(
+                result,
+                Err(ExtractDefusePayloadError::WrongNetworkId)
+            ));
+        }
+    }
+
+    #[rstest]
+    #[case::no_grace(Duration::ZERO, false)]
+    #[case::within_grace(Duration::from_secs(3600), true)]
+    fn deadline_grace(#[case] grace: Duration, #[case] valid: bool) {
+        let result = payload(Deadline::new(Utc::now() - Duration::from_secs(60)))
+            .validate_with_grace(AccountIdRef::new_or_panic("intents.near"), grace);
+        if valid {
+            result.unwrap();
+        } else {
+            assert!(matches!(
+                result,
+                Err(ExtractDefusePayloadError::DeadlineExpired { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn malformed() {
+        let signed: MultiPayload = serde_json::from_value(json!({
+            "standard": "raw_ed25519",
+            "payload": "not a JSON",
+            "public_key": "ed25519:8rVvtHWFr8hasdQGGD5WiQBTyr4iH2ruEPPVfj491RPN",
+            "signature": "ed25519:3vtbNQJHZfuV1s5DykzyjkbNLc583hnkrhTz57eDhd966iqzkor6Twgr4Loh2C195SCSEsiGfrd6KcxpjNq9ZbVj",
+        }))
+        .unwrap();
+
+        // signature is valid for another payload
+        assert!(signed.verify().is_none());
+        assert!(matches!(
+            ExtractDefusePayload::<()>::extract_defuse_payload(signed),
+            Err(ExtractDefusePayloadError::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn into_defuse_error() {
+        assert!(matches!(
+            DefuseError::from(ExtractDefusePayloadError::SignatureInvalid),
+            DefuseError::InvalidSignature
+        ));
+        assert!(matches!(
+            DefuseError::from(ExtractDefusePayloadError::WrongVerifyingContract),
+            DefuseError::WrongVerifyingContract
+        ));
+        assert!(matches!(
+            DefuseError::from(ExtractDefusePayloadError::WrongNetworkId),
+            DefuseError::WrongNetworkId
+        ));
+        assert!(matches!(
+            DefuseError::from(ExtractDefusePayloadError::DeadlineExpired {
+                deadline: Deadline::MAX,
+                now: Deadline::MAX,
+            }),
+            DefuseError::DeadlineExpired { .. }
+        ));
+        assert!(matches!(
+            DefuseError::from(ExtractDefusePayloadError::Malformed(
+                serde_json::from_str::<()>("").unwrap_err()
+            )),
+            DefuseError::JSON(_)
+        ));
+    }
+}
@@ -1,3 +1,5 @@
+use std::borrow::Cow;
+
 use defuse_crypto::{Payload, PublicKey, SignedPayload};
 use defuse_erc191::SignedErc191Payload;
 use defuse_nep413::SignedNep413Payload;
@@ -5,16 +7,31 @@ use defuse_sep53::SignedSep53Payload;
 use defuse_tip191::SignedTip191Payload;
 use defuse_ton_connect::SignedTonConnectPayload;
 use derive_more::derive::From;
-use near_sdk::{CryptoHash, near, serde::de::DeserializeOwned, serde_json};
+use near_sdk::{
+    CryptoHash, near,
+    serde::{Deserialize, Deserializer, Serialize, de::DeserializeOwned},
+    serde_json::{self, Value},
+};
+
+use crate::DefuseError;
 
 use super::{
-    DefusePayload, ExtractDefusePayload, raw::SignedRawEd25519Payload,
+    DefusePayload, ExtractDefusePayload, ExtractDefusePayloadError,
+    near_ed25519::SignedNearEd25519Payload, raw::SignedRawEd25519Payload,
     webauthn::SignedWebAuthnPayload,
 };
 
-#[near(serializers = [json])]
-#[serde(tag = "standard", rename_all = "snake_case")]
-#[derive(Debug, Clone, From)]
+#[cfg_attr(
+    all(feature = "abi", not(target_arch = "wasm32")),
+    derive(::near_sdk::schemars::JsonSchema),
+    schemars(crate = "::near_sdk::schemars")
+)]
+#[derive(Debug, Clone, Serialize, From)]
+#[serde(
+    crate = "::near_sdk::serde",
+    tag = "standard",
+    rename_all = "snake_case"
+)]
 /// Assuming wallets want to interact with Intents protocol, besides preparing the data in a certain
 /// form, they have to have the capability to sign raw messages (off-chain signatures) using an algorithm we understand.
 /// This enum solves that problem.
@@ -51,6 +68,116 @@ pub enum MultiPayload {
     /// SEP-53: The standard for signing data off-chain for Stellar accounts.
     /// See [SEP-53](https://github.com/stellar/stellar-protocol/blob/master/ecosystem/sep-0053.md)
     Sep53(SignedSep53Payload),
+
+    /// NEAR Ed25519: SHA-256 of the payload signed directly with
+    /// NEAR ed25519 access key, without NEP-413 envelope.
+    NearEd25519(SignedNearEd25519Payload),
+}
+
+/// Signing standard of [`MultiPayload`], i.e. its `standard` tag.
+/// [`Display`](std::fmt::Display) and [`FromStr`](std::str::FromStr)
+/// use exactly the same strings as serde.
+#[near(serializers = [borsh, json])]
+#[serde(rename_all = "snake_case")]
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    strum::Display,
+    strum::EnumString,
+    strum::EnumIter,
+)]
+#[strum(serialize_all = "snake_case")]
+pub enum SigningStandard {
+    Nep413,
+    Erc191,
+    Tip191,
+    RawEd25519,
+    #[serde(rename = "webauthn")]
+    #[strum(serialize = "webauthn")]
+    WebAuthn,
+    TonConnect,
+    Sep53,
+    NearEd25519,
+}
+
+impl MultiPayload {
+    /// Returns signing standard of the payload
+    #[inline]
+    pub const fn standard(&self) -> SigningStandard {
+        match self {
+            Self::Nep413(_) => SigningStandard::Nep413,
+            Self::Erc191(_) => SigningStandard::Erc191,
+            Self::Tip191(_) => SigningStandard::Tip191,
+            Self::RawEd25519(_) => SigningStandard::RawEd25519,
+            Self::WebAuthn(_) => SigningStandard::WebAuthn,
+            Self::TonConnect(_) => SigningStandard::TonConnect,
+            Self::Sep53(_) => SigningStandard::Sep53,
+            Self::NearEd25519(_) => SigningStandard::NearEd25519,
+        }
+    }
+
+    /// Parses [`MultiPayload`] from JSON value tagged by `standard`.
+    /// Unlike plain serde tagged enum, reports missing or unknown
+    /// `standard` as [`DefuseError::UnknownSigningStandard`] naming the
+    /// offending tag, so that relayers can tell it apart from malformed
+    /// payload of a known standard.
+    pub fn from_json_value(value: Value) -> Result<Self, DefuseError> {
+        let standard = match value.get("standard") {
+            Some(Value::String(standard)) => standard.clone(),
+            Some(other) => return Err(DefuseError::UnknownSigningStandard(other.to_string())),
+            None => return Err(DefuseError::UnknownSigningStandard(String::new())),
+        };
+
+        let Ok(standard) = standard.parse() else {
+            return Err(DefuseError::UnknownSigningStandard(standard));
+        };
+
+        Ok(match standard {
+            SigningStandard::Nep413 => Self::Nep413(serde_json::from_value(value)?),
+            SigningStandard::Erc191 => Self::Erc191(serde_json::from_value(value)?),
+            SigningStandard::Tip191 => Self::Tip191(serde_json::from_value(value)?),
+            SigningStandard::RawEd25519 => Self::RawEd25519(serde_json::from_value(value)?),
+            SigningStandard::WebAuthn => Self::WebAuthn(serde_json::from_value(value)?),
+            SigningStandard::TonConnect => Self::TonConnect(serde_json::from_value(value)?),
+            SigningStandard::Sep53 => Self::Sep53(serde_json::from_value(value)?),
+            SigningStandard::NearEd25519 => Self::NearEd25519(serde_json::from_value(value)?),
+        })
+    }
+
+    /// Verifies the signature and extracts [`DefusePayload`] in one go.
+    /// Returns the public key the payload was signed with along with
+    /// the payload itself.
+    ///
+    /// NOTE: consumes `self`, so [`hash`](Payload::hash) should be
+    /// calculated beforehand if needed
+    pub fn verify_and_extract<T>(
+        self,
+    ) -> Result<(PublicKey, DefusePayload<T>), ExtractDefusePayloadError>
+    where
+        T: DeserializeOwned,
+    {
+        let public_key = self
+            .verify()
+            .ok_or(ExtractDefusePayloadError::SignatureInvalid)?;
+        let payload = self.extract_defuse_payload()?;
+        Ok((public_key, payload))
+    }
+}
+
+impl<'de> Deserialize<'de> for MultiPayload {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Self::from_json_value(Value::deserialize(deserializer)?)
+            .map_err(near_sdk::serde::de::Error::custom)
+    }
 }
 
 impl Payload for MultiPayload {
@@ -68,6 +195,21 @@ impl Payload for MultiPayload {
             Self::WebAuthn(payload) => payload.hash(),
             Self::TonConnect(payload) => payload.hash(),
             Self::Sep53(payload) => payload.hash(),
+            Self::NearEd25519(payload) => payload.hash(),
+        }
+    }
+
+    #[inline]
+    fn prehash(&self) -> Cow<'_, [u8]> {
+        match self {
+            Self::Nep413(payload) => payload.prehash(),
+            Self::Erc191(payload) => payload.prehash(),
+            Self::Tip191(payload) => payload.prehash(),
+            Self::RawEd25519(payload) => payload.prehash(),
+            Self::WebAuthn(payload) => payload.prehash(),
+            Self::TonConnect(payload) => payload.prehash(),
+            Self::Sep53(payload) => payload.prehash(),
+            Self::NearEd25519(payload) => payload.prehash(),
         }
     }
 }
@@ -85,6 +227,7 @@ impl SignedPayload for MultiPayload {
             Self::WebAuthn(payload) => payload.verify(),
             Self::TonConnect(payload) => payload.verify().map(PublicKey::Ed25519),
             Self::Sep53(payload) => payload.verify().map(PublicKey::Ed25519),
+            Self::NearEd25519(payload) => payload.verify().map(PublicKey::Ed25519),
         }
     }
 }
@@ -93,7 +236,7 @@ impl<T> ExtractDefusePayload<T> for MultiPayload
 where
     T: DeserializeOwned,
 {
-    type Error = serde_json::Error;
+    type Error = ExtractDefusePayloadError;
 
     #[inline]
     fn extract_defuse_payload(self) -> Result<DefusePayload<T>, Self::Error> {
@@ -105,13 +248,26 @@ where
             Self::WebAuthn(payload) => payload.extract_defuse_payload(),
             Self::TonConnect(payload) => payload.extract_defuse_payload(),
             Self::Sep53(payload) => payload.extract_defuse_payload(),
+            Self::NearEd25519(payload) => payload.extract_defuse_payload(),
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use near_sdk::bs58;
+    use core::fmt::Debug;
+
+    use arbitrary::{Arbitrary, Unstructured};
+    use defuse_crypto::{Ed25519, Secp256k1};
+    use defuse_test_utils::{
+        payload::{assert_signed_payload_json_roundtrip, assert_signed_payload_roundtrip},
+        random::random_bytes,
+    };
+    use hex_literal::hex;
+    use near_sdk::{bs58, env, serde_json::json};
+    use rstest::rstest;
+
+    use crate::intents::DefuseIntents;
 
     use super::*;
 
@@ -129,4 +285,202 @@ mod tests {
                 .unwrap()
         );
     }
+
+    #[test]
+    fn verify_as() {
+        let p: MultiPayload = serde_json::from_str(r#"{"standard":"raw_ed25519","payload":"{\"signer_id\":\"74affa71ab030d400fdfa1bed033dfa6fd3ae34f92d17c046ebe368e80d53751\",\"verifying_contract\":\"intents.near\",\"deadline\":{\"timestamp\":1732035219},\"nonce\":\"XVoKfmScb3G+XqH9ke/fSlJ/3xO59sNhCxhpG821BH8=\",\"intents\":[{\"intent\":\"token_diff\",\"diff\":{\"nep141:base-0x833589fcd6edb6e08f4c7c32d4f71b54bda02913.omft.near\":\"-1000\",\"nep141:eth-0xdac17f958d2ee523a2206206994597c13d831ec7.omft.near\":\"998\"}}]}","public_key":"ed25519:8rVvtHWFr8hasdQGGD5WiQBTyr4iH2ruEPPVfj491RPN","signature":"ed25519:3vtbNQJHZfuV1s5DykzyjkbNLc583hnkrhTz57eDhd966iqzkor6Twgr4Loh2C195SCSEsiGfrd6KcxpjNq9ZbVj"}"#).unwrap();
+
+        assert!(
+            p.verify_as(
+                &"ed25519:8rVvtHWFr8hasdQGGD5WiQBTyr4iH2ruEPPVfj491RPN"
+                    .parse()
+                    .unwrap()
+            )
+        );
+        assert!(
+            !p.verify_as(
+                &"ed25519:5TagutioHgKLh7KZ1VEFBYfgRkPtqnKm9LoMnJMJugxm"
+                    .parse()
+                    .unwrap()
+            )
+        );
+    }
+
+    #[test]
+    fn verify_and_extract() {
+        let p: MultiPayload = serde_json::from_str(r#"{"standard":"raw_ed25519","payload":"{\"signer_id\":\"74affa71ab030d400fdfa1bed033dfa6fd3ae34f92d17c046ebe368e80d53751\",\"verifying_contract\":\"intents.near\",\"deadline\":{\"timestamp\":1732035219},\"nonce\":\"XVoKfmScb3G+XqH9ke/fSlJ/3xO59sNhCxhpG821BH8=\",\"intents\":[{\"intent\":\"token_diff\",\"diff\":{\"nep141:base-0x833589fcd6edb6e08f4c7c32d4f71b54bda02913.omft.near\":\"-1000\",\"nep141:eth-0xdac17f958d2ee523a2206206994597c13d831ec7.omft.near\":\"998\"}}]}","public_key":"ed25519:8rVvtHWFr8hasdQGGD5WiQBTyr4iH2ruEPPVfj491RPN","signature":"ed25519:3vtbNQJHZfuV1s5DykzyjkbNLc583hnkrhTz57eDhd966iqzkor6Twgr4Loh2C195SCSEsiGfrd6KcxpjNq9ZbVj"}"#).unwrap();
+
+        let (public_key, payload) = p.clone().verify_and_extract::<DefuseIntents>().unwrap();
+        assert_eq!(
+            public_key,
+            "ed25519:8rVvtHWFr8hasdQGGD5WiQBTyr4iH2ruEPPVfj491RPN"
+                .parse()
+                .unwrap()
+        );
+        assert_eq!(
+            payload.signer_id.as_str(),
+            "74affa71ab030d400fdfa1bed033dfa6fd3ae34f92d17c046ebe368e80d53751"
+        );
+        assert_eq!(payload.intents.len(), 1);
+
+        // signature doesn't match tampered payload
+        let MultiPayload::RawEd25519(mut raw) = p else {
+            unreachable!()
+        };
+        raw.payload = raw.payload.replace("-1000", "-1");
+        assert!(matches!(
+            MultiPayload::from(raw).verify_and_extract::<DefuseIntents>(),
+            Err(ExtractDefusePayloadError::SignatureInvalid)
+        ));
+    }
+
+    #[test]
+    fn signing_standard_matches_tag() {
+        use strum::IntoEnumIterator;
+
+        for standard in SigningStandard::iter() {
+            let tag = standard.to_string();
+            assert_eq!(serde_json::to_value(standard).unwrap(), tag);
+            assert_eq!(tag.parse::<SigningStandard>().unwrap(), standard);
+            assert_eq!(
+                serde_json::from_value::<SigningStandard>(tag.into()).unwrap(),
+                standard
+            );
+        }
+
+        for (tag, standard) in [
+            ("nep413", SigningStandard::Nep413),
+            ("erc191", SigningStandard::Erc191),
+            ("tip191", SigningStandard::Tip191),
+            ("raw_ed25519", SigningStandard::RawEd25519),
+            ("webauthn", SigningStandard::WebAuthn),
+            ("ton_connect", SigningStandard::TonConnect),
+            ("sep53", SigningStandard::Sep53),
+            ("near_ed25519", SigningStandard::NearEd25519),
+        ] {
+            assert_eq!(standard.to_string(), tag);
+            assert_eq!(tag.parse::<SigningStandard>().unwrap(), standard);
+        }
+
+        for tag in ["nep_413", "web_authn", "Nep413", ""] {
+            tag.parse::<SigningStandard>().unwrap_err();
+        }
+
+        let p: MultiPayload = serde_json::from_str(r#"{"standard":"raw_ed25519","payload":"","public_key":"ed25519:8rVvtHWFr8hasdQGGD5WiQBTyr4iH2ruEPPVfj491RPN","signature":"ed25519:3vtbNQJHZfuV1s5DykzyjkbNLc583hnkrhTz57eDhd966iqzkor6Twgr4Loh2C195SCSEsiGfrd6KcxpjNq9ZbVj"}"#).unwrap();
+        assert_eq!(p.standard(), SigningStandard::RawEd25519);
+        assert_eq!(
+            serde_json::to_value(&p).unwrap()["standard"],
+            serde_json::to_value(p.standard()).unwrap()
+        );
+    }
+
+    #[test]
+    fn unknown_standard() {
+        assert!(matches!(
+            MultiPayload::from_json_value(json!({"standard": "nep_413"})),
+            Err(DefuseError::UnknownSigningStandard(standard)) if standard == "nep_413",
+        ));
+        assert!(matches!(
+            MultiPayload::from_json_value(json!({"payload": ""})),
+            Err(DefuseError::UnknownSigningStandard(standard)) if standard.is_empty(),
+        ));
+        // known standard with malformed payload is still a JSON error
+        assert!(matches!(
+            MultiPayload::from_json_value(json!({"standard": "raw_ed25519"})),
+            Err(DefuseError::JSON(_)),
+        ));
+
+        let err = serde_json::from_str::<MultiPayload>(r#"{"standard":"nep_413"}"#).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains(&DefuseError::UnknownSigningStandard("nep_413".to_string()).to_string())
+        );
+    }
+
+    fn arbitrary_roundtrip<T>(bytes: &[u8])
+    where
+        T: for<'a> Arbitrary<'a> + SignedPayload + Serialize + DeserializeOwned + PartialEq + Debug,
+        T::PublicKey: PartialEq + Debug,
+    {
+        assert_signed_payload_roundtrip(&Unstructured::new(bytes).arbitrary::<T>().unwrap());
+    }
+
+    /// Secret key for examples signed with Ed25519
+    const ED25519_SECRET_KEY: [u8; 32] = [1; 32];
+
+    /// Roundtrip of a payload with a valid signature, so that
+    /// `verify()` is checked to succeed after deserialization as well
+    fn example_roundtrip(signed: impl Into<MultiPayload>) {
+        assert_signed_payload_json_roundtrip(&signed.into());
+    }
+
+    fn nep413_example() -> SignedNep413Payload {
+        let payload = defuse_nep413::Nep413Payload::new("Hello NEAR!".to_string())
+            .with_recipient("intents.near");
+        SignedNep413Payload {
+            public_key: Ed25519::public_key(&ED25519_SECRET_KEY),
+            signature: Ed25519::sign(&ED25519_SECRET_KEY, &payload.hash()),
+            payload,
+        }
+    }
+
+    /// Signed in Metamask, see `defuse_erc191` tests
+    fn erc191_example() -> SignedErc191Payload {
+        let mut signature = hex!(
+            "7800a70d05cde2c49ed546a6ce887ce6027c2c268c0285f6efef0cdfc4366b23643790f67a86468ee8301ed12cfffcb07c6530f90a9327ec057800fabd332e471c"
+        );
+        Secp256k1::normalize_signature(&mut signature);
+        SignedErc191Payload {
+            payload: defuse_erc191::Erc191Payload("Hello world!".to_string()),
+            signature,
+        }
+    }
+
+    fn raw_ed25519_example() -> SignedRawEd25519Payload {
+        let payload = "Hello, World!".to_string();
+        SignedRawEd25519Payload {
+            public_key: Ed25519::public_key(&ED25519_SECRET_KEY),
+            signature: Ed25519::sign(&ED25519_SECRET_KEY, payload.as_bytes()),
+            payload,
+        }
+    }
+
+    fn near_ed25519_example() -> SignedNearEd25519Payload {
+        let payload = "Hello, World!".to_string();
+        SignedNearEd25519Payload {
+            public_key: Ed25519::public_key(&ED25519_SECRET_KEY),
+            signature: Ed25519::sign(&ED25519_SECRET_KEY, &env::sha256_array(payload.as_bytes())),
+            payload,
+        }
+    }
+
+    /// See `SignedWebAuthnPayload` tests
+    fn webauthn_example() -> MultiPayload {
+        serde_json::from_str(r#"{"standard":"webauthn","payload":"{\"signer_id\":\"19a8cd22b37802c3cbc0031f55c70f3858ac48dbfb7697c435da637fea0e0e47\",\"verifying_contract\":\"intents.near\",\"deadline\":{\"timestamp\":1732035219},\"nonce\":\"XVoKfmScb3G+XqH9ke/fSlJ/3xO59sNhCxhpG821BH8=\",\"intents\":[{\"intent\":\"token_diff\",\"diff\":{\"nep141:base-0x833589fcd6edb6e08f4c7c32d4f71b54bda02913.omft.near\":\"-1000\",\"nep141:eth-0xdac17f958d2ee523a2206206994597c13d831ec7.omft.near\":\"998\"}}]}","public_key":"ed25519:2jAUugnvWPvMaftKj5TDkyfsfxBwYjkMSf5MRtqDUMHY","signature":"ed25519:2yBp5oExa9BBZQf8habpjLUaSiprvT7srHrK38Bxt9zL1yrkQSeeXMLmkihKCd9frmTdk24YctUdzNN5nGqHWHgb","client_data_json":"{\"type\":\"webauthn.get\",\"challenge\":\"PfRFOFrLxCfyomuDryxhv6v2OzJIWqyMXaMikUYHSmY\",\"origin\":\"http://localhost:3000\"}","authenticator_data":"SZYN5YgOjGh0NBcPZHZgW4_krrmihjLHmVzzuoMdl2MFZ50DuA"}"#).unwrap()
+    }
+
+    /// SEP-53 reference test vector, see `defuse_sep53` examples
+    fn sep53_example() -> SignedSep53Payload {
+        SignedSep53Payload {
+            payload: defuse_sep53::Sep53Payload::new("Hello, World!".to_string()),
+            public_key: hex!("6e5bb46baf172b03950ff085f4c11fc356c75a918331a98ed4839c9c7792b381"),
+            signature: hex!(
+                "7cee5d6d885752104c85eea421dfdcb95abf01f1271d11c4bec3fcbd7874dccd6e2e98b97b8eb23b643cac4073bb77de5d07b0710139180ae9f3cbba78f2ba04"
+            ),
+        }
+    }
+
+    #[rstest]
+    #[case::nep413(|_| example_roundtrip(nep413_example()))]
+    #[case::erc191(|_| example_roundtrip(erc191_example()))]
+    #[case::tip191(arbitrary_roundtrip::<SignedTip191Payload>)]
+    #[case::raw_ed25519(|_| example_roundtrip(raw_ed25519_example()))]
+    #[case::webauthn(|_| example_roundtrip(webauthn_example()))]
+    #[case::ton_connect(arbitrary_roundtrip::<SignedTonConnectPayload>)]
+    #[case::sep53(arbitrary_roundtrip::<SignedSep53Payload>)]
+    #[case::sep53_with_key(|_| example_roundtrip(sep53_example()))]
+    #[case::near_ed25519(|_| example_roundtrip(near_ed25519_example()))]
+    fn signed_payload_serde_roundtrip(random_bytes: Vec<u8>, #[case] roundtrip: fn(&[u8])) {
+        roundtrip(&random_bytes);
+    }
 }
@@ -0,0 +1,60 @@
+use std::borrow::Cow;
+
+use defuse_crypto::{Curve, Ed25519, Payload, SignedPayload, serde::AsCurve};
+use near_sdk::{env, near, serde::de::DeserializeOwned, serde_json};
+use serde_with::serde_as;
+
+use super::{ExtractDefusePayload, ExtractDefusePayloadError};
+
+/// `SHA-256` of JSON-serialized [`DefusePayload`](super::DefusePayload)
+/// signed directly with NEAR ed25519 access key
+#[cfg_attr(
+    all(feature = "abi", not(target_arch = "wasm32")),
+    serde_as(schemars = true)
+)]
+#[cfg_attr(
+    not(all(feature = "abi", not(target_arch = "wasm32"))),
+    serde_as(schemars = false)
+)]
+#[near(serializers = [borsh, json])]
+#[derive(Debug, Clone)]
+pub struct SignedNearEd25519Payload {
+    pub payload: String,
+
+    #[serde_as(as = "AsCurve<Ed25519>")]
+    pub public_key: <Ed25519 as Curve>::PublicKey,
+    #[serde_as(as = "AsCurve<Ed25519>")]
+    pub signature: <Ed25519 as Curve>::Signature,
+}
+
+impl Payload for SignedNearEd25519Payload {
+    #[inline]
+    fn hash(&self) -> [u8; 32] {
+        env::sha256_array(self.payload.as_bytes())
+    }
+
+    #[inline]
+    fn prehash(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(self.payload.as_bytes())
+    }
+}
+
+impl SignedPayload for SignedNearEd25519Payload {
+    type PublicKey = <Ed25519 as Curve>::PublicKey;
+
+    #[inline]
+    fn verify(&self) -> Option<Self::PublicKey> {
+        Ed25519::verify(&self.signature, &self.hash(), &self.public_key)
+    }
+}
+
+impl<T> ExtractDefusePayload<T> for SignedNearEd25519Payload
+where
+    T: DeserializeOwned,
+{
+    type Error = ExtractDefusePayloadError;
+
+    fn extract_defuse_payload(self) -> Result<super::DefusePayload<T>, Self::Error> {
+        serde_json::from_str(&self.payload).map_err(Into::into)
+    }
+}
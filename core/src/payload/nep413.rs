@@ -8,7 +8,7 @@ use near_sdk::{
 
 use crate::Deadline;
 
-use super::{DefusePayload, ExtractDefusePayload};
+use super::{DefusePayload, ExtractDefusePayload, ExtractDefusePayloadError};
 
 #[near(serializers = [json])]
 #[autoimpl(Deref using self.message)]
@@ -27,7 +27,7 @@ impl<T> ExtractDefusePayload<T> for Nep413Payload
 where
     T: DeserializeOwned,
 {
-    type Error = serde_json::Error;
+    type Error = ExtractDefusePayloadError;
 
     fn extract_defuse_payload(self) -> Result<DefusePayload<T>, Self::Error> {
         let Nep413DefuseMessage {
@@ -39,8 +39,13 @@ where
         Ok(DefusePayload {
             signer_id,
             verifying_contract: self.recipient.parse().map_err(|_| {
-                de::Error::invalid_value(de::Unexpected::Str(&self.recipient), &"AccountId")
+                <serde_json::Error as de::Error>::invalid_value(
+                    de::Unexpected::Str(&self.recipient),
+                    &"AccountId",
+                )
             })?,
+            // not supported by `Nep413DefuseMessage`
+            network_id: None,
             deadline,
             nonce: self.nonce,
             message,
@@ -52,7 +57,7 @@ impl<T> ExtractDefusePayload<T> for SignedNep413Payload
 where
     T: DeserializeOwned,
 {
-    type Error = serde_json::Error;
+    type Error = ExtractDefusePayloadError;
 
     #[inline]
     fn extract_defuse_payload(self) -> Result<DefusePayload<T>, Self::Error> {
@@ -1,8 +1,10 @@
+use std::borrow::Cow;
+
 use defuse_crypto::{Curve, Ed25519, Payload, SignedPayload, serde::AsCurve};
 use near_sdk::{env, near, serde::de::DeserializeOwned, serde_json};
 use serde_with::serde_as;
 
-use super::ExtractDefusePayload;
+use super::{ExtractDefusePayload, ExtractDefusePayloadError};
 
 #[cfg_attr(
     all(feature = "abi", not(target_arch = "wasm32")),
@@ -28,6 +30,11 @@ impl Payload for SignedRawEd25519Payload {
     fn hash(&self) -> [u8; 32] {
         env::sha256_array(self.payload.as_bytes())
     }
+
+    #[inline]
+    fn prehash(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(self.payload.as_bytes())
+    }
 }
 
 impl SignedPayload for SignedRawEd25519Payload {
@@ -43,9 +50,9 @@ impl<T> ExtractDefusePayload<T> for SignedRawEd25519Payload
 where
     T: DeserializeOwned,
 {
-    type Error = serde_json::Error;
+    type Error = ExtractDefusePayloadError;
 
     fn extract_defuse_payload(self) -> Result<super::DefusePayload<T>, Self::Error> {
-        serde_json::from_str(&self.payload)
+        serde_json::from_str(&self.payload).map_err(Into::into)
     }
 }
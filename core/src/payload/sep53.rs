@@ -1,4 +1,4 @@
-use crate::payload::{DefusePayload, ExtractDefusePayload};
+use crate::payload::{DefusePayload, ExtractDefusePayload, ExtractDefusePayloadError};
 use defuse_sep53::{Sep53Payload, SignedSep53Payload};
 use near_sdk::{serde::de::DeserializeOwned, serde_json};
 
@@ -6,7 +6,7 @@ impl<T> ExtractDefusePayload<T> for SignedSep53Payload
 where
     T: DeserializeOwned,
 {
-    type Error = serde_json::Error;
+    type Error = ExtractDefusePayloadError;
 
     #[inline]
     fn extract_defuse_payload(self) -> Result<DefusePayload<T>, Self::Error> {
@@ -18,9 +18,9 @@ impl<T> ExtractDefusePayload<T> for Sep53Payload
 where
     T: DeserializeOwned,
 {
-    type Error = serde_json::Error;
+    type Error = ExtractDefusePayloadError;
 
     fn extract_defuse_payload(self) -> Result<DefusePayload<T>, Self::Error> {
-        serde_json::from_str(&self.payload)
+        serde_json::from_str(&self.payload).map_err(Into::into)
     }
 }
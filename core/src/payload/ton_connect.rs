@@ -4,13 +4,13 @@ use near_sdk::{
     serde_json,
 };
 
-use super::{DefusePayload, ExtractDefusePayload};
+use super::{DefusePayload, ExtractDefusePayload, ExtractDefusePayloadError};
 
 impl<T> ExtractDefusePayload<T> for SignedTonConnectPayload
 where
     T: DeserializeOwned,
 {
-    type Error = serde_json::Error;
+    type Error = ExtractDefusePayloadError;
 
     #[inline]
     fn extract_defuse_payload(self) -> Result<DefusePayload<T>, Self::Error> {
@@ -22,11 +22,11 @@ impl<T> ExtractDefusePayload<T> for TonConnectPayload
 where
     T: DeserializeOwned,
 {
-    type Error = serde_json::Error;
+    type Error = ExtractDefusePayloadError;
 
     fn extract_defuse_payload(self) -> Result<DefusePayload<T>, Self::Error> {
         let TonConnectPayloadSchema::Text { text } = self.payload else {
-            return Err(Error::custom("only text payload supported"));
+            return Err(serde_json::Error::custom("only text payload supported").into());
         };
 
         let p: DefusePayload<T> = serde_json::from_str(&text)?;
@@ -41,7 +41,7 @@ where
         //
         // So, we ended up to assert at least following:
         if p.deadline.into_timestamp() < self.timestamp {
-            return Err(Error::custom("deadline < timestamp"));
+            return Err(serde_json::Error::custom("deadline < timestamp").into());
         }
 
         Ok(p)
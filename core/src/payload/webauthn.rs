@@ -2,7 +2,7 @@ use defuse_crypto::{Payload, PublicKey, SignedPayload};
 use defuse_webauthn::PayloadSignature;
 use near_sdk::{CryptoHash, env, near, serde::de::DeserializeOwned, serde_json};
 
-use super::{DefusePayload, ExtractDefusePayload};
+use super::{DefusePayload, ExtractDefusePayload, ExtractDefusePayloadError};
 
 #[near(serializers = [json])]
 #[derive(Debug, Clone)]
@@ -32,11 +32,11 @@ impl<T> ExtractDefusePayload<T> for SignedWebAuthnPayload
 where
     T: DeserializeOwned,
 {
-    type Error = serde_json::Error;
+    type Error = ExtractDefusePayloadError;
 
     #[inline]
     fn extract_defuse_payload(self) -> Result<DefusePayload<T>, Self::Error> {
-        serde_json::from_str(&self.payload)
+        serde_json::from_str(&self.payload).map_err(Into::into)
     }
 }
 
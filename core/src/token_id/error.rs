@@ -1,4 +1,4 @@
-use super::MAX_ALLOWED_TOKEN_ID_LEN;
+use super::{MAX_ALLOWED_TOKEN_ID_LEN, MAX_ALLOWED_TOKEN_ID_STR_LEN};
 use near_account_id::ParseAccountError;
 
 #[derive(thiserror::Error, Debug)]
@@ -9,4 +9,6 @@ pub enum TokenIdError {
     ParseError(#[from] strum::ParseError),
     #[error("token_id is too long. Max length is {MAX_ALLOWED_TOKEN_ID_LEN}, got {0}")]
     TokenIdTooLarge(usize),
+    #[error("token_id string is too long. Max length is {MAX_ALLOWED_TOKEN_ID_STR_LEN}, got {0}")]
+    TooLong(usize),
 }
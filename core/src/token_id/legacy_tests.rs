@@ -122,9 +122,11 @@ impl FromStr for LegacyTokenId {
     }
 }
 
-impl From<TokenId> for LegacyTokenId {
-    fn from(token_id: TokenId) -> Self {
-        match token_id {
+impl TryFrom<TokenId> for LegacyTokenId {
+    type Error = ();
+
+    fn try_from(token_id: TokenId) -> Result<Self, Self::Error> {
+        Ok(match token_id {
             TokenId::Nep141(token_id) => Self::Nep141(token_id.into_contract_id()),
             TokenId::Nep171(token_id) => {
                 let (contract_id, nft_token_id) = token_id.into_contract_id_and_nft_token_id();
@@ -134,14 +136,18 @@ impl From<TokenId> for LegacyTokenId {
                 let (contract_id, mt_token_id) = token_id.into_contract_id_and_mt_token_id();
                 Self::Nep245(contract_id, mt_token_id)
             }
-        }
+            // native token didn't exist in legacy representation
+            TokenId::Native => return Err(()),
+        })
     }
 }
 
 #[rstest]
 #[trace]
 fn borsh_roundtrip(#[from(make_arbitrary)] token_id: TokenId) {
-    let legacy_token_id: LegacyTokenId = token_id.clone().into();
+    let Ok(legacy_token_id) = LegacyTokenId::try_from(token_id.clone()) else {
+        return;
+    };
 
     let ser = borsh::to_vec(&token_id).unwrap();
     assert_eq!(ser, borsh::to_vec(&legacy_token_id).unwrap());
@@ -155,7 +161,9 @@ fn borsh_roundtrip(#[from(make_arbitrary)] token_id: TokenId) {
 #[rstest]
 #[trace]
 fn display_from_str_roundtrip(#[from(make_arbitrary)] token_id: TokenId) {
-    let legacy_token_id: LegacyTokenId = token_id.clone().into();
+    let Ok(legacy_token_id) = LegacyTokenId::try_from(token_id.clone()) else {
+        return;
+    };
 
     let ser = token_id.to_string();
     assert_eq!(ser, legacy_token_id.to_string());
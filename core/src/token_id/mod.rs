@@ -10,11 +10,16 @@ use core::{
     fmt::{self, Debug, Display},
     str::FromStr,
 };
-use near_sdk::near;
+use near_sdk::{AccountIdRef, near};
 use serde_with::{DeserializeFromStr, SerializeDisplay};
 use strum::{EnumDiscriminants, EnumIter, EnumString};
 
-const MAX_ALLOWED_TOKEN_ID_LEN: usize = 127;
+pub(crate) const MAX_ALLOWED_TOKEN_ID_LEN: usize = 127;
+
+/// Max length of a string representation of [`TokenId`], i.e.
+/// `<type>:<contract_id>:<token_id>`
+pub const MAX_ALLOWED_TOKEN_ID_STR_LEN: usize =
+    "nep245".len() + 1 + AccountIdRef::MAX_LEN + 1 + MAX_ALLOWED_TOKEN_ID_LEN;
 
 #[cfg_attr(any(feature = "arbitrary", test), derive(arbitrary::Arbitrary))]
 #[derive(
@@ -41,6 +46,14 @@ pub enum TokenId {
     Nep141(Nep141TokenId),
     Nep171(Nep171TokenId),
     Nep245(Nep245TokenId),
+    /// Native NEAR, see [`TokenId::NATIVE`]
+    #[from(skip)]
+    Native,
+}
+
+impl TokenId {
+    /// String representation of [`TokenId::Native`]
+    pub const NATIVE: &'static str = "native";
 }
 
 impl Debug for TokenId {
@@ -56,6 +69,7 @@ impl Debug for TokenId {
             Self::Nep245(token_id) => {
                 write!(f, "{}:{}", TokenIdType::Nep245, token_id)
             }
+            Self::Native => f.write_str(Self::NATIVE),
         }
     }
 }
@@ -72,6 +86,12 @@ impl FromStr for TokenId {
 
     #[inline]
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() > MAX_ALLOWED_TOKEN_ID_STR_LEN {
+            return Err(TokenIdError::TooLong(s.len()));
+        }
+        if s == Self::NATIVE {
+            return Ok(Self::Native);
+        }
         let (typ, data) = s
             .split_once(':')
             .ok_or(strum::ParseError::VariantNotFound)?;
@@ -79,6 +99,8 @@ impl FromStr for TokenId {
             TokenIdType::Nep141 => data.parse().map(Self::Nep141),
             TokenIdType::Nep171 => data.parse().map(Self::Nep171),
             TokenIdType::Nep245 => data.parse().map(Self::Nep245),
+            // native token doesn't have any data
+            TokenIdType::Native => Err(strum::ParseError::VariantNotFound.into()),
         }
     }
 }
@@ -117,6 +139,7 @@ mod abi {
                             Nep245TokenId::new("mt.near".parse().unwrap(), "token_id1".to_string())
                                 .unwrap(),
                         ),
+                        TokenId::Native,
                     ]
                     .map(|s| s.to_string())
                     .to_vec()
@@ -146,6 +169,7 @@ mod tests {
             ("nep141:abc", "0003000000616263"),
             ("nep171:abc:xyz", "01030000006162630300000078797a"),
             ("nep245:abc:xyz", "02030000006162630300000078797a"),
+            ("native", "03"),
         )]
         (token_id_str, borsh_expected_hex): (&str, &str),
     ) {
@@ -160,6 +184,34 @@ mod tests {
         assert_eq!(got.to_string(), token_id_str);
     }
 
+    #[rstest]
+    #[trace]
+    fn native_with_data_fails(#[values("native:", "native:abc", "native:abc:xyz")] s: &str) {
+        s.parse::<TokenId>().unwrap_err();
+    }
+
+    #[test]
+    fn max_str_len() {
+        let contract_id = "a".repeat(AccountIdRef::MAX_LEN);
+
+        let s = format!(
+            "nep245:{contract_id}:{}",
+            "b".repeat(MAX_ALLOWED_TOKEN_ID_LEN)
+        );
+        assert_eq!(s.len(), MAX_ALLOWED_TOKEN_ID_STR_LEN);
+        let token_id: TokenId = s.parse().unwrap();
+        assert_eq!(token_id.to_string(), s);
+
+        let s = format!(
+            "nep245:{contract_id}:{}",
+            "b".repeat(MAX_ALLOWED_TOKEN_ID_LEN + 1)
+        );
+        assert!(matches!(
+            s.parse::<TokenId>(),
+            Err(TokenIdError::TooLong(len)) if len == MAX_ALLOWED_TOKEN_ID_STR_LEN + 1
+        ));
+    }
+
     #[rstest]
     #[trace]
     fn borsh_roundtrip(#[from(make_arbitrary)] token_id: TokenId) {
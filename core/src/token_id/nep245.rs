@@ -28,6 +28,11 @@ pub struct Nep245TokenId {
 }
 
 impl Nep245TokenId {
+    /// Returns [`TokenIdError::TokenIdTooLarge`] if `mt_token_id` is longer
+    /// than [`MAX_ALLOWED_TOKEN_ID_LEN`].
+    ///
+    /// Note that `mt_token_id` is allowed to contain `:`, since `contract_id`
+    /// can't contain it and, thus, the first `:` is always the delimiter.
     pub fn new(
         contract_id: AccountId,
         mt_token_id: defuse_nep245::TokenId,
@@ -98,6 +103,17 @@ mod tests {
         assert_eq!(got, token_id);
     }
 
+    #[rstest]
+    #[trace]
+    fn colon_in_token_id_roundtrip(
+        #[values(":", "a:b", "::", "nep141:ft.near", "nep245:mt.near:a:b")] token_id: &str,
+    ) {
+        let token_id =
+            Nep245TokenId::new("contract.near".parse().unwrap(), token_id.to_string()).unwrap();
+        let got: Nep245TokenId = token_id.to_string().parse().unwrap();
+        assert_eq!(got, token_id);
+    }
+
     #[rstest]
     fn token_id_length(random_bytes: Vec<u8>) {
         let mut u = Unstructured::new(&random_bytes);
@@ -13,7 +13,7 @@ impl Curve for Ed25519 {
     type VerifyingKey = Self::PublicKey;
 
     #[inline]
-    fn verify(
+    fn verify_prehashed(
         signature: &Self::Signature,
         message: &Self::Message,
         public_key: &Self::VerifyingKey,
@@ -28,8 +28,84 @@ impl Curve for Ed25519 {
             .then_some(public_key)
             .copied()
     }
+
+    /// Ed25519 hashes the message internally, so it's verified as is
+    #[inline]
+    fn verify_message(
+        signature: &Self::Signature,
+        message: &[u8],
+        public_key: &Self::VerifyingKey,
+    ) -> Option<Self::PublicKey> {
+        Self::verify_prehashed(signature, message, public_key)
+    }
 }
 
 impl TypedCurve for Ed25519 {
     const CURVE_TYPE: CurveType = CurveType::Ed25519;
 }
+
+#[cfg(any(feature = "signing", test))]
+impl Ed25519 {
+    /// Derives public key from 32-byte secret key (i.e. seed)
+    #[inline]
+    pub fn public_key(secret_key: &[u8; 32]) -> <Self as Curve>::PublicKey {
+        ed25519_dalek::SigningKey::from_bytes(secret_key)
+            .verifying_key()
+            .to_bytes()
+    }
+
+    /// Signs `message` with 32-byte secret key (i.e. seed)
+    #[inline]
+    pub fn sign(secret_key: &[u8; 32], message: &[u8]) -> <Self as Curve>::Signature {
+        use ed25519_dalek::Signer;
+
+        ed25519_dalek::SigningKey::from_bytes(secret_key)
+            .sign(message)
+            .to_bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ed25519_dalek::{Signer, SigningKey};
+
+    use super::*;
+
+    #[test]
+    fn sign_roundtrip() {
+        let secret_key = [3; 32];
+        let public_key = Ed25519::public_key(&secret_key);
+        let signature = Ed25519::sign(&secret_key, b"hello");
+
+        assert_eq!(
+            Ed25519::verify(&signature, b"hello", &public_key),
+            Some(public_key)
+        );
+        assert_eq!(Ed25519::verify(&signature, b"world", &public_key), None);
+    }
+
+    #[test]
+    fn verify_message_and_prehashed() {
+        let signing_key = SigningKey::from_bytes(&[7; 32]);
+        let public_key = signing_key.verifying_key().to_bytes();
+        let message = b"hello";
+        let signature = signing_key.sign(message).to_bytes();
+
+        assert_eq!(
+            Ed25519::verify_message(&signature, message, &public_key),
+            Some(public_key)
+        );
+        assert_eq!(
+            Ed25519::verify_prehashed(&signature, message, &public_key),
+            Some(public_key)
+        );
+        assert_eq!(
+            Ed25519::verify(&signature, message, &public_key),
+            Some(public_key)
+        );
+        assert_eq!(
+            Ed25519::verify_message(&signature, b"world", &public_key),
+            None
+        );
+    }
+}
@@ -18,11 +18,31 @@ pub trait Curve {
     /// Public key that should be known prior to verification
     type VerifyingKey;
 
+    /// Verifies signature over [`Message`](Curve::Message), i.e. the
+    /// digest for curves which sign prehashed messages
+    fn verify_prehashed(
+        signature: &Self::Signature,
+        digest: &Self::Message,
+        verifying_key: &Self::VerifyingKey,
+    ) -> Option<Self::PublicKey>;
+
+    /// Verifies signature over raw `message`, hashing it first
+    /// according to the curve's convention if needed
+    fn verify_message(
+        signature: &Self::Signature,
+        message: &[u8],
+        verifying_key: &Self::VerifyingKey,
+    ) -> Option<Self::PublicKey>;
+
+    /// Alias for [`verify_prehashed`](Curve::verify_prehashed)
+    #[inline]
     fn verify(
         signature: &Self::Signature,
         message: &Self::Message,
         verifying_key: &Self::VerifyingKey,
-    ) -> Option<Self::PublicKey>;
+    ) -> Option<Self::PublicKey> {
+        Self::verify_prehashed(signature, message, verifying_key)
+    }
 }
 
 #[derive(Display, IntoStaticStr, EnumString)]
@@ -67,4 +87,6 @@ pub enum ParseCurveError {
     WrongCurveType,
     #[error("base58: {0}")]
     Base58(#[from] bs58::decode::Error),
+    #[error("invalid public key")]
+    InvalidPublicKey,
 }
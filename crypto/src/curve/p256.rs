@@ -1,5 +1,5 @@
 use super::{Curve, CurveType, TypedCurve};
-use near_sdk::CryptoHash;
+use near_sdk::{CryptoHash, env};
 use p256::{
     EncodedPoint,
     ecdsa::{Signature, VerifyingKey, signature::hazmat::PrehashVerifier},
@@ -20,7 +20,7 @@ impl Curve for P256 {
 
     type VerifyingKey = Self::PublicKey;
 
-    fn verify(
+    fn verify_prehashed(
         signature: &Self::Signature,
         prehashed: &Self::Message,
         public_key: &Self::VerifyingKey,
@@ -46,6 +46,16 @@ impl Curve for P256 {
             .then_some(public_key)
             .copied()
     }
+
+    /// Message is hashed with SHA-256, as in ES256
+    #[inline]
+    fn verify_message(
+        signature: &Self::Signature,
+        message: &[u8],
+        public_key: &Self::VerifyingKey,
+    ) -> Option<Self::PublicKey> {
+        Self::verify_prehashed(signature, &env::sha256_array(message), public_key)
+    }
 }
 
 impl TypedCurve for P256 {
@@ -1,3 +1,4 @@
+use k256::elliptic_curve::sec1::ToEncodedPoint;
 use near_sdk::{CryptoHash, env};
 
 use super::{Curve, CurveType, TypedCurve};
@@ -24,7 +25,7 @@ impl Curve for Secp256k1 {
     type VerifyingKey = ();
 
     #[inline]
-    fn verify(
+    fn verify_prehashed(
         [signature @ .., v]: &Self::Signature,
         hash: &Self::Message,
         _verifying_key: &(),
@@ -36,8 +37,131 @@ impl Curve for Secp256k1 {
             true,
         )
     }
+
+    /// Message is hashed with Keccak-256, as in Ethereum
+    #[inline]
+    fn verify_message(
+        signature: &Self::Signature,
+        message: &[u8],
+        verifying_key: &(),
+    ) -> Option<Self::PublicKey> {
+        Self::verify_prehashed(signature, &env::keccak256_array(message), verifying_key)
+    }
+}
+
+impl Secp256k1 {
+    /// Converts SEC1-encoded public key, either compressed (`0x02`/`0x03`
+    /// prefix) or uncompressed (`0x04` prefix), as well as raw `x || y`
+    /// coordinates, into [`PublicKey`](Curve::PublicKey)
+    pub fn normalize_public_key(bytes: &[u8]) -> Option<<Self as Curve>::PublicKey> {
+        if let Ok(public_key) = bytes.try_into() {
+            return Some(public_key);
+        }
+        k256::PublicKey::from_sec1_bytes(bytes)
+            .ok()?
+            .to_encoded_point(false)
+            .as_bytes()
+            .get(1..)?
+            .try_into()
+            .ok()
+    }
 }
 
 impl TypedCurve for Secp256k1 {
     const CURVE_TYPE: CurveType = CurveType::Secp256k1;
 }
+
+#[cfg(any(feature = "signing", test))]
+impl Secp256k1 {
+    /// Derives public key from 32-byte secret key.
+    /// Returns `None` if the secret key is not a valid scalar
+    pub fn public_key(secret_key: &[u8; 32]) -> Option<<Self as Curve>::PublicKey> {
+        k256::ecdsa::SigningKey::from_bytes(secret_key.into())
+            .ok()?
+            .verifying_key()
+            .to_encoded_point(false)
+            .as_bytes()
+            .get(1..)?
+            .try_into()
+            .ok()
+    }
+
+    /// Signs prehashed `digest` with 32-byte secret key, producing
+    /// non-malleable signature with recovery byte v ∈ {0, 1}.
+    /// Returns `None` if the secret key is not a valid scalar
+    pub fn sign(
+        secret_key: &[u8; 32],
+        digest: &<Self as Curve>::Message,
+    ) -> Option<<Self as Curve>::Signature> {
+        let (signature, recovery_id) = k256::ecdsa::SigningKey::from_bytes(secret_key.into())
+            .ok()?
+            .sign_prehash_recoverable(digest)
+            .ok()?;
+
+        let mut sig = [0; 65];
+        sig[..64].copy_from_slice(&signature.to_bytes());
+        sig[64] = recovery_id.to_byte();
+        Some(sig)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use k256::ecdsa::SigningKey;
+
+    use super::*;
+
+    #[test]
+    fn sign_roundtrip() {
+        let secret_key = [3; 32];
+        let public_key = Secp256k1::public_key(&secret_key).unwrap();
+        let digest = env::keccak256_array(b"hello");
+        let signature = Secp256k1::sign(&secret_key, &digest).unwrap();
+
+        assert_eq!(
+            Secp256k1::verify(&signature, &digest, &()),
+            Some(public_key)
+        );
+        assert_eq!(
+            Secp256k1::verify_message(&signature, b"hello", &()),
+            Some(public_key)
+        );
+
+        // zero is not a valid secret key
+        assert_eq!(Secp256k1::sign(&[0; 32], &digest), None);
+    }
+
+    #[test]
+    fn verify_message_and_prehashed() {
+        let signing_key = SigningKey::from_bytes(&[7; 32].into()).unwrap();
+        let public_key: [u8; 64] = signing_key
+            .verifying_key()
+            .to_encoded_point(false)
+            .as_bytes()[1..]
+            .try_into()
+            .unwrap();
+
+        let message = b"hello";
+        let digest = env::keccak256_array(message);
+        let (signature, recovery_id) = signing_key.sign_prehash_recoverable(&digest).unwrap();
+        let mut sig = [0; 65];
+        sig[..64].copy_from_slice(&signature.to_bytes());
+        sig[64] = recovery_id.to_byte();
+
+        assert_eq!(
+            Secp256k1::verify_message(&sig, message, &()),
+            Some(public_key)
+        );
+        assert_eq!(
+            Secp256k1::verify_prehashed(&sig, &digest, &()),
+            Some(public_key)
+        );
+        assert_eq!(Secp256k1::verify(&sig, &digest, &()), Some(public_key));
+
+        // forgetting to prehash recovers a different key or nothing
+        assert_ne!(
+            Secp256k1::verify_message(&sig, &digest, &()),
+            Some(public_key)
+        );
+    }
+}
@@ -7,6 +7,8 @@
 //! Intents engine can compute message hashes and verify signatures without
 //! knowing the concrete standard.
 
+use std::borrow::Cow;
+
 pub use near_sdk::CryptoHash;
 
 /// Data that can be deterministically hashed for signing or verification.
@@ -16,6 +18,16 @@ pub use near_sdk::CryptoHash;
 /// the digest that should be signed or used for verification.
 pub trait Payload {
     fn hash(&self) -> CryptoHash;
+
+    /// Bytes fed to the hash function to produce [`hash`](Payload::hash).
+    ///
+    /// Useful for reproducing the exact signing input on the client side.
+    /// Defaults to the hash itself for standards that have no distinct
+    /// pre-image.
+    #[inline]
+    fn prehash(&self) -> Cow<'_, [u8]> {
+        Cow::Owned(self.hash().to_vec())
+    }
 }
 
 /// Extension of [`Payload`] for types that include a signature.
@@ -27,4 +39,18 @@ pub trait SignedPayload: Payload {
     type PublicKey;
 
     fn verify(&self) -> Option<Self::PublicKey>;
+
+    /// Verifies the signature and checks that it was made by `expected`
+    /// public key.
+    ///
+    /// NOTE: public keys are not secret, so there is no need for
+    /// constant-time comparison here.
+    #[inline]
+    fn verify_as(&self, expected: &Self::PublicKey) -> bool
+    where
+        Self::PublicKey: PartialEq,
+    {
+        self.verify()
+            .is_some_and(|public_key| public_key == *expected)
+    }
 }
@@ -118,7 +118,12 @@ impl FromStr for PublicKey {
         let decoder = bs58::decode(data.as_bytes());
         match curve {
             CurveType::Ed25519 => decoder.into_array_const().map(Self::Ed25519),
-            CurveType::Secp256k1 => decoder.into_array_const().map(Self::Secp256k1),
+            CurveType::Secp256k1 => {
+                // accept compressed and SEC1-prefixed keys as well
+                return Secp256k1::normalize_public_key(&decoder.into_vec()?)
+                    .map(Self::Secp256k1)
+                    .ok_or(ParseCurveError::InvalidPublicKey);
+            }
             CurveType::P256 => decoder.into_array_const().map(Self::P256),
         }
         .map_err(Into::into)
@@ -178,7 +183,7 @@ mod abi {
         }
 
         pub(super) fn example_secp256k1() -> Self {
-            "secp256k1:5KN6ZfGZgH1puWwH1Nc1P8xyrFZSPHDw3WUP6iitsjCECJLrGBq"
+            "secp256k1:3SB8tA9Kbn7FBtT6GWR6AJk73QceudisHaGThPoLCDgC9tan7d3cwZFiDZtrmhSAf8aTynEdQ3N7KXhMm3nWhekP"
                 .parse()
                 .unwrap()
         }
@@ -187,6 +192,8 @@ mod abi {
 
 #[cfg(test)]
 mod tests {
+    use k256::elliptic_curve::sec1::ToEncodedPoint;
+
     use super::*;
 
     #[test]
@@ -205,11 +212,12 @@ mod tests {
     #[test]
     fn implicit_secp256k1() {
         assert_eq!(
-            "secp256k1:5KN6ZfGZgH1puWwH1Nc1P8xyrFZSPHDw3WUP6iitsjCECJLrGBq"
+            "secp256k1:3SB8tA9Kbn7FBtT6GWR6AJk73QceudisHaGThPoLCDgC9tan7d3cwZFiDZtrmhSAf8aTynEdQ3N7KXhMm3nWhekP"
                 .parse::<PublicKey>()
                 .unwrap()
                 .to_implicit_account_id(),
-            AccountIdRef::new_or_panic("0xbff77166b39599e54e391156eef7b8191e02be92")
+            // public key of secret key `1`, i.e. generator point
+            AccountIdRef::new_or_panic("0x7e5f4552091a69125d5dfcb7b8c2659029395bdf")
         );
     }
 
@@ -223,4 +231,79 @@ mod tests {
             AccountIdRef::new_or_panic("0x7edf07ede58238026db3f90fc8032633b69b8de5")
         );
     }
+
+    #[test]
+    fn unprefixed_ed25519() {
+        assert_eq!(
+            "5TagutioHgKLh7KZ1VEFBYfgRkPtqnKm9LoMnJMJugxm"
+                .parse::<PublicKey>()
+                .unwrap(),
+            "ed25519:5TagutioHgKLh7KZ1VEFBYfgRkPtqnKm9LoMnJMJugxm"
+                .parse::<PublicKey>()
+                .unwrap()
+        );
+        assert!(matches!(
+            "unknown:5TagutioHgKLh7KZ1VEFBYfgRkPtqnKm9LoMnJMJugxm".parse::<PublicKey>(),
+            Err(ParseCurveError::WrongCurveType)
+        ));
+    }
+
+    #[test]
+    fn near_string_roundtrip() {
+        for s in [
+            "ed25519:5TagutioHgKLh7KZ1VEFBYfgRkPtqnKm9LoMnJMJugxm",
+            "secp256k1:3SB8tA9Kbn7FBtT6GWR6AJk73QceudisHaGThPoLCDgC9tan7d3cwZFiDZtrmhSAf8aTynEdQ3N7KXhMm3nWhekP",
+        ] {
+            assert_eq!(s.parse::<PublicKey>().unwrap().to_string(), s);
+        }
+    }
+
+    #[test]
+    fn secp256k1_compressed_and_prefixed() {
+        let encoded = k256::SecretKey::from_slice(&[7; 32])
+            .unwrap()
+            .public_key()
+            .to_encoded_point(false);
+        let compressed = encoded.compress();
+        let parse = |bytes: &[u8]| {
+            format!("secp256k1:{}", bs58::encode(bytes).into_string()).parse::<PublicKey>()
+        };
+
+        let pk = parse(&encoded.as_bytes()[1..]).unwrap();
+        assert_eq!(parse(encoded.as_bytes()).unwrap(), pk);
+        assert_eq!(parse(compressed.as_bytes()).unwrap(), pk);
+
+        // serialization stays canonical
+        assert_eq!(
+            pk.to_string(),
+            format!(
+                "secp256k1:{}",
+                bs58::encode(&encoded.as_bytes()[1..]).into_string()
+            )
+        );
+
+        for prefix in [0x00, 0x04, 0x05] {
+            let mut malformed = compressed.as_bytes().to_vec();
+            malformed[0] = prefix;
+            assert!(matches!(
+                parse(&malformed),
+                Err(ParseCurveError::InvalidPublicKey)
+            ));
+        }
+        let mut malformed = encoded.as_bytes().to_vec();
+        malformed[0] = 0x02;
+        assert!(matches!(
+            parse(&malformed),
+            Err(ParseCurveError::InvalidPublicKey)
+        ));
+    }
+
+    #[test]
+    fn mismatched_prefix() {
+        // secp256k1 public key data with ed25519 prefix
+        assert!(matches!(
+            "ed25519:5KN6ZfGZgH1puWwH1Nc1P8xyrFZSPHDw3WUP6iitsjCECJLrGBq".parse::<PublicKey>(),
+            Err(ParseCurveError::Base58(_))
+        ));
+    }
 }
@@ -5,6 +5,9 @@ use serde_with::{DeserializeAs, SerializeAs};
 
 use crate::TypedCurve;
 
+/// Serializes bytes as `<curve>:<base58>` (i.e. `ed25519:...`).
+/// Deserialization additionally accepts raw base58 without a curve
+/// prefix, while a prefix of any other curve is rejected.
 pub struct AsCurve<C: TypedCurve>(PhantomData<C>);
 
 impl<C: TypedCurve, const N: usize> SerializeAs<[u8; N]> for AsCurve<C> {
@@ -66,3 +69,53 @@ mod abi {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use near_sdk::serde_json;
+    use serde_with::{de::DeserializeAsWrap, ser::SerializeAsWrap};
+
+    use crate::{Curve, Ed25519};
+
+    use super::*;
+
+    type PublicKey = <Ed25519 as Curve>::PublicKey;
+
+    const PUBLIC_KEY_BASE58: &str = "5TagutioHgKLh7KZ1VEFBYfgRkPtqnKm9LoMnJMJugxm";
+
+    fn deserialize(s: &str) -> serde_json::Result<PublicKey> {
+        // `AsCurve` borrows from input, so deserialize from a JSON string
+        serde_json::from_str::<DeserializeAsWrap<PublicKey, AsCurve<Ed25519>>>(
+            &serde_json::to_string(s)?,
+        )
+        .map(DeserializeAsWrap::into_inner)
+    }
+
+    #[test]
+    fn prefixed_and_raw() {
+        let prefixed = deserialize(&format!("ed25519:{PUBLIC_KEY_BASE58}")).unwrap();
+
+        assert_eq!(deserialize(PUBLIC_KEY_BASE58).unwrap(), prefixed);
+        assert_eq!(
+            deserialize(&format!("ED25519:{PUBLIC_KEY_BASE58}")).unwrap(),
+            prefixed
+        );
+
+        assert_eq!(
+            serde_json::to_value(SerializeAsWrap::<_, AsCurve<Ed25519>>::new(&prefixed)).unwrap(),
+            format!("ed25519:{PUBLIC_KEY_BASE58}")
+        );
+    }
+
+    #[test]
+    fn wrong_prefix() {
+        for prefix in ["secp256k1", "unknown", ""] {
+            assert!(
+                deserialize(&format!("{prefix}:{PUBLIC_KEY_BASE58}"))
+                    .unwrap_err()
+                    .to_string()
+                    .contains("wrong curve type")
+            );
+        }
+    }
+}
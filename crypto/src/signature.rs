@@ -3,6 +3,10 @@ use core::{
     str::FromStr,
 };
 
+use k256::{
+    Scalar,
+    elliptic_curve::{PrimeField, scalar::IsHigh},
+};
 use near_sdk::{bs58, near};
 
 use crate::{Curve, CurveType, Ed25519, P256, ParseCurveError, Secp256k1};
@@ -38,6 +42,42 @@ impl Signature {
             Self::P256(data) => data,
         }
     }
+
+    /// Brings the signature to canonical form, see
+    /// [`Secp256k1::normalize_signature`]
+    #[inline]
+    pub fn normalize(&mut self) {
+        if let Self::Secp256k1(signature) = self {
+            Secp256k1::normalize_signature(signature);
+        }
+    }
+}
+
+impl Secp256k1 {
+    /// Brings signature produced by a wallet to canonical form:
+    /// * shifts Ethereum-style recovery byte `v` ∈ {27, 28} to {0, 1}
+    /// * replaces high `s` with `n - s` and flips the recovery byte,
+    ///   so that the same public key is recovered
+    ///
+    /// Signatures with `s` not being a valid scalar are left untouched
+    /// and will fail verification.
+    pub fn normalize_signature(signature: &mut <Self as Curve>::Signature) {
+        let [rs @ .., v] = signature;
+        if *v >= 27 {
+            // Ethereum only uses uncompressed keys, with corresponding value v=27/28
+            // https://bitcoin.stackexchange.com/a/38909/58790
+            *v -= 27;
+        }
+
+        let s: &mut [u8; 32] = rs.last_chunk_mut().unwrap_or_else(|| unreachable!());
+        let Some(scalar) = Option::<Scalar>::from(Scalar::from_repr((*s).into())) else {
+            return;
+        };
+        if scalar.is_high().into() {
+            *s = (-scalar).to_bytes().into();
+            *v ^= 1;
+        }
+    }
 }
 
 impl Debug for Signature {
@@ -140,3 +180,72 @@ mod abi {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use k256::{ecdsa::SigningKey, elliptic_curve::sec1::ToEncodedPoint};
+    use near_sdk::env;
+
+    use super::*;
+
+    fn sign(message: &[u8]) -> ([u8; 65], <Secp256k1 as Curve>::PublicKey) {
+        let signing_key = SigningKey::from_bytes(&[7; 32].into()).unwrap();
+        let (signature, recovery_id) = signing_key
+            .sign_prehash_recoverable(&env::keccak256_array(message))
+            .unwrap();
+        let mut sig = [0; 65];
+        sig[..64].copy_from_slice(&signature.to_bytes());
+        sig[64] = recovery_id.to_byte();
+        (
+            sig,
+            Secp256k1::normalize_public_key(
+                signing_key
+                    .verifying_key()
+                    .to_encoded_point(false)
+                    .as_bytes(),
+            )
+            .unwrap(),
+        )
+    }
+
+    #[test]
+    fn normalize_high_s() {
+        let message = b"hello";
+        let (low_s, public_key) = sign(message);
+
+        // make malleable counterpart: (r, n - s, v ^ 1)
+        let mut high_s = low_s;
+        let s = Scalar::from_repr(<[u8; 32]>::try_from(&low_s[32..64]).unwrap().into()).unwrap();
+        high_s[32..64].copy_from_slice(&(-s).to_bytes());
+        high_s[64] ^= 1;
+        assert_eq!(Secp256k1::verify_message(&high_s, message, &()), None);
+
+        let mut normalized = high_s;
+        Secp256k1::normalize_signature(&mut normalized);
+        assert_eq!(normalized, low_s);
+        assert_eq!(
+            Secp256k1::verify_message(&normalized, message, &()),
+            Some(public_key)
+        );
+
+        // already canonical signature is left as is
+        let mut signature = Signature::Secp256k1(low_s);
+        signature.normalize();
+        assert_eq!(signature, Signature::Secp256k1(low_s));
+    }
+
+    #[test]
+    fn normalize_ethereum_v() {
+        let message = b"hello";
+        let (sig, public_key) = sign(message);
+
+        let mut shifted = sig;
+        shifted[64] += 27;
+        Secp256k1::normalize_signature(&mut shifted);
+        assert_eq!(shifted, sig);
+        assert_eq!(
+            Secp256k1::verify_message(&shifted, message, &()),
+            Some(public_key)
+        );
+    }
+}
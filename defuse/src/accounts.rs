@@ -1,9 +1,9 @@
 use std::collections::HashSet;
 
 use defuse_core::{Nonce, crypto::PublicKey};
-use defuse_serde_utils::base64::AsBase64;
+use defuse_serde_utils::base64::{AsBase64, AsBase64OrBase58};
 use near_plugins::AccessControllable;
-use near_sdk::{AccountId, ext_contract};
+use near_sdk::{AccountId, ext_contract, near};
 
 #[ext_contract(ext_account_manager)]
 pub trait AccountManager {
@@ -13,21 +13,51 @@ pub trait AccountManager {
     /// Returns set of public keys registered for given account
     fn public_keys_of(&self, account_id: &AccountId) -> HashSet<PublicKey>;
 
+    /// Same as [`public_keys_of`](AccountManager::public_keys_of), but
+    /// paginated. The implicit public key, if any, goes last.
+    fn public_keys_of_paged(
+        &self,
+        account_id: &AccountId,
+        from_index: Option<u32>,
+        limit: Option<u32>,
+    ) -> Vec<PublicKey>;
+
     /// Registers or re-activates `public_key` under the caller account_id.
+    /// Returns `false` if the key was already registered.
     ///
     /// NOTE: MUST attach 1 yⓃ for security purposes.
-    fn add_public_key(&mut self, public_key: PublicKey);
+    fn add_public_key(&mut self, public_key: PublicKey) -> bool;
 
     /// Deactivate `public_key` from the caller account_id,
     /// i.e. this key can't be used to make any actions unless it's re-created.
+    /// Returns `false` if the key wasn't registered.
     ///
     /// NOTE: MUST attach 1 yⓃ for security purposes.
-    fn remove_public_key(&mut self, public_key: PublicKey);
+    fn remove_public_key(&mut self, public_key: PublicKey) -> bool;
 
     /// Returns whether given nonce was already used by the account
     /// NOTE: nonces are non-sequential and follow
     /// [permit2 nonce schema](https://docs.uniswap.org/contracts/permit2/reference/signature-transfer#nonce-schema).
-    fn is_nonce_used(&self, account_id: &AccountId, nonce: AsBase64<Nonce>) -> bool;
+    /// `nonce` is accepted either in base64 or base58.
+    fn is_nonce_used(&self, account_id: &AccountId, nonce: AsBase64OrBase58<Nonce>) -> bool;
+
+    /// Batch version of [`is_nonce_used`](AccountManager::is_nonce_used),
+    /// returns results in the same order as `nonces`
+    fn are_nonces_used(
+        &self,
+        account_id: &AccountId,
+        nonces: Vec<AsBase64OrBase58<Nonce>>,
+    ) -> Vec<bool>;
+
+    /// Returns used nonces of given account, paginated. Nonces committed
+    /// before they could be enumerated are not listed, use
+    /// [`is_nonce_used`](AccountManager::is_nonce_used) to check them.
+    fn used_nonces_of(
+        &self,
+        account_id: &AccountId,
+        from_index: Option<u32>,
+        limit: Option<u32>,
+    ) -> Vec<AsBase64<Nonce>>;
 
     /// Clears all expired nonces for given accounts.
     /// Omitting any errors, e.g. if account doesn't exist or nonces are not expired.
@@ -62,8 +92,11 @@ pub trait AccountForceLocker: AccessControllable {
     ///
     /// Attached deposit of 1yN is required for security purposes.
     ///
+    /// Optional `reason` is included in errors returned for operations
+    /// on the locked account.
+    ///
     /// NOTE: this still allows for force withdrawals/transfers
-    fn force_lock_account(&mut self, account_id: AccountId) -> bool;
+    fn force_lock_account(&mut self, account_id: AccountId, reason: Option<String>) -> bool;
 
     /// Unlocks given `account_id`.
     /// Returns `false` if the account wasn't in locked state.
@@ -71,3 +104,25 @@ pub trait AccountForceLocker: AccessControllable {
     /// Attached deposit of 1yN is required for security purposes.
     fn force_unlock_account(&mut self, account_id: &AccountId) -> bool;
 }
+
+#[ext_contract(ext_account_exporter)]
+pub trait AccountExporter {
+    /// Paginated enumeration of all accounts with short summaries,
+    /// e.g. for off-chain snapshotting before an upgrade. Token balances
+    /// are not included.
+    fn export_accounts(
+        &self,
+        from_index: Option<u32>,
+        limit: Option<u32>,
+    ) -> Vec<(AccountId, AccountSummary)>;
+}
+
+/// See [`AccountExporter::export_accounts`]
+#[near(serializers = [json])]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccountSummary {
+    /// Number of public keys, including the implicit one, if any
+    pub public_keys: u32,
+    pub locked: bool,
+    pub auth_by_predecessor_id_enabled: bool,
+}
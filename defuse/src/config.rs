@@ -0,0 +1,85 @@
+use std::collections::HashSet;
+
+use defuse_core::{fees::FeesConfig, payload::multi::SigningStandard};
+use near_plugins::AccessControllable;
+use near_sdk::{AccountId, ext_contract, near};
+
+use crate::fees::FeesSettings;
+
+#[ext_contract(ext_config_manager)]
+#[allow(clippy::module_name_repetitions)]
+pub trait ConfigManager: AccessControllable {
+    /// Returns current config, see [`ConfigView`]
+    fn config(&self) -> ConfigView;
+
+    /// Replaces all settings at once.
+    ///
+    /// NOTE: MUST attach 1 yⓃ for security purposes.
+    fn set_settings(&mut self, settings: Settings);
+}
+
+/// Snapshot of `DefuseConfig` as currently stored in the contract.
+/// Roles are omitted, since they are exposed by access control views.
+#[near(serializers = [json])]
+#[derive(Debug, Clone)]
+pub struct ConfigView {
+    pub wnear_id: AccountId,
+    pub fees: FeesConfig,
+    #[serde(flatten)]
+    pub fees_settings: FeesSettings,
+    #[serde(flatten)]
+    pub settings: Settings,
+}
+
+/// Part of `DefuseConfig` which is stored separately from the rest of
+/// the contract state and can be changed via
+/// [`ConfigManager::set_settings`].
+///
+/// NOTE: stored as a versioned enum, so adding a field requires a new
+/// version to be added there as well.
+#[near(serializers = [borsh, json])]
+#[serde(default)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Settings {
+    /// Maximum number of signed payloads accepted by a single call to
+    /// `execute_intents` or `simulate_intents`
+    pub max_intents_per_call: u32,
+
+    /// Emit `intent_executed` event for each intent in the order of
+    /// processing. Disabled by default to avoid event spam
+    pub verbose_events: bool,
+
+    /// Signing standards accepted by `execute_intents` and
+    /// `simulate_intents`. `None` means all standards are accepted
+    pub allowed_standards: Option<HashSet<SigningStandard>>,
+
+    /// Maximum number of public keys an account can have, not counting
+    /// the implicit one. `None` means unlimited
+    pub max_public_keys: Option<u32>,
+
+    /// Tolerance (in milliseconds) for clock skew between signers and the
+    /// chain: signed payloads are accepted until `deadline + grace`.
+    /// Expirable nonces are still checked against their own deadline
+    pub deadline_grace_ms: u32,
+
+    /// NEAR network the contract is deployed to, e.g. `mainnet`. Signed
+    /// payloads bound to a `network_id` are accepted only if it matches
+    pub network_id: Option<String>,
+}
+
+impl Settings {
+    pub const DEFAULT_MAX_INTENTS_PER_CALL: u32 = 1000;
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            max_intents_per_call: Self::DEFAULT_MAX_INTENTS_PER_CALL,
+            verbose_events: false,
+            allowed_standards: None,
+            max_public_keys: None,
+            deadline_grace_ms: 0,
+            network_id: None,
+        }
+    }
+}
@@ -1,5 +1,6 @@
 mod v0;
 mod v1;
+mod v2;
 
 use std::{
     borrow::Cow,
@@ -16,7 +17,7 @@ use near_sdk::{
     near,
 };
 
-use crate::contract::accounts::account::entry::{v0::AccountV0, v1::AccountV1};
+use crate::contract::accounts::account::entry::{v0::AccountV0, v1::AccountV1, v2::AccountV2};
 
 use super::Account;
 
@@ -42,37 +43,85 @@ impl From<Lock<Account>> for AccountEntry {
     }
 }
 
-/// Versioned [Account] state for de/serialization.
+/// Versioned [Account] state for serialization.
+///
+/// Deserialization is dispatched by [`VersionedAccountEntry::DESERIALIZERS`]
+/// instead, so the order of variants here must match the order of entries
+/// there.
 #[derive(Debug)]
 #[near(serializers = [borsh])]
 enum VersionedAccountEntry<'a> {
     V0(Cow<'a, PanicOnClone<AccountV0>>),
     V1(Cow<'a, PanicOnClone<Lock<AccountV1>>>),
+    V2(Cow<'a, PanicOnClone<Lock<AccountV2>>>),
     // When upgrading to a new version, given current version `N`:
     // 1. Copy current `Account` struct definition and name it `AccountVN`
     // 2. Add variant `VN(Cow<'a, PanicOnClone<Lock<AccountVN>>>)` before `Latest`
-    // 3. Handle new variant in `match` expessions below
-    // 4. Add tests for `VN -> Latest` migration
+    // 3. Add corresponding entry to `DESERIALIZERS` before `Latest`
+    // 4. Retarget `From<AccountV(N-1)> for Account` to `AccountVN`,
+    //    implement `From<AccountVN> for Account` and
+    //    `From<Lock<AccountVN>> for Lock<Account>`
+    // 5. Add tests for `VN -> Latest` migration
     Latest(Cow<'a, PanicOnClone<Lock<Account>>>),
 }
 
-impl From<VersionedAccountEntry<'_>> for Lock<Account> {
-    fn from(versioned: VersionedAccountEntry<'_>) -> Self {
-        // Borsh always deserializes into `Cow::Owned`, so it's
-        // safe to call `Cow::<PanicOnClone<_>>::into_owned()` here.
-        match versioned {
-            VersionedAccountEntry::V0(account) => {
-                Self::unlocked(account.into_owned().into_inner().into())
-            }
-            VersionedAccountEntry::V1(account) => account
-                .into_owned()
-                .into_inner()
-                .map_inner_unchecked(Into::into),
-            VersionedAccountEntry::Latest(account) => account
-                .into_owned()
-                .into_inner()
-                .map_inner_unchecked(Into::into),
-        }
+type DeserializeFn = fn(&mut dyn io::Read) -> io::Result<Lock<Account>>;
+
+impl VersionedAccountEntry<'_> {
+    /// Deserializers of each version, indexed by its borsh discriminant
+    const DESERIALIZERS: &[DeserializeFn] = &[
+        // V0
+        Self::deserialize_version::<AccountV0>,
+        // V1
+        Self::deserialize_version::<Lock<AccountV1>>,
+        // V2
+        Self::deserialize_version::<Lock<AccountV2>>,
+        // Latest
+        Self::deserialize_version::<Lock<Account>>,
+    ];
+
+    fn deserialize_version<T>(mut reader: &mut dyn io::Read) -> io::Result<Lock<Account>>
+    where
+        T: BorshDeserialize + Into<Lock<Account>>,
+    {
+        T::deserialize_reader(&mut reader).map(Into::into)
+    }
+
+    fn deserialize_versioned<R>(reader: &mut R) -> io::Result<Lock<Account>>
+    where
+        R: io::Read,
+    {
+        let version = u8::deserialize_reader(reader)?;
+        let deserialize = Self::DESERIALIZERS
+            .get(usize::from(version))
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("unknown account version: {version}"),
+                )
+            })?;
+        deserialize(reader)
+    }
+}
+
+impl From<AccountV0> for Lock<Account> {
+    #[inline]
+    fn from(account: AccountV0) -> Self {
+        Self::unlocked(AccountV2::from(account).into())
+    }
+}
+
+impl From<Lock<AccountV1>> for Lock<Account> {
+    #[inline]
+    fn from(account: Lock<AccountV1>) -> Self {
+        account.map_inner_unchecked(|account| AccountV2::from(account).into())
+    }
+}
+
+impl From<Lock<AccountV2>> for Lock<Account> {
+    #[inline]
+    fn from(account: Lock<AccountV2>) -> Self {
+        account.map_inner_unchecked(Into::into)
     }
 }
 
@@ -84,13 +133,6 @@ impl<'a> From<&'a Lock<Account>> for VersionedAccountEntry<'a> {
     }
 }
 
-// Used for legacy accounts deserialization
-impl From<AccountV0> for VersionedAccountEntry<'_> {
-    fn from(value: AccountV0) -> Self {
-        Self::V0(Cow::Owned(value.into()))
-    }
-}
-
 struct MaybeVersionedAccountEntry;
 
 impl MaybeVersionedAccountEntry {
@@ -122,7 +164,7 @@ impl BorshDeserializeAs<Lock<Account>> for MaybeVersionedAccountEntry {
         let prefix = u32::deserialize_reader(&mut buf.as_slice())?;
 
         if prefix == Self::VERSIONED_MAGIC_PREFIX {
-            VersionedAccountEntry::deserialize_reader(reader)
+            VersionedAccountEntry::deserialize_versioned(reader)
         } else {
             // legacy account
             AccountV0::deserialize_reader(
@@ -131,7 +173,6 @@ impl BorshDeserializeAs<Lock<Account>> for MaybeVersionedAccountEntry {
             )
             .map(Into::into)
         }
-        .map(Into::into)
     }
 }
 
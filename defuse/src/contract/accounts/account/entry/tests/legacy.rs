@@ -7,11 +7,15 @@ use std::{
 use arbitrary_with::{Arbitrary, As, arbitrary};
 use defuse_bitmap::U256;
 use defuse_borsh_utils::adapters::to_vec_as;
-use defuse_core::{Result, crypto::PublicKey, token_id::TokenId};
+use defuse_core::{
+    Result,
+    crypto::PublicKey,
+    token_id::{TokenId, nep141::Nep141TokenId},
+};
 use defuse_near_utils::{Lock, PanicOnClone, arbitrary::ArbitraryAccountId};
 use defuse_test_utils::random::make_arbitrary;
 use near_sdk::{
-    AccountId,
+    AccountId, AccountIdRef,
     borsh::{self, BorshDeserialize, BorshSerialize},
 };
 use rstest::rstest;
@@ -20,7 +24,10 @@ use crate::contract::accounts::{
     Account,
     account::{
         AccountEntry,
-        entry::{AccountV0, MaybeVersionedAccountEntry, VersionedAccountEntry, v1::AccountV1},
+        entry::{
+            AccountV0, MaybeVersionedAccountEntry, VersionedAccountEntry, v1::AccountV1,
+            v2::AccountV2,
+        },
         nonces::tests::random_nonces,
     },
 };
@@ -39,9 +46,17 @@ fn deserialize_and_check_legacy_account(
 
     // commit new nonces
     for nonce in random_nonces {
-        assert!(account.commit_nonce(*nonce).is_ok());
+        assert!(account.commit_nonce(*nonce).unwrap());
     }
 
+    // allowances didn't exist before, so there are none after migration
+    let (delegate_id, token_id) = allowance_key();
+    assert_eq!(account.allowances_count(), 0);
+    assert_eq!(account.allowance(&delegate_id, &token_id), 0);
+    account.set_allowance(delegate_id.clone(), token_id.clone(), 100);
+
+    let snapshot = account.snapshot(data.nonces.iter().chain(random_nonces).copied());
+
     let serialized_versioned = borsh::to_vec(&versioned).unwrap();
     drop(versioned);
 
@@ -50,11 +65,32 @@ fn deserialize_and_check_legacy_account(
         .as_locked()
         .expect("legacy accounts must be unlocked by default");
     data.assert_contained_in(account);
+    assert_eq!(
+        account.snapshot(data.nonces.iter().chain(random_nonces).copied()),
+        snapshot,
+    );
 
     // check new nonces existence
     for &n in random_nonces {
         assert!(account.is_nonce_used(n));
     }
+
+    // allowances granted after migration are persisted
+    assert_eq!(account.allowances_count(), 1);
+    assert_eq!(account.allowance(&delegate_id, &token_id), 100);
+
+    // nonces committed after migration are enumerable
+    let used: HashSet<_> = account.iter_used_nonces().collect();
+    for n in random_nonces {
+        assert!(used.contains(n));
+    }
+}
+
+fn allowance_key() -> (AccountId, TokenId) {
+    (
+        "delegate.near".parse().unwrap(),
+        Nep141TokenId::new("ft.near".parse().unwrap()).into(),
+    )
 }
 
 #[rstest]
@@ -71,6 +107,8 @@ fn legacy_upgrade(#[from(make_arbitrary)] data: AccountData, random_nonces: Vec<
 
 #[rstest]
 #[case::v0(PhantomData::<Lock<AccountV1>>)]
+#[case::v2(PhantomData::<Lock<AccountV2>>)]
+#[case::latest(PhantomData::<Lock<Account>>)]
 #[allow(clippy::used_underscore_binding)]
 fn versioned_upgrade<T>(
     #[from(make_arbitrary)] data: AccountData,
@@ -120,7 +158,7 @@ impl AccountData {
         }
 
         for nonce in &self.nonces {
-            assert!(legacy.commit_nonce(*nonce).is_ok());
+            assert!(legacy.commit_nonce(*nonce).unwrap());
         }
 
         for (token_id, &amount) in &self.token_balances {
@@ -149,7 +187,7 @@ trait LegacyAccountBuilder {
     fn new(prefix: &[u8], account_id: &AccountId) -> Self;
     fn add_public_key(&mut self, account_id: &AccountId, pk: PublicKey) -> bool;
     fn remove_public_key(&mut self, account_id: &AccountId, pk: &PublicKey) -> bool;
-    fn commit_nonce(&mut self, nonce: U256) -> Result<()>;
+    fn commit_nonce(&mut self, nonce: U256) -> Result<bool>;
     fn add_balance(&mut self, token_id: TokenId, amount: u128) -> bool;
 }
 
@@ -169,7 +207,7 @@ macro_rules! impl_legacy_account_builder {
                 self.remove_public_key(account_id, pk)
             }
 
-            fn commit_nonce(&mut self, nonce: U256) -> Result<()> {
+            fn commit_nonce(&mut self, nonce: U256) -> Result<bool> {
                 self.commit_nonce(nonce)
             }
 
@@ -195,7 +233,7 @@ macro_rules! impl_lock_account_builder {
                 self.get_mut().unwrap().remove_public_key(account_id, pk)
             }
 
-            fn commit_nonce(&mut self, nonce: U256) -> Result<()> {
+            fn commit_nonce(&mut self, nonce: U256) -> Result<bool> {
                 self.get_mut().unwrap().commit_nonce(nonce)
             }
 
@@ -212,9 +250,32 @@ macro_rules! impl_lock_account_builder {
 
 impl_legacy_account_builder!(AccountV0);
 impl_lock_account_builder!(AccountV1);
+impl_lock_account_builder!(AccountV2);
+impl_lock_account_builder!(Account);
 
 impl<'a> From<&'a Lock<AccountV1>> for VersionedAccountEntry<'a> {
     fn from(value: &'a Lock<AccountV1>) -> Self {
         Self::V1(Cow::Borrowed(PanicOnClone::from_ref(value)))
     }
 }
+
+impl<'a> From<&'a Lock<AccountV2>> for VersionedAccountEntry<'a> {
+    fn from(value: &'a Lock<AccountV2>) -> Self {
+        Self::V2(Cow::Borrowed(PanicOnClone::from_ref(value)))
+    }
+}
+
+#[test]
+fn deserializers_match_discriminants() {
+    let account = Lock::unlocked(Account::new(
+        b"prefix".as_slice(),
+        AccountIdRef::new_or_panic("alice.near"),
+    ));
+    let serialized = borsh::to_vec(&VersionedAccountEntry::from(&account)).unwrap();
+
+    // `Latest` is always the last version
+    assert_eq!(
+        usize::from(serialized[0]),
+        VersionedAccountEntry::DESERIALIZERS.len() - 1,
+    );
+}
@@ -9,11 +9,13 @@ use near_sdk::{
 use defuse_core::{Nonces, crypto::PublicKey};
 
 use crate::contract::accounts::{
-    Account, AccountState, MaybeLegacyAccountNonces,
+    AccountState, MaybeLegacyAccountNonces,
     account::{AccountFlags, AccountPrefix},
 };
 
-/// Legacy: V0 of [`Account`]
+use super::v2::AccountV2;
+
+/// Legacy: V0 of [`Account`](crate::contract::accounts::Account)
 #[derive(Debug)]
 #[near(serializers = [borsh])]
 #[autoimpl(Deref using self.state)]
@@ -29,7 +31,7 @@ pub struct AccountV0 {
     pub(super) prefix: Vec<u8>,
 }
 
-impl From<AccountV0> for Account {
+impl From<AccountV0> for AccountV2 {
     fn from(
         AccountV0 {
             nonces,
@@ -154,7 +156,7 @@ pub(super) mod tests {
         }
 
         #[inline]
-        pub fn commit_nonce(&mut self, n: U256) -> Result<()> {
+        pub fn commit_nonce(&mut self, n: U256) -> Result<bool> {
             self.nonces.commit(n)
         }
     }
@@ -9,11 +9,13 @@ use near_sdk::{
 use defuse_core::{Nonces, crypto::PublicKey};
 
 use crate::contract::accounts::{
-    Account, AccountState, MaybeLegacyAccountNonces,
+    AccountState, MaybeLegacyAccountNonces,
     account::{AccountFlags, AccountPrefix},
 };
 
-/// Legacy: V1 of [`Account`]
+use super::v2::AccountV2;
+
+/// Legacy: V1 of [`Account`](crate::contract::accounts::Account)
 #[derive(Debug)]
 #[near(serializers = [borsh])]
 #[autoimpl(Deref using self.state)]
@@ -29,7 +31,7 @@ pub struct AccountV1 {
     pub(super) prefix: Vec<u8>,
 }
 
-impl From<AccountV1> for Account {
+impl From<AccountV1> for AccountV2 {
     fn from(
         AccountV1 {
             nonces,
@@ -170,7 +172,7 @@ pub(super) mod tests {
         }
 
         #[inline]
-        pub fn commit_nonce(&mut self, n: U256) -> Result<()> {
+        pub fn commit_nonce(&mut self, n: U256) -> Result<bool> {
             self.nonces.commit(n)
         }
 
@@ -0,0 +1,167 @@
+use defuse_near_utils::NestPrefix;
+use impl_tools::autoimpl;
+use near_sdk::{
+    near,
+    store::{IterableMap, IterableSet},
+};
+
+use defuse_core::crypto::PublicKey;
+
+use crate::contract::accounts::{
+    Account, AccountState, MaybeLegacyAccountNonces,
+    account::{AccountFlags, AccountPrefix},
+};
+
+/// Legacy: V2 of [`Account`]
+#[derive(Debug)]
+#[near(serializers = [borsh])]
+#[autoimpl(Deref using self.state)]
+#[autoimpl(DerefMut using self.state)]
+pub struct AccountV2 {
+    pub(super) nonces: MaybeLegacyAccountNonces,
+
+    pub(super) flags: AccountFlags,
+    pub(super) public_keys: IterableSet<PublicKey>,
+
+    pub state: AccountState,
+
+    pub(super) prefix: Vec<u8>,
+}
+
+impl From<AccountV2> for Account {
+    fn from(
+        AccountV2 {
+            nonces,
+            flags,
+            public_keys,
+            state,
+            prefix,
+        }: AccountV2,
+    ) -> Self {
+        Self {
+            nonces,
+            // nonces committed before V3 can't be enumerated
+            nonce_words: IterableSet::new(prefix.as_slice().nest(AccountPrefix::NonceWords)),
+            flags,
+            public_keys,
+            allowances: IterableMap::new(prefix.as_slice().nest(AccountPrefix::Allowances)),
+            state,
+            prefix,
+        }
+    }
+}
+
+/// Legacy implementation of [`AccountV2`]
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+
+    use defuse_bitmap::U256;
+    use near_sdk::{AccountIdRef, store::LookupMap};
+
+    use defuse_core::{
+        Result,
+        accounts::{AccountEvent, PublicKeyEvent},
+        events::DefuseEvent,
+    };
+    use std::borrow::Cow;
+
+    impl AccountV2 {
+        #[inline]
+        pub fn new<S>(prefix: S, me: &AccountIdRef) -> Self
+        where
+            S: near_sdk::IntoStorageKey,
+        {
+            let prefix = prefix.into_storage_key();
+
+            Self {
+                nonces: MaybeLegacyAccountNonces::new(LookupMap::with_hasher(
+                    prefix.as_slice().nest(AccountPrefix::OptimizedNonces),
+                )),
+                flags: (!me.get_account_type().is_implicit())
+                    .then_some(AccountFlags::IMPLICIT_PUBLIC_KEY_REMOVED)
+                    .unwrap_or_else(AccountFlags::empty),
+                public_keys: IterableSet::new(prefix.as_slice().nest(AccountPrefix::PublicKeys)),
+                state: AccountState::new(prefix.as_slice().nest(AccountPrefix::State)),
+                prefix,
+            }
+        }
+
+        #[inline]
+        #[must_use]
+        pub fn add_public_key(&mut self, me: &AccountIdRef, public_key: PublicKey) -> bool {
+            if !self.maybe_add_public_key(me, public_key) {
+                return false;
+            }
+
+            DefuseEvent::PublicKeyAdded(AccountEvent::new(
+                Cow::Borrowed(me),
+                PublicKeyEvent {
+                    public_key: Cow::Borrowed(&public_key),
+                },
+            ))
+            .emit();
+
+            true
+        }
+
+        #[inline]
+        #[must_use]
+        fn maybe_add_public_key(&mut self, me: &AccountIdRef, public_key: PublicKey) -> bool {
+            if me == public_key.to_implicit_account_id() {
+                let was_removed = self.is_implicit_public_key_removed();
+                self.set_implicit_public_key_removed(false);
+                was_removed
+            } else {
+                self.public_keys.insert(public_key)
+            }
+        }
+
+        #[inline]
+        #[must_use]
+        pub fn remove_public_key(&mut self, me: &AccountIdRef, public_key: &PublicKey) -> bool {
+            if !self.maybe_remove_public_key(me, public_key) {
+                return false;
+            }
+
+            DefuseEvent::PublicKeyRemoved(AccountEvent::new(
+                Cow::Borrowed(me),
+                PublicKeyEvent {
+                    public_key: Cow::Borrowed(public_key),
+                },
+            ))
+            .emit();
+
+            true
+        }
+
+        #[inline]
+        #[must_use]
+        fn maybe_remove_public_key(&mut self, me: &AccountIdRef, public_key: &PublicKey) -> bool {
+            if me == public_key.to_implicit_account_id() {
+                let was_removed = self.is_implicit_public_key_removed();
+                self.set_implicit_public_key_removed(true);
+                !was_removed
+            } else {
+                self.public_keys.remove(public_key)
+            }
+        }
+
+        #[inline]
+        pub fn commit_nonce(&mut self, n: U256) -> Result<bool> {
+            self.nonces.commit(n)
+        }
+
+        #[inline]
+        const fn is_implicit_public_key_removed(&self) -> bool {
+            self.flags
+                .contains(AccountFlags::IMPLICIT_PUBLIC_KEY_REMOVED)
+        }
+
+        #[inline]
+        fn set_implicit_public_key_removed(&mut self, removed: bool) {
+            self.flags
+                .set(AccountFlags::IMPLICIT_PUBLIC_KEY_REMOVED, removed);
+        }
+    }
+}
@@ -6,22 +6,23 @@ pub use self::{entry::*, nonces::MaybeLegacyAccountNonces};
 use std::borrow::Cow;
 
 use bitflags::bitflags;
-use defuse_bitmap::U256;
+use defuse_bitmap::{U248, U256};
 use defuse_core::{
     Result,
     accounts::{AccountEvent, PublicKeyEvent},
     crypto::PublicKey,
     events::DefuseEvent,
     intents::account::SetAuthByPredecessorId,
+    token_id::TokenId,
 };
 
 use defuse_near_utils::NestPrefix;
 use impl_tools::autoimpl;
 use near_sdk::{
-    AccountIdRef, BorshStorageKey, IntoStorageKey,
+    AccountId, AccountIdRef, BorshStorageKey, IntoStorageKey,
     borsh::BorshSerialize,
     near,
-    store::{IterableSet, LookupMap},
+    store::{IterableMap, IterableSet, LookupMap},
 };
 
 use super::AccountState;
@@ -34,10 +35,17 @@ use super::AccountState;
 #[autoimpl(DerefMut using self.state)]
 pub struct Account {
     nonces: MaybeLegacyAccountNonces,
+    /// Prefixes of 256-bit words in `nonces` which have committed nonces,
+    /// so that they can be enumerated
+    nonce_words: IterableSet<U248>,
 
     flags: AccountFlags,
     public_keys: IterableSet<PublicKey>,
 
+    /// Remaining amounts of tokens that other accounts are allowed to
+    /// transfer from this account
+    allowances: IterableMap<(AccountId, TokenId), u128>,
+
     pub state: AccountState,
 
     prefix: Vec<u8>,
@@ -55,10 +63,12 @@ impl Account {
             nonces: MaybeLegacyAccountNonces::new(LookupMap::with_hasher(
                 prefix.as_slice().nest(AccountPrefix::OptimizedNonces),
             )),
+            nonce_words: IterableSet::new(prefix.as_slice().nest(AccountPrefix::NonceWords)),
             flags: (!me.get_account_type().is_implicit())
                 .then_some(AccountFlags::IMPLICIT_PUBLIC_KEY_REMOVED)
                 .unwrap_or_else(AccountFlags::empty),
             public_keys: IterableSet::new(prefix.as_slice().nest(AccountPrefix::PublicKeys)),
+            allowances: IterableMap::new(prefix.as_slice().nest(AccountPrefix::Allowances)),
             state: AccountState::new(prefix.as_slice().nest(AccountPrefix::State)),
             prefix,
         }
@@ -139,14 +149,27 @@ impl Account {
         )
     }
 
+    /// Returns number of added public keys, not counting the implicit one
+    #[inline]
+    pub fn public_keys_count(&self) -> u32 {
+        self.public_keys.len()
+    }
+
     #[inline]
     pub fn is_nonce_used(&self, nonce: U256) -> bool {
         self.nonces.is_used(nonce)
     }
 
+    /// Commits the nonce.
+    /// Returns `false` if it was already committed before.
     #[inline]
-    pub fn commit_nonce(&mut self, nonce: U256) -> Result<()> {
-        self.nonces.commit(nonce)
+    pub fn commit_nonce(&mut self, nonce: U256) -> Result<bool> {
+        let committed = self.nonces.commit(nonce)?;
+        if committed {
+            let [word @ .., _] = nonce;
+            self.nonce_words.insert(word);
+        }
+        Ok(committed)
     }
 
     /// Clears the nonce if it was expired.
@@ -154,7 +177,22 @@ impl Account {
     /// regardless of whether it was previously committed or not.
     #[inline]
     pub fn clear_expired_nonce(&mut self, nonce: U256) -> bool {
-        self.nonces.clear_expired(nonce)
+        let cleared = self.nonces.clear_expired(nonce);
+        if cleared {
+            let [word @ .., _] = nonce;
+            self.nonce_words.remove(&word);
+        }
+        cleared
+    }
+
+    /// Iterates over used nonces. Nonces committed before the account
+    /// was migrated to the current version are not enumerated, but are
+    /// still reported by [`.is_nonce_used()`](Self::is_nonce_used).
+    #[inline]
+    pub fn iter_used_nonces(&self) -> impl Iterator<Item = U256> + '_ {
+        self.nonce_words
+            .iter()
+            .flat_map(|word| self.nonces.iter_word(*word))
     }
 
     #[inline]
@@ -194,6 +232,63 @@ impl Account {
         }
         was_enabled
     }
+
+    /// Returns remaining amount of `token_id` that `delegate_id` is
+    /// allowed to transfer from this account
+    #[inline]
+    pub fn allowance(&self, delegate_id: &AccountIdRef, token_id: &TokenId) -> u128 {
+        self.allowances
+            .get(&(delegate_id.to_owned(), token_id.clone()))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Returns number of non-zero allowances granted by this account
+    #[inline]
+    pub fn allowances_count(&self) -> u32 {
+        self.allowances.len()
+    }
+
+    /// Overrides the allowance of `delegate_id` to transfer `token_id`
+    /// from this account, zero `amount` revokes it and removes the entry
+    #[inline]
+    pub fn set_allowance(&mut self, delegate_id: AccountId, token_id: TokenId, amount: u128) {
+        let key = (delegate_id, token_id);
+        if amount == 0 {
+            self.allowances.remove(&key);
+        } else {
+            self.allowances.insert(key, amount);
+        }
+    }
+
+    /// Materializes a plain cloneable view of the account state, checking
+    /// only given `nonces` for being used, since they can't be iterated.
+    #[cfg(test)]
+    pub(crate) fn snapshot(&self, nonces: impl IntoIterator<Item = U256>) -> AccountSnapshot {
+        AccountSnapshot {
+            flags: self.flags,
+            public_keys: self.public_keys.iter().copied().collect(),
+            used_nonces: nonces
+                .into_iter()
+                .filter(|&n| self.is_nonce_used(n))
+                .collect(),
+            token_balances: self
+                .token_balances
+                .into_iter()
+                .map(|(token_id, &amount)| (token_id.clone(), amount))
+                .collect(),
+        }
+    }
+}
+
+/// See [`Account::snapshot`]
+#[cfg(test)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct AccountSnapshot {
+    flags: AccountFlags,
+    public_keys: std::collections::BTreeSet<PublicKey>,
+    used_nonces: std::collections::BTreeSet<U256>,
+    token_balances: std::collections::BTreeMap<defuse_core::token_id::TokenId, u128>,
 }
 
 #[allow(deprecated)]
@@ -208,6 +303,8 @@ mod prefix {
         PublicKeys,
         State,
         OptimizedNonces,
+        Allowances,
+        NonceWords,
     }
 }
 
@@ -6,7 +6,7 @@ use near_sdk::{
     store::{LookupMap, key::Sha256},
 };
 
-use defuse_core::{DefuseError, Nonce, Nonces, Result};
+use defuse_core::{Nonce, Nonces, Result};
 
 pub type MaybeLegacyAccountNonces =
     MaybeLegacyNonces<LookupMap<U248, U256, Sha256>, LookupMap<U248, U256>>;
@@ -44,15 +44,17 @@ where
         }
     }
 
+    /// Commits the nonce.
+    /// Returns `false` if it was already committed before.
     #[inline]
-    pub fn commit(&mut self, nonce: Nonce) -> Result<()> {
+    pub fn commit(&mut self, nonce: Nonce) -> Result<bool> {
         // Check legacy maps for used nonce
         if self
             .legacy
             .as_ref()
             .is_some_and(|legacy| legacy.is_used(nonce))
         {
-            return Err(DefuseError::NonceUsed);
+            return Ok(false);
         }
 
         // New nonces can be committed only to the new map
@@ -77,6 +79,20 @@ where
         // Expirable nonces can not be in the legacy map
         self.nonces.clear_expired(nonce)
     }
+
+    /// Iterates over nonces committed to the new map within the 256-bit
+    /// word with given prefix
+    #[inline]
+    pub fn iter_word(&self, word: U248) -> impl Iterator<Item = Nonce> + '_ {
+        (0..=u8::MAX)
+            .map(move |bit| {
+                let mut nonce: Nonce = [0; 32];
+                nonce[..word.len()].copy_from_slice(&word);
+                nonce[word.len()] = bit;
+                nonce
+            })
+            .filter(|&nonce| self.nonces.is_used(nonce))
+    }
 }
 
 #[cfg(test)]
@@ -86,7 +102,7 @@ pub(super) mod tests {
 
     use chrono::{Days, Utc};
     use defuse_bitmap::U256;
-    use defuse_core::{Deadline, ExpirableNonce};
+    use defuse_core::{Deadline, DefuseError, ExpirableNonce};
     use defuse_test_utils::random::{Rng, range_to_random_size, rng};
 
     use rstest::fixture;
@@ -171,10 +187,7 @@ pub(super) mod tests {
             LookupMap::with_hasher(random_bytes),
         );
 
-        assert!(matches!(
-            new.commit(random_nonces[0]).unwrap_err(),
-            DefuseError::NonceUsed
-        ));
+        assert!(!new.commit(random_nonces[0]).unwrap());
     }
 
     #[rstest]
@@ -182,12 +195,9 @@ pub(super) mod tests {
         let mut new = MaybeLegacyAccountNonces::new(LookupMap::with_hasher(random_bytes));
         let nonce = generate_nonce(false, &mut rng);
 
-        new.commit(nonce).expect("First commit should succeed");
+        assert!(new.commit(nonce).expect("First commit should succeed"));
 
-        assert!(matches!(
-            new.commit(nonce).unwrap_err(),
-            DefuseError::NonceUsed
-        ));
+        assert!(!new.commit(nonce).unwrap());
     }
 
     #[rstest]
@@ -16,13 +16,19 @@ impl AccountForceLocker for Contract {
 
     #[access_control_any(roles(Role::DAO, Role::UnrestrictedAccountLocker))]
     #[payable]
-    fn force_lock_account(&mut self, account_id: AccountId) -> bool {
+    fn force_lock_account(&mut self, account_id: AccountId, reason: Option<String>) -> bool {
         assert_one_yocto();
-        let locked = self
-            .accounts
-            .get_or_create(account_id.clone())
-            .lock()
-            .is_some();
+        let locked = match self.accounts.get_mut(&account_id) {
+            Some(account) => account.lock_with_reason(reason).is_some(),
+            None => {
+                // pre-create the account, so that it can still receive
+                // deposits while being locked
+                self.accounts
+                    .get_or_create_locked(account_id.clone())
+                    .force_lock_with_reason(reason);
+                true
+            }
+        };
         if locked {
             DefuseEvent::AccountLocked(AccountEvent::new(account_id, ())).emit();
         }
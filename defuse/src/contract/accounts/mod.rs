@@ -11,8 +11,10 @@ use defuse_core::{
     crypto::PublicKey,
     engine::{State, StateView},
 };
-use defuse_near_utils::{Lock, NestPrefix, PREDECESSOR_ACCOUNT_ID, UnwrapOrPanic};
-use defuse_serde_utils::base64::AsBase64;
+use defuse_near_utils::{
+    Lock, NestPrefix, PREDECESSOR_ACCOUNT_ID, UnwrapOrPanic, UnwrapOrPanicError,
+};
+use defuse_serde_utils::base64::{AsBase64, AsBase64OrBase58};
 
 use near_sdk::{
     AccountId, AccountIdRef, BorshStorageKey, FunctionError, IntoStorageKey, assert_one_yocto,
@@ -20,7 +22,7 @@ use near_sdk::{
 };
 
 use crate::{
-    accounts::AccountManager,
+    accounts::{AccountExporter, AccountManager, AccountSummary},
     contract::{Contract, ContractExt, accounts::AccountEntry},
 };
 
@@ -34,24 +36,88 @@ impl AccountManager for Contract {
         StateView::iter_public_keys(self, account_id).collect()
     }
 
+    fn public_keys_of_paged(
+        &self,
+        account_id: &AccountId,
+        from_index: Option<u32>,
+        limit: Option<u32>,
+    ) -> Vec<PublicKey> {
+        let from_index: usize = from_index
+            .unwrap_or_default()
+            .try_into()
+            .unwrap_or_panic_display();
+
+        let iter = StateView::iter_public_keys(self, account_id).skip(from_index);
+
+        match limit {
+            Some(l) => iter.take(l.try_into().unwrap_or_panic_display()).collect(),
+            None => iter.collect(),
+        }
+    }
+
     #[payable]
-    fn add_public_key(&mut self, public_key: PublicKey) {
+    fn add_public_key(&mut self, public_key: PublicKey) -> bool {
         assert_one_yocto();
         State::add_public_key(self, self.ensure_auth_predecessor_id().clone(), public_key)
-            .unwrap_or_panic();
+            .map(|()| true)
+            .or_else(|err| match err {
+                DefuseError::PublicKeyExists(..) => Ok(false),
+                err => Err(err),
+            })
+            .unwrap_or_panic()
     }
 
     #[payable]
-    fn remove_public_key(&mut self, public_key: PublicKey) {
+    fn remove_public_key(&mut self, public_key: PublicKey) -> bool {
         assert_one_yocto();
         State::remove_public_key(self, self.ensure_auth_predecessor_id().clone(), public_key)
-            .unwrap_or_panic();
+            .map(|()| true)
+            .or_else(|err| match err {
+                DefuseError::PublicKeyNotExist(..) => Ok(false),
+                err => Err(err),
+            })
+            .unwrap_or_panic()
     }
 
-    fn is_nonce_used(&self, account_id: &AccountId, nonce: AsBase64<Nonce>) -> bool {
+    fn is_nonce_used(&self, account_id: &AccountId, nonce: AsBase64OrBase58<Nonce>) -> bool {
         StateView::is_nonce_used(self, account_id, nonce.into_inner())
     }
 
+    fn are_nonces_used(
+        &self,
+        account_id: &AccountId,
+        nonces: Vec<AsBase64OrBase58<Nonce>>,
+    ) -> Vec<bool> {
+        let account = self.accounts.get(account_id).map(Lock::as_inner_unchecked);
+        nonces
+            .into_iter()
+            .map(|nonce| account.is_some_and(|account| account.is_nonce_used(nonce.into_inner())))
+            .collect()
+    }
+
+    fn used_nonces_of(
+        &self,
+        account_id: &AccountId,
+        from_index: Option<u32>,
+        limit: Option<u32>,
+    ) -> Vec<AsBase64<Nonce>> {
+        let Some(account) = self.accounts.get(account_id).map(Lock::as_inner_unchecked) else {
+            return Vec::new();
+        };
+
+        let from_index: usize = from_index
+            .unwrap_or_default()
+            .try_into()
+            .unwrap_or_panic_display();
+
+        let iter = account.iter_used_nonces().skip(from_index).map(AsBase64);
+
+        match limit {
+            Some(l) => iter.take(l.try_into().unwrap_or_panic_display()).collect(),
+            None => iter.collect(),
+        }
+    }
+
     fn cleanup_expired_nonces(&mut self, nonces: Vec<(AccountId, Vec<AsBase64<Nonce>>)>) {
         for (account_id, nonces) in nonces {
             // NOTE: all errors are omitted
@@ -76,6 +142,46 @@ impl AccountManager for Contract {
     }
 }
 
+#[near]
+impl AccountExporter for Contract {
+    fn export_accounts(
+        &self,
+        from_index: Option<u32>,
+        limit: Option<u32>,
+    ) -> Vec<(AccountId, AccountSummary)> {
+        let from_index: usize = from_index
+            .unwrap_or_default()
+            .try_into()
+            .unwrap_or_panic_display();
+
+        let iter = self
+            .accounts
+            .iter()
+            .skip(from_index)
+            .map(|(account_id, account)| {
+                let locked = account.is_locked();
+                let account = account.as_inner_unchecked();
+                (
+                    account_id.clone(),
+                    AccountSummary {
+                        public_keys: account
+                            .iter_public_keys(account_id)
+                            .count()
+                            .try_into()
+                            .unwrap_or_panic_display(),
+                        locked,
+                        auth_by_predecessor_id_enabled: account.is_auth_by_predecessor_id_enabled(),
+                    },
+                )
+            });
+
+        match limit {
+            Some(l) => iter.take(l.try_into().unwrap_or_panic_display()).collect(),
+            None => iter.collect(),
+        }
+    }
+}
+
 impl Contract {
     #[inline]
     pub fn ensure_auth_predecessor_id(&self) -> &'static AccountId {
@@ -116,19 +222,46 @@ impl Accounts {
         self.accounts.get_mut(account_id).map(|a| &mut **a)
     }
 
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (&AccountId, &Lock<Account>)> {
+        self.accounts
+            .iter()
+            .map(|(account_id, account)| (account_id, &**account))
+    }
+
     /// Gets or creates an account with given `account_id`.
     /// NOTE: The created account will be unblocked by default.
     #[inline]
     pub fn get_or_create(&mut self, account_id: AccountId) -> &mut Lock<Account> {
+        self.get_or_create_with_lock(account_id, false)
+    }
+
+    /// Same as [`get_or_create`](Self::get_or_create), but the account is
+    /// created in locked state, e.g. to be unlocked later by governance.
+    /// Lock state of already existing account is left as is.
+    #[inline]
+    pub fn get_or_create_locked(&mut self, account_id: AccountId) -> &mut Lock<Account> {
+        self.get_or_create_with_lock(account_id, true)
+    }
+
+    #[inline]
+    fn get_or_create_with_lock(
+        &mut self,
+        account_id: AccountId,
+        locked: bool,
+    ) -> &mut Lock<Account> {
         self.accounts
             .entry(account_id)
             .or_insert_with_key(|account_id| {
-                Lock::unlocked(Account::new(
-                    self.prefix
-                        .as_slice()
-                        .nest(AccountsPrefix::Account(account_id)),
-                    account_id,
-                ))
+                Lock::new(
+                    locked,
+                    Account::new(
+                        self.prefix
+                            .as_slice()
+                            .nest(AccountsPrefix::Account(account_id)),
+                        account_id,
+                    ),
+                )
                 .into()
             })
     }
@@ -1,9 +1,16 @@
-use std::collections::{HashMap, HashSet};
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+};
 
 use defuse_core::fees::FeesConfig;
-use near_sdk::{AccountId, near};
+use near_plugins::{AccessControllable, access_control_any};
+use near_sdk::{AccountId, assert_one_yocto, near, require};
 
-use super::Role;
+pub use crate::config::{ConfigView, Settings};
+use crate::{config::ConfigManager, fees::FeesSettings};
+
+use super::{Contract, ContractExt, Role};
 
 #[near(serializers = [json])]
 #[derive(Debug, Clone)]
@@ -11,6 +18,58 @@ pub struct DefuseConfig {
     pub wnear_id: AccountId,
     pub fees: FeesConfig,
     pub roles: RolesConfig,
+    #[serde(flatten)]
+    pub fees_settings: FeesSettings,
+    #[serde(flatten)]
+    pub settings: Settings,
+}
+
+#[near]
+impl ConfigManager for Contract {
+    fn config(&self) -> ConfigView {
+        ConfigView {
+            wnear_id: self.wnear_id.clone(),
+            fees: self.fees.clone(),
+            fees_settings: self.fees_settings().clone(),
+            settings: self.settings().clone(),
+        }
+    }
+
+    #[access_control_any(roles(Role::DAO))]
+    #[payable]
+    fn set_settings(&mut self, settings: Settings) {
+        assert_one_yocto();
+        require!(*self.settings() != settings, "same");
+        self.store_settings(settings);
+    }
+}
+
+/// Versioned [`Settings`] as stored in the contract.
+#[near(serializers = [borsh])]
+#[derive(Debug)]
+pub(super) enum VersionedSettings<'a> {
+    // When adding a field to `Settings`, given current version `N`:
+    // 1. Copy current `Settings` struct definition and name it `SettingsVN`
+    // 2. Add variant `VN(SettingsVN)` before `Latest`
+    // 3. Add corresponding arm to `From<VersionedSettings> for Settings`
+    Latest(Cow<'a, Settings>),
+}
+
+impl From<VersionedSettings<'_>> for Settings {
+    #[inline]
+    fn from(versioned: VersionedSettings<'_>) -> Self {
+        match versioned {
+            VersionedSettings::Latest(settings) => settings.into_owned(),
+        }
+    }
+}
+
+impl<'a> From<&'a Settings> for VersionedSettings<'a> {
+    #[inline]
+    fn from(settings: &'a Settings) -> Self {
+        // always serialize as latest version
+        Self::Latest(Cow::Borrowed(settings))
+    }
 }
 
 #[near(serializers = [json])]
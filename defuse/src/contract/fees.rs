@@ -3,12 +3,16 @@ use std::borrow::Cow;
 
 use defuse_core::{
     events::DefuseIntentEmit,
-    fees::{FeeChangedEvent, FeeCollectorChangedEvent, Pips},
+    fees::{
+        FeeChangedEvent, FeeCollectorChangedEvent, FeeLimits, FeeLimitsChangedEvent, Pips,
+        ReferralFeeShareChangedEvent,
+    },
+    token_id::TokenId,
 };
 use near_plugins::{AccessControllable, Pausable, access_control_any, pause};
 use near_sdk::{AccountId, assert_one_yocto, near, require};
 
-use crate::fees::FeesManager;
+use crate::fees::{FeesManager, FeesSettings};
 
 use super::{Contract, ContractExt, Role};
 
@@ -49,4 +53,88 @@ impl FeesManager for Contract {
     fn fee_collector(&self) -> &AccountId {
         &self.fees.fee_collector
     }
+
+    #[pause(name = "intents")]
+    #[access_control_any(roles(Role::DAO, Role::FeesManager))]
+    #[payable]
+    fn set_referral_fee_share(&mut self, referral_fee_share: Pips) {
+        assert_one_yocto();
+        let mut fees_settings = self.fees_settings().clone();
+        require!(
+            fees_settings.referral_fee_share != referral_fee_share,
+            "same"
+        );
+        let old_referral_fee_share =
+            mem::replace(&mut fees_settings.referral_fee_share, referral_fee_share);
+        self.store_fees_settings(fees_settings);
+        ReferralFeeShareChangedEvent {
+            old_referral_fee_share,
+            new_referral_fee_share: referral_fee_share,
+        }
+        .emit();
+    }
+
+    fn referral_fee_share(&self) -> Pips {
+        self.fees_settings().referral_fee_share
+    }
+
+    #[pause(name = "intents")]
+    #[access_control_any(roles(Role::DAO, Role::FeesManager))]
+    #[payable]
+    fn set_fee_limits(&mut self, token_id: TokenId, fee_limits: FeeLimits) {
+        assert_one_yocto();
+        let mut fees_settings = self.fees_settings().clone();
+        let old_fee_limits = if fee_limits == FeeLimits::default() {
+            fees_settings.fee_limits.remove(&token_id)
+        } else {
+            fees_settings
+                .fee_limits
+                .insert(token_id.clone(), fee_limits)
+        }
+        .unwrap_or_default();
+        require!(old_fee_limits != fee_limits, "same");
+        self.store_fees_settings(fees_settings);
+        FeeLimitsChangedEvent {
+            token_id: Cow::Owned(token_id),
+            old_fee_limits,
+            new_fee_limits: fee_limits,
+        }
+        .emit();
+    }
+
+    fn fee_limits(&self, token_id: TokenId) -> FeeLimits {
+        self.fees_settings()
+            .fee_limits
+            .get(&token_id)
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+/// Versioned [`FeesSettings`] as stored in the contract.
+#[near(serializers = [borsh])]
+#[derive(Debug)]
+pub(super) enum VersionedFeesSettings<'a> {
+    // When adding a field to `FeesSettings`, given current version `N`:
+    // 1. Copy current `FeesSettings` struct definition and name it `FeesSettingsVN`
+    // 2. Add variant `VN(FeesSettingsVN)` before `Latest`
+    // 3. Add corresponding arm to `From<VersionedFeesSettings> for FeesSettings`
+    Latest(Cow<'a, FeesSettings>),
+}
+
+impl From<VersionedFeesSettings<'_>> for FeesSettings {
+    #[inline]
+    fn from(versioned: VersionedFeesSettings<'_>) -> Self {
+        match versioned {
+            VersionedFeesSettings::Latest(fees_settings) => fees_settings.into_owned(),
+        }
+    }
+}
+
+impl<'a> From<&'a FeesSettings> for VersionedFeesSettings<'a> {
+    #[inline]
+    fn from(fees_settings: &'a FeesSettings) -> Self {
+        // always serialize as latest version
+        Self::Latest(Cow::Borrowed(fees_settings))
+    }
 }
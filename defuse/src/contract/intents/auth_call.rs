@@ -1,12 +1,18 @@
-use defuse_auth_call::ext_auth_callee;
-use defuse_core::intents::auth::AuthCall;
-use near_sdk::{AccountId, Gas, Promise, PromiseResult, env, near, require};
+use defuse_auth_call::{ext_auth_callee, ext_auth_value_callee};
+use defuse_core::{
+    accounts::AccountEvent,
+    events::DefuseEvent,
+    intents::auth::{AuthCall, AuthCallValueEvent},
+};
+use defuse_near_utils::CURRENT_ACCOUNT_ID;
+use near_sdk::{AccountId, Gas, Promise, PromiseResult, env, near, require, serde_json};
 
 use crate::contract::{Contract, ContractExt};
 
 #[near]
 impl Contract {
     pub(crate) const DO_AUTH_CALL_MIN_GAS: Gas = Gas::from_tgas(5);
+    pub(crate) const ON_AUTH_VALUE_RESOLVE_GAS: Gas = Gas::from_tgas(5);
 
     #[must_use]
     #[private]
@@ -20,9 +26,42 @@ impl Contract {
 
         let min_gas = auth_call.min_gas();
 
-        ext_auth_callee::ext(auth_call.contract_id)
+        if !auth_call.return_value {
+            return ext_auth_callee::ext(auth_call.contract_id)
+                .with_attached_deposit(auth_call.attached_deposit)
+                .with_static_gas(min_gas)
+                .on_auth(signer_id, auth_call.msg);
+        }
+
+        ext_auth_value_callee::ext(auth_call.contract_id.clone())
             .with_attached_deposit(auth_call.attached_deposit)
             .with_static_gas(min_gas)
-            .on_auth(signer_id, auth_call.msg)
+            .on_auth_value(signer_id.clone(), auth_call.msg)
+            .then(
+                Self::ext(CURRENT_ACCOUNT_ID.clone())
+                    .with_static_gas(Self::ON_AUTH_VALUE_RESOLVE_GAS)
+                    // do not distribute remaining gas here
+                    .with_unused_gas_weight(0)
+                    .on_auth_value_resolve(signer_id, auth_call.contract_id),
+            )
+    }
+
+    #[private]
+    pub fn on_auth_value_resolve(signer_id: AccountId, contract_id: AccountId) -> Option<String> {
+        let value = match env::promise_result(0) {
+            PromiseResult::Successful(data) => serde_json::from_slice(&data).ok(),
+            PromiseResult::Failed => None,
+        };
+
+        DefuseEvent::AuthCallValue(AccountEvent::new(
+            signer_id,
+            AuthCallValueEvent {
+                contract_id,
+                value: value.clone(),
+            },
+        ))
+        .emit();
+
+        value
     }
 }
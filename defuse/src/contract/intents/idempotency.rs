@@ -0,0 +1,76 @@
+use std::collections::VecDeque;
+
+use defuse_core::{DefuseError, Result, crypto::Payload, payload::multi::MultiPayload};
+use near_sdk::{CryptoHash, env, near};
+
+/// Ring buffer of the most recent idempotency keys passed to
+/// `execute_intents()` by a single predecessor, along with hashes of
+/// the payloads they were submitted with
+#[near(serializers = [borsh])]
+#[derive(Debug, Default)]
+pub struct IdempotencyKeys(VecDeque<([u8; 32], CryptoHash)>);
+
+impl IdempotencyKeys {
+    /// Maximum number of keys remembered per predecessor, the oldest
+    /// one is evicted when exceeded
+    pub const CAPACITY: usize = 64;
+
+    /// Commitment to the whole batch of signed payloads
+    pub fn payloads_hash(signed: &[MultiPayload]) -> CryptoHash {
+        env::sha256_array(
+            &signed
+                .iter()
+                .flat_map(|payload| payload.hash())
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    /// Returns `Ok(true)` if the key was already seen recently with
+    /// the same payloads, or an error if it was seen with different ones.
+    pub fn contains(&self, key: [u8; 32], payloads_hash: CryptoHash) -> Result<bool> {
+        let Some((_, seen)) = self.0.iter().find(|(k, _)| *k == key) else {
+            return Ok(false);
+        };
+        if *seen != payloads_hash {
+            return Err(DefuseError::IdempotencyKeyMismatch);
+        }
+        Ok(true)
+    }
+
+    /// Remembers the key, evicting the oldest one if at capacity.
+    /// The key MUST NOT be already [`contained`](Self::contains).
+    pub fn insert(&mut self, key: [u8; 32], payloads_hash: CryptoHash) {
+        if self.0.len() >= Self::CAPACITY {
+            self.0.pop_front();
+        }
+        self.0.push_back((key, payloads_hash));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evicts_oldest() {
+        let mut keys = IdempotencyKeys::default();
+        for i in 0..=IdempotencyKeys::CAPACITY {
+            let key = [u8::try_from(i).unwrap(); 32];
+            assert!(!keys.contains(key, [0; 32]).unwrap());
+            keys.insert(key, [0; 32]);
+        }
+        assert!(!keys.contains([0; 32], [0; 32]).unwrap());
+        assert!(keys.contains([1; 32], [0; 32]).unwrap());
+    }
+
+    #[test]
+    fn different_payloads() {
+        let mut keys = IdempotencyKeys::default();
+        keys.insert([0; 32], [1; 32]);
+        assert!(matches!(
+            keys.contains([0; 32], [2; 32]),
+            Err(DefuseError::IdempotencyKeyMismatch)
+        ));
+        assert!(keys.contains([0; 32], [1; 32]).unwrap());
+    }
+}
@@ -1,41 +1,81 @@
 mod auth_call;
 mod execute;
+mod idempotency;
 mod relayer;
 mod simulate;
 mod state;
 
 use defuse_core::{
-    DefuseError,
+    DefuseError, Result,
     engine::{Engine, StateView},
+    fees::Pips,
+    intents::{DefuseIntents, token_diff::TokenDiff},
     payload::multi::MultiPayload,
 };
 use defuse_near_utils::UnwrapOrPanic;
 use defuse_nep245::MtEvent;
+use defuse_serde_utils::base64::AsBase64;
 use execute::ExecuteInspector;
+use idempotency::IdempotencyKeys;
 use near_plugins::{Pausable, pause};
-use near_sdk::{FunctionError, near};
+use near_sdk::{
+    AccountId, CryptoHash, FunctionError, assert_one_yocto, env, near, store::LookupMap,
+};
 use simulate::SimulateInspector;
 
-use crate::intents::{Intents, SimulationOutput, StateOutput};
+use crate::intents::{Intents, SimulationOutput, StateOutput, TokenDiffClosure};
 
-use super::{Contract, ContractExt};
+use super::{Contract, ContractExt, Prefix};
 
 #[near]
 impl Intents for Contract {
     #[pause(name = "intents")]
     #[inline]
-    fn execute_intents(&mut self, signed: Vec<MultiPayload>) {
+    fn execute_intents(
+        &mut self,
+        signed: Vec<MultiPayload>,
+        idempotency_key: Option<AsBase64<[u8; 32]>>,
+    ) {
+        let predecessor_id = env::predecessor_account_id();
+        let idempotency_key = idempotency_key.map(|key| {
+            if signed.is_empty() {
+                DefuseError::IdempotencyKeyWithoutPayloads.panic();
+            }
+            (key.into_inner(), IdempotencyKeys::payloads_hash(&signed))
+        });
+        if let Some((key, payloads_hash)) = idempotency_key {
+            if Self::is_idempotency_key_used(&predecessor_id, key, payloads_hash).unwrap_or_panic()
+            {
+                // already executed successfully, since the key is only
+                // persisted along with the rest of the state
+                return;
+            }
+        }
+
+        self.check_signed_intents(&signed).unwrap_or_panic();
+
         Engine::new(self, ExecuteInspector::default())
             .execute_signed_intents(signed)
             .unwrap_or_panic()
             .as_mt_event()
             .as_ref()
             .map(MtEvent::emit);
+
+        if let Some((key, payloads_hash)) = idempotency_key {
+            // persist only after signed payloads were verified, so that
+            // storage can't be occupied without valid signatures
+            Self::idempotency_keys()
+                .entry(predecessor_id)
+                .or_default()
+                .insert(key, payloads_hash);
+        }
     }
 
     #[pause(name = "intents")]
     #[inline]
     fn simulate_intents(&self, signed: Vec<MultiPayload>) -> SimulationOutput {
+        self.check_signed_intents(&signed).unwrap_or_panic();
+
         let mut inspector = SimulateInspector::default();
         let engine = Engine::new(self.cached(), &mut inspector);
 
@@ -49,8 +89,79 @@ impl Intents for Contract {
         SimulationOutput {
             intents_executed: inspector.intents_executed,
             min_deadline: inspector.min_deadline,
+            min_promise_gas: inspector.min_promise_gas,
             invariant_violated,
+            verified_by: inspector.verified_by,
             state: StateOutput { fee: self.fee() },
         }
     }
+
+    #[pause(name = "intents")]
+    #[payable]
+    fn execute_intents_by_predecessor(&mut self, intents: DefuseIntents) {
+        assert_one_yocto();
+        let signer_id = self.ensure_auth_predecessor_id();
+
+        Engine::new(self, ExecuteInspector::default())
+            .execute_intents_by_predecessor(signer_id, intents)
+            .unwrap_or_panic()
+            .as_mt_event()
+            .as_ref()
+            .map(MtEvent::emit);
+    }
+
+    fn simulate_token_diff_closure(
+        &self,
+        diffs: Vec<TokenDiff>,
+        fee: Option<Pips>,
+    ) -> TokenDiffClosure {
+        let fee = fee.unwrap_or_else(|| self.fee());
+
+        TokenDiffClosure {
+            closure: TokenDiff::closure_many(diffs, fee, &self.fees_settings().fee_limits)
+                .ok_or(DefuseError::BalanceOverflow)
+                .unwrap_or_panic(),
+            fee,
+        }
+    }
+}
+
+impl Contract {
+    /// Fail fast on too many signed payloads or disallowed signing
+    /// standards before doing any verification work
+    fn check_signed_intents(&self, signed: &[MultiPayload]) -> Result<()> {
+        let settings = self.settings();
+
+        let max = settings.max_intents_per_call;
+        if !u32::try_from(signed.len()).is_ok_and(|count| count <= max) {
+            return Err(DefuseError::TooManyIntents(max));
+        }
+
+        if let Some(standard) = settings.allowed_standards.as_ref().and_then(|allowed| {
+            signed
+                .iter()
+                .map(MultiPayload::standard)
+                .find(|standard| !allowed.contains(standard))
+        }) {
+            return Err(DefuseError::SigningStandardNotAllowed(standard));
+        }
+
+        Ok(())
+    }
+
+    /// Idempotency keys are namespaced by predecessor, so that others
+    /// can neither front-run nor evict them
+    fn idempotency_keys() -> LookupMap<AccountId, IdempotencyKeys> {
+        LookupMap::new(Prefix::IdempotencyKeys)
+    }
+
+    fn is_idempotency_key_used(
+        predecessor_id: &AccountId,
+        key: [u8; 32],
+        payloads_hash: CryptoHash,
+    ) -> Result<bool> {
+        Self::idempotency_keys()
+            .get(predecessor_id)
+            .map_or(Ok(false), |keys| keys.contains(key, payloads_hash))
+    }
 }
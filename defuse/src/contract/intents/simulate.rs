@@ -1,15 +1,18 @@
 use defuse_core::{
     Deadline, Nonce,
     accounts::{AccountEvent, NonceEvent},
+    crypto::PublicKey,
     engine::Inspector,
     events::DefuseEvent,
     intents::IntentEvent,
 };
-use near_sdk::{AccountIdRef, CryptoHash};
+use near_sdk::{AccountIdRef, CryptoHash, Gas};
 
 pub struct SimulateInspector {
     pub intents_executed: Vec<IntentEvent<AccountEvent<'static, NonceEvent>>>,
     pub min_deadline: Deadline,
+    pub min_promise_gas: Gas,
+    pub verified_by: Vec<PublicKey>,
 }
 
 impl Default for SimulateInspector {
@@ -17,6 +20,8 @@ impl Default for SimulateInspector {
         Self {
             intents_executed: Vec::new(),
             min_deadline: Deadline::MAX,
+            min_promise_gas: Gas::from_gas(0),
+            verified_by: Vec::new(),
         }
     }
 }
@@ -41,4 +46,14 @@ impl Inspector for SimulateInspector {
             intent_hash,
         ));
     }
+
+    #[inline]
+    fn on_public_key_verified(&mut self, _signer_id: &AccountIdRef, public_key: &PublicKey) {
+        self.verified_by.push(*public_key);
+    }
+
+    #[inline]
+    fn on_promise_gas(&mut self, gas: Gas) {
+        self.min_promise_gas = self.min_promise_gas.saturating_add(gas);
+    }
 }
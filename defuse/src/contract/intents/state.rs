@@ -2,17 +2,18 @@ use defuse_core::{
     DefuseError, Nonce, Result,
     crypto::PublicKey,
     engine::{State, StateView},
-    fees::Pips,
+    fees::{FeeLimits, Pips},
     intents::{
+        allowance::Allowance,
         auth::AuthCall,
-        tokens::{FtWithdraw, MtWithdraw, NativeWithdraw, NftWithdraw, StorageDeposit},
+        tokens::{FtWithdraw, MtWithdraw, NativeWithdraw, NftWithdraw, StorageDeposit, UnwrapNear},
     },
     token_id::{TokenId, nep141::Nep141TokenId},
 };
 use defuse_near_utils::{CURRENT_ACCOUNT_ID, Lock};
 use defuse_wnear::{NEAR_WITHDRAW_GAS, ext_wnear};
-use near_sdk::{AccountId, AccountIdRef, NearToken, json_types::U128};
-use std::borrow::Cow;
+use near_sdk::{AccountId, AccountIdRef, Gas, NearToken, json_types::U128};
+use std::{borrow::Cow, time::Duration};
 
 use crate::contract::{Contract, accounts::Account};
 
@@ -37,6 +38,40 @@ impl StateView for Contract {
         Cow::Borrowed(self.state.fees.fee_collector.as_ref())
     }
 
+    #[inline]
+    fn referral_fee_share(&self) -> Pips {
+        self.fees_settings().referral_fee_share
+    }
+
+    #[inline]
+    fn fee_limits(&self, token_id: &TokenId) -> FeeLimits {
+        self.fees_settings()
+            .fee_limits
+            .get(token_id)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    #[inline]
+    fn verbose_events(&self) -> bool {
+        self.settings().verbose_events
+    }
+
+    #[inline]
+    fn max_public_keys(&self) -> Option<u32> {
+        self.settings().max_public_keys
+    }
+
+    #[inline]
+    fn deadline_grace(&self) -> Duration {
+        Duration::from_millis(self.settings().deadline_grace_ms.into())
+    }
+
+    #[inline]
+    fn network_id(&self) -> Option<String> {
+        self.settings().network_id.clone()
+    }
+
     #[inline]
     fn has_public_key(&self, account_id: &AccountIdRef, public_key: &PublicKey) -> bool {
         self.accounts
@@ -78,11 +113,52 @@ impl StateView for Contract {
             .unwrap_or_default()
     }
 
+    fn balances_of(&self, account_id: &AccountIdRef, token_ids: &[TokenId]) -> Vec<u128> {
+        let Some(account) = self.accounts.get(account_id).map(Lock::as_inner_unchecked) else {
+            return vec![0; token_ids.len()];
+        };
+        token_ids
+            .iter()
+            .map(|token_id| account.token_balances.amount_for(token_id))
+            .collect()
+    }
+
+    #[inline]
+    fn allowance(
+        &self,
+        owner_id: &AccountIdRef,
+        delegate_id: &AccountIdRef,
+        token_id: &TokenId,
+    ) -> u128 {
+        self.accounts
+            .get(owner_id)
+            .map(Lock::as_inner_unchecked)
+            .map(|account| account.allowance(delegate_id, token_id))
+            .unwrap_or_default()
+    }
+
+    #[inline]
+    fn allowances_count(&self, owner_id: &AccountIdRef) -> u32 {
+        self.accounts
+            .get(owner_id)
+            .map(Lock::as_inner_unchecked)
+            .map(Account::allowances_count)
+            .unwrap_or_default()
+    }
+
     #[inline]
     fn is_account_locked(&self, account_id: &AccountIdRef) -> bool {
         self.accounts.get(account_id).is_some_and(Lock::is_locked)
     }
 
+    #[inline]
+    fn account_lock_reason(&self, account_id: &AccountIdRef) -> Option<String> {
+        self.accounts
+            .get(account_id)
+            .and_then(Lock::lock_reason)
+            .map(ToOwned::to_owned)
+    }
+
     #[inline]
     fn is_auth_by_predecessor_id_enabled(&self, account_id: &AccountIdRef) -> bool {
         self.accounts
@@ -95,10 +171,23 @@ impl StateView for Contract {
 impl State for Contract {
     #[inline]
     fn add_public_key(&mut self, account_id: AccountId, public_key: PublicKey) -> Result<()> {
-        self.accounts
+        let max_public_keys = self.max_public_keys();
+        let account = self
+            .accounts
             .get_or_create(account_id.clone())
-            .get_mut()
-            .ok_or_else(|| DefuseError::AccountLocked(account_id.clone()))?
+            .try_get_mut()
+            .map_err(|reason| DefuseError::account_locked(&account_id, reason))?;
+
+        // implicit public key doesn't count against the limit
+        if let Some(max) = max_public_keys.filter(|&max| {
+            account_id != public_key.to_implicit_account_id()
+                && !account.has_public_key(&account_id, &public_key)
+                && account.public_keys_count() >= max
+        }) {
+            return Err(DefuseError::TooManyPublicKeys(account_id, max));
+        }
+
+        account
             .add_public_key(&account_id, public_key)
             .then_some(())
             .ok_or(DefuseError::PublicKeyExists(account_id, public_key))
@@ -108,8 +197,8 @@ impl State for Contract {
     fn remove_public_key(&mut self, account_id: AccountId, public_key: PublicKey) -> Result<()> {
         self.accounts
             .get_or_create(account_id.clone())
-            .get_mut()
-            .ok_or_else(|| DefuseError::AccountLocked(account_id.clone()))?
+            .try_get_mut()
+            .map_err(|reason| DefuseError::account_locked(&account_id, reason))?
             .remove_public_key(&account_id, &public_key)
             .then_some(())
             .ok_or(DefuseError::PublicKeyNotExist(account_id, public_key))
@@ -119,9 +208,11 @@ impl State for Contract {
     fn commit_nonce(&mut self, account_id: AccountId, nonce: Nonce) -> Result<()> {
         self.accounts
             .get_or_create(account_id.clone())
-            .get_mut()
-            .ok_or(DefuseError::AccountLocked(account_id))?
-            .commit_nonce(nonce)
+            .try_get_mut()
+            .map_err(|reason| DefuseError::account_locked(&account_id, reason))?
+            .commit_nonce(nonce)?
+            .then_some(())
+            .ok_or(DefuseError::NonceUsed { account_id, nonce })
     }
 
     #[inline]
@@ -176,8 +267,8 @@ impl State for Contract {
             .accounts
             .get_mut(owner_id)
             .ok_or_else(|| DefuseError::AccountNotFound(owner_id.to_owned()))?
-            .get_mut()
-            .ok_or_else(|| DefuseError::AccountLocked(owner_id.to_owned()))?;
+            .try_get_mut()
+            .map_err(|reason| DefuseError::account_locked(owner_id, reason))?;
 
         for (token_id, amount) in tokens {
             if amount == 0 {
@@ -193,6 +284,39 @@ impl State for Contract {
         Ok(())
     }
 
+    fn set_allowance(
+        &mut self,
+        owner_id: AccountId,
+        delegate_id: AccountId,
+        token_id: TokenId,
+        amount: u128,
+    ) -> Result<()> {
+        let account = if amount == 0 {
+            let Some(account) = self.accounts.get_mut(&owner_id) else {
+                // nothing to revoke
+                return Ok(());
+            };
+            account
+        } else {
+            self.accounts.get_or_create(owner_id.clone())
+        }
+        .try_get_mut()
+        .map_err(|reason| DefuseError::account_locked(&owner_id, reason))?;
+
+        if amount != 0
+            && account.allowance(&delegate_id, &token_id) == 0
+            && account.allowances_count() >= Allowance::MAX_PER_ACCOUNT
+        {
+            return Err(DefuseError::TooManyAllowances(
+                owner_id,
+                Allowance::MAX_PER_ACCOUNT,
+            ));
+        }
+
+        account.set_allowance(delegate_id, token_id, amount);
+        Ok(())
+    }
+
     fn ft_withdraw(&mut self, owner_id: &AccountIdRef, withdraw: FtWithdraw) -> Result<()> {
         self.internal_ft_withdraw(owner_id.to_owned(), withdraw, false)
             // detach promise
@@ -232,10 +356,43 @@ impl State for Contract {
             .then(
                 // do_native_withdraw only after unwrapping NEAR
                 Self::ext(CURRENT_ACCOUNT_ID.clone())
-                    .with_static_gas(Self::DO_NATIVE_WITHDRAW_GAS)
+                    .with_static_gas(withdraw.do_native_withdraw_gas())
                     // do not distribute remaining gas here
                     .with_unused_gas_weight(0)
-                    .do_native_withdraw(withdraw),
+                    .do_native_withdraw(
+                        withdraw.require_account_exists.then(|| owner_id.to_owned()),
+                        withdraw,
+                    ),
+            );
+
+        Ok(())
+    }
+
+    fn unwrap_near(&mut self, owner_id: &AccountIdRef, unwrap: UnwrapNear) -> Result<()> {
+        self.withdraw(
+            owner_id,
+            [(
+                Nep141TokenId::new(self.wnear_id().into_owned()).into(),
+                unwrap.amount.as_yoctonear(),
+            )],
+            Some("unwrap"),
+            false,
+        )?;
+
+        // detach promise
+        let _ = ext_wnear::ext(self.wnear_id.clone())
+            .with_attached_deposit(NearToken::from_yoctonear(1))
+            .with_static_gas(NEAR_WITHDRAW_GAS)
+            // do not distribute remaining gas here
+            .with_unused_gas_weight(0)
+            .near_withdraw(U128(unwrap.amount.as_yoctonear()))
+            .then(
+                // credit native balance only after unwrapping NEAR
+                Self::ext(CURRENT_ACCOUNT_ID.clone())
+                    .with_static_gas(UnwrapNear::DO_UNWRAP_NEAR_GAS)
+                    // do not distribute remaining gas here
+                    .with_unused_gas_weight(0)
+                    .do_unwrap_near(owner_id.to_owned(), unwrap.amount),
             );
 
         Ok(())
@@ -246,6 +403,19 @@ impl State for Contract {
         owner_id: &AccountIdRef,
         storage_deposit: StorageDeposit,
     ) -> Result<()> {
+        if storage_deposit.from_native {
+            self.withdraw(
+                owner_id,
+                [(TokenId::Native, storage_deposit.amount.as_yoctonear())],
+                Some("withdraw"),
+                false,
+            )?;
+
+            // detach promise
+            let _ = Self::storage_deposit_promise(storage_deposit);
+            return Ok(());
+        }
+
         self.withdraw(
             owner_id,
             [(
@@ -266,7 +436,7 @@ impl State for Contract {
             .then(
                 // do_storage_deposit only after unwrapping NEAR
                 Self::ext(CURRENT_ACCOUNT_ID.clone())
-                    .with_static_gas(Self::DO_STORAGE_DEPOSIT_GAS)
+                    .with_static_gas(StorageDeposit::DO_STORAGE_DEPOSIT_GAS)
                     // do not distribute remaining gas here
                     .with_unused_gas_weight(0)
                     .do_storage_deposit(storage_deposit),
@@ -286,8 +456,8 @@ impl State for Contract {
         } else {
             self.accounts.get_or_create(account_id.clone())
         }
-        .get_mut()
-        .ok_or_else(|| DefuseError::AccountLocked(account_id.clone()))
+        .try_get_mut()
+        .map_err(|reason| DefuseError::account_locked(&account_id, reason))
         .map(|account| account.set_auth_by_predecessor_id(&account_id, enable))
     }
 
@@ -317,7 +487,13 @@ impl State for Contract {
                     // do_auth_call only after unwrapping NEAR
                     Self::ext(CURRENT_ACCOUNT_ID.clone())
                         .with_static_gas(
-                            Self::DO_AUTH_CALL_MIN_GAS.saturating_add(auth_call.min_gas()),
+                            Self::DO_AUTH_CALL_MIN_GAS
+                                .saturating_add(auth_call.min_gas())
+                                .saturating_add(if auth_call.return_value {
+                                    Self::ON_AUTH_VALUE_RESOLVE_GAS
+                                } else {
+                                    Gas::from_gas(0)
+                                }),
                         )
                         .do_auth_call(signer_id.to_owned(), auth_call),
                 )
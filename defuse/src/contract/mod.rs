@@ -10,22 +10,27 @@ mod state;
 mod tokens;
 mod upgrade;
 
-use core::iter;
+use core::{cell::OnceCell, iter};
 
 use defuse_core::Result;
+use defuse_near_utils::UnwrapOrPanicError;
 
 use events::PostponedMtBurnEvents;
 use impl_tools::autoimpl;
 use near_plugins::{AccessControlRole, AccessControllable, Pausable, access_control};
 use near_sdk::{
-    BorshStorageKey, PanicOnDefault, borsh::BorshDeserialize, near, require, store::LookupSet,
+    BorshStorageKey, IntoStorageKey, PanicOnDefault,
+    borsh::{self, BorshDeserialize},
+    env, near, require,
+    store::LookupSet,
 };
 
-use crate::Defuse;
+use crate::{Defuse, fees::FeesSettings};
 
 use self::{
     accounts::Accounts,
-    config::{DefuseConfig, RolesConfig},
+    config::{DefuseConfig, RolesConfig, Settings, VersionedSettings},
+    fees::VersionedFeesSettings,
     state::ContractState,
 };
 
@@ -70,6 +75,14 @@ pub struct Contract {
 
     #[borsh(skip)]
     postponed_burns: PostponedMtBurnEvents,
+
+    /// Settings are loaded from storage at most once per call
+    #[borsh(skip)]
+    settings: OnceCell<Settings>,
+
+    /// Fees settings are loaded from storage at most once per call
+    #[borsh(skip)]
+    fees_settings: OnceCell<FeesSettings>,
 }
 
 #[near]
@@ -83,8 +96,12 @@ impl Contract {
             state: ContractState::new(Prefix::State, config.wnear_id, config.fees),
             relayer_keys: LookupSet::new(Prefix::RelayerKeys),
             postponed_burns: PostponedMtBurnEvents::new(),
+            settings: OnceCell::new(),
+            fees_settings: OnceCell::new(),
         };
         contract.init_acl(config.roles);
+        contract.store_settings(config.settings);
+        contract.store_fees_settings(config.fees_settings);
         contract
     }
 
@@ -110,6 +127,52 @@ impl Contract {
     }
 }
 
+impl Contract {
+    /// Returns settings stored separately from the rest of the state,
+    /// or defaults if they were never set.
+    pub(crate) fn settings(&self) -> &Settings {
+        self.settings.get_or_init(|| {
+            env::storage_read(&Prefix::Settings.into_storage_key())
+                .map(|data| {
+                    borsh::from_slice::<VersionedSettings>(&data)
+                        .unwrap_or_panic_display()
+                        .into()
+                })
+                .unwrap_or_default()
+        })
+    }
+
+    fn store_settings(&mut self, settings: Settings) {
+        env::storage_write(
+            &Prefix::Settings.into_storage_key(),
+            &borsh::to_vec(&VersionedSettings::from(&settings)).unwrap_or_panic_display(),
+        );
+        self.settings = OnceCell::from(settings);
+    }
+
+    /// Returns fees settings stored separately from the rest of the state,
+    /// or defaults if they were never set.
+    pub(crate) fn fees_settings(&self) -> &FeesSettings {
+        self.fees_settings.get_or_init(|| {
+            env::storage_read(&Prefix::FeesSettings.into_storage_key())
+                .map(|data| {
+                    borsh::from_slice::<VersionedFeesSettings>(&data)
+                        .unwrap_or_panic_display()
+                        .into()
+                })
+                .unwrap_or_default()
+        })
+    }
+
+    fn store_fees_settings(&mut self, fees_settings: FeesSettings) {
+        env::storage_write(
+            &Prefix::FeesSettings.into_storage_key(),
+            &borsh::to_vec(&VersionedFeesSettings::from(&fees_settings)).unwrap_or_panic_display(),
+        );
+        self.fees_settings = OnceCell::from(fees_settings);
+    }
+}
+
 #[near]
 impl Defuse for Contract {}
 
@@ -119,4 +182,7 @@ enum Prefix {
     Accounts,
     State,
     RelayerKeys,
+    Settings,
+    IdempotencyKeys,
+    FeesSettings,
 }
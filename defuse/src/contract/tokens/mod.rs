@@ -3,12 +3,12 @@ mod nep171;
 mod nep245;
 
 use super::Contract;
-use defuse_core::{DefuseError, Result, token_id::TokenId};
+use defuse_core::{DefuseError, Result, intents::tokens::StorageDeposit, token_id::TokenId};
 use defuse_nep245::{MtBurnEvent, MtEvent, MtMintEvent};
 use near_sdk::{AccountId, AccountIdRef, Gas, json_types::U128};
 use std::borrow::Cow;
 
-pub const STORAGE_DEPOSIT_GAS: Gas = Gas::from_tgas(10);
+pub const STORAGE_DEPOSIT_GAS: Gas = StorageDeposit::STORAGE_DEPOSIT_GAS;
 
 impl Contract {
     pub(crate) fn deposit(
@@ -24,7 +24,7 @@ impl Contract {
             .as_inner_unchecked_mut();
 
         let mut mint_event = MtMintEvent {
-            owner_id: owner_id.into(),
+            owner_id: owner_id.clone().into(),
             token_ids: Vec::new().into(),
             amounts: Vec::new().into(),
             memo: memo.map(Into::into),
@@ -46,10 +46,14 @@ impl Contract {
             match token_id {
                 TokenId::Nep171(ref tid) => {
                     if total_supply > 1 {
-                        return Err(DefuseError::NftAlreadyDeposited(tid.clone()));
+                        return Err(DefuseError::NftAlreadyDeposited(
+                            tid.clone(),
+                            (owner.token_balances.amount_for(&token_id) > 0)
+                                .then(|| owner_id.clone()),
+                        ));
                     }
                 }
-                TokenId::Nep141(_) | TokenId::Nep245(_) => {}
+                TokenId::Nep141(_) | TokenId::Nep245(_) | TokenId::Native => {}
             }
             owner
                 .token_balances
@@ -75,8 +79,8 @@ impl Contract {
             .accounts
             .get_mut(owner_id)
             .ok_or_else(|| DefuseError::AccountNotFound(owner_id.to_owned()))?
-            .get_mut_maybe_forced(force)
-            .ok_or_else(|| DefuseError::AccountLocked(owner_id.to_owned()))?;
+            .try_get_mut_maybe_forced(force)
+            .map_err(|reason| DefuseError::account_locked(owner_id, reason))?;
 
         let mut burn_event = MtBurnEvent {
             owner_id: Cow::Owned(owner_id.to_owned()),
@@ -1,20 +1,105 @@
-use defuse_core::intents::tokens::NativeWithdraw;
-use near_sdk::{Gas, Promise, PromiseResult, env, near, require};
+use defuse_core::{
+    intents::tokens::NativeWithdraw,
+    token_id::{TokenId, nep141::Nep141TokenId},
+};
+use defuse_near_utils::{CURRENT_ACCOUNT_ID, UnwrapOrPanic};
+use defuse_wnear::{NEAR_DEPOSIT_GAS, ext_wnear};
+use near_sdk::{
+    AccountId, Gas, NearToken, Promise, PromiseOrValue, PromiseResult, env, near, require,
+};
 
 use crate::contract::{Contract, ContractExt};
 
 #[near]
 impl Contract {
-    pub(crate) const DO_NATIVE_WITHDRAW_GAS: Gas = Gas::from_tgas(10);
+    /// Must fit into [`NativeWithdraw::NATIVE_RESOLVE_WITHDRAW_GAS`]
+    /// together with re-wrapping NEAR
+    pub(crate) const NATIVE_RESOLVE_REFUND_GAS: Gas = Gas::from_tgas(10);
 
+    /// Transfers unwrapped NEAR to the receiver. If `refund_to` is given,
+    /// the transfer is resolved and refunded on failure.
     #[must_use]
     #[private]
-    pub fn do_native_withdraw(withdraw: NativeWithdraw) -> Promise {
+    pub fn do_native_withdraw(refund_to: Option<AccountId>, withdraw: NativeWithdraw) -> Promise {
         require!(
             matches!(env::promise_result(0), PromiseResult::Successful(data) if data.is_empty()),
             "near_withdraw failed",
         );
 
-        Promise::new(withdraw.receiver_id).transfer(withdraw.amount)
+        let transfer = Promise::new(withdraw.receiver_id).transfer(withdraw.amount);
+        let Some(owner_id) = refund_to else {
+            return transfer;
+        };
+
+        transfer.then(
+            Self::ext(CURRENT_ACCOUNT_ID.clone())
+                .with_static_gas(NativeWithdraw::NATIVE_RESOLVE_WITHDRAW_GAS)
+                // do not distribute remaining gas here
+                .with_unused_gas_weight(0)
+                .native_resolve_withdraw(owner_id, withdraw.amount),
+        )
+    }
+
+    #[private]
+    pub fn native_resolve_withdraw(
+        &mut self,
+        owner_id: AccountId,
+        amount: NearToken,
+    ) -> PromiseOrValue<()> {
+        if matches!(env::promise_result(0), PromiseResult::Successful(_)) {
+            return PromiseOrValue::Value(());
+        }
+
+        // NEAR was already unwrapped, so wrap it back to refund wNEAR
+        ext_wnear::ext(self.wnear_id.clone())
+            .with_attached_deposit(amount)
+            .with_static_gas(NEAR_DEPOSIT_GAS)
+            // do not distribute remaining gas here
+            .with_unused_gas_weight(0)
+            .near_deposit()
+            .then(
+                Self::ext(CURRENT_ACCOUNT_ID.clone())
+                    .with_static_gas(Self::NATIVE_RESOLVE_REFUND_GAS)
+                    // do not distribute remaining gas here
+                    .with_unused_gas_weight(0)
+                    .native_resolve_refund(owner_id, amount),
+            )
+            .into()
+    }
+
+    #[private]
+    pub fn native_resolve_refund(&mut self, owner_id: AccountId, amount: NearToken) {
+        let token_id = if matches!(env::promise_result(0), PromiseResult::Successful(_)) {
+            Nep141TokenId::new(self.wnear_id.clone()).into()
+        } else {
+            // attached NEAR is returned on failed `near_deposit()`, so
+            // refund it to native balance instead
+            TokenId::Native
+        };
+
+        // do not panic in callback, since refunded tokens would be lost
+        if let Err(err) = self.deposit(
+            owner_id,
+            [(token_id, amount.as_yoctonear())],
+            Some("refund"),
+        ) {
+            env::log_str(&err.to_string());
+        }
+    }
+
+    #[private]
+    pub fn do_unwrap_near(&mut self, owner_id: AccountId, amount: NearToken) {
+        let (token_id, memo) = if matches!(
+            env::promise_result(0),
+            PromiseResult::Successful(data) if data.is_empty()
+        ) {
+            (TokenId::Native, "unwrap")
+        } else {
+            // refund wNEAR
+            (Nep141TokenId::new(self.wnear_id.clone()).into(), "refund")
+        };
+
+        self.deposit(owner_id, [(token_id, amount.as_yoctonear())], Some(memo))
+            .unwrap_or_panic();
     }
 }
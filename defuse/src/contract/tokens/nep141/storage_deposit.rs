@@ -1,14 +1,11 @@
 use defuse_core::intents::tokens::StorageDeposit;
 use near_contract_standards::storage_management::ext_storage_management;
-use near_sdk::{Gas, Promise, PromiseResult, env, near, require};
+use near_sdk::{Promise, PromiseResult, env, near, require};
 
 use crate::contract::{Contract, ContractExt, tokens::STORAGE_DEPOSIT_GAS};
 
 #[near]
 impl Contract {
-    pub(crate) const DO_STORAGE_DEPOSIT_GAS: Gas =
-        Gas::from_tgas(5).saturating_add(STORAGE_DEPOSIT_GAS);
-
     #[must_use]
     #[private]
     pub fn do_storage_deposit(storage_deposit: StorageDeposit) -> Promise {
@@ -17,6 +14,12 @@ impl Contract {
             "near_withdraw failed",
         );
 
+        Self::storage_deposit_promise(storage_deposit)
+    }
+}
+
+impl Contract {
+    pub(crate) fn storage_deposit_promise(storage_deposit: StorageDeposit) -> Promise {
         ext_storage_management::ext(storage_deposit.contract_id)
             .with_attached_deposit(storage_deposit.amount)
             .with_static_gas(STORAGE_DEPOSIT_GAS)
@@ -1,6 +1,8 @@
 use crate::contract::{Contract, ContractExt};
 use defuse_core::{DefuseError, Result, engine::StateView, token_id::TokenId};
-use defuse_near_utils::{CURRENT_ACCOUNT_ID, UnwrapOrPanic, UnwrapOrPanicError};
+use defuse_near_utils::{
+    CURRENT_ACCOUNT_ID, PREDECESSOR_ACCOUNT_ID, UnwrapOrPanic, UnwrapOrPanicError,
+};
 use defuse_nep245::{MtEvent, MtTransferEvent, MultiTokenCore, receiver::ext_mt_receiver};
 use near_plugins::{Pausable, pause};
 use near_sdk::{
@@ -180,8 +182,8 @@ impl Contract {
             self.accounts
                 .get_mut(sender_id)
                 .ok_or_else(|| DefuseError::AccountNotFound(sender_id.to_owned()))?
-                .get_mut_maybe_forced(force)
-                .ok_or_else(|| DefuseError::AccountLocked(sender_id.to_owned()))?
+                .try_get_mut_maybe_forced(force)
+                .map_err(|reason| DefuseError::account_locked(sender_id, reason))?
                 .token_balances
                 .sub(token_id.clone(), amount)
                 .ok_or(DefuseError::BalanceOverflow)?;
@@ -196,7 +198,8 @@ impl Contract {
 
         MtEvent::MtTransfer(
             [MtTransferEvent {
-                authorized_id: None,
+                // forced transfers are attributed to the permissioned caller
+                authorized_id: force.then(|| Cow::Borrowed(PREDECESSOR_ACCOUNT_ID.as_ref())),
                 old_owner_id: sender_id.into(),
                 new_owner_id: Cow::Borrowed(receiver_id),
                 token_ids: token_ids.into(),
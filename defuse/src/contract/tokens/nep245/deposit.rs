@@ -59,11 +59,11 @@ impl MultiTokenReceiver for Contract {
 
         if !msg.execute_intents.is_empty() {
             if msg.refund_if_fails {
-                self.execute_intents(msg.execute_intents);
+                self.execute_intents(msg.execute_intents, None);
             } else {
                 // detach promise
                 let _ = ext_intents::ext(CURRENT_ACCOUNT_ID.clone())
-                    .execute_intents(msg.execute_intents);
+                    .execute_intents(msg.execute_intents, None);
             }
         }
 
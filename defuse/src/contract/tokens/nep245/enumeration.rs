@@ -1,4 +1,7 @@
-use crate::contract::{Contract, ContractExt};
+use crate::{
+    contract::{Contract, ContractExt},
+    tokens::nep245::MultiTokenTotalSupplies,
+};
 use defuse_core::token_id::TokenIdType;
 use defuse_near_utils::UnwrapOrPanicError;
 use defuse_nep245::{Token, enumeration::MultiTokenEnumeration};
@@ -52,7 +55,7 @@ impl MultiTokenEnumeration for Contract {
                 token_id: token_id.to_string(),
                 owner_id: match TokenIdType::from(token_id) {
                     TokenIdType::Nep171 => Some(account_id.clone()),
-                    TokenIdType::Nep141 | TokenIdType::Nep245 => None,
+                    TokenIdType::Nep141 | TokenIdType::Nep245 | TokenIdType::Native => None,
                 },
             });
 
@@ -62,3 +65,27 @@ impl MultiTokenEnumeration for Contract {
         }
     }
 }
+
+#[near]
+impl MultiTokenTotalSupplies for Contract {
+    fn total_supplies(
+        &self,
+        from_index: Option<U128>,
+        limit: Option<u32>,
+    ) -> Vec<(defuse_nep245::TokenId, U128)> {
+        let from_index = from_index.map_or(0, |v| v.0);
+        let from_index: usize = from_index.try_into().unwrap_or_panic_display();
+
+        let iter = self
+            .state
+            .total_supplies
+            .iter()
+            .skip(from_index)
+            .map(|(token_id, amount)| (token_id.to_string(), U128(*amount)));
+
+        match limit {
+            Some(l) => iter.take(l.try_into().unwrap_or_panic_display()).collect(),
+            None => iter.collect(),
+        }
+    }
+}
@@ -63,7 +63,7 @@ impl Contract {
         withdraw: MtWithdraw,
         force: bool,
     ) -> Result<PromiseOrValue<Vec<U128>>> {
-        if withdraw.token_ids.len() != withdraw.amounts.len() || withdraw.token_ids.is_empty() {
+        if !withdraw.is_valid() {
             return Err(DefuseError::InvalidIntent);
         }
 
@@ -126,8 +126,11 @@ impl Contract {
         .into())
     }
 
+    /// Gas attached to `mt_resolve_withdraw()`:
+    /// `gas_base + gas_per_token * token_count`.
+    /// Worst case (i.e. refund of all tokens) is covered by sandbox tests.
     #[must_use]
-    fn mt_resolve_withdraw_gas(token_count: usize) -> Gas {
+    pub fn mt_resolve_withdraw_gas(token_count: usize) -> Gas {
         // Values chosen to be similar to `MT_RESOLVE_TRANSFER_*` values
         const MT_RESOLVE_WITHDRAW_PER_TOKEN_GAS: Gas = Gas::from_tgas(2);
         const MT_RESOLVE_WITHDRAW_BASE_GAS: Gas = Gas::from_tgas(8);
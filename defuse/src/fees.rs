@@ -1,6 +1,11 @@
-use defuse_core::fees::Pips;
+use std::collections::HashMap;
+
+use defuse_core::{
+    fees::{FeeLimits, Pips},
+    token_id::TokenId,
+};
 use near_plugins::AccessControllable;
-use near_sdk::{AccountId, ext_contract};
+use near_sdk::{AccountId, ext_contract, near};
 
 #[ext_contract(ext_fees_manager)]
 #[allow(clippy::module_name_repetitions)]
@@ -11,4 +16,33 @@ pub trait FeesManager: AccessControllable {
 
     fn set_fee_collector(&mut self, fee_collector: AccountId);
     fn fee_collector(&self) -> &AccountId;
+
+    /// Set share of the fee collected by `token_diff` intents which goes
+    /// to their `referral`, if any
+    fn set_referral_fee_share(&mut self, referral_fee_share: Pips);
+    fn referral_fee_share(&self) -> Pips;
+
+    /// Set absolute bounds on the fee collected by `token_diff` intents
+    /// for given token. Default (i.e. unset) bounds remove them.
+    fn set_fee_limits(&mut self, token_id: TokenId, fee_limits: FeeLimits);
+    fn fee_limits(&self, token_id: TokenId) -> FeeLimits;
+}
+
+/// Part of fees configuration which is stored separately from the rest
+/// of the contract state and can be changed via [`FeesManager`].
+///
+/// NOTE: stored as a versioned enum, so adding a field requires a new
+/// version to be added there as well.
+#[near(serializers = [borsh, json])]
+#[serde(default)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FeesSettings {
+    /// Share of the fee collected by `token_diff` intents which goes to
+    /// their `referral`, if any
+    pub referral_fee_share: Pips,
+
+    /// Absolute bounds on the fee collected by `token_diff` intents per
+    /// token in addition to the percentage `fee`. Tokens exempt from
+    /// the percentage fee are exempt from these bounds as well
+    pub fee_limits: HashMap<TokenId, FeeLimits>,
 }
@@ -1,23 +1,61 @@
+use std::collections::BTreeMap;
+
 use defuse_core::{
     Deadline, Result,
     accounts::{AccountEvent, NonceEvent},
+    amounts::Amounts,
+    crypto::PublicKey as DefusePublicKey,
     engine::deltas::InvariantViolated,
     fees::Pips,
-    intents::IntentEvent,
+    intents::{
+        DefuseIntents, IntentEvent,
+        token_diff::{TokenDeltas, TokenDiff},
+    },
     payload::multi::MultiPayload,
 };
 
+use defuse_serde_utils::base64::AsBase64;
 use near_plugins::AccessControllable;
-use near_sdk::{Promise, PublicKey, ext_contract, near};
-use serde_with::serde_as;
+use near_sdk::{Gas, Promise, PublicKey, ext_contract, near};
+use serde_with::{DisplayFromStr, serde_as};
 
 use crate::fees::FeesManager;
 
 #[ext_contract(ext_intents)]
 pub trait Intents: FeesManager {
-    fn execute_intents(&mut self, signed: Vec<MultiPayload>);
+    /// Executes signed intents. If `idempotency_key` was already used
+    /// by one of the recent successful calls from the same predecessor
+    /// with the same payloads, this is a no-op, so that relayers can
+    /// safely resubmit the same batch. Reusing the key for different
+    /// payloads fails. The key is remembered only once signed payloads
+    /// were verified and executed, so `signed` MUST NOT be empty.
+    fn execute_intents(
+        &mut self,
+        signed: Vec<MultiPayload>,
+        idempotency_key: Option<AsBase64<[u8; 32]>>,
+    );
 
     fn simulate_intents(&self, signed: Vec<MultiPayload>) -> SimulationOutput;
+
+    /// Executes intents on behalf of the caller without any signature.
+    /// Requires authentication by `PREDECESSOR_ID` to be enabled for the
+    /// caller.
+    ///
+    /// Emits `intents_executed` event with hash of `intents` themselves
+    /// and zero nonce, since no nonce is committed.
+    ///
+    /// NOTE: MUST attach 1 yⓃ for security purposes.
+    fn execute_intents_by_predecessor(&mut self, intents: DefuseIntents);
+
+    /// Returns closure for given set of distinct `token_diff` intents,
+    /// i.e. deltas that should be given in a single `token_diff` to
+    /// successfully execute all of them. Current fee is used, unless
+    /// `fee` is given explicitly. Current fee limits are always applied.
+    fn simulate_token_diff_closure(
+        &self,
+        diffs: Vec<TokenDiff>,
+        fee: Option<Pips>,
+    ) -> TokenDiffClosure;
 }
 
 #[cfg_attr(
@@ -37,11 +75,21 @@ pub struct SimulationOutput {
     /// Minimum deadline among all simulated intents
     pub min_deadline: Deadline,
 
+    /// Sum of minimum required gas for all Function Call Promises
+    /// created by simulated intents. The actual gas to attach to
+    /// `execute_intents()` should also account for its own execution.
+    pub min_promise_gas: Gas,
+
     /// Unmatched token deltas needed to keep the invariant.
     /// If not empty, can be used along with fee to calculate `token_diff` closure.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub invariant_violated: Option<InvariantViolated>,
 
+    /// Public keys which verified each of the signed payloads, in the
+    /// same order as they were given
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub verified_by: Vec<DefusePublicKey>,
+
     /// Additional info about current state
     pub state: StateOutput,
 }
@@ -61,6 +109,25 @@ pub struct StateOutput {
     pub fee: Pips,
 }
 
+#[cfg_attr(
+    all(feature = "abi", not(target_arch = "wasm32")),
+    serde_as(schemars = true)
+)]
+#[cfg_attr(
+    not(all(feature = "abi", not(target_arch = "wasm32"))),
+    serde_as(schemars = false)
+)]
+#[near(serializers = [json])]
+#[derive(Debug, Clone)]
+pub struct TokenDiffClosure {
+    /// Deltas to be given in a single `token_diff` intent
+    #[serde_as(as = "Amounts<BTreeMap<_, DisplayFromStr>>")]
+    pub closure: TokenDeltas,
+
+    /// Fee used to calculate the closure
+    pub fee: Pips,
+}
+
 #[ext_contract(ext_relayer_keys)]
 pub trait RelayerKeys: AccessControllable {
     /// Adds access key for calling `execute_signed_intents`
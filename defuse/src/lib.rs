@@ -3,6 +3,7 @@
 pub mod contract;
 
 pub mod accounts;
+pub mod config;
 pub mod fees;
 pub mod intents;
 pub mod tokens;
@@ -22,7 +23,11 @@ use near_contract_standards::{
 use near_plugins::{AccessControllable, Pausable};
 use near_sdk::ext_contract;
 
-use crate::{accounts::AccountForceLocker, tokens::nep245::MultiTokenForcedCore};
+use crate::{
+    accounts::{AccountExporter, AccountForceLocker},
+    config::ConfigManager,
+    tokens::nep245::{MultiTokenForcedCore, MultiTokenTotalSupplies},
+};
 
 use self::{
     accounts::AccountManager,
@@ -50,13 +55,16 @@ pub trait Defuse:
     + MultiTokenReceiver
     + MultiTokenWithdrawer
     + MultiTokenEnumeration
+    + MultiTokenTotalSupplies
     // Governance
     + AccessControllable
+    + ConfigManager
     + MultiTokenForcedCore
     + FungibleTokenForceWithdrawer
     + NonFungibleTokenForceWithdrawer
     + MultiTokenForcedWithdrawer
     + AccountForceLocker
+    + AccountExporter
     + Pausable
     + ControllerUpgradable
     + FullAccessKeys
@@ -26,6 +26,14 @@ pub struct DepositMessage {
 }
 
 impl DepositMessage {
+    /// Returns builder which validates the message on
+    /// [`build()`](DepositMessageBuilder::build)
+    #[must_use]
+    #[inline]
+    pub fn builder() -> DepositMessageBuilder {
+        DepositMessageBuilder::default()
+    }
+
     #[must_use]
     #[inline]
     pub const fn new(receiver_id: AccountId) -> Self {
@@ -51,6 +59,65 @@ impl DepositMessage {
     }
 }
 
+#[derive(Debug, Clone, Default)]
+pub struct DepositMessageBuilder {
+    receiver_id: Option<String>,
+    execute_intents: Vec<MultiPayload>,
+    refund_if_fails: bool,
+}
+
+impl DepositMessageBuilder {
+    #[must_use]
+    #[inline]
+    pub fn receiver_id(mut self, receiver_id: impl Into<String>) -> Self {
+        self.receiver_id = Some(receiver_id.into());
+        self
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn execute_intents(mut self, intents: impl IntoIterator<Item = MultiPayload>) -> Self {
+        self.execute_intents.extend(intents);
+        self
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn refund_if_fails(mut self) -> Self {
+        self.refund_if_fails = true;
+        self
+    }
+
+    pub fn build(self) -> Result<DepositMessage, DepositMessageError> {
+        let receiver_id = self
+            .receiver_id
+            .ok_or(DepositMessageError::MissingReceiverId)?
+            .parse()?;
+
+        // refund flag is meaningless without intents and would be
+        // dropped when serialized into `msg`
+        if self.refund_if_fails && self.execute_intents.is_empty() {
+            return Err(DepositMessageError::RefundWithoutIntents);
+        }
+
+        Ok(DepositMessage {
+            receiver_id,
+            execute_intents: self.execute_intents,
+            refund_if_fails: self.refund_if_fails,
+        })
+    }
+}
+
+#[derive(Debug, ThisError)]
+pub enum DepositMessageError {
+    #[error("receiver_id is missing")]
+    MissingReceiverId,
+    #[error("invalid receiver_id: {0}")]
+    InvalidReceiverId(#[from] ParseAccountError),
+    #[error("refund_if_fails requires execute_intents")]
+    RefundWithoutIntents,
+}
+
 impl Display for DepositMessage {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -82,3 +149,81 @@ pub enum ParseDepositMessageError {
     #[error("JSON: {0}")]
     JSON(#[from] serde_json::Error),
 }
+
+#[cfg(test)]
+mod tests {
+    use near_sdk::serde_json::json;
+
+    use super::*;
+
+    fn signed() -> MultiPayload {
+        serde_json::from_value(json!({
+            "standard": "raw_ed25519",
+            "payload": "{}",
+            "public_key": "ed25519:8rVvtHWFr8hasdQGGD5WiQBTyr4iH2ruEPPVfj491RPN",
+            "signature": "ed25519:3vtbNQJHZfuV1s5DykzyjkbNLc583hnkrhTz57eDhd966iqzkor6Twgr4Loh2C195SCSEsiGfrd6KcxpjNq9ZbVj",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn builder() {
+        let msg = DepositMessage::builder()
+            .receiver_id("alice.near")
+            .execute_intents([signed()])
+            .refund_if_fails()
+            .build()
+            .unwrap();
+        assert_eq!(msg.receiver_id, "alice.near");
+        assert_eq!(msg.execute_intents.len(), 1);
+        assert!(msg.refund_if_fails);
+
+        let msg = DepositMessage::builder()
+            .receiver_id("alice.near")
+            .build()
+            .unwrap();
+        assert!(msg.execute_intents.is_empty());
+        assert!(!msg.refund_if_fails);
+    }
+
+    #[test]
+    fn builder_validation() {
+        assert!(matches!(
+            DepositMessage::builder().build(),
+            Err(DepositMessageError::MissingReceiverId),
+        ));
+        assert!(matches!(
+            DepositMessage::builder().receiver_id("Alice!").build(),
+            Err(DepositMessageError::InvalidReceiverId(_)),
+        ));
+        assert!(matches!(
+            DepositMessage::builder()
+                .receiver_id("alice.near")
+                .refund_if_fails()
+                .build(),
+            Err(DepositMessageError::RefundWithoutIntents),
+        ));
+    }
+
+    #[test]
+    fn display_from_str_roundtrip() {
+        for msg in [
+            DepositMessage::new("alice.near".parse().unwrap()),
+            DepositMessage::new("alice.near".parse().unwrap()).with_execute_intents([signed()]),
+            DepositMessage::new("alice.near".parse().unwrap())
+                .with_execute_intents([signed(), signed()])
+                .with_refund_if_fails(),
+        ] {
+            let s = msg.to_string();
+            let parsed: DepositMessage = s.parse().unwrap();
+            assert_eq!(parsed.receiver_id, msg.receiver_id);
+            assert_eq!(parsed.execute_intents.len(), msg.execute_intents.len());
+            assert_eq!(parsed.refund_if_fails, msg.refund_if_fails);
+            assert_eq!(parsed.to_string(), s);
+        }
+        assert_eq!(
+            DepositMessage::new("alice.near".parse().unwrap()).to_string(),
+            "alice.near"
+        );
+    }
+}
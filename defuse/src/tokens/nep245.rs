@@ -1,6 +1,8 @@
 #![allow(clippy::too_many_arguments)]
 
-use defuse_nep245::{MultiTokenCore, TokenId, receiver::MultiTokenReceiver};
+use defuse_nep245::{
+    MultiTokenCore, TokenId, enumeration::MultiTokenEnumeration, receiver::MultiTokenReceiver,
+};
 use near_plugins::AccessControllable;
 use near_sdk::{AccountId, PromiseOrValue, ext_contract, json_types::U128};
 
@@ -23,6 +25,13 @@ pub trait MultiTokenWithdrawer: MultiTokenReceiver + MultiTokenWithdrawResolver
     ) -> PromiseOrValue<Vec<U128>>;
 }
 
+#[ext_contract(ext_mt_total_supplies)]
+pub trait MultiTokenTotalSupplies: MultiTokenEnumeration {
+    /// Paginated total supplies of all tokens held by the contract, in the
+    /// same order as `mt_tokens`. Use `mt_supply` for a single token.
+    fn total_supplies(&self, from_index: Option<U128>, limit: Option<u32>) -> Vec<(TokenId, U128)>;
+}
+
 #[ext_contract(mt_withdraw_resolver)]
 pub trait MultiTokenWithdrawResolver {
     fn mt_resolve_withdraw(
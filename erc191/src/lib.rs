@@ -1,3 +1,5 @@
+use std::borrow::Cow;
+
 use defuse_crypto::{CryptoHash, Curve, Payload, Secp256k1, SignedPayload, serde::AsCurve};
 use impl_tools::autoimpl;
 use near_sdk::{env, near};
@@ -25,6 +27,11 @@ impl Payload for Erc191Payload {
     fn hash(&self) -> CryptoHash {
         env::keccak256_array(&self.prehash())
     }
+
+    #[inline]
+    fn prehash(&self) -> Cow<'_, [u8]> {
+        Cow::Owned(Self::prehash(self))
+    }
 }
 
 #[cfg_attr(
@@ -52,6 +59,11 @@ impl Payload for SignedErc191Payload {
     fn hash(&self) -> CryptoHash {
         self.payload.hash()
     }
+
+    #[inline]
+    fn prehash(&self) -> Cow<'_, [u8]> {
+        self.payload.prehash()
+    }
 }
 
 impl SignedPayload for SignedErc191Payload {
@@ -59,7 +71,10 @@ impl SignedPayload for SignedErc191Payload {
 
     #[inline]
     fn verify(&self) -> Option<Self::PublicKey> {
-        Secp256k1::verify(&self.signature, &self.payload.hash(), &())
+        // wallets may produce `v` ∈ {27, 28} as well as high `s`
+        let mut signature = self.signature;
+        Secp256k1::normalize_signature(&mut signature);
+        Secp256k1::verify(&signature, &self.payload.hash(), &())
     }
 }
 
@@ -73,11 +88,7 @@ mod tests {
     use rstest::rstest;
 
     fn fix_v_in_signature(mut sig: [u8; 65]) -> [u8; 65] {
-        if *sig.last().unwrap() >= 27 {
-            // Ethereum only uses uncompressed keys, with corresponding value v=27/28
-            // https://bitcoin.stackexchange.com/a/38909/58790
-            *sig.last_mut().unwrap() -= 27;
-        }
+        Secp256k1::normalize_signature(&mut sig);
         sig
     }
 
@@ -113,6 +124,79 @@ mod tests {
         assert_eq!(signed_payload.verify(), Some(public_key));
     }
 
+    #[test]
+    fn verify_eth_sig_util() {
+        let msg = "hello world";
+
+        // `personal_sign` from `@metamask/eth-sig-util`, using private key:
+        // 4af1bceebf7f3634ec3cff8a2c38e51178d5d4ce585c52d6043e5e2cc3418bb0
+        let signature = hex_literal::hex!(
+            "51beb1bb7bac43e257f5759efaf9b7f3ab3e0b1849d087ceef6d91d06f9a1dde70eca37e363e30f702fc5f0dfd74f67db18e3fd3751a317b9a46549b67857bc11b"
+        );
+        let signature = fix_v_in_signature(signature);
+
+        // address: 0x29c76e6ad8f28bb1004902578fb108c507be341b
+        let public_key = hex_literal::hex!(
+            "9e9e45b2ec5f070b4e26f57c7fedf647afa7a03e894789816fbd12fedc5acd79d0dfeea925688e177caccb8f5e09f0c289bbcfc7adb98d76f5f8c5259478903a"
+        );
+
+        let signed_payload = SignedErc191Payload {
+            payload: Erc191Payload(msg.to_string()),
+            signature,
+        };
+
+        assert_eq!(signed_payload.verify(), Some(public_key));
+
+        let bad_signed_payload = SignedErc191Payload {
+            payload: Erc191Payload("hello world!".to_string()),
+            signature,
+        };
+
+        assert_ne!(bad_signed_payload.verify(), Some(public_key));
+    }
+
+    #[test]
+    fn verify_as() {
+        let signed_payload = SignedErc191Payload {
+            payload: Erc191Payload("hello world".to_string()),
+            signature: fix_v_in_signature(hex_literal::hex!(
+                "51beb1bb7bac43e257f5759efaf9b7f3ab3e0b1849d087ceef6d91d06f9a1dde70eca37e363e30f702fc5f0dfd74f67db18e3fd3751a317b9a46549b67857bc11b"
+            )),
+        };
+
+        assert!(signed_payload.verify_as(&hex_literal::hex!(
+            "9e9e45b2ec5f070b4e26f57c7fedf647afa7a03e894789816fbd12fedc5acd79d0dfeea925688e177caccb8f5e09f0c289bbcfc7adb98d76f5f8c5259478903a"
+        )));
+        assert!(!signed_payload.verify_as(&hex_literal::hex!(
+            "85a66984273f338ce4ef7b85e5430b008307e8591bb7c1b980852cf6423770b801f41e9438155eb53a5e20f748640093bb42ae3aeca035f7b7fd7a1a21f22f68"
+        )));
+    }
+
+    #[test]
+    fn verify_non_canonical() {
+        let public_key = hex_literal::hex!(
+            "85a66984273f338ce4ef7b85e5430b008307e8591bb7c1b980852cf6423770b801f41e9438155eb53a5e20f748640093bb42ae3aeca035f7b7fd7a1a21f22f68"
+        );
+
+        for signature in [
+            // as produced by wallet, with v=28
+            hex_literal::hex!(
+                "7800a70d05cde2c49ed546a6ce887ce6027c2c268c0285f6efef0cdfc4366b23643790f67a86468ee8301ed12cfffcb07c6530f90a9327ec057800fabd332e471c"
+            ),
+            // malleable counterpart: (r, n - s, 27)
+            hex_literal::hex!(
+                "7800a70d05cde2c49ed546a6ce887ce6027c2c268c0285f6efef0cdfc4366b239bc86f098579b97117cfe12ed300034e3e49abeda4b5784fba5a5d92130312fa1b"
+            ),
+        ] {
+            let signed_payload = SignedErc191Payload {
+                payload: Erc191Payload("Hello world!".to_string()),
+                signature,
+            };
+
+            assert_eq!(signed_payload.verify(), Some(public_key));
+        }
+    }
+
     #[rstest]
     fn tamper_message_fails(mut rng: impl Rng) {
         let msg = "Hello world!";
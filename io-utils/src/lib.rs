@@ -1,4 +1,6 @@
-use std::io::{Read, Result, Write};
+use std::io::{self, Read, Result, Write};
+
+use digest::{Digest, Output};
 
 pub trait ReadExt: Read {
     /// Creates a `TeeReader` that wraps the current reader and duplicates all read bytes into the given writer.
@@ -12,6 +14,32 @@ pub trait ReadExt: Read {
             writer,
         }
     }
+
+    /// Creates a `HashingReader` that wraps the current reader and feeds
+    /// all read bytes into a new instance of digest `D`.
+    fn hashing<D>(self) -> HashingReader<Self, D>
+    where
+        Self: Sized,
+        D: Digest,
+    {
+        HashingReader {
+            reader: self,
+            digest: D::new(),
+        }
+    }
+
+    /// Creates a `LimitedReader` that wraps the current reader and fails
+    /// with [`io::ErrorKind::InvalidData`] once more than `max` bytes are
+    /// read. Unlike [`Read::take`], it doesn't stop silently at the limit.
+    fn limited(self, max: u64) -> LimitedReader<Self>
+    where
+        Self: Sized,
+    {
+        LimitedReader {
+            reader: self,
+            remaining: max,
+        }
+    }
 }
 impl<R> ReadExt for R where R: Read {}
 
@@ -48,9 +76,88 @@ where
     }
 }
 
+/// A reader that wraps another reader and computes a digest of all bytes
+/// read so far.
+pub struct HashingReader<R, D> {
+    reader: R,
+    digest: D,
+}
+
+impl<R, D> HashingReader<R, D>
+where
+    D: Digest,
+{
+    /// Returns the digest of all bytes read so far
+    #[inline]
+    pub fn finalize(self) -> Output<D> {
+        self.digest.finalize()
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> (R, D) {
+        (self.reader, self.digest)
+    }
+}
+
+impl<R, D> Read for HashingReader<R, D>
+where
+    R: Read,
+    D: Digest,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let n = self.reader.read(buf)?;
+        self.digest.update(&buf[..n]);
+        Ok(n)
+    }
+}
+
+/// A reader that wraps another reader and fails once more than a given
+/// number of bytes are read.
+pub struct LimitedReader<R> {
+    reader: R,
+    remaining: u64,
+}
+
+impl<R> LimitedReader<R> {
+    /// Returns number of bytes that can still be read before the limit is
+    /// exceeded
+    #[inline]
+    pub const fn remaining(&self) -> u64 {
+        self.remaining
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R> Read for LimitedReader<R>
+where
+    R: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        // read one byte past the limit to detect if it was exceeded
+        let max = usize::try_from(self.remaining.saturating_add(1))
+            .map_or(buf.len(), |max| buf.len().min(max));
+        let n = self.reader.read(&mut buf[..max])?;
+        self.remaining = u64::try_from(n)
+            .ok()
+            .and_then(|n| self.remaining.checked_sub(n))
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "read limit exceeded"))?;
+        Ok(n)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use defuse_near_utils::digest::Sha256;
+    use defuse_test_utils::random::random_bytes;
+    use rstest::rstest;
     use std::io::{Cursor, Read};
 
     #[test]
@@ -91,4 +198,51 @@ mod tests {
         let written = output.into_inner();
         assert_eq!(&written[..n], b"stream this");
     }
+
+    #[rstest]
+    fn hashing_matches_full_buffer(random_bytes: Vec<u8>, #[values(1, 7, 4096)] chunk_size: usize) {
+        let mut hashing = Cursor::new(&random_bytes).hashing::<Sha256>();
+
+        let mut read = Vec::new();
+        let mut chunk = vec![0; chunk_size];
+        loop {
+            let n = hashing.read(&mut chunk).unwrap();
+            if n == 0 {
+                break;
+            }
+            read.extend_from_slice(&chunk[..n]);
+        }
+
+        assert_eq!(read, random_bytes);
+        assert_eq!(hashing.finalize(), Sha256::digest(&random_bytes));
+    }
+
+    #[rstest]
+    fn limited_under_limit(random_bytes: Vec<u8>, #[values(0, 1, 100)] extra: u64) {
+        let max = u64::try_from(random_bytes.len()).unwrap() + extra;
+        let mut limited = Cursor::new(&random_bytes).limited(max);
+
+        let mut read = Vec::new();
+        limited.read_to_end(&mut read).unwrap();
+        assert_eq!(read, random_bytes);
+        assert_eq!(limited.remaining(), extra);
+    }
+
+    #[rstest]
+    fn limited_over_limit(#[values(0, 1, 10)] max: u64) {
+        let input = [0u8; 11];
+        let mut limited = Cursor::new(&input).limited(max);
+
+        let mut read = Vec::new();
+        let err = limited.read_to_end(&mut read).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        // exact reads past the limit fail as well
+        let mut buf = [0u8; 11];
+        let err = Cursor::new(&input)
+            .limited(max)
+            .read_exact(&mut buf)
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
 }
@@ -1,6 +1,88 @@
 use near_sdk::{Gas, env};
+use thiserror::Error as ThisError;
 
 #[inline]
 pub fn gas_left() -> Gas {
     env::prepaid_gas().saturating_sub(env::used_gas())
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ThisError)]
+#[error("minimum gas {required} exceeds budget {budget}")]
+pub struct GasBudgetExceeded {
+    pub required: Gas,
+    pub budget: Gas,
+}
+
+/// Splits `total` gas across promises with given minimum gas each.
+/// Every promise gets its minimum plus an equal share of what remains,
+/// while the indivisible remainder goes to the first promises one unit
+/// each. This is an explicit equivalent of attaching equal
+/// [`GasWeight`](near_sdk::GasWeight) to all of them.
+pub fn split_gas(total: Gas, min_gas: &[Gas]) -> Result<Vec<Gas>, GasBudgetExceeded> {
+    let remaining = min_gas
+        .iter()
+        .try_fold(total, |left, &min| left.checked_sub(min))
+        .ok_or_else(|| GasBudgetExceeded {
+            required: min_gas
+                .iter()
+                .fold(Gas::from_gas(0), |sum, &min| sum.saturating_add(min)),
+            budget: total,
+        })?;
+
+    let count = u64::try_from(min_gas.len()).unwrap_or_else(|_| unreachable!());
+    let (share, remainder) = remaining
+        .as_gas()
+        .checked_div(count)
+        .zip(remaining.as_gas().checked_rem(count))
+        .unwrap_or_default();
+
+    Ok(min_gas
+        .iter()
+        .zip(0..)
+        .map(|(&min, i)| {
+            min.saturating_add(Gas::from_gas(share))
+                .saturating_add(Gas::from_gas((i < remainder).into()))
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::empty(100, &[], &[])]
+    #[case::even(90, &[10, 20, 30], &[20, 30, 40])]
+    #[case::remainder(62, &[10, 20, 30], &[11, 21, 30])]
+    #[case::exact(60, &[10, 20, 30], &[10, 20, 30])]
+    fn split(#[case] total: u64, #[case] min_gas: &[u64], #[case] expected: &[u64]) {
+        let min_gas: Vec<_> = min_gas.iter().copied().map(Gas::from_gas).collect();
+        let split = split_gas(Gas::from_gas(total), &min_gas).unwrap();
+
+        assert_eq!(
+            split,
+            expected
+                .iter()
+                .copied()
+                .map(Gas::from_gas)
+                .collect::<Vec<_>>()
+        );
+        assert!(
+            split.iter().map(|gas| gas.as_gas()).sum::<u64>() == total || min_gas.is_empty(),
+            "whole budget must be distributed"
+        );
+    }
+
+    #[rstest]
+    #[case(59, &[10, 20, 30])]
+    #[case(u64::MAX, &[u64::MAX, 1])]
+    fn over_budget(#[case] total: u64, #[case] min_gas: &[u64]) {
+        let min_gas: Vec<_> = min_gas.iter().copied().map(Gas::from_gas).collect();
+        assert!(matches!(
+            split_gas(Gas::from_gas(total), &min_gas),
+            Err(GasBudgetExceeded { budget, .. }) if budget == Gas::from_gas(total)
+        ));
+    }
+}
@@ -9,15 +9,22 @@ use near_sdk::{
 /// A persistent lock, which stores its state (whether it's locked or unlocked)
 /// on-chain, so that the inner value can be accessed depending on
 /// the current state of the lock.
+///
+/// Locked state can optionally carry a human-readable reason. In borsh it's
+/// only written when present, so that the layout stays compatible with
+/// locks serialized before reasons were introduced. The reason is owned
+/// by default, while [`Lock::as_ref`] and [`Lock::as_mut`] borrow it.
 #[derive(Debug, Default, PartialEq, Eq)]
-#[near(serializers = [borsh, json])]
-pub struct Lock<T> {
+#[near(serializers = [json])]
+pub struct Lock<T, R = String> {
     #[serde(
         default,
         // do not serialize `false`
         skip_serializing_if = "::core::ops::Not::not"
     )]
     locked: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    reason: Option<R>,
     #[serde(flatten)]
     value: T,
 }
@@ -26,7 +33,11 @@ impl<T> Lock<T> {
     #[must_use]
     #[inline]
     pub const fn new(locked: bool, value: T) -> Self {
-        Self { locked, value }
+        Self {
+            locked,
+            reason: None,
+            value,
+        }
     }
 
     #[must_use]
@@ -40,10 +51,13 @@ impl<T> Lock<T> {
     pub const fn locked(value: T) -> Self {
         Self::new(true, value)
     }
+}
 
+impl<T, R> Lock<T, R> {
     #[inline]
-    pub const fn set_locked(&mut self, locked: bool) -> &mut Self {
+    pub fn set_locked(&mut self, locked: bool) -> &mut Self {
         self.locked = locked;
+        self.reason = None;
         self
     }
 
@@ -75,6 +89,19 @@ impl<T> Lock<T> {
         self.locked
     }
 
+    /// Returns the reason given at lock time, if the lock is locked
+    #[must_use]
+    #[inline]
+    pub fn lock_reason(&self) -> Option<&str>
+    where
+        R: AsRef<str>,
+    {
+        self.reason
+            .as_ref()
+            .map(AsRef::as_ref)
+            .filter(|_| self.is_locked())
+    }
+
     #[must_use]
     #[inline]
     pub const fn as_locked(&self) -> Option<&T> {
@@ -114,17 +141,30 @@ impl<T> Lock<T> {
 
     #[must_use]
     #[inline]
-    pub const fn lock(&mut self) -> Option<&mut T> {
+    pub fn lock(&mut self) -> Option<&mut T> {
+        self.lock_with_reason(None)
+    }
+
+    /// Same as [`Lock::lock`], but stores the given `reason`, which can be
+    /// later retrieved with [`Lock::lock_reason`]
+    #[must_use]
+    #[inline]
+    pub fn lock_with_reason(&mut self, reason: Option<R>) -> Option<&mut T> {
         if self.is_locked() {
             return None;
         }
-        self.locked = true;
-        Some(self.as_inner_unchecked_mut())
+        Some(self.force_lock_with_reason(reason))
+    }
+
+    #[inline]
+    pub fn force_lock(&mut self) -> &mut T {
+        self.force_lock_with_reason(None)
     }
 
     #[inline]
-    pub const fn force_lock(&mut self) -> &mut T {
+    pub fn force_lock_with_reason(&mut self, reason: Option<R>) -> &mut T {
         self.locked = true;
+        self.reason = reason;
         self.as_inner_unchecked_mut()
     }
 
@@ -156,6 +196,29 @@ impl<T> Lock<T> {
         }
     }
 
+    /// Same as [`Lock::get_mut`], but returns [`Lock::lock_reason`]
+    /// if locked
+    #[inline]
+    pub fn try_get_mut(&mut self) -> Result<&mut T, Option<&str>>
+    where
+        R: AsRef<str>,
+    {
+        self.try_get_mut_maybe_forced(false)
+    }
+
+    /// Same as [`Lock::get_mut_maybe_forced`], but returns
+    /// [`Lock::lock_reason`] if locked and not forced
+    #[inline]
+    pub fn try_get_mut_maybe_forced(&mut self, force: bool) -> Result<&mut T, Option<&str>>
+    where
+        R: AsRef<str>,
+    {
+        if self.is_locked() && !force {
+            return Err(self.reason.as_ref().map(AsRef::as_ref));
+        }
+        Ok(self.as_inner_unchecked_mut())
+    }
+
     #[must_use]
     #[inline]
     pub fn into_unlocked(self) -> Option<T> {
@@ -167,36 +230,120 @@ impl<T> Lock<T> {
 
     #[must_use]
     #[inline]
-    pub const fn unlock(&mut self) -> Option<&mut T> {
+    pub fn unlock(&mut self) -> Option<&mut T> {
         if !self.is_locked() {
             return None;
         }
-        self.locked = false;
-        Some(self.as_inner_unchecked_mut())
+        Some(self.force_unlock())
     }
 
     #[inline]
-    pub const fn force_unlock(&mut self) -> &mut T {
+    pub fn force_unlock(&mut self) -> &mut T {
         self.locked = false;
+        self.reason = None;
         self.as_inner_unchecked_mut()
     }
 
     #[inline]
-    pub const fn as_ref(&self) -> Lock<&T> {
-        Lock::new(self.is_locked(), self.as_inner_unchecked())
+    pub fn as_ref(&self) -> Lock<&T, &str>
+    where
+        R: AsRef<str>,
+    {
+        Lock {
+            locked: self.locked,
+            reason: self.reason.as_ref().map(AsRef::as_ref),
+            value: &self.value,
+        }
     }
 
     #[inline]
-    pub const fn as_mut(&mut self) -> Lock<&mut T> {
-        Lock::new(self.is_locked(), self.as_inner_unchecked_mut())
+    pub fn as_mut(&mut self) -> Lock<&mut T, &str>
+    where
+        R: AsRef<str>,
+    {
+        Lock {
+            locked: self.locked,
+            reason: self.reason.as_ref().map(AsRef::as_ref),
+            value: &mut self.value,
+        }
     }
 
     #[inline]
-    pub fn map_inner_unchecked<U, F>(self, f: F) -> Lock<U>
+    pub fn map_inner_unchecked<U, F>(self, f: F) -> Lock<U, R>
     where
         F: FnOnce(T) -> U,
     {
-        Lock::new(self.is_locked(), f(self.into_inner_unchecked()))
+        Lock {
+            locked: self.locked,
+            reason: self.reason,
+            value: f(self.value),
+        }
+    }
+}
+
+/// Borsh-serialized state of [`Lock`], which precedes its value.
+/// It's a superset of `bool`, which was used before reasons were introduced.
+#[near(serializers = [borsh])]
+enum LockState<R> {
+    Unlocked,
+    Locked,
+    LockedWithReason(R),
+}
+
+impl<T> Lock<T> {
+    fn serialize_state<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        match (self.locked, self.reason.as_deref()) {
+            (false, _) => LockState::Unlocked,
+            (true, None) => LockState::Locked,
+            (true, Some(reason)) => LockState::LockedWithReason(reason),
+        }
+        .serialize(writer)
+    }
+
+    fn deserialize_state<R>(reader: &mut R) -> io::Result<(bool, Option<String>)>
+    where
+        R: io::Read,
+    {
+        Ok(match LockState::deserialize_reader(reader)? {
+            LockState::Unlocked => (false, None),
+            LockState::Locked => (true, None),
+            LockState::LockedWithReason(reason) => (true, Some(reason)),
+        })
+    }
+}
+
+impl<T> BorshSerialize for Lock<T>
+where
+    T: BorshSerialize,
+{
+    #[inline]
+    fn serialize<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        self.serialize_state(writer)?;
+        self.value.serialize(writer)
+    }
+}
+
+impl<T> BorshDeserialize for Lock<T>
+where
+    T: BorshDeserialize,
+{
+    #[inline]
+    fn deserialize_reader<R>(reader: &mut R) -> io::Result<Self>
+    where
+        R: io::Read,
+    {
+        let (locked, reason) = Self::deserialize_state(reader)?;
+        Ok(Self {
+            locked,
+            reason,
+            value: T::deserialize_reader(reader)?,
+        })
     }
 }
 
@@ -216,11 +363,8 @@ where
     where
         W: io::Write,
     {
-        Lock {
-            locked: source.locked,
-            value: AsWrap::<&T, &As>::new(&source.value),
-        }
-        .serialize(writer)
+        source.serialize_state(writer)?;
+        AsWrap::<&T, &As>::new(&source.value).serialize(writer)
     }
 }
 
@@ -233,10 +377,8 @@ where
     where
         R: io::Read,
     {
-        Lock::<AsWrap<T, As>>::deserialize_reader(reader).map(|v| Lock {
-            locked: v.locked,
-            value: v.value.into_inner(),
-        })
+        Lock::<AsWrap<T, As>>::deserialize_reader(reader)
+            .map(|v| v.map_inner_unchecked(AsWrap::into_inner))
     }
 }
 
@@ -259,3 +401,38 @@ fn test() {
     *a.as_locked_mut().unwrap() += 1;
     assert_eq!(*a.as_inner_unchecked(), 2);
 }
+
+#[cfg(test)]
+#[test]
+fn test_reason() {
+    use near_sdk::borsh;
+
+    let mut a = Lock::new(false, 0u32);
+    assert_eq!(
+        a.lock_with_reason(Some("compromised".to_string())).copied(),
+        Some(0)
+    );
+    assert_eq!(a.lock_reason(), Some("compromised"));
+    assert_eq!(a.lock_with_reason(Some("other".to_string())), None);
+    assert_eq!(a.lock_reason(), Some("compromised"));
+    assert_eq!(a.try_get_mut(), Err(Some("compromised")));
+    assert_eq!(a.try_get_mut_maybe_forced(true).copied(), Ok(0));
+    assert_eq!(a.as_ref().lock_reason(), Some("compromised"));
+    assert_eq!(a.as_mut().try_get_mut(), Err(Some("compromised")));
+
+    let serialized = borsh::to_vec(&a).unwrap();
+    assert_eq!(borsh::from_slice::<Lock<u32>>(&serialized).unwrap(), a);
+
+    assert_eq!(a.unlock().copied(), Some(0));
+    assert_eq!(a.lock_reason(), None);
+
+    // locks without reason are serialized the same way as before
+    // reasons were introduced: `(bool, T)`
+    for locked in [false, true] {
+        let lock = Lock::new(locked, 1u32);
+        let serialized = borsh::to_vec(&lock).unwrap();
+        assert_eq!(serialized, borsh::to_vec(&(locked, 1u32)).unwrap());
+        assert_eq!(borsh::from_slice::<Lock<u32>>(&serialized).unwrap(), lock);
+        assert_eq!(lock.lock_reason(), None);
+    }
+}
@@ -19,3 +19,59 @@ pub struct NestedPrefix<S, P> {
     parent: S,
     nested: P,
 }
+
+/// Nests prefixes multiple levels deep in one call:
+/// `nest_all!(prefix, a, b, c)` is equivalent to
+/// `prefix.nest(a).nest(b).nest(c)`, so the whole key is still
+/// serialized with a single allocation.
+#[macro_export]
+macro_rules! nest_all {
+    ($prefix:expr $(,)?) => {
+        $prefix
+    };
+    ($prefix:expr, $nested:expr $(, $rest:expr)* $(,)?) => {
+        $crate::nest_all!($crate::NestPrefix::nest($prefix, $nested) $(, $rest)*)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use near_sdk::AccountId;
+
+    use super::*;
+
+    #[derive(BorshSerialize, BorshStorageKey)]
+    #[borsh(crate = "::near_sdk::borsh")]
+    enum Prefix {
+        Accounts,
+        Account(AccountId),
+        Balances,
+    }
+
+    #[test]
+    fn nest_all_equals_chained_nest() {
+        let account_id: AccountId = "alice.near".parse().unwrap();
+
+        let chained = b"root"
+            .as_slice()
+            .nest(Prefix::Accounts)
+            .nest(Prefix::Account(account_id.clone()))
+            .nest(Prefix::Balances)
+            .into_storage_key();
+
+        assert_eq!(
+            nest_all!(
+                b"root".as_slice(),
+                Prefix::Accounts,
+                Prefix::Account(account_id.clone()),
+                Prefix::Balances,
+            )
+            .into_storage_key(),
+            chained,
+        );
+        assert_eq!(
+            nest_all!(b"root".as_slice()).into_storage_key(),
+            b"root".as_slice().into_storage_key(),
+        );
+    }
+}
@@ -1,12 +1,15 @@
 use core::fmt::Display;
+use std::borrow::Cow;
 
 use defuse_crypto::{CryptoHash, Curve, Ed25519, Payload, SignedPayload, serde::AsCurve};
 use defuse_near_utils::UnwrapOrPanicError;
 use defuse_nep461::{OffchainMessage, SignedMessageNep};
 use defuse_serde_utils::base64::Base64;
 use impl_tools::autoimpl;
-use near_sdk::{borsh, env, near};
+use near_account_id::ParseAccountError;
+use near_sdk::{AccountIdRef, borsh, env, near};
 use serde_with::serde_as;
+use thiserror::Error as ThisError;
 
 /// See [NEP-413](https://github.com/near/NEPs/blob/master/neps/nep-0413.md)
 #[cfg_attr(
@@ -71,17 +74,48 @@ impl Nep413Payload {
         self
     }
 
+    /// Checks that the message is not empty and the recipient is a valid
+    /// account id
+    pub fn validate(&self) -> Result<(), Nep413PayloadError> {
+        if self.message.is_empty() {
+            return Err(Nep413PayloadError::EmptyMessage);
+        }
+        AccountIdRef::new(&self.recipient)?;
+        Ok(())
+    }
+
+    /// Finishes the builder with [`validate`](Self::validate)
+    #[inline]
+    pub fn validated(self) -> Result<Self, Nep413PayloadError> {
+        self.validate().map(|()| self)
+    }
+
+    /// NEP-413 serialization that gets hashed and signed:
+    /// `borsh(prefix_tag) || borsh(payload)`
     #[inline]
     pub fn prehash(&self) -> Vec<u8> {
         borsh::to_vec(&(Self::OFFCHAIN_PREFIX_TAG, self)).unwrap_or_panic_display()
     }
 }
 
+#[derive(Debug, ThisError)]
+pub enum Nep413PayloadError {
+    #[error("empty message")]
+    EmptyMessage,
+    #[error("invalid recipient: {0}")]
+    InvalidRecipient(#[from] ParseAccountError),
+}
+
 impl Payload for Nep413Payload {
     #[inline]
     fn hash(&self) -> CryptoHash {
         env::sha256_array(&self.prehash())
     }
+
+    #[inline]
+    fn prehash(&self) -> Cow<'_, [u8]> {
+        Cow::Owned(Self::prehash(self))
+    }
 }
 
 #[cfg_attr(
@@ -109,6 +143,11 @@ impl Payload for SignedNep413Payload {
     fn hash(&self) -> CryptoHash {
         self.payload.hash()
     }
+
+    #[inline]
+    fn prehash(&self) -> Cow<'_, [u8]> {
+        self.payload.prehash()
+    }
 }
 
 impl SignedPayload for SignedNep413Payload {
@@ -119,3 +158,56 @@ impl SignedPayload for SignedNep413Payload {
         Ed25519::verify(&self.signature, &self.hash(), &self.public_key)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use hex_literal::hex;
+
+    use super::*;
+
+    fn payload() -> Nep413Payload {
+        Nep413Payload::new("Hello NEAR!".to_string())
+            .with_nonce(core::array::from_fn(|i| i.try_into().unwrap()))
+            .with_recipient("intents.near")
+            .with_callback_url("https://example.com/callback".to_string())
+    }
+
+    #[test]
+    fn prehash() {
+        let payload = payload().validated().unwrap();
+
+        assert_eq!(
+            payload.prehash(),
+            hex!(
+                "9d010080"
+                "0b00000048656c6c6f204e45415221"
+                "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f"
+                "0c000000696e74656e74732e6e656172"
+                "011c00000068747470733a2f2f6578616d706c652e636f6d2f63616c6c6261636b"
+            )
+        );
+        assert_eq!(
+            payload.hash(),
+            hex!("a5ee9ffa9093425e48745c65d3ef2ce0518c9f8ec2d9bd1a8c7c1cc1565b689c")
+        );
+    }
+
+    #[test]
+    fn validate() {
+        assert!(matches!(
+            Nep413Payload::new(String::new())
+                .with_recipient("intents.near")
+                .validated(),
+            Err(Nep413PayloadError::EmptyMessage)
+        ));
+        assert!(matches!(
+            payload().with_recipient("Invalid Recipient").validated(),
+            Err(Nep413PayloadError::InvalidRecipient(_))
+        ));
+        // recipient is required
+        assert!(matches!(
+            Nep413Payload::new("Hello NEAR!".to_string()).validated(),
+            Err(Nep413PayloadError::InvalidRecipient(_))
+        ));
+    }
+}
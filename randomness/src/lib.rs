@@ -1,6 +1,9 @@
 pub use rand::prelude::SliceRandom;
 pub use rand::{CryptoRng, Rng, RngCore, SeedableRng, seq};
 
+use rand::rngs::StdRng;
+use rand_chacha::ChaCha20Rng;
+
 pub mod distributions {
     pub use rand::distr::{Alphanumeric, Distribution, StandardUniform, weighted::WeightedIndex};
     pub mod uniform {
@@ -15,10 +18,93 @@ pub mod rngs {
 
 #[must_use]
 pub fn make_true_rng() -> impl Rng + CryptoRng {
-    rand::rngs::StdRng::from_os_rng()
+    StdRng::from_os_rng()
 }
 
 #[must_use]
 pub fn make_pseudo_rng() -> impl Rng {
     rand::rngs::ThreadRng::default()
 }
+
+/// Derives `n` independent child RNGs from a single `seed`.
+///
+/// Each child is seeded with a 32-byte output of `ChaCha20` keyed with `seed`
+/// on its own stream `i`, which acts as a PRF over `(seed, i)`. Children are
+/// therefore uncorrelated with each other, but reproducible given the same
+/// `seed`.
+pub fn derive_rngs(seed: [u8; 32], n: usize) -> Vec<impl Rng + SeedableRng> {
+    let mut prf = ChaCha20Rng::from_seed(seed);
+    (0u64..)
+        .take(n)
+        .map(|i| {
+            prf.set_stream(i);
+            prf.set_word_pos(0);
+            StdRng::from_seed(prf.random())
+        })
+        .collect()
+}
+
+/// Picks one of `items` at random, with probability proportional to its
+/// weight.
+///
+/// Returns `None` if `items` is empty or all weights are zero.
+pub fn weighted_choice<'a, T>(rng: &mut impl Rng, items: &'a [(T, u64)]) -> Option<&'a T> {
+    use distributions::{Distribution, WeightedIndex};
+
+    let index = WeightedIndex::new(items.iter().map(|(_, weight)| *weight)).ok()?;
+    items.get(index.sample(rng)).map(|(item, _)| item)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weighted_choice_proportional() {
+        const DRAWS: u64 = 100_000;
+
+        let mut rng = StdRng::seed_from_u64(42);
+        let items = [("a", 1), ("b", 3), ("c", 0), ("d", 6)];
+
+        let mut counts = [0u64; 4];
+        for _ in 0..DRAWS {
+            let chosen = weighted_choice(&mut rng, &items).unwrap();
+            counts[items.iter().position(|(item, _)| item == chosen).unwrap()] += 1;
+        }
+
+        assert_eq!(counts[2], 0, "zero-weighted item was chosen");
+        for ((_, weight), count) in items.iter().zip(counts) {
+            let expected = DRAWS * weight / 10;
+            assert!(
+                count.abs_diff(expected) <= DRAWS / 100,
+                "expected ~{expected}, got {count}"
+            );
+        }
+    }
+
+    #[test]
+    fn weighted_choice_none() {
+        let mut rng = StdRng::seed_from_u64(42);
+
+        assert_eq!(weighted_choice::<()>(&mut rng, &[]), None);
+        assert_eq!(weighted_choice(&mut rng, &[("a", 0), ("b", 0)]), None);
+    }
+
+    #[test]
+    fn derive_rngs_independent_and_stable() {
+        let seed = [7; 32];
+
+        let first: Vec<u64> = derive_rngs(seed, 2)
+            .iter_mut()
+            .map(RngCore::next_u64)
+            .collect();
+        let second: Vec<u64> = derive_rngs(seed, 2)
+            .iter_mut()
+            .map(RngCore::next_u64)
+            .collect();
+
+        assert_eq!(first, second, "same seed must give same sequences");
+        assert_ne!(first[0], first[1], "children must not correlate");
+        assert_eq!(first, [2136511787233319796, 11661172988850608699]);
+    }
+}
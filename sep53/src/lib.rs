@@ -1,12 +1,18 @@
+use std::borrow::Cow;
+
 use defuse_crypto::{CryptoHash, Curve, Ed25519, Payload, SignedPayload, serde::AsCurve};
 use impl_tools::autoimpl;
 use near_sdk::{env, near};
 use serde_with::serde_as;
 
+#[cfg(any(feature = "strkey", test))]
+pub mod strkey;
+
 /// See [SEP-53](https://github.com/stellar/stellar-protocol/blob/master/ecosystem/sep-0053.md)
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[near(serializers = [json])]
 #[serde(rename_all = "snake_case")]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Sep53Payload {
     pub payload: String,
 }
@@ -23,13 +29,48 @@ impl Sep53Payload {
     }
 }
 
+#[cfg(any(feature = "signing", test))]
+impl Sep53Payload {
+    /// Signs the payload with Ed25519 secret key given as Stellar StrKey
+    /// seed, i.e. `S...`
+    pub fn sign_with_strkey(self, seed: &str) -> Result<SignedSep53Payload, StrkeyError> {
+        let stellar_strkey::Strkey::PrivateKeyEd25519(secret_key) =
+            stellar_strkey::Strkey::from_string(seed)?
+        else {
+            return Err(StrkeyError::NotEd25519Seed);
+        };
+
+        Ok(SignedSep53Payload {
+            public_key: Ed25519::public_key(&secret_key.0),
+            signature: Ed25519::sign(&secret_key.0, &self.hash()),
+            payload: self,
+        })
+    }
+}
+
+#[cfg(any(feature = "signing", test))]
+#[derive(Debug, thiserror::Error)]
+pub enum StrkeyError {
+    #[error("invalid StrKey: {0}")]
+    Decode(#[from] stellar_strkey::DecodeError),
+
+    #[error("StrKey is not an Ed25519 seed")]
+    NotEd25519Seed,
+}
+
 impl Payload for Sep53Payload {
     #[inline]
     fn hash(&self) -> CryptoHash {
         env::sha256_array(&self.prehash())
     }
+
+    #[inline]
+    fn prehash(&self) -> Cow<'_, [u8]> {
+        Cow::Owned(Self::prehash(self))
+    }
 }
 
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(
     all(feature = "abi", not(target_arch = "wasm32")),
     serde_as(schemars = true)
@@ -39,8 +80,12 @@ impl Payload for Sep53Payload {
     serde_as(schemars = false)
 )]
 #[near(serializers = [json])]
+#[cfg_attr(
+    all(feature = "abi", not(target_arch = "wasm32")),
+    schemars(example = "self::examples::signed_sep53_payload")
+)]
 #[autoimpl(Deref using self.payload)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SignedSep53Payload {
     #[serde(flatten)]
     pub payload: Sep53Payload,
@@ -56,6 +101,11 @@ impl Payload for SignedSep53Payload {
     fn hash(&self) -> CryptoHash {
         self.payload.hash()
     }
+
+    #[inline]
+    fn prehash(&self) -> Cow<'_, [u8]> {
+        self.payload.prehash()
+    }
 }
 
 impl SignedPayload for SignedSep53Payload {
@@ -67,12 +117,30 @@ impl SignedPayload for SignedSep53Payload {
     }
 }
 
+#[cfg(all(feature = "abi", not(target_arch = "wasm32")))]
+mod examples {
+    use super::*;
+
+    use hex_literal::hex;
+
+    /// SEP-53 reference test vector, see `tests::reference_test_vectors()`
+    pub fn signed_sep53_payload() -> SignedSep53Payload {
+        SignedSep53Payload {
+            payload: Sep53Payload::new("Hello, World!".to_string()),
+            public_key: hex!("6e5bb46baf172b03950ff085f4c11fc356c75a918331a98ed4839c9c7792b381"),
+            signature: hex!(
+                "7cee5d6d885752104c85eea421dfdcb95abf01f1271d11c4bec3fcbd7874dccd6e2e98b97b8eb23b643cac4073bb77de5d07b0710139180ae9f3cbba78f2ba04"
+            ),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{Sep53Payload, SignedSep53Payload};
     use base64::{Engine, engine::general_purpose::STANDARD};
-    use defuse_crypto::{Payload, SignedPayload};
-    use defuse_test_utils::random::{CryptoRng, Rng, gen_random_string, random_bytes, rng};
+    use defuse_crypto::{Ed25519, Payload, SignedPayload};
+    use defuse_test_utils::random::{CryptoRng, Rng, gen_random_string, rng};
     use defuse_test_utils::tamper::{tamper_bytes, tamper_string};
     use ed25519_dalek::Verifier;
     use ed25519_dalek::{SigningKey, ed25519::signature::SignerMut};
@@ -80,6 +148,26 @@ mod tests {
     use rstest::rstest;
     use stellar_strkey::Strkey;
 
+    #[rstest]
+    #[case("")]
+    #[case("Hello, World!")]
+    fn hash_is_digest_of_prehash(#[case] msg: &str) {
+        let payload = SignedSep53Payload {
+            payload: Sep53Payload::new(msg.to_string()),
+            public_key: [0; 32],
+            signature: [0; 64],
+        };
+
+        assert_eq!(
+            Payload::prehash(&payload),
+            Sep53Payload::prehash(&payload.payload)
+        );
+        assert_eq!(
+            payload.hash(),
+            near_sdk::env::sha256_array(&Payload::prehash(&payload))
+        );
+    }
+
     #[test]
     fn reference_test_vectors() {
         // 1) Decode the StrKey seed -> raw 32 bytes
@@ -157,47 +245,50 @@ mod tests {
         }
     }
 
-    /// Decode our test seed into a NEAR ED25519 secret + public key
-    fn make_ed25519_key(rng: &mut (impl Rng + CryptoRng)) -> near_crypto::SecretKey {
-        // We have to use dalek because near interface doesn't support making keys from bytes
-        // so we start from dalek, generate a random key, then use it in a new near_crypto key
-        let key_len = ed25519_dalek::SECRET_KEY_LENGTH;
-        let bytes = random_bytes(key_len..=key_len, rng);
-        let signing_key = SigningKey::from_bytes(&bytes.try_into().unwrap());
-        let verifying_key = signing_key.verifying_key();
+    #[rstest]
+    #[case(
+        "Hello, World!",
+        "fO5dbYhXUhBMhe6kId/cuVq/AfEnHRHEvsP8vXh03M1uLpi5e46yO2Q8rEBzu3feXQewcQE5GArp88u6ePK6BA=="
+    )]
+    #[case(
+        "こんにちは、世界！",
+        "CDU265Xs8y3OWbB/56H9jPgUss5G9A0qFuTqH2zs2YDgTm+++dIfmAEceFqB7bhfN3am59lCtDXrCtwH2k1GBA=="
+    )]
+    fn sign_with_strkey_reference_vectors(#[case] msg: &str, #[case] expected_sig_b64: &str) {
+        let signed = Sep53Payload::new(msg.to_string())
+            .sign_with_strkey("SAKICEVQLYWGSOJS4WW7HZJWAHZVEEBS527LHK5V4MLJALYKICQCJXMW")
+            .unwrap();
+
+        assert_eq!(STANDARD.encode(signed.signature), expected_sig_b64);
+        assert_eq!(signed.verify(), Some(signed.public_key));
+    }
 
-        near_crypto::SecretKey::ED25519(near_crypto::ED25519SecretKey(
-            signing_key
-                .as_bytes()
-                .iter()
-                .chain(verifying_key.as_bytes())
-                .copied()
-                .collect::<Vec<_>>()
-                .try_into()
-                .unwrap(),
-        ))
+    #[rstest]
+    #[case("GAKICEVQLYWGSOJS4WW7HZJWAHZVEEBS527LHK5V4MLJALYKICQCJXMW")]
+    #[case("not a strkey")]
+    fn sign_with_strkey_rejects_non_seed(#[case] seed: &str) {
+        Sep53Payload::new("Hello, World!".to_string())
+            .sign_with_strkey(seed)
+            .unwrap_err();
     }
 
     #[rstest]
     fn tampered_message_fails(mut rng: impl Rng + CryptoRng) {
-        let sk = make_ed25519_key(&mut rng);
-        let pk = sk.public_key();
+        let sk = rng.random();
+        let pk = Ed25519::public_key(&sk);
 
         let msg = gen_random_string(&mut rng, 100..1000);
 
         // sign the “good” message
         let payload = Sep53Payload::new(msg.clone());
         let hash = payload.hash();
-        let sig = match sk.sign(hash.as_ref()) {
-            near_crypto::Signature::ED25519(signature) => signature,
-            near_crypto::Signature::SECP256K1(_) => unreachable!(),
-        };
+        let sig = Ed25519::sign(&sk, &hash);
 
         {
             let signed_good = SignedSep53Payload {
                 payload,
-                public_key: pk.key_data().try_into().unwrap(),
-                signature: sig.to_bytes(),
+                public_key: pk,
+                signature: sig,
             };
             assert!(signed_good.verify().is_some());
         }
@@ -210,8 +301,8 @@ mod tests {
             let bad_payload = Sep53Payload::new(tempered_message);
             let signed_bad = SignedSep53Payload {
                 payload: bad_payload,
-                public_key: pk.key_data().try_into().unwrap(),
-                signature: sig.to_bytes(),
+                public_key: pk,
+                signature: sig,
             };
             assert_eq!(signed_bad.verify(), None);
         }
@@ -219,38 +310,50 @@ mod tests {
 
     #[rstest]
     fn tampered_signature_fails(mut rng: impl Rng + CryptoRng) {
-        let sk = make_ed25519_key(&mut rng);
-        let pk = sk.public_key();
+        let sk = rng.random();
+        let pk = Ed25519::public_key(&sk);
 
         let msg = gen_random_string(&mut rng, 100..1000);
 
         // sign the canonical payload
         let payload = Sep53Payload::new(msg);
         let hash = payload.hash();
-        let sig = match sk.sign(hash.as_ref()) {
-            near_crypto::Signature::ED25519(signature) => signature,
-            near_crypto::Signature::SECP256K1(_) => unreachable!(),
-        };
+        let sig = Ed25519::sign(&sk, &hash);
 
         {
             let signed_good = SignedSep53Payload {
                 payload: payload.clone(),
-                public_key: pk.key_data().try_into().unwrap(),
-                signature: sig.into(),
+                public_key: pk,
+                signature: sig,
             };
             assert!(signed_good.verify().is_some());
         }
 
         // tamper with the signature, and expect failure
         {
-            let bad_bytes = tamper_bytes(&mut rng, sig.to_bytes().as_ref(), false);
+            let bad_bytes = tamper_bytes(&mut rng, &sig, false);
 
             let signed_bad = SignedSep53Payload {
                 payload,
-                public_key: pk.key_data().try_into().unwrap(),
+                public_key: pk,
                 signature: bad_bytes.try_into().unwrap(),
             };
             assert!(signed_bad.verify().is_none());
         }
     }
+
+    #[cfg(all(feature = "abi", not(target_arch = "wasm32")))]
+    #[test]
+    fn schema_example() {
+        let example = super::examples::signed_sep53_payload();
+        let schema =
+            near_sdk::serde_json::to_value(near_sdk::schemars::schema_for!(SignedSep53Payload))
+                .unwrap();
+
+        assert_eq!(
+            schema["examples"],
+            near_sdk::serde_json::json!([near_sdk::serde_json::to_value(&example).unwrap()])
+        );
+        assert_eq!(example.verify(), Some(example.public_key));
+    }
 }
@@ -0,0 +1,105 @@
+use near_sdk::serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+use serde_with::{DeserializeAs, SerializeAs};
+use stellar_strkey::ed25519::PublicKey;
+
+/// Serializes Ed25519 public key as Stellar StrKey account address
+/// (i.e. `G...`), an alternative to the default `AsCurve<Ed25519>`
+/// representation.
+pub struct AsStrkey;
+
+impl SerializeAs<[u8; 32]> for AsStrkey {
+    fn serialize_as<S>(source: &[u8; 32], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        PublicKey(*source).to_string().serialize(serializer)
+    }
+}
+
+impl<'de> DeserializeAs<'de, [u8; 32]> for AsStrkey {
+    fn deserialize_as<D>(deserializer: D) -> Result<[u8; 32], D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = <&str as Deserialize>::deserialize(deserializer)?;
+        PublicKey::from_string(s)
+            .map(|pk| pk.0)
+            .map_err(de::Error::custom)
+    }
+}
+
+#[cfg(all(feature = "abi", not(target_arch = "wasm32")))]
+mod abi {
+    use super::*;
+
+    use near_sdk::schemars::{
+        JsonSchema,
+        r#gen::SchemaGenerator,
+        schema::{InstanceType, Schema, SchemaObject, StringValidation},
+    };
+    use serde_with::schemars_0_8::JsonSchemaAs;
+
+    impl JsonSchemaAs<[u8; 32]> for AsStrkey {
+        fn schema_name() -> String {
+            String::schema_name()
+        }
+
+        fn json_schema(_gen: &mut SchemaGenerator) -> Schema {
+            SchemaObject {
+                instance_type: Some(InstanceType::String.into()),
+                string: Some(
+                    StringValidation {
+                        pattern: Some("^G[A-Z2-7]{55}$".to_string()),
+                        ..Default::default()
+                    }
+                    .into(),
+                ),
+                ..Default::default()
+            }
+            .into()
+        }
+
+        fn is_referenceable() -> bool {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hex_literal::hex;
+    use near_sdk::serde_json;
+    use rstest::rstest;
+    use serde_with::{de::DeserializeAsWrap, ser::SerializeAsWrap};
+
+    use super::*;
+
+    /// Public key of SEP-53 reference test vector
+    const PUBLIC_KEY: [u8; 32] =
+        hex!("6e5bb46baf172b03950ff085f4c11fc356c75a918331a98ed4839c9c7792b381");
+    const PUBLIC_KEY_STRKEY: &str = "GBXFXNDLV4LSWA4VB7YIL5GBD7BVNR22SGBTDKMO2SBZZHDXSKZYCP7L";
+
+    fn deserialize(s: &str) -> serde_json::Result<[u8; 32]> {
+        // `AsStrkey` borrows from input, so deserialize from a JSON string
+        serde_json::from_str::<DeserializeAsWrap<[u8; 32], AsStrkey>>(&serde_json::to_string(s)?)
+            .map(DeserializeAsWrap::into_inner)
+    }
+
+    #[test]
+    fn roundtrip() {
+        let serialized =
+            serde_json::to_value(SerializeAsWrap::<_, AsStrkey>::new(&PUBLIC_KEY)).unwrap();
+        assert_eq!(serialized, PUBLIC_KEY_STRKEY);
+
+        assert_eq!(deserialize(PUBLIC_KEY_STRKEY).unwrap(), PUBLIC_KEY);
+    }
+
+    #[rstest]
+    #[case::seed("SAKICEVQLYWGSOJS4WW7HZJWAHZVEEBS527LHK5V4MLJALYKICQCJXMW")]
+    #[case::bad_checksum("GBXFXNDLV4LSWA4VB7YIL5GBD7BVNR22SGBTDKMO2SBZZHDXSKZYCP7A")]
+    #[case::base58("ed25519:8QnVYDk6kFLwjzhCcmnhfFJdWFnWDhsLDK3ifaAqMaMd")]
+    #[case::empty("")]
+    fn invalid(#[case] s: &str) {
+        deserialize(s).unwrap_err();
+    }
+}
@@ -79,6 +79,91 @@ impl<T> AsBase64<T> {
     }
 }
 
+/// Serializes as [`Base64`], but also accepts base58 on deserialization.
+/// Base64 is tried first: if the string is not valid base64 or decodes
+/// into bytes not convertible to `T`, it's decoded as base58.
+#[cfg(feature = "base58")]
+pub struct Base64OrBase58;
+
+#[cfg(feature = "base58")]
+impl<T> SerializeAs<T> for Base64OrBase58
+where
+    T: AsRef<[u8]>,
+{
+    #[inline]
+    fn serialize_as<S>(source: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        Base64::<Standard, Padded>::serialize_as(source, serializer)
+    }
+}
+
+#[cfg(feature = "base58")]
+impl<'de, T> DeserializeAs<'de, T> for Base64OrBase58
+where
+    T: TryFrom<Vec<u8>>,
+{
+    fn deserialize_as<D>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        use near_sdk::{
+            base64::{Engine, engine::general_purpose::STANDARD},
+            bs58,
+            serde::de,
+        };
+
+        let s = String::deserialize(deserializer)?;
+
+        if let Some(value) = STANDARD
+            .decode(&s)
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+        {
+            return Ok(value);
+        }
+
+        let bytes = bs58::decode(&s)
+            .into_vec()
+            .map_err(|_| de::Error::custom(format_args!("`{s}` is neither base64 nor base58")))?;
+        let length = bytes.len();
+        bytes.try_into().map_err(|_| {
+            de::Error::custom(format_args!(
+                "can't convert a byte vector of length {length} into the output type"
+            ))
+        })
+    }
+}
+
+/// Same as [`AsBase64`], but also accepts base58 on deserialization,
+/// see [`Base64OrBase58`]
+#[cfg(feature = "base58")]
+#[cfg_attr(
+    all(feature = "abi", not(target_arch = "wasm32")),
+    serde_as(schemars = true),
+    derive(::near_sdk::schemars::JsonSchema),
+    schemars(crate = "::near_sdk::schemars", transparent)
+)]
+#[cfg_attr(
+    not(all(feature = "abi", not(target_arch = "wasm32"))),
+    serde_as(schemars = false)
+)]
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, From)]
+#[serde(
+    crate = "::near_sdk::serde",
+    bound(serialize = "T: AsRef<[u8]>", deserialize = "T: TryFrom<Vec<u8>>")
+)]
+pub struct AsBase64OrBase58<T>(#[serde_as(as = "Base64OrBase58")] pub T);
+
+#[cfg(feature = "base58")]
+impl<T> AsBase64OrBase58<T> {
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
 #[cfg(all(feature = "abi", not(target_arch = "wasm32")))]
 mod abi {
     use super::*;
@@ -116,4 +201,56 @@ mod abi {
             false
         }
     }
+
+    #[cfg(feature = "base58")]
+    impl<T> JsonSchemaAs<T> for Base64OrBase58 {
+        fn schema_name() -> String {
+            <Base64 as JsonSchemaAs<T>>::schema_name()
+        }
+
+        fn json_schema(r#gen: &mut SchemaGenerator) -> Schema {
+            <Base64 as JsonSchemaAs<T>>::json_schema(r#gen)
+        }
+
+        fn is_referenceable() -> bool {
+            false
+        }
+    }
+}
+
+#[cfg(all(test, feature = "base58"))]
+mod tests {
+    use near_sdk::{bs58, serde_json};
+
+    use super::*;
+
+    #[test]
+    fn base64_or_base58() {
+        // 44-char base58 strings are also valid unpadded base64, but decode
+        // into 33 bytes, so base58 must still be tried as a fallback
+        for bytes in [
+            [0u8; 32],
+            [0xff; 32],
+            core::array::from_fn(|i| u8::try_from(i).unwrap()),
+        ] {
+            let base64 = serde_json::to_string(&AsBase64(bytes)).unwrap();
+            let base58 = serde_json::to_string(&bs58::encode(bytes).into_string()).unwrap();
+
+            for encoded in [&base64, &base58] {
+                let decoded: AsBase64OrBase58<[u8; 32]> = serde_json::from_str(encoded).unwrap();
+                assert_eq!(decoded.into_inner(), bytes);
+            }
+
+            assert_eq!(
+                serde_json::to_string(&AsBase64OrBase58(bytes)).unwrap(),
+                base64
+            );
+        }
+    }
+
+    #[test]
+    fn invalid() {
+        serde_json::from_str::<AsBase64OrBase58<[u8; 32]>>(r#""0OIl""#).unwrap_err();
+        serde_json::from_str::<AsBase64OrBase58<[u8; 32]>>(r#""abc""#).unwrap_err();
+    }
 }
@@ -1,3 +1,4 @@
 pub mod asserts;
+pub mod payload;
 pub mod random;
 pub mod tamper;
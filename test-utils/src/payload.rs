@@ -0,0 +1,44 @@
+use core::fmt::Debug;
+
+use defuse_crypto::{Payload, SignedPayload};
+use near_sdk::{
+    serde::{Serialize, de::DeserializeOwned},
+    serde_json,
+};
+
+/// Asserts that `signed` survives JSON round-trip and that
+/// [`verify()`](SignedPayload::verify) yields the same result for both
+/// the original and deserialized payloads
+#[track_caller]
+pub fn assert_signed_payload_roundtrip<T>(signed: &T)
+where
+    T: SignedPayload + Serialize + DeserializeOwned + PartialEq + Debug,
+    T::PublicKey: PartialEq + Debug,
+{
+    let serialized = serde_json::to_string(signed).unwrap();
+    let deserialized: T = serde_json::from_str(&serialized).unwrap();
+    assert_eq!(&deserialized, signed, "{serialized}");
+
+    let public_key = signed.verify();
+    assert_eq!(signed.verify(), public_key);
+    assert_eq!(deserialized.verify(), public_key);
+}
+
+/// Same as [`assert_signed_payload_roundtrip`] for types without
+/// [`PartialEq`], comparing their JSON representations instead.
+/// Additionally asserts that `signed` has a valid signature.
+#[track_caller]
+pub fn assert_signed_payload_json_roundtrip<T>(signed: &T)
+where
+    T: SignedPayload + Serialize + DeserializeOwned,
+    T::PublicKey: PartialEq + Debug,
+{
+    let serialized = serde_json::to_value(signed).unwrap();
+    let deserialized: T = serde_json::from_value(serialized.clone()).unwrap();
+    assert_eq!(serde_json::to_value(&deserialized).unwrap(), serialized);
+    assert_eq!(deserialized.hash(), signed.hash(), "{serialized}");
+
+    let public_key = signed.verify();
+    assert!(public_key.is_some(), "invalid signature: {serialized}");
+    assert_eq!(deserialized.verify(), public_key);
+}
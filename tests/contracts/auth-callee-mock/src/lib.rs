@@ -0,0 +1,25 @@
+use defuse_auth_call::{AuthCallee, AuthValueCallee};
+use near_sdk::{AccountId, PromiseOrValue, near};
+
+/// Mock implementation of [`AuthCallee`] and [`AuthValueCallee`] used in
+/// tests. It doesn't whitelist callers, so it MUST NOT be used in production.
+#[near(contract_state)]
+#[derive(Default)]
+pub struct Contract;
+
+#[near]
+impl AuthCallee for Contract {
+    #[payable]
+    fn on_auth(&mut self, _signer_id: AccountId, _msg: String) -> PromiseOrValue<()> {
+        PromiseOrValue::Value(())
+    }
+}
+
+#[near]
+impl AuthValueCallee for Contract {
+    /// Returns `<signer_id>:<msg>`
+    #[payable]
+    fn on_auth_value(&mut self, signer_id: AccountId, msg: String) -> PromiseOrValue<String> {
+        PromiseOrValue::Value(format!("{signer_id}:{msg}"))
+    }
+}
@@ -0,0 +1,43 @@
+use near_contract_standards::fungible_token::receiver::FungibleTokenReceiver;
+use near_sdk::{AccountId, Promise, PromiseOrValue, env, json_types::U128, near};
+
+/// Mock implementation of [`FungibleTokenReceiver`] used in tests.
+#[near(contract_state)]
+#[derive(Default)]
+pub struct Contract;
+
+#[near]
+impl FungibleTokenReceiver for Contract {
+    /// Keeps the amount given in `msg` and returns the rest as unused.
+    /// Keeps everything if `msg` is not a valid amount.
+    fn ft_on_transfer(
+        &mut self,
+        _sender_id: AccountId,
+        amount: U128,
+        msg: String,
+    ) -> PromiseOrValue<U128> {
+        let used = msg.parse().unwrap_or(amount.0);
+        PromiseOrValue::Value(U128(amount.0.saturating_sub(used)))
+    }
+}
+
+#[near]
+impl Contract {
+    /// Wraps attached NEAR and transfers it to `receiver_id` via
+    /// [`defuse_wnear::near_deposit_and_transfer_call`]. This account
+    /// must be registered on `wnear_id` beforehand.
+    #[payable]
+    pub fn near_deposit_and_transfer_call(
+        &mut self,
+        wnear_id: AccountId,
+        receiver_id: AccountId,
+        msg: String,
+    ) -> Promise {
+        defuse_wnear::near_deposit_and_transfer_call(
+            wnear_id,
+            env::attached_deposit(),
+            &receiver_id,
+            &msg,
+        )
+    }
+}
@@ -1,14 +1,20 @@
-use std::time::Duration;
+use std::{borrow::Cow, time::Duration};
 
 use arbitrary::Unstructured;
 use defuse::core::{
-    Deadline, DefuseError,
+    Deadline, DefuseError, Nonce,
+    accounts::{AccountEvent, NonceEvent},
     amounts::Amounts,
-    intents::{DefuseIntents, account::SetAuthByPredecessorId, tokens::Transfer},
+    events::DefuseEvent,
+    intents::{
+        DefuseIntents, Intent, IntentEvent, account::SetAuthByPredecessorId, tokens::Transfer,
+    },
     token_id::{TokenId, nep141::Nep141TokenId},
 };
 use defuse_test_utils::{asserts::ResultAssertsExt, random::random_bytes};
+use near_sdk::NearToken;
 use rstest::rstest;
+use serde_json::json;
 
 use crate::{
     tests::defuse::{
@@ -197,3 +203,109 @@ async fn test(random_bytes: Vec<u8>) {
         );
     }
 }
+
+#[tokio::test]
+#[rstest]
+async fn execute_intents_by_predecessor() {
+    let env = Env::new().await;
+
+    let ft1: TokenId = Nep141TokenId::new(env.ft1.clone()).into();
+    env.defuse_ft_deposit_to(&env.ft1, 1000, env.user1.id())
+        .await
+        .unwrap();
+
+    let intents = DefuseIntents {
+        intents: [Transfer {
+            receiver_id: env.user2.id().clone(),
+            tokens: Amounts::new([(ft1.clone(), 300)].into()),
+            memo: None,
+        }
+        .into()]
+        .into(),
+    };
+
+    // no signed payload, authenticated by PREDECESSOR_ID only
+    let result = env
+        .user1
+        .call(env.defuse.id(), "execute_intents_by_predecessor")
+        .deposit(NearToken::from_yoctonear(1))
+        .args_json(json!({
+            "intents": intents,
+        }))
+        .max_gas()
+        .transact()
+        .await
+        .unwrap()
+        .into_result()
+        .unwrap();
+
+    // intent_hash can be computed off-chain
+    let Intent::Transfer(transfer) = intents.intents[0].clone() else {
+        unreachable!()
+    };
+    let expected = DefuseEvent::Transfer(Cow::Owned(vec![IntentEvent::new(
+        AccountEvent::new(env.user1.id().clone(), Cow::Owned(transfer)),
+        intents.hash().unwrap(),
+    )]))
+    .to_json_event_string();
+    assert!(
+        result.logs().contains(&expected.as_str()),
+        "{expected} not found in {:#?}",
+        result.logs()
+    );
+
+    // no nonce is committed, so it's reported as zero
+    let expected = DefuseEvent::IntentsExecuted(Cow::Owned(vec![IntentEvent::new(
+        AccountEvent::new(env.user1.id().clone(), NonceEvent::new(Nonce::default())),
+        intents.hash().unwrap(),
+    )]))
+    .to_json_event_string();
+    assert!(
+        result.logs().contains(&expected.as_str()),
+        "{expected} not found in {:#?}",
+        result.logs()
+    );
+
+    assert_eq!(
+        env.defuse
+            .mt_balance_of(env.user1.id(), &ft1.to_string())
+            .await
+            .unwrap(),
+        700
+    );
+    assert_eq!(
+        env.defuse
+            .mt_balance_of(env.user2.id(), &ft1.to_string())
+            .await
+            .unwrap(),
+        300
+    );
+
+    env.user1
+        .disable_auth_by_predecessor_id(env.defuse.id())
+        .await
+        .unwrap();
+
+    env.user1
+        .call(env.defuse.id(), "execute_intents_by_predecessor")
+        .deposit(NearToken::from_yoctonear(1))
+        .args_json(json!({
+            "intents": intents,
+        }))
+        .max_gas()
+        .transact()
+        .await
+        .unwrap()
+        .into_result()
+        .assert_err_contains(
+            DefuseError::AuthByPredecessorIdDisabled(env.user1.id().clone()).to_string(),
+        );
+
+    assert_eq!(
+        env.defuse
+            .mt_balance_of(env.user1.id(), &ft1.to_string())
+            .await
+            .unwrap(),
+        700
+    );
+}
@@ -0,0 +1,66 @@
+use std::collections::HashMap;
+
+use defuse::accounts::AccountSummary;
+use near_sdk::AccountId;
+use near_workspaces::Account;
+use rstest::rstest;
+use serde_json::json;
+
+use crate::tests::defuse::{accounts::AccountManagerExt, env::Env};
+
+async fn export_accounts(
+    viewer: &Account,
+    defuse_contract_id: &AccountId,
+    from_index: usize,
+    limit: u32,
+) -> anyhow::Result<Vec<(AccountId, AccountSummary)>> {
+    viewer
+        .view(defuse_contract_id, "export_accounts")
+        .args_json(json!({
+            "from_index": from_index,
+            "limit": limit,
+        }))
+        .await?
+        .json()
+        .map_err(Into::into)
+}
+
+#[tokio::test]
+#[rstest]
+async fn export_accounts_paginated(#[values(1, 2, 5)] limit: u32) {
+    let env = Env::new().await;
+
+    for user in [&env.user1, &env.user2, &env.user3] {
+        env.defuse_ft_deposit_to(&env.ft1, 1000, user.id())
+            .await
+            .unwrap();
+    }
+    env.user3
+        .disable_auth_by_predecessor_id(env.defuse.id())
+        .await
+        .unwrap();
+
+    let mut paged = Vec::new();
+    loop {
+        let page = export_accounts(&env.user1, env.defuse.id(), paged.len(), limit)
+            .await
+            .unwrap();
+        assert!(page.len() <= limit.try_into().unwrap());
+        if page.is_empty() {
+            break;
+        }
+        paged.extend(page);
+    }
+
+    let exported: HashMap<_, _> = paged.iter().cloned().collect();
+    assert_eq!(exported.len(), paged.len(), "duplicate accounts exported");
+
+    for user in [&env.user1, &env.user2, &env.user3] {
+        let summary = exported[user.id()];
+        assert!(!summary.locked);
+        assert_eq!(
+            summary.auth_by_predecessor_id_enabled,
+            user.id() != env.user3.id()
+        );
+    }
+}
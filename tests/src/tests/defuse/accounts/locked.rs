@@ -5,14 +5,19 @@ use defuse::{
     contract::Role,
     core::{
         Deadline, DefuseError, Nonce,
+        accounts::AccountEvent,
         crypto::PublicKey,
-        intents::DefuseIntents,
+        events::DefuseEvent,
+        intents::{DefuseIntents, tokens::FtWithdraw},
         token_id::{TokenId, nep141::Nep141TokenId},
     },
 };
 
 use defuse_test_utils::{asserts::ResultAssertsExt, random::random_bytes};
+use near_sdk::NearToken;
+use near_workspaces::Account;
 use rstest::rstest;
+use serde_json::json;
 
 use crate::{
     tests::defuse::{
@@ -121,7 +126,9 @@ async fn test_lock_account(random_bytes: Vec<u8>) {
         locked_account
             .add_public_key(env.defuse.id(), pk)
             .await
-            .assert_err_contains(DefuseError::AccountLocked(env.user1.id().clone()).to_string());
+            .assert_err_contains(
+                DefuseError::AccountLocked(env.user1.id().clone(), None).to_string(),
+            );
         assert!(
             !env.defuse
                 .has_public_key(locked_account.id(), &pk)
@@ -141,7 +148,9 @@ async fn test_lock_account(random_bytes: Vec<u8>) {
         locked_account
             .remove_public_key(env.defuse.id(), locked_pk)
             .await
-            .assert_err_contains(DefuseError::AccountLocked(env.user1.id().clone()).to_string());
+            .assert_err_contains(
+                DefuseError::AccountLocked(env.user1.id().clone(), None).to_string(),
+            );
         assert!(
             env.defuse
                 .has_public_key(locked_account.id(), &locked_pk)
@@ -290,7 +299,7 @@ async fn test_lock_account(random_bytes: Vec<u8>) {
             )])
             .await
             .assert_err_contains(
-                DefuseError::AccountLocked(locked_account.id().clone()).to_string(),
+                DefuseError::AccountLocked(locked_account.id().clone(), None).to_string(),
             );
 
         assert!(
@@ -373,3 +382,222 @@ async fn test_lock_account(random_bytes: Vec<u8>) {
         );
     }
 }
+
+#[tokio::test]
+#[rstest]
+async fn test_lock_account_with_reason(random_bytes: Vec<u8>) {
+    const REASON: &str = "suspicious activity";
+
+    let mut u = Unstructured::new(&random_bytes);
+
+    let env = Env::builder().deployer_as_super_admin().build().await;
+
+    let locked_account = &env.user1;
+    let account_locker = &env.user2;
+
+    for role in [
+        Role::UnrestrictedAccountLocker,
+        Role::UnrestrictedAccountUnlocker,
+    ] {
+        env.acl_grant_role(env.defuse.id(), role, account_locker.id())
+            .await
+            .unwrap();
+    }
+
+    assert!(
+        account_locker
+            .force_lock_account_with_reason(env.defuse.id(), locked_account.id(), Some(REASON))
+            .await
+            .unwrap()
+    );
+
+    locked_account
+        .add_public_key(env.defuse.id(), u.arbitrary().unwrap())
+        .await
+        .assert_err_contains(
+            DefuseError::AccountLocked(locked_account.id().clone(), Some(REASON.to_string()))
+                .to_string(),
+        );
+
+    // reason is cleared on unlock
+    assert!(
+        account_locker
+            .force_unlock_account(env.defuse.id(), locked_account.id())
+            .await
+            .unwrap()
+    );
+    assert!(
+        account_locker
+            .force_lock_account(env.defuse.id(), locked_account.id())
+            .await
+            .unwrap()
+    );
+    locked_account
+        .add_public_key(env.defuse.id(), u.arbitrary().unwrap())
+        .await
+        .assert_err_contains(
+            DefuseError::AccountLocked(locked_account.id().clone(), None).to_string(),
+        );
+}
+
+#[tokio::test]
+#[rstest]
+async fn test_lock_not_yet_existing_account(random_bytes: Vec<u8>) {
+    let mut u = Unstructured::new(&random_bytes);
+
+    let env = Env::builder().deployer_as_super_admin().build().await;
+
+    let account_locker = &env.user2;
+    for role in [
+        Role::UnrestrictedAccountLocker,
+        Role::UnrestrictedAccountUnlocker,
+    ] {
+        env.acl_grant_role(env.defuse.id(), role, account_locker.id())
+            .await
+            .unwrap();
+    }
+
+    // implicit account, which can sign intents without adding public keys
+    let implicit_pk = PublicKey::Ed25519(
+        env.user1
+            .secret_key()
+            .public_key()
+            .key_data()
+            .try_into()
+            .unwrap(),
+    );
+    let locked_account = Account::from_secret_key(
+        implicit_pk.to_implicit_account_id(),
+        env.user1.secret_key().clone(),
+        env.sandbox().worker(),
+    );
+
+    // pre-create the account in locked state
+    assert!(
+        account_locker
+            .force_lock_account(env.defuse.id(), locked_account.id())
+            .await
+            .unwrap()
+    );
+    assert!(
+        env.is_account_locked(env.defuse.id(), locked_account.id())
+            .await
+            .unwrap()
+    );
+
+    let ft1: TokenId = Nep141TokenId::new(env.ft1.clone()).into();
+    env.defuse_ft_deposit_to(&env.ft1, 1000, locked_account.id())
+        .await
+        .expect("deposits to locked account should be allowed");
+
+    let withdraw = |nonce| {
+        locked_account.sign_defuse_message(
+            SigningStandard::default(),
+            env.defuse.id(),
+            nonce,
+            Deadline::MAX,
+            DefuseIntents {
+                intents: [FtWithdraw {
+                    token: env.ft1.clone(),
+                    receiver_id: env.user3.id().clone(),
+                    amount: 1000.into(),
+                    memo: None,
+                    msg: None,
+                    storage_deposit: None,
+                    min_gas: None,
+                }
+                .into()]
+                .into(),
+            },
+        )
+    };
+
+    env.defuse
+        .execute_intents([withdraw(u.arbitrary().unwrap())])
+        .await
+        .assert_err_contains(
+            DefuseError::AccountLocked(locked_account.id().clone(), None).to_string(),
+        );
+    assert_eq!(
+        env.defuse
+            .mt_balance_of(locked_account.id(), &ft1.to_string())
+            .await
+            .unwrap(),
+        1000
+    );
+
+    assert!(
+        account_locker
+            .force_unlock_account(env.defuse.id(), locked_account.id())
+            .await
+            .unwrap()
+    );
+
+    env.defuse
+        .execute_intents([withdraw(u.arbitrary().unwrap())])
+        .await
+        .unwrap();
+    assert_eq!(
+        env.defuse
+            .mt_balance_of(locked_account.id(), &ft1.to_string())
+            .await
+            .unwrap(),
+        0
+    );
+}
+
+#[tokio::test]
+#[rstest]
+async fn test_lock_unlock_events() {
+    let env = Env::builder().deployer_as_super_admin().build().await;
+
+    let account_locker = &env.user2;
+    let locked_account = &env.user1;
+    for role in [
+        Role::UnrestrictedAccountLocker,
+        Role::UnrestrictedAccountUnlocker,
+    ] {
+        env.acl_grant_role(env.defuse.id(), role, account_locker.id())
+            .await
+            .unwrap();
+    }
+
+    for (method, event, locked) in [
+        (
+            "force_lock_account",
+            DefuseEvent::AccountLocked(AccountEvent::new(locked_account.id(), ())),
+            true,
+        ),
+        (
+            "force_unlock_account",
+            DefuseEvent::AccountUnlocked(AccountEvent::new(locked_account.id(), ())),
+            false,
+        ),
+    ] {
+        let result = account_locker
+            .call(env.defuse.id(), method)
+            .args_json(json!({
+                "account_id": locked_account.id(),
+            }))
+            .deposit(NearToken::from_yoctonear(1))
+            .max_gas()
+            .transact()
+            .await
+            .unwrap()
+            .into_result()
+            .unwrap();
+
+        let expected = event.to_json_event_string();
+        assert!(
+            result.logs().contains(&expected.as_str()),
+            "{expected} not found in {:#?}",
+            result.logs()
+        );
+        assert_eq!(
+            env.is_account_locked(env.defuse.id(), locked_account.id())
+                .await
+                .unwrap(),
+            locked
+        );
+    }
+}
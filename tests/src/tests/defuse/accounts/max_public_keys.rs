@@ -0,0 +1,114 @@
+use arbitrary::Unstructured;
+use defuse::core::{DefuseError, crypto::PublicKey};
+use defuse_test_utils::{asserts::ResultAssertsExt, random::random_bytes};
+use near_sdk::NearToken;
+use near_workspaces::Account;
+use rstest::rstest;
+
+use crate::tests::defuse::{accounts::AccountManagerExt, env::Env};
+
+#[tokio::test]
+#[rstest]
+async fn max_public_keys(random_bytes: Vec<u8>) {
+    let mut u = Unstructured::new(&random_bytes);
+    // user1 already has its own public key registered by `Env`
+    let env = Env::builder().max_public_keys(3).build().await;
+
+    let [pk1, pk2, pk3]: [PublicKey; 3] = u.arbitrary().unwrap();
+
+    assert!(
+        env.user1
+            .add_public_key(env.defuse.id(), pk1)
+            .await
+            .unwrap()
+    );
+    assert!(
+        env.user1
+            .add_public_key(env.defuse.id(), pk2)
+            .await
+            .unwrap()
+    );
+
+    // existing key is reported as such, even when the limit is reached
+    assert!(
+        !env.user1
+            .add_public_key(env.defuse.id(), pk2)
+            .await
+            .unwrap()
+    );
+
+    env.user1
+        .add_public_key(env.defuse.id(), pk3)
+        .await
+        .assert_err_contains(DefuseError::TooManyPublicKeys(env.user1.id().clone(), 3).to_string());
+    assert!(
+        !env.defuse
+            .has_public_key(env.user1.id(), &pk3)
+            .await
+            .unwrap()
+    );
+
+    // removing a key frees up a slot
+    assert!(
+        env.user1
+            .remove_public_key(env.defuse.id(), pk1)
+            .await
+            .unwrap()
+    );
+    assert!(
+        env.user1
+            .add_public_key(env.defuse.id(), pk3)
+            .await
+            .unwrap()
+    );
+    assert!(
+        env.defuse
+            .has_public_key(env.user1.id(), &pk3)
+            .await
+            .unwrap()
+    );
+}
+
+#[tokio::test]
+#[rstest]
+async fn max_public_keys_ignores_implicit(random_bytes: Vec<u8>) {
+    let mut u = Unstructured::new(&random_bytes);
+    let env = Env::builder().max_public_keys(1).build().await;
+
+    let implicit_pk = PublicKey::Ed25519(
+        env.user1
+            .secret_key()
+            .public_key()
+            .key_data()
+            .try_into()
+            .unwrap(),
+    );
+    let implicit_account = Account::from_secret_key(
+        implicit_pk.to_implicit_account_id(),
+        env.user1.secret_key().clone(),
+        env.sandbox().worker(),
+    );
+    env.transfer_near(implicit_account.id(), NearToken::from_near(1))
+        .await
+        .unwrap()
+        .unwrap();
+
+    let [pk1, pk2]: [PublicKey; 2] = u.arbitrary().unwrap();
+
+    implicit_account
+        .add_public_key(env.defuse.id(), pk1)
+        .await
+        .unwrap();
+    implicit_account
+        .add_public_key(env.defuse.id(), pk2)
+        .await
+        .assert_err_contains(
+            DefuseError::TooManyPublicKeys(implicit_account.id().clone(), 1).to_string(),
+        );
+    assert!(
+        env.defuse
+            .has_public_key(implicit_account.id(), &implicit_pk)
+            .await
+            .unwrap()
+    );
+}
@@ -1,6 +1,10 @@
 mod auth_by_predecessor_id;
+mod export;
 mod locked;
+mod max_public_keys;
 mod nonces;
+mod public_keys;
+mod rotate_public_key;
 mod traits;
 
 use defuse::core::{Nonce, crypto::PublicKey};
@@ -15,13 +19,13 @@ pub trait AccountManagerExt {
         &self,
         defuse_contract_id: &AccountId,
         public_key: PublicKey,
-    ) -> anyhow::Result<()>;
+    ) -> anyhow::Result<bool>;
 
     async fn remove_public_key(
         &self,
         defuse_contract_id: &AccountId,
         public_key: PublicKey,
-    ) -> anyhow::Result<()>;
+    ) -> anyhow::Result<bool>;
 
     async fn cleanup_expired_nonces(
         &self,
@@ -43,6 +47,19 @@ pub trait AccountManagerExt {
 
     async fn is_nonce_used(&self, account_id: &AccountId, nonce: &Nonce) -> anyhow::Result<bool>;
 
+    async fn are_nonces_used(
+        &self,
+        account_id: &AccountId,
+        nonces: &[Nonce],
+    ) -> anyhow::Result<Vec<bool>>;
+
+    async fn used_nonces_of(
+        &self,
+        account_id: &AccountId,
+        from_index: Option<u32>,
+        limit: Option<u32>,
+    ) -> anyhow::Result<Vec<Nonce>>;
+
     async fn is_auth_by_predecessor_id_enabled(
         &self,
         account_id: &AccountIdRef,
@@ -59,8 +76,7 @@ impl AccountManagerExt for near_workspaces::Account {
         &self,
         defuse_contract_id: &AccountId,
         public_key: PublicKey,
-    ) -> anyhow::Result<()> {
-        // TODO: check bool output
+    ) -> anyhow::Result<bool> {
         self.call(defuse_contract_id, "add_public_key")
             .deposit(NearToken::from_yoctonear(1))
             .args_json(json!({
@@ -69,15 +85,16 @@ impl AccountManagerExt for near_workspaces::Account {
             .max_gas()
             .transact()
             .await?
-            .into_result()?;
-        Ok(())
+            .into_result()?
+            .json()
+            .map_err(Into::into)
     }
 
     async fn remove_public_key(
         &self,
         defuse_contract_id: &AccountId,
         public_key: PublicKey,
-    ) -> anyhow::Result<()> {
+    ) -> anyhow::Result<bool> {
         self.call(defuse_contract_id, "remove_public_key")
             .deposit(NearToken::from_yoctonear(1))
             .args_json(json!({
@@ -86,8 +103,9 @@ impl AccountManagerExt for near_workspaces::Account {
             .max_gas()
             .transact()
             .await?
-            .into_result()?;
-        Ok(())
+            .into_result()?
+            .json()
+            .map_err(Into::into)
     }
 
     async fn cleanup_expired_nonces(
@@ -153,6 +171,39 @@ impl AccountManagerExt for near_workspaces::Account {
             .map_err(Into::into)
     }
 
+    async fn are_nonces_used(
+        &self,
+        account_id: &AccountId,
+        nonces: &[Nonce],
+    ) -> anyhow::Result<Vec<bool>> {
+        self.view(self.id(), "are_nonces_used")
+            .args_json(json!({
+                "account_id": account_id,
+                "nonces": nonces.iter().map(AsBase64).collect::<Vec<_>>(),
+            }))
+            .await?
+            .json()
+            .map_err(Into::into)
+    }
+
+    async fn used_nonces_of(
+        &self,
+        account_id: &AccountId,
+        from_index: Option<u32>,
+        limit: Option<u32>,
+    ) -> anyhow::Result<Vec<Nonce>> {
+        self.view(self.id(), "used_nonces_of")
+            .args_json(json!({
+                "account_id": account_id,
+                "from_index": from_index,
+                "limit": limit,
+            }))
+            .await?
+            .json::<Vec<AsBase64<Nonce>>>()
+            .map(|nonces| nonces.into_iter().map(AsBase64::into_inner).collect())
+            .map_err(Into::into)
+    }
+
     async fn is_auth_by_predecessor_id_enabled(
         &self,
         account_id: &AccountIdRef,
@@ -185,7 +236,7 @@ impl AccountManagerExt for near_workspaces::Contract {
         &self,
         defuse_contract_id: &AccountId,
         public_key: PublicKey,
-    ) -> anyhow::Result<()> {
+    ) -> anyhow::Result<bool> {
         self.as_account()
             .add_public_key(defuse_contract_id, public_key)
             .await
@@ -195,7 +246,7 @@ impl AccountManagerExt for near_workspaces::Contract {
         &self,
         defuse_contract_id: &AccountId,
         public_key: PublicKey,
-    ) -> anyhow::Result<()> {
+    ) -> anyhow::Result<bool> {
         self.as_account()
             .remove_public_key(defuse_contract_id, public_key)
             .await
@@ -233,6 +284,25 @@ impl AccountManagerExt for near_workspaces::Contract {
         self.as_account().is_nonce_used(account_id, nonce).await
     }
 
+    async fn are_nonces_used(
+        &self,
+        account_id: &AccountId,
+        nonces: &[Nonce],
+    ) -> anyhow::Result<Vec<bool>> {
+        self.as_account().are_nonces_used(account_id, nonces).await
+    }
+
+    async fn used_nonces_of(
+        &self,
+        account_id: &AccountId,
+        from_index: Option<u32>,
+        limit: Option<u32>,
+    ) -> anyhow::Result<Vec<Nonce>> {
+        self.as_account()
+            .used_nonces_of(account_id, from_index, limit)
+            .await
+    }
+
     async fn is_auth_by_predecessor_id_enabled(
         &self,
         account_id: &AccountIdRef,
@@ -1,6 +1,6 @@
 use arbitrary::{Arbitrary, Unstructured};
 use chrono::{TimeDelta, Utc};
-use defuse::core::{Deadline, ExpirableNonce, intents::DefuseIntents};
+use defuse::core::{Deadline, DefuseError, ExpirableNonce, Nonce, intents::DefuseIntents};
 use itertools::Itertools;
 
 use std::time::Duration;
@@ -18,6 +18,34 @@ use crate::tests::defuse::{
     intents::ExecuteIntentsExt,
 };
 
+#[tokio::test]
+#[rstest]
+async fn replayed_intent_rejected(#[notrace] mut rng: impl Rng) {
+    let env = Env::new().await;
+    let nonce = rng.random();
+
+    let signed = env.user1.sign_defuse_message(
+        SigningStandard::arbitrary(&mut Unstructured::new(&rng.random::<[u8; 1]>())).unwrap(),
+        env.defuse.id(),
+        nonce,
+        Deadline::MAX,
+        DefuseIntents { intents: [].into() },
+    );
+
+    env.defuse.execute_intents([signed.clone()]).await.unwrap();
+
+    env.defuse
+        .execute_intents([signed])
+        .await
+        .assert_err_contains(
+            DefuseError::NonceUsed {
+                account_id: env.user1.id().clone(),
+                nonce,
+            }
+            .to_string(),
+        );
+}
+
 #[tokio::test]
 #[rstest]
 async fn test_commit_nonces(#[notrace] mut rng: impl Rng) {
@@ -60,7 +88,10 @@ async fn test_commit_nonces(#[notrace] mut rng: impl Rng) {
             DefuseIntents { intents: [].into() },
         )])
         .await
-        .assert_err_contains("deadline has expired");
+        .assert_err_contains(format!(
+            "deadline '{}' has expired at",
+            deadline.into_timestamp()
+        ));
 
     // deadline is greater than nonce
     let deadline = Deadline::new(current_timestamp.checked_add_signed(timeout_delta).unwrap());
@@ -233,3 +264,146 @@ async fn cleanup_multiple_nonces(
         gas_used.total_gas_burnt(),
     );
 }
+
+#[tokio::test]
+#[rstest]
+async fn is_nonce_used_base64_or_base58(#[notrace] mut rng: impl Rng) {
+    use near_sdk::{
+        base64::{Engine, engine::general_purpose::STANDARD},
+        bs58,
+    };
+    use serde_json::json;
+
+    let env = Env::new().await;
+    let used_nonce: Nonce = rng.random();
+    let unused_nonce: Nonce = rng.random();
+
+    env.defuse
+        .execute_intents([env.user1.sign_defuse_message(
+            SigningStandard::default(),
+            env.defuse.id(),
+            used_nonce,
+            Deadline::MAX,
+            DefuseIntents { intents: [].into() },
+        )])
+        .await
+        .unwrap();
+
+    for (nonce, used) in [(used_nonce, true), (unused_nonce, false)] {
+        for encoded in [STANDARD.encode(nonce), bs58::encode(nonce).into_string()] {
+            let is_used: bool = env
+                .defuse
+                .view("is_nonce_used")
+                .args_json(json!({
+                    "account_id": env.user1.id(),
+                    "nonce": encoded,
+                }))
+                .await
+                .unwrap()
+                .json()
+                .unwrap();
+            assert_eq!(is_used, used, "{encoded}");
+        }
+    }
+}
+
+#[tokio::test]
+#[rstest]
+async fn are_nonces_used(#[notrace] mut rng: impl Rng) {
+    let env = Env::new().await;
+
+    let nonces: [Nonce; 4] = rng.random();
+    // commit every other nonce
+    env.defuse
+        .execute_intents(nonces.iter().step_by(2).map(|&nonce| {
+            env.user1.sign_defuse_message(
+                SigningStandard::default(),
+                env.defuse.id(),
+                nonce,
+                Deadline::MAX,
+                DefuseIntents { intents: [].into() },
+            )
+        }))
+        .await
+        .unwrap();
+
+    let used = env
+        .defuse
+        .are_nonces_used(env.user1.id(), &nonces)
+        .await
+        .unwrap();
+    assert_eq!(used, [true, false, true, false]);
+
+    for (nonce, used) in nonces.iter().zip(used) {
+        assert_eq!(
+            env.defuse
+                .is_nonce_used(env.user1.id(), nonce)
+                .await
+                .unwrap(),
+            used,
+        );
+    }
+
+    // nonces are tracked per account
+    assert_eq!(
+        env.defuse
+            .are_nonces_used(env.user2.id(), &nonces)
+            .await
+            .unwrap(),
+        [false; 4],
+    );
+}
+
+#[tokio::test]
+#[rstest]
+async fn used_nonces_of(#[notrace] mut rng: impl Rng) {
+    let env = Env::new().await;
+
+    let nonces: [Nonce; 5] = rng.random();
+    env.defuse
+        .execute_intents(nonces.iter().map(|&nonce| {
+            env.user1.sign_defuse_message(
+                SigningStandard::default(),
+                env.defuse.id(),
+                nonce,
+                Deadline::MAX,
+                DefuseIntents { intents: [].into() },
+            )
+        }))
+        .await
+        .unwrap();
+
+    let used = env
+        .defuse
+        .used_nonces_of(env.user1.id(), None, None)
+        .await
+        .unwrap();
+    assert_eq!(
+        used.iter().sorted().collect_vec(),
+        nonces.iter().sorted().collect_vec(),
+    );
+
+    // paginated in the same order
+    let mut paged = Vec::new();
+    for from_index in (0..nonces.len()).step_by(2) {
+        paged.extend(
+            env.defuse
+                .used_nonces_of(
+                    env.user1.id(),
+                    Some(from_index.try_into().unwrap()),
+                    Some(2),
+                )
+                .await
+                .unwrap(),
+        );
+    }
+    assert_eq!(paged, used);
+
+    assert!(
+        env.defuse
+            .used_nonces_of(env.user2.id(), None, None)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
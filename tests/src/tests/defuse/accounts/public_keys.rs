@@ -0,0 +1,111 @@
+use std::collections::HashSet;
+
+use arbitrary::Unstructured;
+use defuse::core::crypto::PublicKey;
+use defuse_test_utils::random::random_bytes;
+use near_sdk::NearToken;
+use near_workspaces::Account;
+use rstest::rstest;
+use serde_json::json;
+
+use crate::tests::defuse::{accounts::AccountManagerExt, env::Env};
+
+#[tokio::test]
+#[rstest]
+async fn public_keys_of_paged(random_bytes: Vec<u8>, #[values(1, 2, 5)] limit: u32) {
+    let mut u = Unstructured::new(&random_bytes);
+    let env = Env::new().await;
+
+    let implicit_pk = PublicKey::Ed25519(
+        env.user1
+            .secret_key()
+            .public_key()
+            .key_data()
+            .try_into()
+            .unwrap(),
+    );
+    let implicit_account = Account::from_secret_key(
+        implicit_pk.to_implicit_account_id(),
+        env.user1.secret_key().clone(),
+        env.sandbox().worker(),
+    );
+    env.transfer_near(implicit_account.id(), NearToken::from_near(1))
+        .await
+        .unwrap()
+        .unwrap();
+
+    let added: [PublicKey; 4] = u.arbitrary().unwrap();
+    for pk in added {
+        implicit_account
+            .add_public_key(env.defuse.id(), pk)
+            .await
+            .unwrap();
+    }
+
+    let all: HashSet<PublicKey> = env
+        .defuse
+        .view("public_keys_of")
+        .args_json(json!({
+            "account_id": implicit_account.id(),
+        }))
+        .await
+        .unwrap()
+        .json()
+        .unwrap();
+    assert_eq!(all, added.into_iter().chain([implicit_pk]).collect());
+
+    let mut paged = Vec::new();
+    loop {
+        let page: Vec<PublicKey> = env
+            .defuse
+            .view("public_keys_of_paged")
+            .args_json(json!({
+                "account_id": implicit_account.id(),
+                "from_index": paged.len(),
+                "limit": limit,
+            }))
+            .await
+            .unwrap()
+            .json()
+            .unwrap();
+        assert!(page.len() <= limit.try_into().unwrap());
+        if page.is_empty() {
+            break;
+        }
+        paged.extend(page);
+    }
+
+    // implicit public key goes last
+    assert_eq!(paged.last(), Some(&implicit_pk));
+    assert_eq!(paged.len(), all.len());
+    assert_eq!(paged.into_iter().collect::<HashSet<_>>(), all);
+}
+
+#[tokio::test]
+#[rstest]
+async fn add_remove_public_key_returns_changed(random_bytes: Vec<u8>) {
+    let mut u = Unstructured::new(&random_bytes);
+    let env = Env::new().await;
+
+    let pk: PublicKey = u.arbitrary().unwrap();
+
+    assert!(env.user1.add_public_key(env.defuse.id(), pk).await.unwrap());
+    assert!(
+        !env.user1.add_public_key(env.defuse.id(), pk).await.unwrap(),
+        "duplicate key shouldn't be reported as added"
+    );
+
+    assert!(
+        env.user1
+            .remove_public_key(env.defuse.id(), pk)
+            .await
+            .unwrap()
+    );
+    assert!(
+        !env.user1
+            .remove_public_key(env.defuse.id(), pk)
+            .await
+            .unwrap(),
+        "missing key shouldn't be reported as removed"
+    );
+}
@@ -0,0 +1,125 @@
+use arbitrary::Unstructured;
+use defuse::core::{
+    Deadline, DefuseError,
+    crypto::PublicKey,
+    intents::{DefuseIntents, account::RotatePublicKey},
+};
+use defuse_test_utils::{asserts::ResultAssertsExt, random::random_bytes};
+use near_workspaces::Account;
+use rstest::rstest;
+
+use crate::tests::defuse::{
+    DefuseSigner, SigningStandard, accounts::AccountManagerExt, env::Env,
+    intents::ExecuteIntentsExt,
+};
+
+#[tokio::test]
+#[rstest]
+async fn rotate_implicit_public_key(random_bytes: Vec<u8>) {
+    let mut u = Unstructured::new(&random_bytes);
+    let env = Env::new().await;
+
+    let old_pk = PublicKey::Ed25519(
+        env.user1
+            .secret_key()
+            .public_key()
+            .key_data()
+            .try_into()
+            .unwrap(),
+    );
+    let implicit_account = Account::from_secret_key(
+        old_pk.to_implicit_account_id(),
+        env.user1.secret_key().clone(),
+        env.sandbox().worker(),
+    );
+    let new_pk: PublicKey = u.arbitrary().unwrap();
+
+    assert!(
+        env.defuse
+            .has_public_key(implicit_account.id(), &old_pk)
+            .await
+            .unwrap()
+    );
+
+    env.defuse
+        .execute_intents([implicit_account.sign_defuse_message(
+            u.arbitrary().unwrap(),
+            env.defuse.id(),
+            u.arbitrary().unwrap(),
+            Deadline::MAX,
+            DefuseIntents {
+                intents: [RotatePublicKey {
+                    old: old_pk,
+                    new: new_pk,
+                }
+                .into()]
+                .into(),
+            },
+        )])
+        .await
+        .unwrap();
+
+    assert!(
+        !env.defuse
+            .has_public_key(implicit_account.id(), &old_pk)
+            .await
+            .unwrap()
+    );
+    assert!(
+        env.defuse
+            .has_public_key(implicit_account.id(), &new_pk)
+            .await
+            .unwrap()
+    );
+}
+
+#[tokio::test]
+#[rstest]
+async fn rotate_to_existing_public_key_fails(random_bytes: Vec<u8>) {
+    let mut u = Unstructured::new(&random_bytes);
+    let env = Env::new().await;
+
+    let old_pk: PublicKey = env
+        .user1
+        .secret_key()
+        .public_key()
+        .to_string()
+        .parse()
+        .unwrap();
+    let new_pk: PublicKey = u.arbitrary().unwrap();
+
+    env.user1
+        .add_public_key(env.defuse.id(), new_pk)
+        .await
+        .unwrap();
+
+    env.defuse
+        .execute_intents([env.user1.sign_defuse_message(
+            SigningStandard::default(),
+            env.defuse.id(),
+            u.arbitrary().unwrap(),
+            Deadline::MAX,
+            DefuseIntents {
+                intents: [RotatePublicKey {
+                    old: old_pk,
+                    new: new_pk,
+                }
+                .into()]
+                .into(),
+            },
+        )])
+        .await
+        .assert_err_contains(
+            DefuseError::PublicKeyExists(env.user1.id().clone(), new_pk).to_string(),
+        );
+
+    // nothing has changed
+    for pk in [old_pk, new_pk] {
+        assert!(
+            env.defuse
+                .has_public_key(env.user1.id(), &pk)
+                .await
+                .unwrap()
+        );
+    }
+}
@@ -12,6 +12,16 @@ pub trait AccountForceLockerExt {
         &self,
         contract_id: &AccountId,
         account_id: &AccountIdRef,
+    ) -> anyhow::Result<bool> {
+        self.force_lock_account_with_reason(contract_id, account_id, None)
+            .await
+    }
+
+    async fn force_lock_account_with_reason(
+        &self,
+        contract_id: &AccountId,
+        account_id: &AccountIdRef,
+        reason: Option<&str>,
     ) -> anyhow::Result<bool>;
 
     async fn force_unlock_account(
@@ -36,14 +46,16 @@ impl AccountForceLockerExt for near_workspaces::Account {
             .map_err(Into::into)
     }
 
-    async fn force_lock_account(
+    async fn force_lock_account_with_reason(
         &self,
         contract_id: &AccountId,
         account_id: &AccountIdRef,
+        reason: Option<&str>,
     ) -> anyhow::Result<bool> {
         self.call(contract_id, "force_lock_account")
             .args_json(json!({
                 "account_id": account_id,
+                "reason": reason,
             }))
             .deposit(NearToken::from_yoctonear(1))
             .max_gas()
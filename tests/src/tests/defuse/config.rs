@@ -0,0 +1,220 @@
+use crate::{tests::defuse::env::Env, utils::acl::AclExt};
+use defuse::{
+    contract::{
+        Role,
+        config::{ConfigView, Settings},
+    },
+    core::{
+        events::DefuseEvent,
+        fees::{FeeLimits, FeeLimitsChangedEvent, Pips, ReferralFeeShareChangedEvent},
+        payload::multi::SigningStandard,
+        token_id::{TokenId, nep141::Nep141TokenId},
+    },
+    fees::FeesSettings,
+};
+use near_sdk::NearToken;
+use near_workspaces::Account;
+use serde_json::json;
+use std::borrow::Cow;
+
+#[tokio::test]
+async fn config_round_trip() {
+    let env = Env::builder()
+        .fee(Pips::ONE_PERCENT)
+        .max_intents_per_call(10)
+        .referral_fee_share(Pips::ONE_PERCENT * 30)
+        .verbose_events(true)
+        .allowed_standards([SigningStandard::Nep413])
+        .build()
+        .await;
+
+    let config: ConfigView = env.defuse.view("config").await.unwrap().json().unwrap();
+
+    assert_eq!(&config.wnear_id, env.wnear.id());
+    assert_eq!(config.fees.fee, Pips::ONE_PERCENT);
+    assert_eq!(&config.fees.fee_collector, env.id());
+    assert_eq!(config.settings.max_intents_per_call, 10);
+    assert_eq!(
+        config.fees_settings.referral_fee_share,
+        Pips::ONE_PERCENT * 30
+    );
+    assert!(config.settings.verbose_events);
+    assert_eq!(
+        config.settings.allowed_standards,
+        Some([SigningStandard::Nep413].into())
+    );
+}
+
+#[tokio::test]
+async fn set_settings() {
+    let env = Env::new().await;
+
+    let settings = Settings {
+        max_intents_per_call: 5,
+        verbose_events: true,
+        deadline_grace_ms: 1000,
+        ..Default::default()
+    };
+    let set_settings = |caller: &Account| {
+        caller
+            .call(env.defuse.id(), "set_settings")
+            .args_json(json!({
+                "settings": settings,
+            }))
+            .deposit(NearToken::from_yoctonear(1))
+            .max_gas()
+            .transact()
+    };
+
+    // non-privileged caller is rejected
+    set_settings(&env.user1)
+        .await
+        .unwrap()
+        .into_result()
+        .unwrap_err();
+
+    env.acl_grant_role(env.defuse.id(), Role::DAO, env.user1.id())
+        .await
+        .unwrap();
+
+    set_settings(&env.user1)
+        .await
+        .unwrap()
+        .into_result()
+        .unwrap();
+
+    let config: ConfigView = env.defuse.view("config").await.unwrap().json().unwrap();
+    assert_eq!(config.settings, settings);
+
+    // same settings are rejected
+    set_settings(&env.user1)
+        .await
+        .unwrap()
+        .into_result()
+        .unwrap_err();
+}
+
+#[tokio::test]
+async fn set_fees_settings() {
+    let env = Env::new().await;
+
+    let token_id: TokenId = Nep141TokenId::new(env.ft1.clone()).into();
+    let fee_limits = FeeLimits {
+        min_fee: Some(5),
+        max_fee: Some(1_000),
+    };
+
+    let set_referral_fee_share = |caller: &Account| {
+        caller
+            .call(env.defuse.id(), "set_referral_fee_share")
+            .args_json(json!({
+                "referral_fee_share": Pips::ONE_PERCENT * 30,
+            }))
+            .deposit(NearToken::from_yoctonear(1))
+            .max_gas()
+            .transact()
+    };
+    let set_fee_limits = |caller: &Account| {
+        caller
+            .call(env.defuse.id(), "set_fee_limits")
+            .args_json(json!({
+                "token_id": token_id,
+                "fee_limits": fee_limits,
+            }))
+            .deposit(NearToken::from_yoctonear(1))
+            .max_gas()
+            .transact()
+    };
+
+    // non-privileged caller is rejected
+    set_referral_fee_share(&env.user1)
+        .await
+        .unwrap()
+        .into_result()
+        .unwrap_err();
+    set_fee_limits(&env.user1)
+        .await
+        .unwrap()
+        .into_result()
+        .unwrap_err();
+
+    env.acl_grant_role(env.defuse.id(), Role::FeesManager, env.user1.id())
+        .await
+        .unwrap();
+
+    let result = set_referral_fee_share(&env.user1)
+        .await
+        .unwrap()
+        .into_result()
+        .unwrap();
+    let expected = DefuseEvent::ReferralFeeShareChanged(ReferralFeeShareChangedEvent {
+        old_referral_fee_share: Pips::ZERO,
+        new_referral_fee_share: Pips::ONE_PERCENT * 30,
+    })
+    .to_json_event_string();
+    assert!(
+        result.logs().contains(&expected.as_str()),
+        "{expected} not found in {:#?}",
+        result.logs()
+    );
+
+    let result = set_fee_limits(&env.user1)
+        .await
+        .unwrap()
+        .into_result()
+        .unwrap();
+    let expected = DefuseEvent::FeeLimitsChanged(FeeLimitsChangedEvent {
+        token_id: Cow::Borrowed(&token_id),
+        old_fee_limits: FeeLimits::default(),
+        new_fee_limits: fee_limits,
+    })
+    .to_json_event_string();
+    assert!(
+        result.logs().contains(&expected.as_str()),
+        "{expected} not found in {:#?}",
+        result.logs()
+    );
+
+    assert_eq!(
+        env.defuse
+            .view("referral_fee_share")
+            .await
+            .unwrap()
+            .json::<Pips>()
+            .unwrap(),
+        Pips::ONE_PERCENT * 30
+    );
+    assert_eq!(
+        env.defuse
+            .view("fee_limits")
+            .args_json(json!({
+                "token_id": token_id,
+            }))
+            .await
+            .unwrap()
+            .json::<FeeLimits>()
+            .unwrap(),
+        fee_limits
+    );
+
+    let config: ConfigView = env.defuse.view("config").await.unwrap().json().unwrap();
+    assert_eq!(
+        config.fees_settings,
+        FeesSettings {
+            referral_fee_share: Pips::ONE_PERCENT * 30,
+            fee_limits: [(token_id, fee_limits)].into(),
+        }
+    );
+
+    // same values are rejected
+    set_referral_fee_share(&env.user1)
+        .await
+        .unwrap()
+        .into_result()
+        .unwrap_err();
+    set_fee_limits(&env.user1)
+        .await
+        .unwrap()
+        .into_result()
+        .unwrap_err();
+}
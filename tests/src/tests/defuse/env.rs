@@ -9,9 +9,14 @@ use anyhow::anyhow;
 use defuse::{
     contract::{
         Role,
-        config::{DefuseConfig, RolesConfig},
+        config::{DefuseConfig, RolesConfig, Settings},
     },
-    core::fees::{FeesConfig, Pips},
+    core::{
+        fees::{FeeLimits, FeesConfig, Pips},
+        payload::multi::SigningStandard,
+        token_id::TokenId,
+    },
+    fees::FeesSettings,
     tokens::DepositMessage,
 };
 use defuse_poa_factory::contract::Role as POAFactoryRole;
@@ -150,6 +155,9 @@ pub struct EnvBuilder {
     deployer_as_super_admin: bool,
     disable_ft_storage_deposit: bool,
     disable_registration: bool,
+
+    fees_settings: FeesSettings,
+    settings: Settings,
 }
 
 impl EnvBuilder {
@@ -198,6 +206,49 @@ impl EnvBuilder {
         self
     }
 
+    pub const fn max_intents_per_call(mut self, max_intents_per_call: u32) -> Self {
+        self.settings.max_intents_per_call = max_intents_per_call;
+        self
+    }
+
+    pub const fn referral_fee_share(mut self, referral_fee_share: Pips) -> Self {
+        self.fees_settings.referral_fee_share = referral_fee_share;
+        self
+    }
+
+    pub const fn verbose_events(mut self, verbose_events: bool) -> Self {
+        self.settings.verbose_events = verbose_events;
+        self
+    }
+
+    pub const fn max_public_keys(mut self, max_public_keys: u32) -> Self {
+        self.settings.max_public_keys = Some(max_public_keys);
+        self
+    }
+
+    pub const fn deadline_grace_ms(mut self, deadline_grace_ms: u32) -> Self {
+        self.settings.deadline_grace_ms = deadline_grace_ms;
+        self
+    }
+
+    pub fn network_id(mut self, network_id: impl Into<String>) -> Self {
+        self.settings.network_id = Some(network_id.into());
+        self
+    }
+
+    pub fn fee_limits(mut self, token_id: TokenId, fee_limits: FeeLimits) -> Self {
+        self.fees_settings.fee_limits.insert(token_id, fee_limits);
+        self
+    }
+
+    pub fn allowed_standards(
+        mut self,
+        allowed_standards: impl IntoIterator<Item = SigningStandard>,
+    ) -> Self {
+        self.settings.allowed_standards = Some(allowed_standards.into_iter().collect());
+        self
+    }
+
     // pub fn staging_duration(mut self, staging_duration: Duration) -> Self {
     //     self.staging_duration = Some(staging_duration);
     //     self
@@ -249,6 +300,8 @@ impl EnvBuilder {
                             fee_collector: self.fee_collector.unwrap_or_else(|| root.id().clone()),
                         },
                         roles: self.roles,
+                        fees_settings: self.fees_settings,
+                        settings: self.settings,
                     },
                 )
                 .await
@@ -0,0 +1,163 @@
+use crate::{
+    tests::defuse::{DefuseSigner, SigningStandard, env::Env, intents::ExecuteIntentsExt},
+    utils::mt::MtExt,
+};
+use defuse::core::{
+    Deadline, DefuseError,
+    intents::{
+        DefuseIntents, Intent,
+        allowance::{Allowance, TransferFrom},
+        tokens::Transfer,
+    },
+    token_id::{TokenId, nep141::Nep141TokenId},
+};
+use defuse_randomness::Rng;
+use defuse_test_utils::{asserts::ResultAssertsExt, random::rng};
+use rstest::rstest;
+
+#[tokio::test]
+#[rstest]
+#[trace]
+async fn delegate_spends_allowance(#[notrace] mut rng: impl Rng) {
+    let env = Env::new().await;
+
+    let ft1 = TokenId::from(Nep141TokenId::new(env.ft1.clone()));
+    env.defuse_ft_deposit_to(&env.ft1, 1000, env.user1.id())
+        .await
+        .unwrap();
+
+    // user1 allows user2 to spend up to 500 of ft1
+    env.defuse
+        .execute_intents([env.user1.sign_defuse_message(
+            SigningStandard::default(),
+            env.defuse.id(),
+            rng.random(),
+            Deadline::MAX,
+            DefuseIntents {
+                intents: [Allowance {
+                    delegate: env.user2.id().clone(),
+                    token_id: ft1.clone(),
+                    amount: 500,
+                }
+                .into()]
+                .into(),
+            },
+        )])
+        .await
+        .unwrap();
+
+    let transfer_from = |amount| TransferFrom {
+        owner_id: env.user1.id().clone(),
+        transfer: Transfer {
+            receiver_id: env.user3.id().clone(),
+            tokens: std::iter::once((ft1.clone(), amount)).collect(),
+            memo: None,
+        },
+    };
+
+    // within allowance
+    env.defuse
+        .execute_intents([env.user2.sign_defuse_message(
+            SigningStandard::default(),
+            env.defuse.id(),
+            rng.random(),
+            Deadline::MAX,
+            DefuseIntents {
+                intents: [transfer_from(300).into()].into(),
+            },
+        )])
+        .await
+        .unwrap();
+
+    // beyond remaining allowance, even though user1 has enough balance
+    env.defuse
+        .execute_intents([env.user2.sign_defuse_message(
+            SigningStandard::default(),
+            env.defuse.id(),
+            rng.random(),
+            Deadline::MAX,
+            DefuseIntents {
+                intents: [transfer_from(201).into()].into(),
+            },
+        )])
+        .await
+        .assert_err_contains(
+            DefuseError::AllowanceExceeded {
+                owner_id: env.user1.id().clone(),
+                delegate_id: env.user2.id().clone(),
+                token_id: ft1.clone(),
+            }
+            .to_string(),
+        );
+
+    for (account_id, balance) in [(env.user1.id(), 700), (env.user3.id(), 300)] {
+        assert_eq!(
+            env.defuse
+                .mt_balance_of(account_id, &ft1.to_string())
+                .await
+                .unwrap(),
+            balance
+        );
+    }
+}
+
+#[tokio::test]
+#[rstest]
+#[trace]
+async fn allowances_limit(#[notrace] mut rng: impl Rng) {
+    let env = Env::new().await;
+
+    let allowance = |i, amount| -> Intent {
+        Allowance {
+            delegate: env.user2.id().clone(),
+            token_id: Nep141TokenId::new(format!("ft{i}.near").parse().unwrap()).into(),
+            amount,
+        }
+        .into()
+    };
+    let sign = |nonce, intents| {
+        env.user1.sign_defuse_message(
+            SigningStandard::default(),
+            env.defuse.id(),
+            nonce,
+            Deadline::MAX,
+            DefuseIntents { intents },
+        )
+    };
+
+    env.defuse
+        .execute_intents([sign(
+            rng.random(),
+            (0..Allowance::MAX_PER_ACCOUNT)
+                .map(|i| allowance(i, 100))
+                .collect(),
+        )])
+        .await
+        .unwrap();
+
+    // overriding existing allowance doesn't take a new slot
+    env.defuse
+        .execute_intents([sign(rng.random(), vec![allowance(0, 200)])])
+        .await
+        .unwrap();
+
+    env.defuse
+        .execute_intents([sign(
+            rng.random(),
+            vec![allowance(Allowance::MAX_PER_ACCOUNT, 100)],
+        )])
+        .await
+        .assert_err_contains(
+            DefuseError::TooManyAllowances(env.user1.id().clone(), Allowance::MAX_PER_ACCOUNT)
+                .to_string(),
+        );
+
+    // revoking frees a slot
+    env.defuse
+        .execute_intents([sign(
+            rng.random(),
+            vec![allowance(0, 0), allowance(Allowance::MAX_PER_ACCOUNT, 100)],
+        )])
+        .await
+        .unwrap();
+}
@@ -0,0 +1,74 @@
+use std::sync::LazyLock;
+
+use defuse::core::{
+    Deadline,
+    accounts::AccountEvent,
+    events::DefuseEvent,
+    intents::{
+        DefuseIntents,
+        auth::{AuthCall, AuthCallValueEvent},
+    },
+};
+use defuse_randomness::Rng;
+use defuse_test_utils::random::rng;
+use near_sdk::NearToken;
+use rstest::rstest;
+
+use crate::{
+    tests::defuse::{DefuseSigner, SigningStandard, env::Env, intents::ExecuteIntentsExt},
+    utils::{account::AccountExt, read_wasm},
+};
+
+static AUTH_CALLEE_MOCK_WASM: LazyLock<Vec<u8>> =
+    LazyLock::new(|| read_wasm("defuse_auth_callee_mock"));
+
+#[tokio::test]
+#[rstest]
+#[trace]
+async fn auth_call_return_value(#[notrace] mut rng: impl Rng) {
+    let env = Env::builder().build().await;
+
+    let callee = env
+        .deploy_contract("auth-callee", &AUTH_CALLEE_MOCK_WASM)
+        .await
+        .unwrap();
+
+    let msg = "hello";
+
+    let result = env
+        .defuse
+        .execute_intents([env.user1.sign_defuse_message(
+            SigningStandard::default(),
+            env.defuse.id(),
+            rng.random(),
+            Deadline::MAX,
+            DefuseIntents {
+                intents: [AuthCall {
+                    contract_id: callee.id().clone(),
+                    msg: msg.to_string(),
+                    attached_deposit: NearToken::from_yoctonear(0),
+                    min_gas: None,
+                    return_value: true,
+                }
+                .into()]
+                .into(),
+            },
+        )])
+        .await
+        .unwrap();
+
+    let expected = DefuseEvent::AuthCallValue(AccountEvent::new(
+        env.user1.id().clone(),
+        AuthCallValueEvent {
+            contract_id: callee.id().clone(),
+            value: Some(format!("{}:{msg}", env.user1.id())),
+        },
+    ))
+    .to_json_event_string();
+
+    assert!(
+        result.logs().contains(&expected),
+        "{expected} not found in {:#?}",
+        result.logs()
+    );
+}
@@ -0,0 +1,74 @@
+use std::time::Duration;
+
+use crate::{
+    tests::defuse::{DefuseSigner, SigningStandard, env::Env, intents::ExecuteIntentsExt},
+    utils::mt::MtExt,
+};
+use chrono::Utc;
+use defuse::core::{
+    Deadline,
+    intents::{DefuseIntents, tokens::Transfer},
+    token_id::{TokenId, nep141::Nep141TokenId},
+};
+use defuse_randomness::Rng;
+use defuse_test_utils::{asserts::ResultAssertsExt, random::rng};
+use rstest::rstest;
+
+/// Payload signed with a deadline that has just passed is accepted only
+/// if it still fits into the configured grace window
+#[tokio::test]
+#[rstest]
+#[trace]
+async fn deadline_grace(
+    #[notrace] mut rng: impl Rng,
+    #[values(0, 600_000)] deadline_grace_ms: u32,
+) {
+    let env = Env::builder()
+        .deadline_grace_ms(deadline_grace_ms)
+        .build()
+        .await;
+
+    let ft1 = TokenId::from(Nep141TokenId::new(env.ft1.clone()));
+    env.defuse_ft_deposit_to(&env.ft1, 1000, env.user1.id())
+        .await
+        .unwrap();
+
+    let deadline = Deadline::new(Utc::now() - Duration::from_secs(60));
+    let result = env
+        .defuse
+        .execute_intents([env.user1.sign_defuse_message(
+            SigningStandard::default(),
+            env.defuse.id(),
+            rng.random(),
+            deadline,
+            DefuseIntents {
+                intents: [Transfer {
+                    receiver_id: env.user2.id().clone(),
+                    tokens: std::iter::once((ft1.clone(), 1000)).collect(),
+                    memo: None,
+                }
+                .into()]
+                .into(),
+            },
+        )])
+        .await;
+
+    let accepted = deadline_grace_ms > 0;
+    if accepted {
+        result.unwrap();
+    } else {
+        // block timestamp is not known in advance
+        result.assert_err_contains(format!(
+            "deadline '{}' has expired at",
+            deadline.into_timestamp()
+        ));
+    }
+
+    assert_eq!(
+        env.defuse
+            .mt_balance_of(env.user2.id(), &ft1.to_string())
+            .await
+            .unwrap(),
+        if accepted { 1000 } else { 0 }
+    );
+}
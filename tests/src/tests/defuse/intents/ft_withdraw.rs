@@ -3,7 +3,7 @@ use crate::tests::defuse::DefuseExt;
 use crate::tests::defuse::tokens::nep141::traits::DefuseFtReceiver;
 use crate::{
     tests::defuse::{DefuseSigner, SigningStandard, env::Env},
-    utils::{ft::FtExt, mt::MtExt, wnear::WNearExt},
+    utils::{account::AccountExt, ft::FtExt, mt::MtExt, read_wasm, wnear::WNearExt},
 };
 use arbitrary::{Arbitrary, Unstructured};
 use defuse::core::token_id::{TokenId, nep141::Nep141TokenId};
@@ -12,14 +12,18 @@ use defuse::core::{
     intents::{DefuseIntents, tokens::FtWithdraw},
 };
 use defuse::{
-    contract::config::{DefuseConfig, RolesConfig},
+    contract::config::{DefuseConfig, RolesConfig, Settings},
     core::fees::{FeesConfig, Pips},
+    fees::FeesSettings,
 };
 use defuse_randomness::Rng;
 use defuse_test_utils::{asserts::ResultAssertsExt, random::rng};
 use near_sdk::{AccountId, Gas, NearToken};
 use rstest::rstest;
-use std::time::Duration;
+use std::{sync::LazyLock, time::Duration};
+
+static FT_RECEIVER_MOCK_WASM: LazyLock<Vec<u8>> =
+    LazyLock::new(|| read_wasm("defuse_ft_receiver_mock"));
 
 #[tokio::test]
 #[rstest]
@@ -273,6 +277,8 @@ async fn ft_withdraw_intent_msg(
                     fee_collector: env.id().clone(),
                 },
                 roles: RolesConfig::default(),
+                fees_settings: FeesSettings::default(),
+                settings: Settings::default(),
             },
         )
         .await
@@ -392,3 +398,71 @@ async fn ft_withdraw_intent_msg(
         1000
     );
 }
+
+/// Amount returned as unused by `ft_on_transfer()` should be refunded
+#[tokio::test]
+#[rstest]
+#[trace]
+async fn ft_withdraw_intent_msg_partial_refund(
+    #[notrace] mut rng: impl Rng,
+    #[values(0, 300, 1000)] used: u128,
+) {
+    let env = Env::new().await;
+
+    let receiver = env
+        .deploy_contract("ft-receiver", &FT_RECEIVER_MOCK_WASM)
+        .await
+        .unwrap();
+
+    env.poa_factory
+        .ft_storage_deposit_many(&env.ft1, &[receiver.id()])
+        .await
+        .unwrap();
+
+    env.defuse_ft_deposit_to(&env.ft1, 1000, env.user1.id())
+        .await
+        .unwrap();
+
+    env.defuse
+        .execute_intents([env.user1.sign_defuse_message(
+            SigningStandard::default(),
+            env.defuse.id(),
+            rng.random(),
+            Deadline::MAX,
+            DefuseIntents {
+                intents: [FtWithdraw {
+                    token: env.ft1.clone(),
+                    receiver_id: receiver.id().clone(),
+                    amount: 1000.into(),
+                    memo: None,
+                    msg: Some(used.to_string()),
+                    storage_deposit: None,
+                    min_gas: None,
+                }
+                .into()]
+                .into(),
+            },
+        )])
+        .await
+        .unwrap();
+
+    let ft1 = TokenId::from(Nep141TokenId::new(env.ft1.clone()));
+    assert_eq!(
+        env.mt_contract_balance_of(env.defuse.id(), env.user1.id(), &ft1.to_string())
+            .await
+            .unwrap(),
+        1000 - used
+    );
+    assert_eq!(
+        env.ft_token_balance_of(&env.ft1, env.defuse.id())
+            .await
+            .unwrap(),
+        1000 - used
+    );
+    assert_eq!(
+        env.ft_token_balance_of(&env.ft1, receiver.id())
+            .await
+            .unwrap(),
+        used
+    );
+}
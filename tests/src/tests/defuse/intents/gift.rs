@@ -0,0 +1,86 @@
+use std::borrow::Cow;
+
+use crate::{
+    tests::defuse::{DefuseSigner, SigningStandard, env::Env, intents::ExecuteIntentsExt},
+    utils::mt::MtExt,
+};
+use defuse::core::{
+    Deadline,
+    accounts::AccountEvent,
+    crypto::Payload,
+    events::DefuseEvent,
+    intents::{
+        DefuseIntents, IntentEvent,
+        tokens::{Gift, Transfer},
+    },
+    token_id::{TokenId, nep141::Nep141TokenId},
+};
+use defuse_randomness::Rng;
+use defuse_test_utils::random::rng;
+use rstest::rstest;
+
+#[tokio::test]
+#[rstest]
+#[trace]
+async fn gift(#[notrace] mut rng: impl Rng) {
+    let env = Env::new().await;
+
+    let ft1 = TokenId::from(Nep141TokenId::new(env.ft1.clone()));
+    env.defuse_ft_deposit_to(&env.ft1, 1000, env.user1.id())
+        .await
+        .unwrap();
+
+    let gift = Gift {
+        transfer: Transfer {
+            receiver_id: env.user2.id().clone(),
+            tokens: std::iter::once((ft1.clone(), 300)).collect(),
+            memo: Some("happy birthday".to_string()),
+        },
+    };
+
+    let signed = env.user1.sign_defuse_message(
+        SigningStandard::default(),
+        env.defuse.id(),
+        rng.random(),
+        Deadline::MAX,
+        DefuseIntents {
+            intents: [gift.clone().into()].into(),
+        },
+    );
+    let intent_hash = signed.hash();
+
+    let result = env.defuse.execute_intents([signed]).await.unwrap();
+
+    assert_eq!(
+        env.defuse
+            .mt_balance_of(env.user1.id(), &ft1.to_string())
+            .await
+            .unwrap(),
+        700
+    );
+    assert_eq!(
+        env.defuse
+            .mt_balance_of(env.user2.id(), &ft1.to_string())
+            .await
+            .unwrap(),
+        300
+    );
+
+    let expected = DefuseEvent::Gift(Cow::Owned(vec![IntentEvent::new(
+        AccountEvent::new(env.user1.id().clone(), Cow::Owned(gift)),
+        intent_hash,
+    )]))
+    .to_json_event_string();
+    assert!(
+        result.logs().contains(&expected),
+        "{expected} not found in {:#?}",
+        result.logs()
+    );
+    assert!(
+        !result
+            .logs()
+            .iter()
+            .any(|log| log.contains(r#""event":"transfer""#)),
+        "gift should not emit a transfer event"
+    );
+}
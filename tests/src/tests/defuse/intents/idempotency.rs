@@ -0,0 +1,144 @@
+use crate::{
+    tests::defuse::{DefuseSigner, SigningStandard, env::Env},
+    utils::{mt::MtExt, test_log::TestLog},
+};
+use defuse::core::{
+    Deadline, DefuseError,
+    intents::{DefuseIntents, tokens::Transfer},
+    token_id::{TokenId, nep141::Nep141TokenId},
+};
+use defuse_randomness::Rng;
+use defuse_serde_utils::base64::AsBase64;
+use defuse_test_utils::{asserts::ResultAssertsExt, random::rng};
+use rstest::rstest;
+use serde_json::json;
+
+#[tokio::test]
+#[rstest]
+#[trace]
+async fn execute_intents_idempotency_key(#[notrace] mut rng: impl Rng) {
+    let env = Env::new().await;
+
+    let ft1 = TokenId::from(Nep141TokenId::new(env.ft1.clone()));
+    env.defuse_ft_deposit_to(&env.ft1, 1000, env.user1.id())
+        .await
+        .unwrap();
+
+    let signed = env.user1.sign_defuse_message(
+        SigningStandard::default(),
+        env.defuse.id(),
+        rng.random(),
+        Deadline::MAX,
+        DefuseIntents {
+            intents: [Transfer {
+                receiver_id: env.user2.id().clone(),
+                tokens: std::iter::once((ft1.clone(), 300)).collect(),
+                memo: None,
+            }
+            .into()]
+            .into(),
+        },
+    );
+    let args = json!({
+        "signed": [signed],
+        "idempotency_key": AsBase64(rng.random::<[u8; 32]>()),
+    });
+
+    let mut results = Vec::new();
+    for _ in 0..2 {
+        let result: TestLog = env
+            .user3
+            .call(env.defuse.id(), "execute_intents")
+            .args_json(&args)
+            .max_gas()
+            .transact()
+            .await
+            .unwrap()
+            .into_result()
+            .unwrap()
+            .into();
+        results.push(result);
+    }
+
+    // second submission is a no-op
+    assert!(!results[0].logs().is_empty());
+    assert!(
+        results[1].logs().is_empty(),
+        "unexpected logs: {:#?}",
+        results[1].logs()
+    );
+
+    let signed = env.user1.sign_defuse_message(
+        SigningStandard::default(),
+        env.defuse.id(),
+        rng.random(),
+        Deadline::MAX,
+        DefuseIntents {
+            intents: [Transfer {
+                receiver_id: env.user2.id().clone(),
+                tokens: std::iter::once((ft1.clone(), 100)).collect(),
+                memo: None,
+            }
+            .into()]
+            .into(),
+        },
+    );
+
+    // same key with different payloads is rejected
+    env.user3
+        .call(env.defuse.id(), "execute_intents")
+        .args_json(json!({
+            "signed": [signed],
+            "idempotency_key": args["idempotency_key"],
+        }))
+        .max_gas()
+        .transact()
+        .await
+        .unwrap()
+        .into_result()
+        .assert_err_contains(DefuseError::IdempotencyKeyMismatch.to_string());
+
+    // keys can't be stored without any signed payloads
+    env.user3
+        .call(env.defuse.id(), "execute_intents")
+        .args_json(json!({
+            "signed": [],
+            "idempotency_key": AsBase64(rng.random::<[u8; 32]>()),
+        }))
+        .max_gas()
+        .transact()
+        .await
+        .unwrap()
+        .into_result()
+        .assert_err_contains(DefuseError::IdempotencyKeyWithoutPayloads.to_string());
+
+    // keys are namespaced by predecessor, so the same key from another
+    // caller is executed
+    env.user2
+        .call(env.defuse.id(), "execute_intents")
+        .args_json(json!({
+            "signed": [signed],
+            "idempotency_key": args["idempotency_key"],
+        }))
+        .max_gas()
+        .transact()
+        .await
+        .unwrap()
+        .into_result()
+        .unwrap();
+
+    assert_eq!(
+        env.defuse
+            .mt_balance_of(env.user1.id(), &ft1.to_string())
+            .await
+            .unwrap(),
+        600
+    );
+    assert_eq!(
+        env.defuse
+            .mt_balance_of(env.user2.id(), &ft1.to_string())
+            .await
+            .unwrap(),
+        400
+    );
+}
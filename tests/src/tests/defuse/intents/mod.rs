@@ -2,29 +2,39 @@ use super::{DefuseSigner, accounts::AccountManagerExt, env::Env};
 use crate::tests::defuse::SigningStandard;
 use crate::utils::{crypto::Signer, mt::MtExt, test_log::TestLog};
 use arbitrary::{Arbitrary, Unstructured};
+use defuse::core::DefuseError;
 use defuse::core::token_id::TokenId;
 use defuse::core::token_id::nep141::Nep141TokenId;
 use defuse::{
     core::{
         Deadline,
-        amounts::Amounts,
+        accounts::AccountEvent,
+        crypto::{Payload, PublicKey},
+        events::DefuseEvent,
         intents::{
-            DefuseIntents,
-            tokens::{FtWithdraw, Transfer},
+            DefuseIntents, Intent, IntentEvent, IntentExecutedEvent,
+            tokens::{FtWithdraw, RequireBalance, Transfer},
         },
         payload::{DefusePayload, ExtractDefusePayload, multi::MultiPayload},
     },
     intents::SimulationOutput,
 };
 use defuse_randomness::Rng;
-use defuse_test_utils::random::rng;
-use near_sdk::{AccountId, AccountIdRef};
+use defuse_test_utils::{asserts::ResultAssertsExt, random::rng};
+use near_sdk::{AccountId, AccountIdRef, Gas};
 use rstest::rstest;
 use serde_json::json;
 
+mod allowance;
+mod auth_call;
+mod deadline_grace;
 mod ft_withdraw;
+mod gift;
+mod idempotency;
 mod native_withdraw;
+mod network_id;
 mod relayers;
+mod require_balance;
 mod token_diff;
 
 pub trait ExecuteIntentsExt: AccountManagerExt {
@@ -182,7 +192,7 @@ async fn simulate_is_view_method(
             DefuseIntents {
                 intents: [Transfer {
                     receiver_id: env.user2.id().clone(),
-                    tokens: Amounts::new(std::iter::once((ft1.clone(), 1000)).collect()),
+                    tokens: std::iter::once((ft1.clone(), 1000)).collect(),
                     memo: None,
                 }
                 .into()]
@@ -215,6 +225,251 @@ async fn simulate_is_view_method(
     );
 }
 
+#[tokio::test]
+#[rstest]
+#[trace]
+async fn simulate_min_promise_gas(#[notrace] mut rng: impl Rng) {
+    let env = Env::builder().build().await;
+
+    let ft1 = TokenId::from(Nep141TokenId::new(env.ft1.clone()));
+
+    env.defuse_ft_deposit_to(&env.ft1, 1000, env.user1.id())
+        .await
+        .unwrap();
+
+    let withdraw = |msg: Option<&str>, min_gas: Option<Gas>| -> Intent {
+        FtWithdraw {
+            token: env.ft1.clone(),
+            receiver_id: env.user2.id().clone(),
+            amount: 100.into(),
+            memo: None,
+            msg: msg.map(ToString::to_string),
+            storage_deposit: None,
+            min_gas,
+        }
+        .into()
+    };
+
+    let result = env
+        .defuse
+        .simulate_intents([env.user1.sign_defuse_message(
+            SigningStandard::default(),
+            env.defuse.id(),
+            rng.random(),
+            Deadline::MAX,
+            DefuseIntents {
+                intents: [
+                    // ft_transfer: default 15 TGas
+                    withdraw(None, None),
+                    // ft_transfer_call: default 50 TGas
+                    withdraw(Some(""), None),
+                    // explicit gas above minimum
+                    withdraw(None, Some(Gas::from_tgas(100))),
+                    // no promise
+                    Transfer {
+                        receiver_id: env.user2.id().clone(),
+                        tokens: std::iter::once((ft1.clone(), 100)).collect(),
+                        memo: None,
+                    }
+                    .into(),
+                ]
+                .into(),
+            },
+        )])
+        .await
+        .unwrap();
+
+    result.clone().into_result().unwrap();
+    assert_eq!(result.min_promise_gas, Gas::from_tgas(15 + 50 + 100));
+}
+
+#[tokio::test]
+#[rstest]
+#[trace]
+async fn simulate_reports_verified_by(#[notrace] mut rng: impl Rng) {
+    let env = Env::new().await;
+
+    let signing_pk: PublicKey = env
+        .user1
+        .secret_key()
+        .public_key()
+        .to_string()
+        .parse()
+        .unwrap();
+    let other_pk = PublicKey::arbitrary(&mut Unstructured::new(&rng.random::<[u8; 64]>())).unwrap();
+    env.user1
+        .add_public_key(env.defuse.id(), other_pk)
+        .await
+        .unwrap();
+
+    let result = env
+        .defuse
+        .simulate_intents([env.user1.sign_defuse_message(
+            SigningStandard::default(),
+            env.defuse.id(),
+            rng.random(),
+            Deadline::MAX,
+            DefuseIntents { intents: [].into() },
+        )])
+        .await
+        .unwrap();
+
+    assert_eq!(result.verified_by, [signing_pk]);
+    assert_ne!(signing_pk, other_pk);
+}
+
+#[tokio::test]
+#[rstest]
+#[trace]
+async fn erc191_non_canonical_signature(#[notrace] mut rng: impl Rng) {
+    use defuse::core::erc191::{Erc191Payload, SignedErc191Payload};
+    use k256::{
+        Scalar,
+        ecdsa::SigningKey,
+        elliptic_curve::{PrimeField, sec1::ToEncodedPoint},
+    };
+
+    let env = Env::new().await;
+
+    let signing_key = SigningKey::from_bytes(&rng.random::<[u8; 32]>().into()).unwrap();
+    let public_key = PublicKey::Secp256k1(
+        signing_key
+            .verifying_key()
+            .to_encoded_point(false)
+            .as_bytes()[1..]
+            .try_into()
+            .unwrap(),
+    );
+    env.user1
+        .add_public_key(env.defuse.id(), public_key)
+        .await
+        .unwrap();
+
+    let nonce = rng.random();
+    let payload = Erc191Payload(
+        serde_json::to_string(&DefusePayload {
+            signer_id: env.user1.id().clone(),
+            verifying_contract: env.defuse.id().clone(),
+            network_id: None,
+            deadline: Deadline::MAX,
+            nonce,
+            message: DefuseIntents { intents: [].into() },
+        })
+        .unwrap(),
+    );
+
+    // wallets may produce high `s` along with Ethereum-style `v`:
+    // (r, n - s, 27 + (v ^ 1))
+    let (signature, recovery_id) = signing_key
+        .sign_prehash_recoverable(&payload.hash())
+        .unwrap();
+    let mut high_s = [0; 65];
+    high_s[..64].copy_from_slice(&signature.to_bytes());
+    let s = Scalar::from_repr(<[u8; 32]>::try_from(&high_s[32..64]).unwrap().into()).unwrap();
+    high_s[32..64].copy_from_slice(&(-s).to_bytes());
+    high_s[64] = 27 + (recovery_id.to_byte() ^ 1);
+
+    env.defuse
+        .execute_intents([MultiPayload::Erc191(SignedErc191Payload {
+            payload,
+            signature: high_s,
+        })])
+        .await
+        .unwrap();
+
+    assert!(
+        env.defuse
+            .is_nonce_used(env.user1.id(), &nonce)
+            .await
+            .unwrap()
+    );
+}
+
+#[tokio::test]
+#[rstest]
+#[trace]
+async fn max_intents_per_call(#[notrace] mut rng: impl Rng) {
+    const MAX_INTENTS_PER_CALL: u32 = 2;
+
+    let env = Env::builder()
+        .max_intents_per_call(MAX_INTENTS_PER_CALL)
+        .build()
+        .await;
+
+    let signed: Vec<_> = (0..=MAX_INTENTS_PER_CALL)
+        .map(|_| {
+            env.user1.sign_defuse_message(
+                SigningStandard::default(),
+                env.defuse.id(),
+                rng.random(),
+                Deadline::MAX,
+                DefuseIntents { intents: [].into() },
+            )
+        })
+        .collect();
+
+    env.defuse
+        .execute_intents(signed.clone())
+        .await
+        .assert_err_contains(DefuseError::TooManyIntents(MAX_INTENTS_PER_CALL).to_string());
+
+    // fails before any verification, so no nonces were committed
+    for payload in &signed {
+        let DefusePayload::<DefuseIntents> { nonce, .. } =
+            payload.clone().extract_defuse_payload().unwrap();
+        assert!(
+            !env.defuse
+                .is_nonce_used(env.user1.id(), &nonce)
+                .await
+                .unwrap()
+        );
+    }
+
+    env.defuse
+        .execute_intents(
+            signed
+                .into_iter()
+                .take(MAX_INTENTS_PER_CALL.try_into().unwrap()),
+        )
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+#[rstest]
+#[trace]
+async fn wrong_verifying_contract(#[notrace] mut rng: impl Rng) {
+    let env = Env::new().await;
+
+    let other_contract: AccountId = format!("other.{}", env.id()).parse().unwrap();
+    let nonce = rng.random();
+
+    // signed for another contract, so it can't be replayed on this one
+    let signed = env.user1.sign_defuse_message(
+        SigningStandard::default(),
+        &other_contract,
+        nonce,
+        Deadline::MAX,
+        DefuseIntents { intents: [].into() },
+    );
+
+    env.defuse
+        .simulate_intents([signed.clone()])
+        .await
+        .assert_err_contains(DefuseError::WrongVerifyingContract.to_string());
+    env.defuse
+        .execute_intents([signed])
+        .await
+        .assert_err_contains(DefuseError::WrongVerifyingContract.to_string());
+
+    assert!(
+        !env.defuse
+            .is_nonce_used(env.user1.id(), &nonce)
+            .await
+            .unwrap()
+    );
+}
+
 #[tokio::test]
 #[rstest]
 async fn webauthn(#[values(false, true)] no_registration: bool) {
@@ -268,6 +523,53 @@ async fn webauthn(#[values(false, true)] no_registration: bool) {
     );
 }
 
+#[tokio::test]
+#[rstest]
+#[trace]
+async fn near_ed25519(#[notrace] mut rng: impl Rng) {
+    let env = Env::new().await;
+
+    let ft1 = TokenId::from(Nep141TokenId::new(env.ft1.clone()));
+
+    env.defuse_ft_deposit_to(&env.ft1, 1000, env.user1.id())
+        .await
+        .unwrap();
+
+    let signed = env.user1.sign_defuse_message(
+        SigningStandard::NearEd25519,
+        env.defuse.id(),
+        rng.random(),
+        Deadline::MAX,
+        DefuseIntents {
+            intents: [Transfer {
+                receiver_id: env.user2.id().clone(),
+                tokens: std::iter::once((ft1.clone(), 1000)).collect(),
+                memo: None,
+            }
+            .into()]
+            .into(),
+        },
+    );
+    assert!(matches!(signed, MultiPayload::NearEd25519(_)));
+
+    env.defuse.execute_intents([signed]).await.unwrap();
+
+    assert_eq!(
+        env.defuse
+            .mt_balance_of(env.user1.id(), &ft1.to_string())
+            .await
+            .unwrap(),
+        0
+    );
+    assert_eq!(
+        env.defuse
+            .mt_balance_of(env.user2.id(), &ft1.to_string())
+            .await
+            .unwrap(),
+        1000
+    );
+}
+
 #[tokio::test]
 #[rstest]
 #[trace]
@@ -300,6 +602,7 @@ async fn ton_connect_sign_intent_example(#[notrace] mut rng: impl Rng) {
             text: serde_json::to_string(&DefusePayload {
                 signer_id: "alice.near".parse().unwrap(),
                 verifying_contract: "intent.near".parse().unwrap(),
+                network_id: None,
                 deadline: Deadline::timeout(std::time::Duration::from_secs(120)),
                 nonce: rng.random(),
                 message: intents,
@@ -322,3 +625,151 @@ async fn ton_connect_sign_intent_example(#[notrace] mut rng: impl Rng) {
 
     let _decoded_payload: DefusePayload<DefuseIntents> = signed.extract_defuse_payload().unwrap();
 }
+
+#[tokio::test]
+#[rstest]
+#[trace]
+async fn verbose_events(#[notrace] mut rng: impl Rng, #[values(false, true)] verbose: bool) {
+    let env = Env::builder().verbose_events(verbose).build().await;
+
+    let ft1 = TokenId::from(Nep141TokenId::new(env.ft1.clone()));
+    env.defuse_ft_deposit_to(&env.ft1, 1000, env.user1.id())
+        .await
+        .unwrap();
+
+    let signed = env.user1.sign_defuse_message(
+        SigningStandard::default(),
+        env.defuse.id(),
+        rng.random(),
+        Deadline::MAX,
+        DefuseIntents {
+            intents: [
+                Transfer {
+                    receiver_id: env.user2.id().clone(),
+                    tokens: std::iter::once((ft1.clone(), 100)).collect(),
+                    memo: None,
+                }
+                .into(),
+                RequireBalance {
+                    token_id: ft1.clone(),
+                    min: 900,
+                }
+                .into(),
+                Transfer {
+                    receiver_id: env.user3.id().clone(),
+                    tokens: std::iter::once((ft1.clone(), 200)).collect(),
+                    memo: None,
+                }
+                .into(),
+            ]
+            .into(),
+        },
+    );
+    let intent_hash = signed.hash();
+
+    let result = env.defuse.execute_intents([signed]).await.unwrap();
+
+    let expected: Vec<_> = ["transfer", "require_balance", "transfer"]
+        .into_iter()
+        .enumerate()
+        .map(|(index, kind)| {
+            DefuseEvent::IntentExecuted(IntentEvent::new(
+                AccountEvent::new(
+                    env.user1.id().clone(),
+                    IntentExecutedEvent {
+                        index,
+                        kind: kind.into(),
+                    },
+                ),
+                intent_hash,
+            ))
+            .to_json_event_string()
+        })
+        .collect();
+
+    let emitted: Vec<_> = result
+        .logs()
+        .iter()
+        .filter(|log| log.contains(r#""event":"intent_executed""#))
+        .cloned()
+        .collect();
+
+    if verbose {
+        // emitted in the order of intents
+        assert_eq!(emitted, expected);
+    } else {
+        assert!(emitted.is_empty(), "{emitted:#?}");
+    }
+}
+
+#[tokio::test]
+#[rstest]
+async fn verify_and_extract(
+    #[notrace] mut rng: impl Rng,
+    #[values(
+        SigningStandard::Nep413,
+        SigningStandard::TonConnect,
+        SigningStandard::Sep53,
+        SigningStandard::NearEd25519
+    )]
+    standard: SigningStandard,
+) {
+    let env = Env::new().await;
+
+    let nonce = rng.random();
+    let signed = env.user1.sign_defuse_message(
+        standard,
+        env.defuse.id(),
+        nonce,
+        Deadline::MAX,
+        DefuseIntents { intents: [].into() },
+    );
+    assert_eq!(signed.standard(), standard.into());
+
+    let (public_key, payload) = signed.verify_and_extract::<DefuseIntents>().unwrap();
+    assert_eq!(
+        public_key,
+        env.user1
+            .secret_key()
+            .public_key()
+            .to_string()
+            .parse()
+            .unwrap()
+    );
+    assert_eq!(payload.signer_id, *env.user1.id());
+    assert_eq!(payload.verifying_contract, *env.defuse.id());
+    assert_eq!(payload.nonce, nonce);
+    assert!(payload.intents.is_empty());
+}
+
+#[tokio::test]
+#[rstest]
+#[trace]
+async fn allowed_standards(#[notrace] mut rng: impl Rng) {
+    use defuse::core::payload::multi::SigningStandard as MultiPayloadStandard;
+
+    let env = Env::builder()
+        .allowed_standards([MultiPayloadStandard::Nep413])
+        .build()
+        .await;
+
+    let [ton_connect, nep413] =
+        [SigningStandard::TonConnect, SigningStandard::Nep413].map(|standard| {
+            env.user1.sign_defuse_message(
+                standard,
+                env.defuse.id(),
+                rng.random(),
+                Deadline::MAX,
+                DefuseIntents { intents: [].into() },
+            )
+        });
+
+    env.defuse
+        .execute_intents([ton_connect])
+        .await
+        .assert_err_contains(
+            DefuseError::SigningStandardNotAllowed(MultiPayloadStandard::TonConnect).to_string(),
+        );
+
+    env.defuse.execute_intents([nep413]).await.unwrap();
+}
@@ -5,7 +5,7 @@ use crate::{
         DefuseSigner, SigningStandard, env::Env, intents::ExecuteIntentsExt,
         tokens::nep141::traits::DefuseFtReceiver,
     },
-    utils::{mt::MtExt, wnear::WNearExt},
+    utils::{ft::FtExt, mt::MtExt, wnear::WNearExt},
 };
 use defuse::{
     core::{
@@ -18,7 +18,7 @@ use defuse::{
 };
 use defuse_randomness::Rng;
 use defuse_test_utils::random::rng;
-use near_sdk::NearToken;
+use near_sdk::{AccountId, NearToken};
 use rstest::rstest;
 
 #[tokio::test]
@@ -93,6 +93,7 @@ async fn native_withdraw_intent(mut rng: impl Rng) {
                         NativeWithdraw {
                             receiver_id,
                             amount,
+                            require_account_exists: false,
                         }
                         .into()
                     })
@@ -132,3 +133,79 @@ async fn native_withdraw_intent(mut rng: impl Rng) {
         );
     }
 }
+
+#[tokio::test]
+#[rstest]
+async fn native_withdraw_to_non_existent_account(mut rng: impl Rng) {
+    let env = Env::new().await;
+
+    let amount = NearToken::from_near(10);
+    env.near_deposit(env.wnear.id(), amount)
+        .await
+        .expect("failed to wrap NEAR");
+    env.defuse_ft_deposit(
+        env.defuse.id(),
+        env.wnear.id(),
+        amount.as_yoctonear(),
+        DepositMessage::new(env.user1.id().clone()),
+    )
+    .await
+    .expect("failed to deposit wNEAR to user1");
+
+    let receiver_id: AccountId = format!("non-existent.{}", env.id()).parse().unwrap();
+
+    env.defuse_execute_intents(
+        env.defuse.id(),
+        [env.user1.sign_defuse_message(
+            SigningStandard::default(),
+            env.defuse.id(),
+            rng.random(),
+            Deadline::MAX,
+            DefuseIntents {
+                intents: [NativeWithdraw {
+                    receiver_id: receiver_id.clone(),
+                    amount,
+                    require_account_exists: true,
+                }
+                .into()]
+                .into(),
+            },
+        )],
+    )
+    .await
+    .unwrap();
+
+    assert!(
+        env.sandbox()
+            .worker()
+            .view_account(&receiver_id)
+            .await
+            .is_err(),
+        "receiver account should not be created"
+    );
+
+    // unwrapped NEAR is wrapped back and refunded as wNEAR
+    for (token_id, balance) in [
+        (
+            TokenId::Nep141(Nep141TokenId::new(env.wnear.id().clone())),
+            amount.as_yoctonear(),
+        ),
+        (TokenId::Native, 0),
+    ] {
+        assert_eq!(
+            env.defuse
+                .mt_balance_of(env.user1.id(), &token_id.to_string())
+                .await
+                .unwrap(),
+            balance,
+        );
+    }
+
+    // refunded wNEAR is backed by the contract's wNEAR balance
+    assert_eq!(
+        env.ft_token_balance_of(env.wnear.id(), env.defuse.id())
+            .await
+            .unwrap(),
+        amount.as_yoctonear()
+    );
+}
@@ -0,0 +1,46 @@
+use crate::{
+    tests::defuse::{env::Env, intents::ExecuteIntentsExt},
+    utils::crypto::Signer,
+};
+use defuse::core::{
+    Deadline, DefuseError,
+    intents::DefuseIntents,
+    payload::{DefusePayload, multi::MultiPayload},
+};
+use defuse_randomness::Rng;
+use defuse_test_utils::{asserts::ResultAssertsExt, random::rng};
+use rstest::rstest;
+
+/// Payloads bound to a network are accepted only by contracts
+/// configured with the same `network_id`
+#[tokio::test]
+#[rstest]
+#[trace]
+async fn network_id(
+    #[notrace] mut rng: impl Rng,
+    #[values(None, Some("testnet"), Some("mainnet"))] network_id: Option<&str>,
+) {
+    let env = Env::builder().network_id("testnet").build().await;
+
+    let signed: MultiPayload = env
+        .user1
+        .sign_near_ed25519(
+            serde_json::to_string(&DefusePayload {
+                signer_id: env.user1.id().clone(),
+                verifying_contract: env.defuse.id().clone(),
+                network_id: network_id.map(ToOwned::to_owned),
+                deadline: Deadline::MAX,
+                nonce: rng.random(),
+                message: DefuseIntents { intents: [].into() },
+            })
+            .unwrap(),
+        )
+        .into();
+
+    let result = env.defuse.execute_intents([signed]).await;
+    if network_id == Some("mainnet") {
+        result.assert_err_contains(DefuseError::WrongNetworkId.to_string());
+    } else {
+        result.unwrap();
+    }
+}
@@ -0,0 +1,79 @@
+use crate::{
+    tests::defuse::{DefuseSigner, SigningStandard, env::Env, intents::ExecuteIntentsExt},
+    utils::mt::MtExt,
+};
+use defuse::core::{
+    Deadline, DefuseError,
+    intents::{
+        DefuseIntents,
+        tokens::{RequireBalance, Transfer},
+    },
+    token_id::{TokenId, nep141::Nep141TokenId},
+};
+use defuse_randomness::Rng;
+use defuse_test_utils::{asserts::ResultAssertsExt, random::rng};
+use rstest::rstest;
+
+#[tokio::test]
+#[rstest]
+#[trace]
+async fn require_balance(#[notrace] mut rng: impl Rng, #[values(999, 1000, 1001)] min: u128) {
+    const BALANCE: u128 = 1000;
+
+    let env = Env::new().await;
+
+    let ft1 = TokenId::from(Nep141TokenId::new(env.ft1.clone()));
+
+    env.defuse_ft_deposit_to(&env.ft1, BALANCE, env.user1.id())
+        .await
+        .unwrap();
+
+    let result = env
+        .defuse
+        .execute_intents([env.user1.sign_defuse_message(
+            SigningStandard::default(),
+            env.defuse.id(),
+            rng.random(),
+            Deadline::MAX,
+            DefuseIntents {
+                intents: [
+                    RequireBalance {
+                        token_id: ft1.clone(),
+                        min,
+                    }
+                    .into(),
+                    Transfer {
+                        receiver_id: env.user2.id().clone(),
+                        tokens: std::iter::once((ft1.clone(), BALANCE)).collect(),
+                        memo: None,
+                    }
+                    .into(),
+                ]
+                .into(),
+            },
+        )])
+        .await;
+
+    let met = BALANCE >= min;
+    if met {
+        result.unwrap();
+    } else {
+        result.assert_err_contains(DefuseError::PreconditionFailed.to_string());
+    }
+
+    // the whole batch is reverted if precondition is not met
+    assert_eq!(
+        env.defuse
+            .mt_balance_of(env.user1.id(), &ft1.to_string())
+            .await
+            .unwrap(),
+        if met { 0 } else { BALANCE }
+    );
+    assert_eq!(
+        env.defuse
+            .mt_balance_of(env.user2.id(), &ft1.to_string())
+            .await
+            .unwrap(),
+        if met { BALANCE } else { 0 }
+    );
+}
@@ -1,24 +1,29 @@
 use crate::{
     tests::defuse::{DefuseSigner, SigningStandard, env::Env},
-    utils::mt::MtExt,
+    utils::{acl::AclExt, mt::MtExt},
 };
 use arbitrary::{Arbitrary, Unstructured};
 use defuse::core::token_id::{TokenId, nep141::Nep141TokenId};
 use defuse::core::{
     Deadline,
-    fees::Pips,
+    fees::{FeeLimits, Pips},
     intents::{
         DefuseIntents,
         token_diff::{TokenDeltas, TokenDiff},
     },
     payload::multi::MultiPayload,
 };
+use defuse::{contract::Role, intents::TokenDiffClosure};
 use defuse_randomness::{Rng, make_true_rng};
 use defuse_test_utils::random::rng;
-use near_sdk::AccountId;
+use near_sdk::{AccountId, NearToken};
 use near_workspaces::Account;
 use rstest::rstest;
-use std::{collections::BTreeMap, time::Duration};
+use serde_json::json;
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::Duration,
+};
 
 use super::ExecuteIntentsExt;
 
@@ -52,14 +57,21 @@ async fn swap_p2p(
                         (ft1_token_id.clone(), -100),
                         (
                             ft2_token_id.clone(),
-                            TokenDiff::closure_delta(&ft2_token_id, -200, fee).unwrap(),
+                            TokenDiff::closure_delta(
+                                &ft2_token_id,
+                                -200,
+                                fee,
+                                FeeLimits::default(),
+                            )
+                            .unwrap(),
                         ),
                     ])
                     .unwrap()]
                 .into(),
                 result_balances: std::iter::once((
                     &env.ft2,
-                    TokenDiff::closure_delta(&ft2_token_id, -200, fee).unwrap(),
+                    TokenDiff::closure_delta(&ft2_token_id, -200, fee, FeeLimits::default())
+                        .unwrap(),
                 ))
                 .collect(),
             },
@@ -70,7 +82,13 @@ async fn swap_p2p(
                     .with_apply_deltas([
                         (
                             ft1_token_id.clone(),
-                            TokenDiff::closure_delta(&ft1_token_id, -100, fee).unwrap(),
+                            TokenDiff::closure_delta(
+                                &ft1_token_id,
+                                -100,
+                                fee,
+                                FeeLimits::default(),
+                            )
+                            .unwrap(),
                         ),
                         (ft2_token_id.clone(), -200),
                     ])
@@ -78,7 +96,8 @@ async fn swap_p2p(
                 .into(),
                 result_balances: std::iter::once((
                     &env.ft1,
-                    TokenDiff::closure_delta(&ft1_token_id, -100, fee).unwrap(),
+                    TokenDiff::closure_delta(&ft1_token_id, -100, fee, FeeLimits::default())
+                        .unwrap(),
                 ))
                 .collect(),
             },
@@ -125,11 +144,23 @@ async fn swap_many(
                         .with_apply_deltas([
                             (
                                 ft1_token_id.clone(),
-                                TokenDiff::closure_delta(&ft1_token_id, -100, fee).unwrap(),
+                                TokenDiff::closure_delta(
+                                    &ft1_token_id,
+                                    -100,
+                                    fee,
+                                    FeeLimits::default(),
+                                )
+                                .unwrap(),
                             ),
                             (
                                 ft2_token_id.clone(),
-                                TokenDiff::closure_delta(&ft2_token_id, 200, fee).unwrap(),
+                                TokenDiff::closure_delta(
+                                    &ft2_token_id,
+                                    200,
+                                    fee,
+                                    FeeLimits::default(),
+                                )
+                                .unwrap(),
                             ),
                         ])
                         .unwrap(),
@@ -137,11 +168,23 @@ async fn swap_many(
                         .with_apply_deltas([
                             (
                                 ft2_token_id.clone(),
-                                TokenDiff::closure_delta(&ft2_token_id, 300, fee).unwrap(),
+                                TokenDiff::closure_delta(
+                                    &ft2_token_id,
+                                    300,
+                                    fee,
+                                    FeeLimits::default(),
+                                )
+                                .unwrap(),
                             ),
                             (
                                 ft3_token_id.clone(),
-                                TokenDiff::closure_delta(&ft3_token_id, -500, fee).unwrap(),
+                                TokenDiff::closure_delta(
+                                    &ft3_token_id,
+                                    -500,
+                                    fee,
+                                    FeeLimits::default(),
+                                )
+                                .unwrap(),
                             ),
                         ])
                         .unwrap(),
@@ -150,16 +193,30 @@ async fn swap_many(
                 result_balances: [
                     (
                         &env.ft1,
-                        TokenDiff::closure_delta(&ft1_token_id, -100, fee).unwrap(),
+                        TokenDiff::closure_delta(&ft1_token_id, -100, fee, FeeLimits::default())
+                            .unwrap(),
                     ),
                     (
                         &env.ft2,
-                        1000 + TokenDiff::closure_delta(&ft2_token_id, 200, fee).unwrap()
-                            + TokenDiff::closure_delta(&ft2_token_id, 300, fee).unwrap(),
+                        1000 + TokenDiff::closure_delta(
+                            &ft2_token_id,
+                            200,
+                            fee,
+                            FeeLimits::default(),
+                        )
+                        .unwrap()
+                            + TokenDiff::closure_delta(
+                                &ft2_token_id,
+                                300,
+                                fee,
+                                FeeLimits::default(),
+                            )
+                            .unwrap(),
                     ),
                     (
                         &env.ft3,
-                        TokenDiff::closure_delta(&ft3_token_id, -500, fee).unwrap(),
+                        TokenDiff::closure_delta(&ft3_token_id, -500, fee, FeeLimits::default())
+                            .unwrap(),
                     ),
                 ]
                 .into_iter()
@@ -397,7 +454,8 @@ async fn solver_user_closure(
 
     dbg!(USER_DELTA_IN);
     // propagate RFQ to solver with adjusted amount_in
-    let solver_delta_in = TokenDiff::closure_delta(&token_in, USER_DELTA_IN, fee).unwrap();
+    let solver_delta_in =
+        TokenDiff::closure_delta(&token_in, USER_DELTA_IN, fee, FeeLimits::default()).unwrap();
 
     // assume solver trades 1:2
     let solver_delta_out = solver_delta_in * -2;
@@ -452,16 +510,20 @@ async fn solver_user_closure(
 
     // expect unmatched delta on token_in to be fully covered by user_in
     let expected_unmatched_delta_token_in =
-        TokenDiff::closure_delta(&token_in, USER_DELTA_IN, fee).unwrap();
+        TokenDiff::closure_delta(&token_in, USER_DELTA_IN, fee, FeeLimits::default()).unwrap();
     assert_eq!(
         unmatched_deltas.amount_for(&token_in),
         expected_unmatched_delta_token_in
     );
 
     // calculate user_delta_out to return to the user
-    let user_delta_out =
-        TokenDiff::closure_supply_delta(&token_out, unmatched_deltas.amount_for(&token_out), fee)
-            .unwrap();
+    let user_delta_out = TokenDiff::closure_supply_delta(
+        &token_out,
+        unmatched_deltas.amount_for(&token_out),
+        fee,
+        FeeLimits::default(),
+    )
+    .unwrap();
     dbg!(user_delta_out);
 
     let nonce = rng.random();
@@ -532,3 +594,289 @@ async fn solver_user_closure(
         ]
     );
 }
+
+#[rstest]
+#[tokio::test]
+#[trace]
+async fn simulate_token_diff_closure(
+    #[values(Pips::ZERO, Pips::ONE_BIP, Pips::ONE_PERCENT)] fee: Pips,
+    #[values(None, Some(Pips::ONE_PERCENT))] explicit_fee: Option<Pips>,
+) {
+    let env = Env::builder().fee(fee).build().await;
+
+    let ft1_token_id = TokenId::from(Nep141TokenId::new(env.ft1.clone()));
+    let ft2_token_id = TokenId::from(Nep141TokenId::new(env.ft2.clone()));
+    let ft3_token_id = TokenId::from(Nep141TokenId::new(env.ft3.clone()));
+
+    let diffs = vec![
+        TokenDiff {
+            diff: TokenDeltas::default()
+                .with_apply_deltas([(ft1_token_id.clone(), -1000), (ft2_token_id.clone(), 2000)])
+                .unwrap(),
+            memo: None,
+            referral: None,
+        },
+        TokenDiff {
+            diff: TokenDeltas::default()
+                .with_apply_deltas([(ft2_token_id, -500), (ft3_token_id, 300)])
+                .unwrap(),
+            memo: None,
+            referral: None,
+        },
+    ];
+
+    let result: TokenDiffClosure = env
+        .defuse
+        .view("simulate_token_diff_closure")
+        .args_json(json!({
+            "diffs": diffs,
+            "fee": explicit_fee,
+        }))
+        .await
+        .unwrap()
+        .json()
+        .unwrap();
+
+    // current fee is used unless given explicitly
+    let fee = explicit_fee.unwrap_or(fee);
+    assert_eq!(result.fee, fee);
+    assert_eq!(
+        result.closure,
+        TokenDiff::closure_many(diffs, fee, &HashMap::new()).unwrap()
+    );
+}
+
+/// Closure is calculated with respect to current fee limits, so that it
+/// can be executed as is
+#[rstest]
+#[tokio::test]
+#[trace]
+async fn simulate_token_diff_closure_fee_limits(
+    #[notrace] mut rng: impl Rng,
+    #[values(
+        FeeLimits { min_fee: Some(50), max_fee: None },
+        FeeLimits { min_fee: None, max_fee: Some(3) },
+    )]
+    fee_limits: FeeLimits,
+) {
+    const FT1_IN: u128 = 1000;
+    const FT2_OUT: u128 = 2000;
+    const FT2_BALANCE: u128 = 5000;
+
+    let env = Env::builder().fee(Pips::ONE_PERCENT).build().await;
+
+    let ft1 = TokenId::from(Nep141TokenId::new(env.ft1.clone()));
+    let ft2 = TokenId::from(Nep141TokenId::new(env.ft2.clone()));
+
+    env.acl_grant_role(env.defuse.id(), Role::FeesManager, env.user3.id())
+        .await
+        .unwrap();
+    for token_id in [&ft1, &ft2] {
+        env.user3
+            .call(env.defuse.id(), "set_fee_limits")
+            .args_json(json!({
+                "token_id": token_id,
+                "fee_limits": fee_limits,
+            }))
+            .deposit(NearToken::from_yoctonear(1))
+            .max_gas()
+            .transact()
+            .await
+            .unwrap()
+            .into_result()
+            .unwrap();
+    }
+
+    env.defuse_ft_deposit_to(&env.ft1, FT1_IN, env.user1.id())
+        .await
+        .unwrap();
+    env.defuse_ft_deposit_to(&env.ft2, FT2_BALANCE, env.user2.id())
+        .await
+        .unwrap();
+
+    let user_diff = TokenDiff {
+        diff: TokenDeltas::default()
+            .with_apply_deltas([
+                (ft1.clone(), -i128::try_from(FT1_IN).unwrap()),
+                (ft2.clone(), i128::try_from(FT2_OUT).unwrap()),
+            ])
+            .unwrap(),
+        memo: None,
+        referral: None,
+    };
+
+    let TokenDiffClosure { closure, fee } = env
+        .defuse
+        .view("simulate_token_diff_closure")
+        .args_json(json!({
+            "diffs": [&user_diff],
+        }))
+        .await
+        .unwrap()
+        .json()
+        .unwrap();
+    assert_eq!(
+        closure,
+        TokenDiff::closure_many(
+            [user_diff.clone()],
+            fee,
+            &[(ft1.clone(), fee_limits), (ft2.clone(), fee_limits)].into(),
+        )
+        .unwrap()
+    );
+    // limits make the closure differ from the one without them
+    assert_ne!(
+        closure,
+        TokenDiff::closure_many([user_diff.clone()], fee, &HashMap::new()).unwrap()
+    );
+
+    let solver_ft1_in = closure.amount_for(&ft1);
+    let solver_ft2_out = closure.amount_for(&ft2);
+
+    let signed: Vec<_> = [
+        (&env.user1, user_diff),
+        (
+            &env.user2,
+            TokenDiff {
+                diff: closure,
+                memo: None,
+                referral: None,
+            },
+        ),
+    ]
+    .into_iter()
+    .map(|(signer, diff)| {
+        signer.sign_defuse_message(
+            SigningStandard::default(),
+            env.defuse.id(),
+            rng.random(),
+            Deadline::MAX,
+            DefuseIntents {
+                intents: [diff.into()].into(),
+            },
+        )
+    })
+    .collect();
+
+    env.defuse.execute_intents(signed).await.unwrap();
+
+    assert_eq!(
+        env.mt_contract_balance_of(env.defuse.id(), env.user1.id(), &ft2.to_string())
+            .await
+            .unwrap(),
+        FT2_OUT,
+    );
+    assert_eq!(
+        env.mt_contract_balance_of(env.defuse.id(), env.user2.id(), &ft1.to_string())
+            .await
+            .unwrap(),
+        solver_ft1_in.unsigned_abs(),
+    );
+    assert_eq!(
+        env.mt_contract_balance_of(env.defuse.id(), env.user2.id(), &ft2.to_string())
+            .await
+            .unwrap(),
+        FT2_BALANCE - solver_ft2_out.unsigned_abs(),
+    );
+}
+
+/// Part of collected fees is routed to the referral, if given, and the
+/// rest goes to the fee collector
+#[tokio::test]
+#[rstest]
+#[trace]
+async fn referral_fee_split(
+    #[notrace] mut rng: impl Rng,
+    #[values(Pips::ONE_BIP, Pips::ONE_PERCENT)] fee: Pips,
+    #[values(Pips::ZERO, Pips::from_percent(30).unwrap(), Pips::MAX)] referral_fee_share: Pips,
+    #[values(false, true)] with_referral: bool,
+) {
+    const FT1_IN: u128 = 1000;
+    const FT2_IN: u128 = 2000;
+
+    let env = Env::builder()
+        .fee(fee)
+        .referral_fee_share(referral_fee_share)
+        .build()
+        .await;
+
+    let ft1 = TokenId::from(Nep141TokenId::new(env.ft1.clone()));
+    let ft2 = TokenId::from(Nep141TokenId::new(env.ft2.clone()));
+
+    env.defuse_ft_deposit_to(&env.ft1, FT1_IN, env.user1.id())
+        .await
+        .unwrap();
+    env.defuse_ft_deposit_to(&env.ft2, FT2_IN, env.user2.id())
+        .await
+        .unwrap();
+
+    let referral = with_referral.then(|| env.user3.id().clone());
+    let signed: Vec<_> = [
+        (&env.user1, (&ft1, FT1_IN), &ft2, FT2_IN),
+        (&env.user2, (&ft2, FT2_IN), &ft1, FT1_IN),
+    ]
+    .into_iter()
+    .map(|(signer, (token_in, amount_in), token_out, amount_out)| {
+        signer.sign_defuse_message(
+            SigningStandard::default(),
+            env.defuse.id(),
+            rng.random(),
+            Deadline::MAX,
+            DefuseIntents {
+                intents: [TokenDiff {
+                    diff: TokenDeltas::default()
+                        .with_apply_deltas([
+                            (token_in.clone(), -i128::try_from(amount_in).unwrap()),
+                            (
+                                token_out.clone(),
+                                TokenDiff::closure_delta(
+                                    token_out,
+                                    -i128::try_from(amount_out).unwrap(),
+                                    fee,
+                                    FeeLimits::default(),
+                                )
+                                .unwrap(),
+                            ),
+                        ])
+                        .unwrap(),
+                    memo: None,
+                    referral: referral.clone(),
+                }
+                .into()]
+                .into(),
+            },
+        )
+    })
+    .collect();
+
+    env.defuse.execute_intents(signed).await.unwrap();
+
+    let (referral_fee, _) = fee.split(referral_fee_share);
+    // (referral, fee collector) for each token
+    let (expected_referral, expected_collector): (Vec<_>, Vec<_>) = [FT1_IN, FT2_IN]
+        .into_iter()
+        .map(|amount| {
+            let collected = fee.fee_ceil(amount);
+            let to_referral = if with_referral {
+                referral_fee.fee(amount)
+            } else {
+                0
+            };
+            (to_referral, collected - to_referral)
+        })
+        .unzip();
+
+    let tokens = [ft1.to_string(), ft2.to_string()];
+    assert_eq!(
+        env.mt_contract_batch_balance_of(env.defuse.id(), env.user3.id(), &tokens)
+            .await
+            .unwrap(),
+        expected_referral,
+    );
+    assert_eq!(
+        env.mt_contract_batch_balance_of(env.defuse.id(), env.id(), &tokens)
+            .await
+            .unwrap(),
+        expected_collector,
+    );
+}
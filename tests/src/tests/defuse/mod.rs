@@ -1,4 +1,5 @@
 pub mod accounts;
+mod config;
 mod env;
 mod intents;
 mod storage;
@@ -16,7 +17,10 @@ use defuse::{
     core::{
         Deadline, Nonce,
         nep413::Nep413Payload,
-        payload::{multi::MultiPayload, nep413::Nep413DefuseMessage},
+        payload::{
+            multi::{MultiPayload, SigningStandard as MultiPayloadStandard},
+            nep413::Nep413DefuseMessage,
+        },
         ton_connect::TonConnectPayload,
     },
 };
@@ -91,7 +95,9 @@ impl DefuseSigner for near_workspaces::Account {
                         .unwrap(),
                     )
                     .with_recipient(defuse_contract)
-                    .with_nonce(nonce),
+                    .with_nonce(nonce)
+                    .validated()
+                    .unwrap(),
                 )
                 .into(),
             SigningStandard::TonConnect => self
@@ -106,6 +112,7 @@ impl DefuseSigner for near_workspaces::Account {
                         text: serde_json::to_string(&DefusePayload {
                             signer_id: self.id().clone(),
                             verifying_contract: defuse_contract.clone(),
+                            network_id: None,
                             deadline,
                             nonce,
                             message,
@@ -119,6 +126,7 @@ impl DefuseSigner for near_workspaces::Account {
                     serde_json::to_string(&DefusePayload {
                         signer_id: self.id().clone(),
                         verifying_contract: defuse_contract.clone(),
+                        network_id: None,
                         deadline,
                         nonce,
                         message,
@@ -126,14 +134,40 @@ impl DefuseSigner for near_workspaces::Account {
                     .unwrap(),
                 ))
                 .into(),
+            SigningStandard::NearEd25519 => self
+                .sign_near_ed25519(
+                    serde_json::to_string(&DefusePayload {
+                        signer_id: self.id().clone(),
+                        verifying_contract: defuse_contract.clone(),
+                        network_id: None,
+                        deadline,
+                        nonce,
+                        message,
+                    })
+                    .unwrap(),
+                )
+                .into(),
         }
     }
 }
 
-#[derive(Default, Arbitrary)]
+/// Subset of [`MultiPayloadStandard`] that test accounts are able to sign
+#[derive(Debug, Clone, Copy, Default, Arbitrary)]
 pub enum SigningStandard {
     #[default]
     Nep413,
     TonConnect,
     Sep53,
+    NearEd25519,
+}
+
+impl From<SigningStandard> for MultiPayloadStandard {
+    fn from(standard: SigningStandard) -> Self {
+        match standard {
+            SigningStandard::Nep413 => Self::Nep413,
+            SigningStandard::TonConnect => Self::TonConnect,
+            SigningStandard::Sep53 => Self::Sep53,
+            SigningStandard::NearEd25519 => Self::NearEd25519,
+        }
+    }
 }
@@ -1,10 +1,16 @@
 use crate::{
     tests::defuse::{DefuseSigner, SigningStandard, env::Env, intents::ExecuteIntentsExt},
-    utils::{storage_management::StorageManagementExt, wnear::WNearExt},
+    utils::{mt::MtExt, storage_management::StorageManagementExt, wnear::WNearExt},
 };
 use arbitrary::{Arbitrary, Unstructured};
 use defuse::core::Deadline;
-use defuse::core::intents::{DefuseIntents, tokens::StorageDeposit};
+use defuse::core::{
+    intents::{
+        DefuseIntents,
+        tokens::{StorageDeposit, StorageDepositSelf, UnwrapNear},
+    },
+    token_id::{TokenId, nep141::Nep141TokenId},
+};
 use defuse_randomness::Rng;
 use defuse_test_utils::random::rng;
 use near_sdk::NearToken;
@@ -123,6 +129,7 @@ async fn storage_deposit_success(
                     contract_id: env.ft1.clone(),
                     deposit_for_account_id: env.user2.id().clone(),
                     amount: amount_to_deposit,
+                    from_native: false,
                 }
                 .into()]
                 .into(),
@@ -225,6 +232,7 @@ async fn storage_deposit_fails_user_has_no_balance_in_intents(mut rng: impl Rng)
                 contract_id: env.ft1.clone(),
                 deposit_for_account_id: env.user2.id().clone(),
                 amount: MIN_FT_STORAGE_DEPOSIT_VALUE,
+                from_native: false,
             }
             .into()]
             .into(),
@@ -237,3 +245,196 @@ async fn storage_deposit_fails_user_has_no_balance_in_intents(mut rng: impl Rng)
         .await
         .unwrap_err();
 }
+
+#[tokio::test]
+#[rstest]
+async fn storage_deposit_self(mut rng: impl Rng) {
+    let env = Env::builder()
+        .disable_ft_storage_deposit()
+        .no_registration(false)
+        .build()
+        .await;
+
+    env.fund_account_with_near(env.user1.id(), NearToken::from_near(1000))
+        .await;
+    env.fund_account_with_near(env.defuse.id(), NearToken::from_near(10000))
+        .await;
+
+    assert!(
+        env.storage_balance_of(&env.ft1, env.user1.id())
+            .await
+            .unwrap()
+            .is_none()
+    );
+
+    // For intents contract to have a balance in wnear, we make a storage deposit for it
+    env.poa_factory
+        .storage_deposit(
+            env.wnear.id(),
+            Some(env.defuse.id()),
+            NearToken::from_near(1),
+        )
+        .await
+        .unwrap();
+
+    env.user1
+        .near_deposit(env.wnear.id(), NearToken::from_near(10))
+        .await
+        .unwrap();
+    env.defuse_ft_deposit_to(
+        env.wnear.id(),
+        NearToken::from_near(10).as_yoctonear(),
+        env.user1.id(),
+    )
+    .await
+    .unwrap();
+
+    env.defuse
+        .execute_intents([env.user1.sign_defuse_message(
+            SigningStandard::arbitrary(&mut Unstructured::new(&rng.random::<[u8; 1]>())).unwrap(),
+            env.defuse.id(),
+            rng.random(),
+            Deadline::timeout(std::time::Duration::from_secs(120)),
+            DefuseIntents {
+                intents: [StorageDepositSelf {
+                    contract_id: env.ft1.clone(),
+                    amount: MIN_FT_STORAGE_DEPOSIT_VALUE,
+                    from_native: false,
+                }
+                .into()]
+                .into(),
+            },
+        )])
+        .await
+        .unwrap();
+
+    assert_eq!(
+        env.storage_balance_of(&env.ft1, env.user1.id())
+            .await
+            .unwrap()
+            .map(|v| v.total),
+        Some(MIN_FT_STORAGE_DEPOSIT_VALUE)
+    );
+
+    assert_eq!(
+        env.defuse
+            .mt_balance_of(
+                env.user1.id(),
+                &TokenId::Nep141(Nep141TokenId::new(env.wnear.id().clone())).to_string()
+            )
+            .await
+            .unwrap(),
+        NearToken::from_near(10)
+            .saturating_sub(MIN_FT_STORAGE_DEPOSIT_VALUE)
+            .as_yoctonear(),
+    );
+}
+
+#[tokio::test]
+#[rstest]
+async fn storage_deposit_from_native(mut rng: impl Rng) {
+    let env = Env::builder()
+        .disable_ft_storage_deposit()
+        .no_registration(false)
+        .build()
+        .await;
+
+    env.fund_account_with_near(env.user1.id(), NearToken::from_near(1000))
+        .await;
+    env.fund_account_with_near(env.defuse.id(), NearToken::from_near(10000))
+        .await;
+
+    // For intents contract to have a balance in wnear, we make a storage deposit for it
+    env.poa_factory
+        .storage_deposit(
+            env.wnear.id(),
+            Some(env.defuse.id()),
+            NearToken::from_near(1),
+        )
+        .await
+        .unwrap();
+
+    env.user1
+        .near_deposit(env.wnear.id(), NearToken::from_near(10))
+        .await
+        .unwrap();
+    env.defuse_ft_deposit_to(
+        env.wnear.id(),
+        NearToken::from_near(10).as_yoctonear(),
+        env.user1.id(),
+    )
+    .await
+    .unwrap();
+
+    env.defuse
+        .execute_intents([env.user1.sign_defuse_message(
+            SigningStandard::default(),
+            env.defuse.id(),
+            rng.random(),
+            Deadline::timeout(std::time::Duration::from_secs(120)),
+            DefuseIntents {
+                intents: [UnwrapNear {
+                    amount: NearToken::from_near(10),
+                }
+                .into()]
+                .into(),
+            },
+        )])
+        .await
+        .unwrap();
+
+    assert_eq!(
+        env.defuse
+            .mt_balance_of(
+                env.user1.id(),
+                &TokenId::Nep141(Nep141TokenId::new(env.wnear.id().clone())).to_string()
+            )
+            .await
+            .unwrap(),
+        0,
+    );
+    assert_eq!(
+        env.defuse
+            .mt_balance_of(env.user1.id(), &TokenId::Native.to_string())
+            .await
+            .unwrap(),
+        NearToken::from_near(10).as_yoctonear(),
+    );
+
+    env.defuse
+        .execute_intents([env.user1.sign_defuse_message(
+            SigningStandard::default(),
+            env.defuse.id(),
+            rng.random(),
+            Deadline::timeout(std::time::Duration::from_secs(120)),
+            DefuseIntents {
+                intents: [StorageDepositSelf {
+                    contract_id: env.ft1.clone(),
+                    amount: MIN_FT_STORAGE_DEPOSIT_VALUE,
+                    from_native: true,
+                }
+                .into()]
+                .into(),
+            },
+        )])
+        .await
+        .unwrap();
+
+    assert_eq!(
+        env.storage_balance_of(&env.ft1, env.user1.id())
+            .await
+            .unwrap()
+            .map(|v| v.total),
+        Some(MIN_FT_STORAGE_DEPOSIT_VALUE)
+    );
+
+    assert_eq!(
+        env.defuse
+            .mt_balance_of(env.user1.id(), &TokenId::Native.to_string())
+            .await
+            .unwrap(),
+        NearToken::from_near(10)
+            .saturating_sub(MIN_FT_STORAGE_DEPOSIT_VALUE)
+            .as_yoctonear(),
+    );
+}
@@ -1,3 +1,4 @@
 pub mod nep141;
 mod nep171;
 mod nep245;
+mod total_supplies;
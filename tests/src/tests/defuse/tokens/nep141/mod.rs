@@ -7,7 +7,7 @@ use crate::{
         defuse::{DefuseSigner, env::Env},
         poa::factory::PoAFactoryExt,
     },
-    utils::{acl::AclExt, ft::FtExt, mt::MtExt},
+    utils::{account::AccountExt, acl::AclExt, ft::FtExt, mt::MtExt, read_wasm, wnear::WNearExt},
 };
 use arbitrary::{Arbitrary, Unstructured};
 use defuse::core::token_id::TokenId;
@@ -22,7 +22,7 @@ use defuse::{
 };
 use defuse_randomness::Rng;
 use defuse_test_utils::random::rng;
-use near_sdk::json_types::U128;
+use near_sdk::{NearToken, json_types::U128};
 use rstest::rstest;
 use std::time::Duration;
 
@@ -293,6 +293,98 @@ async fn deposit_withdraw_intent_refund(
     );
 }
 
+#[tokio::test]
+#[rstest]
+#[trace]
+async fn deposit_sponsored_intents(
+    #[notrace] mut rng: impl Rng,
+    #[values(false, true)] refund_if_fails: bool,
+) {
+    use crate::tests::defuse::tokens::nep141::traits::DefuseFtReceiver;
+
+    let env = Env::new().await;
+
+    // user2 sponsors the deposit
+    env.poa_factory_ft_deposit(
+        env.poa_factory.id(),
+        env.poa_ft1_name(),
+        env.user2.id(),
+        1000,
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+
+    // while intents are signed by user1, who receives the deposit
+    let nonce = rng.random();
+    assert_eq!(
+        env.user2
+            .defuse_ft_deposit(
+                env.defuse.id(),
+                &env.ft1,
+                1000,
+                DepositMessage {
+                    receiver_id: env.user1.id().clone(),
+                    execute_intents: [env.user1.sign_defuse_message(
+                        SigningStandard::arbitrary(&mut Unstructured::new(
+                            &rng.random::<[u8; 1]>()
+                        ))
+                        .unwrap(),
+                        env.defuse.id(),
+                        nonce,
+                        Deadline::MAX,
+                        DefuseIntents {
+                            intents: [FtWithdraw {
+                                token: env.ft1.clone(),
+                                receiver_id: env.user3.id().clone(),
+                                amount: U128(600),
+                                memo: None,
+                                msg: None,
+                                storage_deposit: None,
+                                min_gas: None,
+                            }
+                            .into()]
+                            .into(),
+                        },
+                    )]
+                    .into(),
+                    refund_if_fails,
+                },
+            )
+            .await
+            .unwrap(),
+        1000
+    );
+
+    let ft1 = TokenId::from(Nep141TokenId::new(env.ft1.clone()));
+
+    assert_eq!(
+        env.ft_token_balance_of(&env.ft1, env.user2.id())
+            .await
+            .unwrap(),
+        0
+    );
+    assert_eq!(
+        env.mt_contract_balance_of(env.defuse.id(), env.user2.id(), &ft1.to_string())
+            .await
+            .unwrap(),
+        0
+    );
+    assert_eq!(
+        env.mt_contract_balance_of(env.defuse.id(), env.user1.id(), &ft1.to_string())
+            .await
+            .unwrap(),
+        400
+    );
+    assert_eq!(
+        env.ft_token_balance_of(&env.ft1, env.user3.id())
+            .await
+            .unwrap(),
+        600
+    );
+}
+
 #[tokio::test]
 #[rstest]
 async fn ft_force_withdraw(#[values(false, true)] no_registration: bool) {
@@ -374,3 +466,54 @@ async fn ft_force_withdraw(#[values(false, true)] no_registration: bool) {
         1000
     );
 }
+
+#[tokio::test]
+#[rstest]
+async fn near_deposit_and_transfer_call(#[values(true, false)] valid_msg: bool) {
+    let env = Env::new().await;
+
+    let depositor = env
+        .deploy_contract("depositor", &read_wasm("defuse_ft_receiver_mock"))
+        .await
+        .unwrap();
+    env.ft_storage_deposit(env.wnear.id(), Some(depositor.id()))
+        .await
+        .unwrap();
+
+    let amount = NearToken::from_near(10);
+    let used = env
+        .near_deposit_and_transfer_call(
+            depositor.id(),
+            env.wnear.id(),
+            amount,
+            env.defuse.id(),
+            &if valid_msg {
+                DepositMessage::new(env.user1.id().clone()).to_string()
+            } else {
+                // neither JSON nor account id
+                "{invalid".to_string()
+            },
+        )
+        .await
+        .unwrap();
+
+    // refunded to the depositor if not accepted by the receiver
+    let deposited = if valid_msg { amount.as_yoctonear() } else { 0 };
+    assert_eq!(used, deposited);
+    assert_eq!(
+        env.defuse
+            .mt_balance_of(
+                env.user1.id(),
+                &TokenId::from(Nep141TokenId::new(env.wnear.id().clone())).to_string()
+            )
+            .await
+            .unwrap(),
+        deposited
+    );
+    assert_eq!(
+        env.ft_token_balance_of(env.wnear.id(), depositor.id())
+            .await
+            .unwrap(),
+        amount.as_yoctonear() - deposited
+    );
+}
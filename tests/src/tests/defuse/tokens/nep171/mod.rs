@@ -293,3 +293,120 @@ async fn transfer_nft_to_verifier(mut rng: impl Rng) {
         }
     }
 }
+
+#[tokio::test]
+#[rstest]
+#[trace]
+async fn nft_deposit_execute_intents(
+    #[notrace] mut rng: impl Rng,
+    #[values(false, true)] intent_fails: bool,
+) {
+    use defuse::{core::intents::tokens::Transfer, tokens::DepositMessage};
+
+    let env = Env::builder().build().await;
+
+    env.transfer_near(env.user1.id(), NearToken::from_near(100))
+        .await
+        .unwrap()
+        .unwrap();
+
+    let nft_issuer_contract = env
+        .user1
+        .deploy_vanilla_nft_issuer(
+            "nft1",
+            NFTContractMetadata {
+                reference: None,
+                reference_hash: None,
+                spec: NFT_METADATA_SPEC.to_string(),
+                name: "Token nft1".to_string(),
+                symbol: "NFT_TKN".to_string(),
+                icon: None,
+                base_uri: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    let nft_id = gen_random_string(&mut rng, 32..=32);
+    let nft_mt_token_id = DefuseTokenId::from(
+        Nep171TokenId::new(nft_issuer_contract.id().to_owned(), nft_id.clone()).unwrap(),
+    );
+
+    env.user1
+        .nft_mint(
+            nft_issuer_contract.id(),
+            &nft_id,
+            env.user2.id(),
+            &TokenMetadata::default(),
+        )
+        .await
+        .unwrap();
+
+    let msg = DepositMessage {
+        receiver_id: env.user2.id().clone(),
+        execute_intents: [env.user2.sign_defuse_message(
+            SigningStandard::default(),
+            env.defuse.id(),
+            rng.random(),
+            Deadline::MAX,
+            DefuseIntents {
+                intents: [Transfer {
+                    receiver_id: env.user3.id().clone(),
+                    // there is only one NFT, so transferring two fails
+                    tokens: std::iter::once((
+                        nft_mt_token_id.clone(),
+                        if intent_fails { 2 } else { 1 },
+                    ))
+                    .collect(),
+                    memo: None,
+                }
+                .into()]
+                .into(),
+            },
+        )]
+        .into(),
+        refund_if_fails: true,
+    };
+
+    let transferred = env
+        .user2
+        .nft_transfer_call(
+            nft_issuer_contract.id(),
+            env.defuse.id(),
+            nft_id.clone(),
+            None,
+            msg.to_string(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(transferred, !intent_fails);
+
+    let nft = env
+        .user2
+        .nft_token(nft_issuer_contract.id(), &nft_id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    if intent_fails {
+        // the NFT is refunded, since the whole deposit has failed
+        assert_eq!(nft.owner_id, *env.user2.id());
+    } else {
+        assert_eq!(nft.owner_id, *env.defuse.id());
+    }
+
+    assert_eq!(
+        env.defuse
+            .mt_balance_of(env.user2.id(), &nft_mt_token_id.to_string())
+            .await
+            .unwrap(),
+        0
+    );
+    assert_eq!(
+        env.defuse
+            .mt_balance_of(env.user3.id(), &nft_mt_token_id.to_string())
+            .await
+            .unwrap(),
+        u128::from(!intent_fails)
+    );
+}
@@ -1,16 +1,26 @@
 mod letter_gen;
 mod mt_transfer_resolve_gas;
+mod mt_withdraw_gas;
 pub mod traits;
 
-use crate::tests::defuse::DefuseExt;
 use crate::tests::defuse::tokens::nep245::traits::DefuseMtWithdrawer;
+use crate::tests::defuse::{DefuseExt, DefuseSigner, SigningStandard};
 use crate::{tests::defuse::env::Env, utils::mt::MtExt};
-use defuse::contract::config::{DefuseConfig, RolesConfig};
+use defuse::contract::{
+    Role,
+    config::{DefuseConfig, RolesConfig, Settings},
+};
+use defuse::core::Deadline;
 use defuse::core::fees::{FeesConfig, Pips};
+use defuse::core::intents::DefuseIntents;
 use defuse::core::token_id::TokenId;
 use defuse::core::token_id::nep141::Nep141TokenId;
 use defuse::core::token_id::nep245::Nep245TokenId;
+use defuse::fees::FeesSettings;
 use defuse::nep245::Token;
+use defuse_randomness::Rng;
+use defuse_test_utils::random::rng;
+use near_sdk::json_types::U128;
 use rstest::rstest;
 
 #[tokio::test]
@@ -475,6 +485,8 @@ async fn multitoken_withdrawals() {
                     fee_collector: env.id().clone(),
                 },
                 roles: RolesConfig::default(),
+                fees_settings: FeesSettings::default(),
+                settings: Settings::default(),
             },
         )
         .await
@@ -670,12 +682,6 @@ async fn multitoken_withdrawals() {
         );
     }
 
-    // To use this:
-    // 1. Add this to the resolve function: near_sdk::log!("225a33ac58aee0cf8c6ea225223a237a");
-    // 2. Uncomment the key string, which is used to detect this log in promises
-    // 3. Uncomment the code after mt_withdraw calls, and it will print the gas values
-    // let key_string = "225a33ac58aee0cf8c6ea225223a237a";
-
     // Now we do a withdraw of ft1 from defuse2, which will trigger a transfer from defuse2 account in defuse, to user2
     {
         let tokens: Vec<(String, u128)> = vec![(ft1.to_string(), 10)];
@@ -692,15 +698,6 @@ async fn multitoken_withdrawals() {
             )
             .await
             .unwrap();
-
-        // let receipt_logs = test_log
-        //     .logs_and_gas_burnt_in_receipts()
-        //     .iter()
-        //     .filter(|(a, _b)| a.iter().any(|s| s.contains(key_string)))
-        //     .collect::<Vec<_>>();
-        // assert_eq!(receipt_logs.len(), 1);
-
-        // println!("Cost with token count 1: {}", receipt_logs[0].1);
     }
 
     // Now user1 in defuse2 has 90 tokens left of `"nep245:defuse.test.near:nep141:ft1.test.near"`
@@ -765,15 +762,6 @@ async fn multitoken_withdrawals() {
             )
             .await
             .unwrap();
-
-        // let receipt_logs = test_log
-        //     .logs_and_gas_burnt_in_receipts()
-        //     .iter()
-        //     .filter(|(a, _b)| a.iter().any(|s| s.contains(key_string)))
-        //     .collect::<Vec<_>>();
-        // assert_eq!(receipt_logs.len(), 1);
-
-        // println!("Cost with token count 2: {}", receipt_logs[0].1);
     }
 
     // Now we do a withdraw of ft1, ft2 and ft3 from defuse2, which will trigger a transfer from defuse2 account in defuse, to user2
@@ -796,15 +784,6 @@ async fn multitoken_withdrawals() {
             )
             .await
             .unwrap();
-
-        // let receipt_logs = test_log
-        //     .logs_and_gas_burnt_in_receipts()
-        //     .iter()
-        //     .filter(|(a, _b)| a.iter().any(|s| s.contains(key_string)))
-        //     .collect::<Vec<_>>();
-        // assert_eq!(receipt_logs.len(), 1);
-
-        // println!("Cost with token count 3: {}", receipt_logs[0].1);
     }
 
     // We ensure the math is sound after the last two withdrawals
@@ -852,3 +831,159 @@ async fn multitoken_withdrawals() {
         );
     }
 }
+
+#[tokio::test]
+#[rstest]
+#[trace]
+async fn mt_deposit_execute_intents(
+    #[notrace] mut rng: impl Rng,
+    #[values(false, true)] refund_if_fails: bool,
+) {
+    use defuse::{core::intents::tokens::Transfer, tokens::DepositMessage};
+    use serde_json::json;
+
+    let env = Env::new().await;
+
+    // user3 acts as a multi-token contract, so deposited tokens are
+    // `nep245:user3.test.near:<token-id>`
+    let token_id =
+        TokenId::from(Nep245TokenId::new(env.user3.id().clone(), "token1".to_string()).unwrap());
+
+    let msg = DepositMessage {
+        receiver_id: env.user1.id().clone(),
+        execute_intents: [env.user1.sign_defuse_message(
+            SigningStandard::default(),
+            env.defuse.id(),
+            rng.random(),
+            Deadline::MAX,
+            DefuseIntents {
+                intents: [Transfer {
+                    receiver_id: env.user2.id().clone(),
+                    tokens: std::iter::once((token_id.clone(), 300)).collect(),
+                    memo: None,
+                }
+                .into()]
+                .into(),
+            },
+        )]
+        .into(),
+        refund_if_fails,
+    };
+
+    let refunds: Vec<U128> = env
+        .user3
+        .call(env.defuse.id(), "mt_on_transfer")
+        .args_json(json!({
+            "sender_id": env.user1.id(),
+            "previous_owner_ids": [env.user1.id()],
+            "token_ids": ["token1"],
+            "amounts": [U128(1000)],
+            "msg": msg.to_string(),
+        }))
+        .max_gas()
+        .transact()
+        .await
+        .unwrap()
+        .into_result()
+        .unwrap()
+        .json()
+        .unwrap();
+    assert_eq!(refunds, [U128(0)]);
+
+    assert_eq!(
+        env.defuse
+            .mt_balance_of(env.user1.id(), &token_id.to_string())
+            .await
+            .unwrap(),
+        700
+    );
+    assert_eq!(
+        env.defuse
+            .mt_balance_of(env.user2.id(), &token_id.to_string())
+            .await
+            .unwrap(),
+        300
+    );
+}
+
+#[tokio::test]
+#[rstest]
+#[trace]
+async fn mt_force_transfer(#[values(Role::DAO, Role::UnrestrictedWithdrawer)] role: Role) {
+    use near_sdk::NearToken;
+    use serde_json::json;
+
+    use crate::utils::acl::AclExt;
+
+    let env = Env::new().await;
+
+    let ft1 = TokenId::from(Nep141TokenId::new(env.ft1.clone()));
+    env.defuse_ft_deposit_to(&env.ft1, 1000, env.user1.id())
+        .await
+        .unwrap();
+
+    let force_transfer = |caller: &near_workspaces::Account| {
+        caller
+            .call(env.defuse.id(), "mt_force_transfer")
+            .args_json(json!({
+                "owner_id": env.user1.id(),
+                "receiver_id": env.user3.id(),
+                "token_id": ft1.to_string(),
+                "amount": U128(400),
+                "memo": "unwind mistaken deposit",
+            }))
+            .deposit(NearToken::from_yoctonear(1))
+            .max_gas()
+            .transact()
+    };
+
+    // non-privileged caller is rejected
+    force_transfer(&env.user2)
+        .await
+        .unwrap()
+        .into_result()
+        .unwrap_err();
+    assert_eq!(
+        env.defuse
+            .mt_balance_of(env.user1.id(), &ft1.to_string())
+            .await
+            .unwrap(),
+        1000
+    );
+
+    env.acl_grant_role(env.defuse.id(), role, env.user2.id())
+        .await
+        .unwrap();
+
+    let outcome = force_transfer(&env.user2)
+        .await
+        .unwrap()
+        .into_result()
+        .unwrap();
+
+    // forced transfer is attributed to the caller
+    assert!(
+        outcome.logs().iter().any(|log| log.contains(&format!(
+            r#""authorized_id":"{}","old_owner_id":"{}""#,
+            env.user2.id(),
+            env.user1.id(),
+        ))),
+        "{:#?}",
+        outcome.logs()
+    );
+
+    assert_eq!(
+        env.defuse
+            .mt_balance_of(env.user1.id(), &ft1.to_string())
+            .await
+            .unwrap(),
+        600
+    );
+    assert_eq!(
+        env.defuse
+            .mt_balance_of(env.user3.id(), &ft1.to_string())
+            .await
+            .unwrap(),
+        400
+    );
+}
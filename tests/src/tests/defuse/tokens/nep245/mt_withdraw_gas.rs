@@ -0,0 +1,126 @@
+use crate::tests::defuse::env::Env;
+use defuse::{
+    contract::Contract,
+    core::{
+        intents::tokens::MtWithdraw,
+        token_id::{TokenId, nep245::Nep245TokenId},
+    },
+};
+use near_sdk::{Gas, NearToken, json_types::U128};
+use near_workspaces::result::ExecutionFinalResult;
+use rstest::rstest;
+use serde_json::json;
+
+fn gas_burnt_in_receipt_with_log(outcome: &ExecutionFinalResult, log: &str) -> Gas {
+    outcome
+        .receipt_outcomes()
+        .iter()
+        .find(|o| o.logs.iter().any(|l| l.contains(log)))
+        .unwrap_or_else(|| panic!("no receipt with log containing `{log}`"))
+        .gas_burnt
+}
+
+/// Gas attached to `mt_batch_transfer()` and `mt_resolve_withdraw()` should
+/// cover the gas actually burnt by them for any number of tokens
+#[tokio::test]
+#[rstest]
+async fn mt_withdraw_gas(#[values(1, 10, 50)] token_count: usize) {
+    let env = Env::new().await;
+
+    env.transfer_near(env.defuse.id(), NearToken::from_near(1000))
+        .await
+        .unwrap()
+        .unwrap();
+
+    let token_ids = (0..token_count)
+        .map(|i| format!("token{i}"))
+        .collect::<Vec<_>>();
+
+    // Deposit fictitious tokens `nep245:user3.test.near:<token-id>`,
+    // user3 has no contract deployed, so withdrawals to it will fail
+    env.user3
+        .call(env.defuse.id(), "mt_on_transfer")
+        .args_json(json!({
+            "sender_id": env.user1.id(),
+            "previous_owner_ids": vec![env.user1.id(); token_count],
+            "token_ids": &token_ids,
+            "amounts": vec![U128(1000); token_count],
+            "msg": "",
+        }))
+        .max_gas()
+        .transact()
+        .await
+        .unwrap()
+        .into_result()
+        .unwrap();
+
+    let defuse_token_ids = token_ids
+        .iter()
+        .map(|token_id| {
+            TokenId::Nep245(Nep245TokenId::new(env.user3.id().clone(), token_id.clone()).unwrap())
+                .to_string()
+        })
+        .collect::<Vec<_>>();
+
+    // `mt_batch_transfer()` on Defuse as a token contract should fit into
+    // the gas attached by `MtWithdraw`
+    {
+        let outcome = env
+            .user1
+            .call(env.defuse.id(), "mt_batch_transfer")
+            .args_json(json!({
+                "receiver_id": env.user2.id(),
+                "token_ids": &defuse_token_ids,
+                "amounts": vec![U128(1); token_count],
+            }))
+            .deposit(NearToken::from_yoctonear(1))
+            .max_gas()
+            .transact()
+            .await
+            .unwrap();
+
+        let min_gas = MtWithdraw {
+            token: env.defuse.id().clone(),
+            receiver_id: env.user2.id().clone(),
+            token_ids: defuse_token_ids.clone(),
+            amounts: vec![U128(1); token_count],
+            memo: None,
+            msg: None,
+            storage_deposit: None,
+            min_gas: None,
+        }
+        .min_gas();
+
+        let burnt = gas_burnt_in_receipt_with_log(&outcome, "mt_transfer");
+        assert!(
+            burnt <= min_gas,
+            "`mt_batch_transfer` of {token_count} tokens burnt {burnt}, more than {min_gas}",
+        );
+        outcome.into_result().unwrap();
+    }
+
+    // Withdrawal fails, so all tokens should be refunded in `mt_resolve_withdraw()`
+    {
+        let outcome = env
+            .user1
+            .call(env.defuse.id(), "mt_withdraw")
+            .args_json(json!({
+                "token": env.user3.id(),
+                "receiver_id": env.user2.id(),
+                "token_ids": &token_ids,
+                "amounts": vec![U128(1); token_count],
+            }))
+            .deposit(NearToken::from_yoctonear(1))
+            .max_gas()
+            .transact()
+            .await
+            .unwrap();
+
+        let resolve_gas = Contract::mt_resolve_withdraw_gas(token_count);
+        let burnt = gas_burnt_in_receipt_with_log(&outcome, "refund");
+        assert!(
+            burnt <= resolve_gas,
+            "`mt_resolve_withdraw` of {token_count} tokens burnt {burnt}, more than {resolve_gas}",
+        );
+    }
+}
@@ -0,0 +1,115 @@
+use defuse::core::{
+    DefuseError,
+    token_id::{TokenId, nep141::Nep141TokenId, nep171::Nep171TokenId},
+};
+use defuse_test_utils::asserts::ResultAssertsExt;
+use near_sdk::json_types::U128;
+use serde_json::json;
+
+use crate::tests::defuse::{env::Env, tokens::nep141::traits::DefuseFtWithdrawer};
+
+async fn total_supplies(env: &Env) -> Vec<(String, U128)> {
+    env.defuse
+        .view("total_supplies")
+        .args_json(json!({}))
+        .await
+        .unwrap()
+        .json()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn total_supplies_track_deposits_and_withdrawals() {
+    let env = Env::new().await;
+
+    let ft1 = TokenId::from(Nep141TokenId::new(env.ft1.clone())).to_string();
+    let ft2 = TokenId::from(Nep141TokenId::new(env.ft2.clone())).to_string();
+
+    assert!(total_supplies(&env).await.is_empty());
+
+    env.defuse_ft_deposit_to(&env.ft1, 1000, env.user1.id())
+        .await
+        .unwrap();
+    env.defuse_ft_deposit_to(&env.ft1, 500, env.user2.id())
+        .await
+        .unwrap();
+    env.defuse_ft_deposit_to(&env.ft2, 300, env.user1.id())
+        .await
+        .unwrap();
+
+    let mut supplies = total_supplies(&env).await;
+    supplies.sort();
+    assert_eq!(
+        supplies,
+        [(ft1.clone(), U128(1500)), (ft2.clone(), U128(300))]
+    );
+
+    // pagination
+    let first: Vec<(String, U128)> = env
+        .defuse
+        .view("total_supplies")
+        .args_json(json!({ "from_index": U128(1), "limit": 1 }))
+        .await
+        .unwrap()
+        .json()
+        .unwrap();
+    assert_eq!(first.len(), 1);
+    assert!(supplies.contains(&first[0]));
+
+    env.user1
+        .defuse_ft_withdraw(env.defuse.id(), &env.ft1, env.user1.id(), 400, None, None)
+        .await
+        .unwrap();
+
+    let mut supplies = total_supplies(&env).await;
+    supplies.sort();
+    assert_eq!(supplies, [(ft1, U128(1100)), (ft2, U128(300))]);
+}
+
+#[tokio::test]
+async fn nft_total_supply_never_exceeds_one() {
+    let env = Env::new().await;
+
+    // user3 acts as an NFT contract
+    let nft =
+        TokenId::from(Nep171TokenId::new(env.user3.id().clone(), "nft1".to_string()).unwrap());
+
+    let deposit = |receiver_id| {
+        env.user3
+            .call(env.defuse.id(), "nft_on_transfer")
+            .args_json(json!({
+                "sender_id": receiver_id,
+                "previous_owner_id": receiver_id,
+                "token_id": "nft1",
+                "msg": "",
+            }))
+            .max_gas()
+            .transact()
+    };
+
+    deposit(env.user1.id())
+        .await
+        .unwrap()
+        .into_result()
+        .unwrap();
+
+    // holder is reported only when depositing to the same account again
+    for (receiver_id, holder) in [
+        (env.user2.id(), None),
+        (env.user1.id(), Some(env.user1.id())),
+    ] {
+        deposit(receiver_id)
+            .await
+            .unwrap()
+            .into_result()
+            .assert_err_contains(
+                DefuseError::NftAlreadyDeposited(
+                    Nep171TokenId::new(env.user3.id().clone(), "nft1".to_string()).unwrap(),
+                    holder.cloned(),
+                )
+                .to_string(),
+            );
+    }
+
+    assert_eq!(total_supplies(&env).await, [(nft.to_string(), U128(1))]);
+}
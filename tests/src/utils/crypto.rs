@@ -1,6 +1,7 @@
 use defuse::core::{
     crypto::Payload,
     nep413::{Nep413Payload, SignedNep413Payload},
+    payload::near_ed25519::SignedNearEd25519Payload,
     sep53::{Sep53Payload, SignedSep53Payload},
     ton_connect::{SignedTonConnectPayload, TonConnectPayload},
 };
@@ -12,6 +13,7 @@ pub trait Signer {
     fn sign_nep413(&self, payload: Nep413Payload) -> SignedNep413Payload;
     fn sign_ton_connect(&self, payload: TonConnectPayload) -> SignedTonConnectPayload;
     fn sign_sep53(&self, payload: Sep53Payload) -> SignedSep53Payload;
+    fn sign_near_ed25519(&self, payload: String) -> SignedNearEd25519Payload;
 }
 
 impl Signer for Account {
@@ -64,4 +66,20 @@ impl Signer for Account {
             _ => unreachable!(),
         }
     }
+
+    fn sign_near_ed25519(&self, payload: String) -> SignedNearEd25519Payload {
+        let secret_key = Signer::secret_key(self);
+        let hash = near_sdk::env::sha256_array(payload.as_bytes());
+
+        match (secret_key.sign(&hash), secret_key.public_key()) {
+            (near_crypto::Signature::ED25519(sig), near_crypto::PublicKey::ED25519(pk)) => {
+                SignedNearEd25519Payload {
+                    payload,
+                    public_key: pk.0,
+                    signature: sig.to_bytes(),
+                }
+            }
+            _ => unreachable!(),
+        }
+    }
 }
@@ -1,4 +1,4 @@
-use near_sdk::{AccountId, NearToken};
+use near_sdk::{AccountId, NearToken, json_types::U128};
 use near_workspaces::{Account, Contract};
 use serde_json::json;
 
@@ -11,6 +11,19 @@ pub trait WNearExt: FtExt {
     async fn deploy_wrap_near(&self, token: &str) -> anyhow::Result<Contract>;
     async fn near_deposit(&self, wnear_id: &AccountId, amount: NearToken) -> anyhow::Result<()>;
     async fn near_withdraw(&self, wnear_id: &AccountId, amount: NearToken) -> anyhow::Result<()>;
+
+    /// Calls [`defuse_wnear::near_deposit_and_transfer_call`] from
+    /// `depositor_id`, which is expected to be deployed with
+    /// `ft-receiver-mock` and registered on `wnear_id`.
+    /// Returns the amount used by `receiver_id`.
+    async fn near_deposit_and_transfer_call(
+        &self,
+        depositor_id: &AccountId,
+        wnear_id: &AccountId,
+        amount: NearToken,
+        receiver_id: &AccountId,
+        msg: &str,
+    ) -> anyhow::Result<u128>;
 }
 
 impl WNearExt for Account {
@@ -48,6 +61,30 @@ impl WNearExt for Account {
             .map(|_| ())
             .map_err(Into::into)
     }
+
+    async fn near_deposit_and_transfer_call(
+        &self,
+        depositor_id: &AccountId,
+        wnear_id: &AccountId,
+        amount: NearToken,
+        receiver_id: &AccountId,
+        msg: &str,
+    ) -> anyhow::Result<u128> {
+        self.call(depositor_id, "near_deposit_and_transfer_call")
+            .args_json(json!({
+                "wnear_id": wnear_id,
+                "receiver_id": receiver_id,
+                "msg": msg,
+            }))
+            .deposit(amount)
+            .max_gas()
+            .transact()
+            .await?
+            .into_result()?
+            .json::<U128>()
+            .map(|used| used.0)
+            .map_err(Into::into)
+    }
 }
 
 impl WNearExt for Contract {
@@ -62,4 +99,17 @@ impl WNearExt for Contract {
     async fn near_withdraw(&self, wnear_id: &AccountId, amount: NearToken) -> anyhow::Result<()> {
         self.as_account().near_withdraw(wnear_id, amount).await
     }
+
+    async fn near_deposit_and_transfer_call(
+        &self,
+        depositor_id: &AccountId,
+        wnear_id: &AccountId,
+        amount: NearToken,
+        receiver_id: &AccountId,
+        msg: &str,
+    ) -> anyhow::Result<u128> {
+        self.as_account()
+            .near_deposit_and_transfer_call(depositor_id, wnear_id, amount, receiver_id, msg)
+            .await
+    }
 }
@@ -1,11 +1,14 @@
+use std::borrow::Cow;
+
 use defuse_crypto::{CryptoHash, Curve, Payload, Secp256k1, SignedPayload, serde::AsCurve};
 use impl_tools::autoimpl;
 use near_sdk::{env, near};
 use serde_with::serde_as;
 
 /// See [TIP-191](https://github.com/tronprotocol/tips/blob/master/tip-191.md)
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[near(serializers = [json])]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Tip191Payload(pub String);
 
 impl Tip191Payload {
@@ -26,8 +29,14 @@ impl Payload for Tip191Payload {
     fn hash(&self) -> CryptoHash {
         env::keccak256_array(&self.prehash())
     }
+
+    #[inline]
+    fn prehash(&self) -> Cow<'_, [u8]> {
+        Cow::Owned(Self::prehash(self))
+    }
 }
 
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(
     all(feature = "abi", not(target_arch = "wasm32")),
     serde_as(schemars = true)
@@ -37,8 +46,12 @@ impl Payload for Tip191Payload {
     serde_as(schemars = false)
 )]
 #[near(serializers = [json])]
+#[cfg_attr(
+    all(feature = "abi", not(target_arch = "wasm32")),
+    schemars(example = "self::examples::signed_tip191_payload")
+)]
 #[autoimpl(Deref using self.payload)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SignedTip191Payload {
     pub payload: Tip191Payload,
 
@@ -53,6 +66,11 @@ impl Payload for SignedTip191Payload {
     fn hash(&self) -> CryptoHash {
         self.payload.hash()
     }
+
+    #[inline]
+    fn prehash(&self) -> Cow<'_, [u8]> {
+        self.payload.prehash()
+    }
 }
 
 impl SignedPayload for SignedTip191Payload {
@@ -60,7 +78,27 @@ impl SignedPayload for SignedTip191Payload {
 
     #[inline]
     fn verify(&self) -> Option<Self::PublicKey> {
-        Secp256k1::verify(&self.signature, &self.payload.hash(), &())
+        // wallets may produce `v` ∈ {27, 28} as well as high `s`
+        let mut signature = self.signature;
+        Secp256k1::normalize_signature(&mut signature);
+        Secp256k1::verify(&signature, &self.payload.hash(), &())
+    }
+}
+
+#[cfg(all(feature = "abi", not(target_arch = "wasm32")))]
+mod examples {
+    use super::*;
+
+    use hex_literal::hex;
+
+    /// Signed in TronLink, see `tests::verify()`
+    pub fn signed_tip191_payload() -> SignedTip191Payload {
+        SignedTip191Payload {
+            payload: Tip191Payload("Hello, TRON!".to_string()),
+            signature: hex!(
+                "eea1651a60600ec4d9c45e8ae81da1a78377f789f0ac2019de66ad943459913015ef9256809ee0e6bb76e303a0b4802e475c1d26ade5d585292b80c9fe9cb10c01"
+            ),
+        }
     }
 }
 
@@ -74,11 +112,7 @@ mod tests {
     use rstest::rstest;
 
     fn fix_v_in_signature(mut sig: [u8; 65]) -> [u8; 65] {
-        if *sig.last().unwrap() >= 27 {
-            // Ethereum only uses uncompressed keys, with corresponding value v=27/28
-            // https://bitcoin.stackexchange.com/a/38909/58790
-            *sig.last_mut().unwrap() -= 27;
-        }
+        Secp256k1::normalize_signature(&mut sig);
         sig
     }
 
@@ -114,6 +148,31 @@ mod tests {
         assert_eq!(signed_payload.verify(), Some(public_key));
     }
 
+    #[test]
+    fn verify_non_canonical() {
+        let public_key = hex_literal::hex!(
+            "85a66984273f338ce4ef7b85e5430b008307e8591bb7c1b980852cf6423770b801f41e9438155eb53a5e20f748640093bb42ae3aeca035f7b7fd7a1a21f22f68"
+        );
+
+        for signature in [
+            // as produced by wallet, with v=28
+            hex_literal::hex!(
+                "eea1651a60600ec4d9c45e8ae81da1a78377f789f0ac2019de66ad943459913015ef9256809ee0e6bb76e303a0b4802e475c1d26ade5d585292b80c9fe9cb10c1c"
+            ),
+            // malleable counterpart: (r, n - s, 27)
+            hex_literal::hex!(
+                "eea1651a60600ec4d9c45e8ae81da1a78377f789f0ac2019de66ad9434599130ea106da97f611f1944891cfc5f4b7fd07352bfc00162cab696a6ddc2d19990351b"
+            ),
+        ] {
+            let signed_payload = SignedTip191Payload {
+                payload: Tip191Payload("Hello, TRON!".to_string()),
+                signature,
+            };
+
+            assert_eq!(signed_payload.verify(), Some(public_key));
+        }
+    }
+
     #[rstest]
     fn tamper_message_fails(mut rng: impl Rng) {
         let msg = "Hello, TRON!";
@@ -179,4 +238,38 @@ mod tests {
             assert_ne!(bad_signed_payload.verify(), Some(public_key));
         }
     }
+
+    #[rstest]
+    #[case("")]
+    #[case("Hello, TRON!")]
+    fn hash_is_digest_of_prehash(#[case] msg: &str) {
+        let payload = SignedTip191Payload {
+            payload: Tip191Payload(msg.to_string()),
+            signature: [0; 65],
+        };
+
+        assert_eq!(
+            Payload::prehash(&payload),
+            Tip191Payload::prehash(&payload.payload)
+        );
+        assert_eq!(
+            payload.hash(),
+            env::keccak256_array(&Payload::prehash(&payload))
+        );
+    }
+
+    #[cfg(all(feature = "abi", not(target_arch = "wasm32")))]
+    #[test]
+    fn schema_example() {
+        let example = super::examples::signed_tip191_payload();
+        let schema =
+            near_sdk::serde_json::to_value(near_sdk::schemars::schema_for!(SignedTip191Payload))
+                .unwrap();
+
+        assert_eq!(
+            schema["examples"],
+            near_sdk::serde_json::json!([near_sdk::serde_json::to_value(&example).unwrap()])
+        );
+        assert!(example.verify().is_some());
+    }
 }
@@ -18,7 +18,7 @@ use tlb_ton::{
 
 pub use tlb_ton;
 
-#[cfg_attr(test, derive(arbitrary::Arbitrary))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(arbitrary::Arbitrary))]
 #[cfg_attr(
     all(feature = "abi", not(target_arch = "wasm32")),
     serde_as(schemars = true)
@@ -41,13 +41,59 @@ pub struct TonConnectPayload {
     /// dApp domain
     pub domain: String,
     /// UNIX timestamp (in seconds or RFC3339) at the time of singing
-    #[cfg_attr(test, arbitrary(with = ::tlb_ton::UnixTimestamp::arbitrary))]
+    #[cfg_attr(
+        any(test, feature = "arbitrary"),
+        arbitrary(with = ::tlb_ton::UnixTimestamp::arbitrary)
+    )]
     #[serde_as(as = "PickFirst<(_, TimestampSeconds)>")]
     pub timestamp: DateTime<Utc>,
     pub payload: TonConnectPayloadSchema,
 }
 
 impl TonConnectPayload {
+    /// Builds the payload from `address` in either raw or user-friendly
+    /// format, validating it up front: malformed addresses, CRC
+    /// mismatches and workchain ids not fitting into `int8` are rejected
+    /// here instead of failing later when hashing.
+    pub fn with_address_str(
+        address: &str,
+        domain: String,
+        timestamp: DateTime<Utc>,
+        payload: TonConnectPayloadSchema,
+    ) -> Result<Self, StringError> {
+        let address: MsgAddress = address
+            .parse()
+            .map_err(|err| Error::custom(format!("address: {err}")))?;
+        i8::try_from(address.workchain_id).map_err(|_| {
+            Error::custom(format!(
+                "address: invalid workchain id {}",
+                address.workchain_id
+            ))
+        })?;
+        Ok(Self {
+            address,
+            domain,
+            timestamp,
+            payload,
+        })
+    }
+
+    /// Checks that [`Cell`](TonConnectPayloadSchema::Cell) payload was
+    /// signed with `expected_crc` as its `schema_crc`. Returns `false` for
+    /// other payload types.
+    ///
+    /// NOTE: this only compares CRCs. Checking that `cell` actually
+    /// conforms to the schema requires its TL-B definition and is left to
+    /// the integrator.
+    #[must_use]
+    #[inline]
+    pub const fn validate_cell_schema(&self, expected_crc: u32) -> bool {
+        matches!(
+            self.payload,
+            TonConnectPayloadSchema::Cell { schema_crc, .. } if schema_crc == expected_crc
+        )
+    }
+
     fn try_hash(&self) -> Result<near_sdk::CryptoHash, StringError> {
         let timestamp: u64 = self
             .timestamp
@@ -106,7 +152,7 @@ impl Payload for TonConnectPayload {
 }
 
 /// See <https://docs.tonconsole.com/academy/sign-data#choosing-the-right-format>
-#[cfg_attr(test, derive(arbitrary::Arbitrary))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(arbitrary::Arbitrary))]
 #[cfg_attr(
     all(feature = "abi", not(target_arch = "wasm32")),
     serde_as(schemars = true)
@@ -174,7 +220,7 @@ where
     }
 }
 
-#[cfg_attr(test, derive(arbitrary::Arbitrary))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(arbitrary::Arbitrary))]
 #[cfg_attr(
     all(feature = "abi", not(target_arch = "wasm32")),
     serde_as(schemars = true)
@@ -184,6 +230,10 @@ where
     serde_as(schemars = false)
 )]
 #[near(serializers = [json])]
+#[cfg_attr(
+    all(feature = "abi", not(target_arch = "wasm32")),
+    schemars(example = "self::examples::signed_ton_connect_payload")
+)]
 #[autoimpl(Deref using self.payload)]
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SignedTonConnectPayload {
@@ -212,6 +262,33 @@ impl SignedPayload for SignedTonConnectPayload {
     }
 }
 
+#[cfg(all(feature = "abi", not(target_arch = "wasm32")))]
+mod examples {
+    use super::*;
+
+    use hex_literal::hex;
+
+    /// Signed in TON Connect demo dApp, see `tests::verify_binary()`
+    pub fn signed_ton_connect_payload() -> SignedTonConnectPayload {
+        SignedTonConnectPayload {
+            payload: TonConnectPayload {
+                address: "0:f4809e5ffac9dc42a6b1d94c5e74ad5fd86378de675c805f2274d0055cbc9378"
+                    .parse()
+                    .unwrap(),
+                domain: "ton-connect.github.io".to_string(),
+                timestamp: DateTime::from_timestamp(1747760435, 0).unwrap(),
+                payload: TonConnectPayloadSchema::Binary {
+                    bytes: hex!("48656c6c6f2c20544f4e21").into(),
+                },
+            },
+            public_key: hex!("22e795a07e832fc9084ca35a488a711f1dbedef637d4e886a6997d93ee2c2e37"),
+            signature: hex!(
+                "9cf4c1c16b47afce46940eb9cd410894f31544b74206c2254bb1651f9b32cf5b0e482b78a2e8251e54d3517fae4b06c6f23546667d63ff62dccce70451698d01"
+            ),
+        }
+    }
+}
+
 #[cfg(test)]
 #[allow(clippy::unreadable_literal)]
 mod tests {
@@ -340,6 +417,84 @@ mod tests {
         }
     }
 
+    #[rstest]
+    #[case::raw("0:f4809e5ffac9dc42a6b1d94c5e74ad5fd86378de675c805f2274d0055cbc9378")]
+    #[case::user_friendly("EQD0gJ5f-sncQqax2UxedK1f2GN43mdcgF8idNAFXLyTeDDr")]
+    fn with_address_str(#[case] address: &str) {
+        let payload = TonConnectPayload::with_address_str(
+            address,
+            "ton-connect.github.io".to_string(),
+            DateTime::from_timestamp(1747760435, 0).unwrap(),
+            TonConnectPayloadSchema::Binary {
+                bytes: hex!("48656c6c6f2c20544f4e21").into(),
+            },
+        )
+        .unwrap();
+
+        assert_eq!(payload.address.workchain_id, 0);
+        assert_eq!(
+            payload.address.address,
+            hex!("f4809e5ffac9dc42a6b1d94c5e74ad5fd86378de675c805f2274d0055cbc9378")
+        );
+    }
+
+    #[rstest]
+    #[case::empty("")]
+    #[case::not_an_address("hello")]
+    #[case::short_hash("0:f4809e5ffac9dc42a6b1d94c5e74ad5f")]
+    #[case::non_hex_hash("0:z4809e5ffac9dc42a6b1d94c5e74ad5fd86378de675c805f2274d0055cbc9378")]
+    #[case::workchain_overflow(
+        "128:f4809e5ffac9dc42a6b1d94c5e74ad5fd86378de675c805f2274d0055cbc9378"
+    )]
+    #[case::workchain_underflow(
+        "-129:f4809e5ffac9dc42a6b1d94c5e74ad5fd86378de675c805f2274d0055cbc9378"
+    )]
+    #[case::crc_mismatch("EQD0gJ5f-sncQqax2UxedK1f2GN43mdcgF8idNAFXLyTeDDq")]
+    fn with_malformed_address_str(#[case] address: &str) {
+        TonConnectPayload::with_address_str(
+            address,
+            "ton-connect.github.io".to_string(),
+            DateTime::from_timestamp(1747760435, 0).unwrap(),
+            TonConnectPayloadSchema::Text {
+                text: "Hello, TON!".to_string(),
+            },
+        )
+        .unwrap_err();
+    }
+
+    #[rstest]
+    #[case::matching(0x2eccd0c1, true)]
+    #[case::mismatching(0x2eccd0c2, false)]
+    fn validate_cell_schema(#[case] expected_crc: u32, #[case] valid: bool) {
+        let payload = TonConnectPayload {
+            address: "0:f4809e5ffac9dc42a6b1d94c5e74ad5fd86378de675c805f2274d0055cbc9378"
+                .parse()
+                .unwrap(),
+            domain: "ton-connect.github.io".to_string(),
+            timestamp: DateTime::from_timestamp(1747772412, 0).unwrap(),
+            payload: TonConnectPayloadSchema::Cell {
+                schema_crc: 0x2eccd0c1,
+                cell: tlb_ton::BagOfCells::parse_base64(
+                    "te6cckEBAQEAEQAAHgAAAABIZWxsbywgVE9OIb7WCx4=",
+                )
+                .unwrap()
+                .into_single_root()
+                .unwrap()
+                .as_ref()
+                .clone(),
+            },
+        };
+        assert_eq!(payload.validate_cell_schema(expected_crc), valid);
+
+        let text = TonConnectPayload {
+            payload: TonConnectPayloadSchema::Text {
+                text: "Hello, TON!".to_string(),
+            },
+            ..payload
+        };
+        assert!(!text.validate_cell_schema(expected_crc));
+    }
+
     #[rstest]
     fn arbitrary(random_bytes: Vec<u8>) {
         verify_ok(
@@ -348,6 +503,20 @@ mod tests {
         );
     }
 
+    #[cfg(all(feature = "abi", not(target_arch = "wasm32")))]
+    #[test]
+    fn schema_example() {
+        let example = super::examples::signed_ton_connect_payload();
+        let schema =
+            serde_json::to_value(near_sdk::schemars::schema_for!(SignedTonConnectPayload)).unwrap();
+
+        assert_eq!(
+            schema["examples"],
+            serde_json::json!([serde_json::to_value(&example).unwrap()])
+        );
+        verify_ok(&example, true);
+    }
+
     fn verify_ok(signed: &SignedTonConnectPayload, ok: bool) {
         let serialized = serde_json::to_string_pretty(signed).unwrap();
         println!("{}", &serialized);
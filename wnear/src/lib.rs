@@ -2,12 +2,50 @@ use near_contract_standards::{
     fungible_token::{FungibleTokenCore, FungibleTokenResolver},
     storage_management::StorageManagement,
 };
-use near_sdk::{Gas, Promise, ext_contract, json_types::U128};
+use near_sdk::{
+    AccountId, Gas, GasWeight, NearToken, Promise, ext_contract, json_types::U128, serde_json,
+};
 
+pub const NEAR_DEPOSIT_GAS: Gas = Gas::from_tgas(5);
 pub const NEAR_WITHDRAW_GAS: Gas = Gas::from_tgas(10);
+pub const FT_TRANSFER_CALL_MIN_GAS: Gas = Gas::from_tgas(30);
 
 #[ext_contract(ext_wnear)]
 pub trait WNear: FungibleTokenCore + FungibleTokenResolver + StorageManagement {
     fn near_deposit(&mut self);
     fn near_withdraw(&mut self, amount: U128) -> Promise;
 }
+
+/// Wraps `amount` of NEAR and transfers resulting wNEAR to `receiver_id`
+/// via `ft_transfer_call()` with given `msg`, all within a single receipt.
+/// Remaining gas is distributed to `ft_transfer_call()`.
+///
+/// NOTE: current account is expected to be already registered on
+/// `wnear_id`, otherwise the storage fee is deducted by `near_deposit()`
+/// and `ft_transfer_call()` fails due to insufficient balance.
+pub fn near_deposit_and_transfer_call(
+    wnear_id: AccountId,
+    amount: NearToken,
+    receiver_id: &AccountId,
+    msg: &str,
+) -> Promise {
+    Promise::new(wnear_id)
+        .function_call(
+            "near_deposit".to_string(),
+            b"{}".to_vec(),
+            amount,
+            NEAR_DEPOSIT_GAS,
+        )
+        .function_call_weight(
+            "ft_transfer_call".to_string(),
+            serde_json::to_vec(&serde_json::json!({
+                "receiver_id": receiver_id,
+                "amount": U128(amount.as_yoctonear()),
+                "msg": msg,
+            }))
+            .unwrap_or_else(|_| unreachable!()),
+            NearToken::from_yoctonear(1),
+            FT_TRANSFER_CALL_MIN_GAS,
+            GasWeight(1),
+        )
+}